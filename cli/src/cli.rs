@@ -1,7 +1,10 @@
 use std::fmt::{self, Formatter};
+use std::path::PathBuf;
 
+use chrono::NaiveDate;
 use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
-use worklog::operation;
+use jira::models::core::IssueKey;
+use worklog::{error::WorklogError, operation, operation::add::AddEntry};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
 pub(crate) enum LogLevel {
@@ -26,7 +29,7 @@ impl fmt::Display for LogLevel {
 /// Jira worklog utility - add, delete, and list jira worklog entries
 ///
 /// Dates should be specified in the ISO8601 format without a time zone. Local timezone is
-/// always assumed. I.e. `2023-06-01`.
+/// assumed, unless another one is given with `--timezone`. I.e. `2023-06-01`.
 ///
 /// Duration is specified in units of hours, days, or weeks, using the abbreviations 'h','d', and 'w'
 /// respectively.
@@ -55,14 +58,31 @@ pub(crate) enum Command {
     Status(Status),
     /// Subcommands for configuration
     Config(Config),
-    /// Lists all time codes
-    Codes,
+    /// Lists all time codes, sorted by issue key
+    Codes(Codes),
     /// Start a timer
     Start(Start),
     /// Stops current timer
     Stop(Stop),
     /// Synchronize the local data store with remote Jira work logs
     Sync(Synchronisation),
+    /// Manage the locally cached Jira data
+    Cache(Cache),
+    /// Check the configuration, keychain, Jira connection, local database and clock
+    Doctor(Doctor),
+    /// List working days with less time logged than a full working day
+    Gaps(Gaps),
+    /// Export your work logs to a file, like an Excel timesheet
+    Export(Export),
+    /// Manage the favorite issues, which you may log work on by position or alias
+    Fav(Fav),
+    /// List duplicate work log entries, and optionally delete all but the earliest of each
+    Duplicates(Duplicates),
+    /// Manage Jira issues
+    Issue(Issue),
+    /// Lists values for shell completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete(Complete),
 }
 
 #[derive(Args)]
@@ -73,14 +93,89 @@ pub(crate) struct Add {
     ///     --durations Mon:1,5h Tue:1d Wed:3,5h Fri:1d
     #[arg(short, long, num_args(1..))]
     pub durations: Vec<String>,
-    /// Jira issues to register work on
-    #[arg(short, long, required = true)]
-    pub issue: String,
+    /// Jira issue to register work on. Repeat with ISSUE=DURATION pairs to register work on
+    /// several issues with the same start and comment:
+    ///     -i PROJ-1=1h -i PROJ-2=30m
+    #[arg(short = 'i', long = "issue", required_unless_present_any = ["stdin", "favorite", "interactive"])]
+    pub issues: Vec<String>,
+    /// Favorite issue to register work on, given by its position in 'timesheet fav list' or alias
+    #[arg(short = 'f', long, conflicts_with_all = ["issues", "stdin"])]
+    pub favorite: Option<String>,
     /// work started
-    #[arg(name = "started", short, long, requires = "durations")]
+    #[arg(name = "started", short, long)]
     pub started: Option<String>,
     #[arg(name = "comment", short, long)]
     pub comment: Option<String>,
+    /// IANA time zone in which --started is given, like Europe/Oslo, rather than the local one
+    #[arg(long, requires = "started")]
+    pub timezone: Option<String>,
+    /// Local tag of the entries, like billable, which is not sent to Jira. May be repeated
+    #[arg(long = "tag", conflicts_with = "stdin")]
+    pub tags: Vec<String>,
+    #[command(flatten)]
+    pub batch: AddBatchArgs,
+    /// Do not warn about logging time against resolved issues, or issues assigned to others
+    #[arg(long)]
+    pub force: bool,
+    /// Prompt for the issue, date, duration and comment of a single entry
+    #[arg(long, conflicts_with_all = ["issues", "favorite", "durations", "started", "comment", "stdin"])]
+    pub interactive: bool,
+}
+
+/// Options for adding a batch of entries read from stdin
+#[derive(Args)]
+pub(crate) struct AddBatchArgs {
+    /// Read a JSON array of entries from stdin, like:
+    ///     `[{"issue_key": "TIME-147", "started": "2024-02-01T08:00", "duration": "1h", "comment": "Meeting"}]`
+    #[arg(long, conflicts_with_all = ["issues", "durations", "started", "comment"])]
+    pub stdin: bool,
+    /// Do not add any of the entries read from stdin if any of them are invalid
+    #[arg(long, requires = "stdin")]
+    pub atomic: bool,
+}
+
+impl Add {
+    /// Parses the `ISSUE=DURATION` pairs given with `--issue` into one entry per pair.
+    /// Returns `None` if a single issue was given, to be combined with `--durations`.
+    pub fn issue_duration_entries(&self) -> Result<Option<Vec<AddEntry>>, WorklogError> {
+        if !self.issues.iter().any(|issue| issue.contains('=')) {
+            if self.issues.len() > 1 {
+                return Err(WorklogError::BadInput(
+                    "Multiple issues must be given as ISSUE=DURATION pairs".to_string(),
+                ));
+            }
+            return Ok(None);
+        }
+        if !self.durations.is_empty() {
+            return Err(WorklogError::BadInput(
+                "--durations can not be combined with ISSUE=DURATION pairs".to_string(),
+            ));
+        }
+        if self.timezone.is_some() {
+            return Err(WorklogError::BadInput(
+                "--timezone is not supported with ISSUE=DURATION pairs".to_string(),
+            ));
+        }
+        self.issues
+            .iter()
+            .map(|pair| match pair.split_once('=') {
+                Some((issue_key, duration))
+                    if !issue_key.trim().is_empty() && !duration.trim().is_empty() =>
+                {
+                    Ok(AddEntry {
+                        issue_key: issue_key.trim().to_string(),
+                        started: self.started.clone(),
+                        duration: duration.trim().to_string(),
+                        comment: self.comment.clone(),
+                    })
+                }
+                _ => Err(WorklogError::BadInput(format!(
+                    "Issue '{pair}' lacks a duration, use ISSUE=DURATION like {pair}=1h"
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
 }
 
 #[derive(Args)]
@@ -110,9 +205,178 @@ pub(crate) struct Status {
     /// Retrieves all entries after the given date
     #[arg(short, long)]
     pub start_after: Option<String>,
+    /// Only reports the entries started on this date, like 2024-02-15
+    #[arg(long, conflicts_with = "start_after")]
+    pub date: Option<NaiveDate>,
+    /// Retrieves your entries of --date straight from Jira rather than the local database
+    #[arg(long, requires = "date", conflicts_with = "all_users")]
+    pub live: bool,
     /// Reports on all registered Jira users, not just you
     #[arg(short, long)]
     pub all_users: bool,
+    /// Only reports the entries having this local tag, like billable
+    #[arg(long)]
+    pub tag: Option<String>,
+    #[command(flatten)]
+    pub layout: StatusLayout,
+}
+
+/// Options for how the status report is laid out
+#[derive(Args)]
+pub(crate) struct StatusLayout {
+    /// Reports the time logged on subtasks under the key of their parent issue
+    #[arg(long)]
+    pub rollup: bool,
+    /// Adds a column with the weekly totals in decimal hours, like 1.75h
+    #[arg(long)]
+    pub decimal: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Doctor {
+    /// Add the columns missing from the local database before running the checks
+    #[arg(long)]
+    pub repair: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Codes {
+    /// Group the time codes by their components
+    #[arg(long)]
+    pub by_component: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Gaps {
+    /// Check the current month rather than the current week
+    #[arg(short, long)]
+    pub month: bool,
+    /// Hours which must be logged per working day, defaults to the working hours per day in Jira
+    #[arg(long)]
+    pub hours: Option<f32>,
+}
+
+#[derive(Args)]
+pub(crate) struct Issue {
+    #[command(subcommand)]
+    pub cmd: IssueCommand,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum IssueCommand {
+    /// Create a task in Jira, given the components configured in `default_components` for the
+    /// project unless components are specified
+    Create(IssueCreate),
+    /// Delete an issue from Jira, along with its work logs, timers and favorite in the local database
+    Delete(IssueDelete),
+    /// Keep an issue in the local database after its last work log entry is deleted, when
+    /// `prune_orphan_issues` is enabled
+    Pin(IssuePin),
+    /// Allow an issue to be removed from the local database along with its last work log entry
+    Unpin(IssuePin),
+}
+
+#[derive(Args)]
+pub(crate) struct IssuePin {
+    /// The key of the issue
+    pub issue: String,
+}
+
+#[derive(Args)]
+pub(crate) struct IssueCreate {
+    /// The key of the project, like TIME
+    #[arg(short, long)]
+    pub project: String,
+    /// The summary of the issue
+    #[arg(short, long)]
+    pub summary: String,
+    #[arg(short, long)]
+    pub description: Option<String>,
+    /// Name of a component of the issue, may be repeated
+    #[arg(short, long = "component")]
+    pub components: Vec<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct IssueDelete {
+    /// The key of the issue to delete
+    pub issue: String,
+    /// Confirm the deletion, which cannot be undone
+    #[arg(long)]
+    pub yes: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Duplicates {
+    /// Delete the duplicates from Jira and the local database, keeping the entry with the lowest id
+    #[arg(long)]
+    pub delete: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct Fav {
+    #[command(subcommand)]
+    pub cmd: FavCommand,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum FavCommand {
+    /// Add an issue to the favorites, or change the alias of a favorite
+    Add(FavAdd),
+    /// Remove a favorite
+    Rm(FavRm),
+    /// List the favorites along with when work was last logged on them
+    List,
+}
+
+#[derive(Args)]
+pub(crate) struct FavAdd {
+    /// The issue to add
+    #[arg(short, long)]
+    pub issue: String,
+    /// Single word, which may be used in place of the issue key
+    #[arg(short, long)]
+    pub alias: Option<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct FavRm {
+    /// Position in the list of favorites, alias or issue key of the favorite
+    pub favorite: String,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub(crate) enum ExportFormat {
+    /// Excel workbook with a weekly grid of hours per issue and weekday
+    Xlsx,
+}
+
+#[derive(Args)]
+pub(crate) struct Export {
+    /// Format of the exported file
+    #[arg(long, value_enum, default_value_t = ExportFormat::Xlsx)]
+    pub format: ExportFormat,
+    /// The file to write
+    #[arg(short, long)]
+    pub output: PathBuf,
+    /// Export work logs started after this ISO8601 date, defaults to 30 days ago
+    #[arg(short = 'a', long)]
+    pub start_after: Option<String>,
+    /// Limit the export to these issues
+    #[arg(short, long, num_args(1..))]
+    pub issues: Vec<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct Complete {
+    #[command(subcommand)]
+    pub cmd: CompleteCommand,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum CompleteCommand {
+    /// The keys of the issues in the local database, one `key<TAB>summary` per line
+    Issues,
 }
 
 #[derive(Args)]
@@ -133,22 +397,81 @@ pub(crate) enum ConfigCommand {
     Remove,
 }
 
+/// Settings not supplied are left as they are; all of `--token`, `--user` and `--url` are
+/// required when the configuration is created.
 #[derive(Args, Clone)]
 pub(crate) struct UpdateConfiguration {
-    /// The Jira security API token obtained from your Manage Account -> Security
+    /// The Jira security API token obtained from your Manage Account -> Security.
+    /// Stored in the keychain, if there is one, rather than in the configuration file
     #[arg(short, long)]
-    pub token: String,
+    pub token: Option<String>,
     /// Your email address, i.e. me@whereever.com
     #[arg(short, long)]
-    pub user: String,
+    pub user: Option<String>,
     /// The base url to your Jira, typically <https://yourcompany.atlassian.net/rest/api/latest>
     #[arg(long)]
-    pub url: String,
+    pub url: Option<String>,
     // TODO: replace tracking_project with "projects" in the plural
     #[arg(long, default_value = "TIME")]
     pub tracking_project: String,
 }
 
+#[derive(Args)]
+pub(crate) struct Cache {
+    #[command(subcommand)]
+    pub cmd: CacheCommand,
+}
+
+/// Operations on the local cache of Jira issues, components and users.
+/// Work log entries are never removed by these commands.
+#[derive(Subcommand)]
+pub(crate) enum CacheCommand {
+    /// Clear cached data
+    Clear(ClearCache),
+}
+
+/// The cached data which may be cleared
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum CacheTarget {
+    Issues,
+    Components,
+    Users,
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("cache_targets")
+        .args(["issues", "components", "users", "all"])
+        .required(true)
+        .multiple(true)
+))]
+pub(crate) struct ClearCache {
+    /// Clear cached issues not referenced by any work log or timer
+    #[arg(long)]
+    pub issues: bool,
+    /// Clear cached components
+    #[arg(long)]
+    pub components: bool,
+    /// Clear cached users
+    #[arg(long)]
+    pub users: bool,
+    /// Clear issues, components and users
+    #[arg(long)]
+    pub all: bool,
+}
+
+impl ClearCache {
+    /// Whether the `target` is to be cleared, either by its own flag or by `--all`
+    pub fn includes(&self, target: CacheTarget) -> bool {
+        self.all
+            || match target {
+                CacheTarget::Issues => self.issues,
+                CacheTarget::Components => self.components,
+                CacheTarget::Users => self.users,
+            }
+    }
+}
+
 #[derive(Args)]
 pub(crate) struct Synchronisation {
     #[arg(name = "started", short, long)]
@@ -175,23 +498,40 @@ pub(crate) struct Synchronisation {
     /// Retrieves all registered Jira users, not just you
     #[arg(short, long)]
     pub all_users: bool,
+    /// Stop resolving issues from Jira once this many have been retrieved
+    #[arg(long)]
+    pub max_issues: Option<usize>,
+    /// Synchronise both ways: upload the completed timers, and remove local work logs of the
+    /// synchronised issues and period, which have been deleted in Jira
+    #[arg(long)]
+    pub prune: bool,
 }
 
 impl From<Synchronisation> for operation::sync::Sync {
     fn from(value: Synchronisation) -> Self {
         operation::sync::Sync {
             started: value.started,
-            issues: value.issues,
+            issues: value
+                .issues
+                .iter()
+                .map(|s| IssueKey::from(s.as_str()))
+                .collect(),
             projects: value.projects,
             all_users: value.all_users,
+            max_issues: value.max_issues,
+            prune: value.prune,
         }
     }
 }
 
 #[derive(Args)]
 pub(crate) struct Start {
-    #[arg(short, long, long_help = "Issue to start timer on")]
-    pub issue: String,
+    #[arg(
+        short,
+        long,
+        long_help = "Issue, or alias of a favorite, to start timer on, the most recently used issues are listed if omitted"
+    )]
+    pub issue: Option<String>,
     #[arg(short, long, long_help = "Comment to add to work log")]
     pub comment: Option<String>,
     #[arg(
@@ -201,6 +541,15 @@ pub(crate) struct Start {
     )]
     #[allow(clippy::struct_field_names)]
     pub start: Option<String>,
+    /// IANA time zone in which --start is given, like Europe/Oslo, rather than the local one
+    #[arg(long, requires = "start")]
+    pub timezone: Option<String>,
+    /// Start the timer on the issue most recently worked on
+    #[arg(long, conflicts_with = "issue")]
+    pub resume: bool,
+    /// Do not warn if the issue is resolved
+    #[arg(long)]
+    pub force: bool,
 }
 
 #[derive(Args)]
@@ -228,3 +577,106 @@ pub(crate) struct Stop {
     #[arg(short, long, long_help = "Discard the active work log entry")]
     pub discard: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_add(args: &[&str]) -> Add {
+        let opts = Opts::try_parse_from([&["timesheet", "add"], args].concat()).unwrap();
+        let Command::Add(add) = opts.cmd else {
+            panic!("Expected the add command");
+        };
+        add
+    }
+
+    #[test]
+    fn test_issue_duration_pairs() {
+        let add = parse_add(&["-i", "PROJ-1=1h", "-i", "PROJ-2=30m", "-s", "2024-02-01"]);
+        let entries = add.issue_duration_entries().unwrap().unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].issue_key, "PROJ-1");
+        assert_eq!(entries[0].duration, "1h");
+        assert_eq!(entries[1].issue_key, "PROJ-2");
+        assert_eq!(entries[1].duration, "30m");
+        assert!(entries
+            .iter()
+            .all(|entry| entry.started.as_deref() == Some("2024-02-01")));
+    }
+
+    #[test]
+    fn test_single_issue_is_not_a_pair() {
+        let add = parse_add(&["-i", "PROJ-1", "-d", "1h"]);
+        assert!(add.issue_duration_entries().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bare_issue_rejected_among_pairs() {
+        let add = parse_add(&["-i", "PROJ-1=1h", "-i", "PROJ-2"]);
+        assert!(matches!(
+            add.issue_duration_entries(),
+            Err(WorklogError::BadInput(msg)) if msg.contains("PROJ-2")
+        ));
+
+        let add = parse_add(&["-i", "PROJ-1=1h", "-d", "1h"]);
+        assert!(add.issue_duration_entries().is_err());
+    }
+
+    #[test]
+    fn test_favorite_in_place_of_issue() {
+        let add = parse_add(&["-f", "2", "-d", "1h"]);
+        assert_eq!(add.favorite.as_deref(), Some("2"));
+        assert!(add.issues.is_empty());
+
+        assert!(
+            Opts::try_parse_from(["timesheet", "add", "-f", "2", "-i", "PROJ-1", "-d", "1h"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_issue_create_with_components() {
+        let opts = Opts::try_parse_from([
+            "timesheet",
+            "issue",
+            "create",
+            "-p",
+            "time",
+            "-s",
+            "Retro",
+            "-c",
+            "Backend",
+            "-c",
+            "Frontend",
+        ])
+        .unwrap();
+        let Command::Issue(Issue {
+            cmd: IssueCommand::Create(create),
+        }) = opts.cmd
+        else {
+            panic!("Expected the issue create command");
+        };
+        assert_eq!(create.project, "time");
+        assert_eq!(create.components, vec!["Backend", "Frontend"]);
+        assert_eq!(create.description, None);
+    }
+
+    #[test]
+    fn test_multiple_cache_targets() {
+        let opts =
+            Opts::try_parse_from(["timesheet", "cache", "clear", "--issues", "--components"])
+                .unwrap();
+        let Command::Cache(Cache {
+            cmd: CacheCommand::Clear(clear),
+        }) = opts.cmd
+        else {
+            panic!("Expected the cache clear command");
+        };
+        assert!(clear.includes(CacheTarget::Issues));
+        assert!(clear.includes(CacheTarget::Components));
+        assert!(!clear.includes(CacheTarget::Users));
+
+        assert!(Opts::try_parse_from(["timesheet", "cache", "clear"]).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use worklog::error::WorklogError;
+use worklog::ApplicationRuntime;
+
+use crate::cli::{CacheCommand, CacheTarget};
+
+pub(crate) fn execute(runtime: &ApplicationRuntime, cmd: CacheCommand) -> Result<(), WorklogError> {
+    match cmd {
+        CacheCommand::Clear(clear) => {
+            // Components must go before the issues, as they are associated with them
+            if clear.includes(CacheTarget::Components) {
+                let removed = runtime.component_service().clear_components()?;
+                println!("Removed {removed} cached components");
+            }
+            if clear.includes(CacheTarget::Issues) {
+                let removed = runtime.issue_service().clear_issues()?;
+                println!("Removed {removed} cached issues");
+            }
+            if clear.includes(CacheTarget::Users) {
+                let removed = runtime.user_service().clear_users()?;
+                println!("Removed {removed} cached users");
+            }
+        }
+    }
+    Ok(())
+}
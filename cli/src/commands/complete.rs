@@ -0,0 +1,73 @@
+use std::fmt::Write as _;
+
+use worklog::error::WorklogError;
+use worklog::types::JiraIssueInfo;
+use worklog::ApplicationRuntime;
+
+use crate::cli::CompleteCommand;
+
+/// Prints the values completion scripts offer for the given command
+pub(crate) fn execute(
+    runtime: &ApplicationRuntime,
+    cmd: &CompleteCommand,
+) -> Result<(), WorklogError> {
+    match cmd {
+        CompleteCommand::Issues => {
+            let issue_service = runtime.issue_service();
+            let keys = issue_service.all_issue_keys()?;
+            let mut issues = issue_service.get_issues_filtered_by_keys(&keys)?;
+            issues.sort_by(|a, b| a.issue_key.cmp(&b.issue_key));
+            print!("{}", format_issues(&issues));
+        }
+    }
+    Ok(())
+}
+
+/// Formats each issue as `key<TAB>summary` on a line of its own. Tabs and line breaks
+/// in the summary are replaced by spaces to keep the output parseable.
+fn format_issues(issues: &[JiraIssueInfo]) -> String {
+    issues.iter().fold(String::new(), |mut output, issue| {
+        let summary: String = issue
+            .summary
+            .chars()
+            .map(|c| {
+                if matches!(c, '\t' | '\r' | '\n') {
+                    ' '
+                } else {
+                    c
+                }
+            })
+            .collect();
+        let _ = writeln!(output, "{}\t{summary}", issue.issue_key);
+        output
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jira::models::core::IssueKey;
+
+    #[test]
+    fn test_format_issues() {
+        let issues = [
+            JiraIssueInfo {
+                issue_key: IssueKey::from("TIME-1"),
+                summary: "Meetings".to_string(),
+                parent_key: None,
+                ..Default::default()
+            },
+            JiraIssueInfo {
+                issue_key: IssueKey::from("TIME-2"),
+                summary: "Tabs\tand\nbreaks".to_string(),
+                parent_key: None,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            format_issues(&issues),
+            "TIME-1\tMeetings\nTIME-2\tTabs and breaks\n"
+        );
+        assert_eq!(format_issues(&[]), "");
+    }
+}
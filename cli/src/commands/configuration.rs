@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
 use std::process::exit;
 
-use worklog::config::JiraClientConfiguration;
-use worklog::config::{self, AppConfiguration, ApplicationData};
+use worklog::config::{
+    self, AppConfiguration, ApplicationData, CommentConfiguration, DisplayTimeZone,
+};
+use worklog::config::{AuthType, JiraClientConfiguration};
 
 use crate::cli::{ConfigCommand, UpdateConfiguration};
 
@@ -11,7 +14,7 @@ fn list_and_exit() {
         config::configuration_file().to_string_lossy()
     );
 
-    match config::load_with_keychain_lookup() {
+    match config::load_with_keychain_lookup(config::credential_store().as_ref()) {
         Ok(config) => {
             let toml_as_string = config::application_config_to_string(&config).unwrap();
             println!("{toml_as_string}");
@@ -32,12 +35,19 @@ pub fn execute(config: ConfigCommand) {
         }
         // Add new values to the configuration
         Update(settings) => {
-            let app_config = AppConfiguration {
-                jira: settings.clone().into(),
-                application_data: ApplicationData::default(),
+            // The token is looked up in the keychain, to be stored again if the user changes
+            let store = config::credential_store();
+            let existing = config::load_with_keychain_lookup(store.as_ref()).ok();
+            let app_config = match apply_settings(existing, settings) {
+                Ok(app_config) => app_config,
+                Err(e) => {
+                    eprintln!("ERROR: {e}");
+                    exit(1);
+                }
             };
 
-            config::save(&app_config).expect("Unable to save the application config");
+            config::save(&app_config, store.as_ref())
+                .expect("Unable to save the application config");
             println!(
                 "Configuration saved to {}",
                 config::configuration_file().to_string_lossy()
@@ -61,12 +71,98 @@ pub fn execute(config: ConfigCommand) {
     }
 }
 
-impl From<UpdateConfiguration> for JiraClientConfiguration {
-    fn from(val: UpdateConfiguration) -> Self {
-        JiraClientConfiguration {
-            user: val.user,
-            token: val.token,
-            url: val.url,
+/// Applies the supplied settings to the `existing` configuration, leaving the settings not
+/// supplied untouched. Without an existing configuration, a new one is created, which requires
+/// the token, the user and the url.
+fn apply_settings(
+    existing: Option<AppConfiguration>,
+    settings: UpdateConfiguration,
+) -> Result<AppConfiguration, String> {
+    let Some(mut app_config) = existing else {
+        return Ok(AppConfiguration {
+            jira: settings.try_into()?,
+            application_data: ApplicationData::default(),
+            comment: CommentConfiguration::default(),
+            display_tz: DisplayTimeZone::default(),
+            time_code_jql: config::DEFAULT_TIME_CODE_JQL.to_string(),
+            prune_orphan_issues: false,
+            holidays: vec![],
+            default_components: BTreeMap::new(),
+        });
+    };
+
+    if let Some(token) = settings.token {
+        app_config.jira.token = token;
+    }
+    if let Some(user) = settings.user {
+        app_config.jira.user = user;
+    }
+    if let Some(url) = settings.url {
+        app_config.jira.url = url;
+    }
+    Ok(app_config)
+}
+
+impl TryFrom<UpdateConfiguration> for JiraClientConfiguration {
+    type Error = String;
+
+    fn try_from(val: UpdateConfiguration) -> Result<Self, Self::Error> {
+        match (val.token, val.user, val.url) {
+            (Some(token), Some(user), Some(url)) => Ok(JiraClientConfiguration {
+                user,
+                token,
+                url,
+                user_agent: None,
+                api_version: None,
+                auth_type: AuthType::default(),
+            }),
+            _ => Err(
+                "No configuration found, --token, --user and --url are required to create it"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(token: Option<&str>, user: Option<&str>, url: Option<&str>) -> UpdateConfiguration {
+        UpdateConfiguration {
+            token: token.map(str::to_string),
+            user: user.map(str::to_string),
+            url: url.map(str::to_string),
+            tracking_project: "TIME".to_string(),
         }
     }
+
+    #[test]
+    fn test_create_requires_token_user_and_url() {
+        assert!(apply_settings(None, settings(None, Some("me@example.com"), None)).is_err());
+
+        let app_config = apply_settings(
+            None,
+            settings(Some("token"), Some("me@example.com"), Some("http")),
+        )
+        .unwrap();
+        assert_eq!(app_config.jira.token, "token");
+        assert_eq!(app_config.jira.user, "me@example.com");
+        assert_eq!(app_config.jira.url, "http");
+    }
+
+    #[test]
+    fn test_update_leaves_token_untouched() {
+        let existing = apply_settings(
+            None,
+            settings(Some("token"), Some("me@example.com"), Some("http")),
+        )
+        .unwrap();
+
+        let app_config =
+            apply_settings(Some(existing.clone()), settings(None, None, Some("https"))).unwrap();
+        assert_eq!(app_config.jira.url, "https");
+        assert_eq!(app_config.jira.token, existing.jira.token);
+        assert_eq!(app_config.jira.user, existing.jira.user);
+    }
 }
@@ -0,0 +1,33 @@
+use std::process::exit;
+use worklog::doctor;
+
+use crate::cli::Doctor;
+
+/// Runs the diagnostic checks, printing the outcome of each with a hint on how to fix failures.
+/// Exits with a non-zero status if any of the checks failed.
+pub(crate) async fn execute(doctor_opts: Doctor) {
+    if doctor_opts.repair {
+        match doctor::repair_database() {
+            Ok(repaired) if repaired.is_empty() => println!("Nothing to repair in the database"),
+            Ok(repaired) => {
+                for issue in repaired {
+                    println!("Repaired {issue}");
+                }
+            }
+            Err(e) => {
+                eprintln!("ERROR: Unable to repair the database: {e}");
+                exit(1);
+            }
+        }
+    }
+    let results = doctor::run_checks().await;
+    for result in &results {
+        println!("[{}] {}: {}", result.status, result.name, result.message);
+        if let Some(hint) = &result.hint {
+            println!("       {hint}");
+        }
+    }
+    if results.iter().any(doctor::CheckResult::is_failure) {
+        exit(1);
+    }
+}
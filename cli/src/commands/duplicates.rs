@@ -0,0 +1,52 @@
+use jira::models::core::IssueKey;
+use worklog::error::WorklogError;
+use worklog::ApplicationRuntime;
+
+use crate::cli::Duplicates;
+
+/// Lists the duplicate work log entries. With `--delete`, all but the first entry of each group,
+/// which has the lowest id, are deleted from Jira and the local database.
+pub(crate) async fn execute(
+    runtime: &ApplicationRuntime,
+    duplicates: &Duplicates,
+) -> Result<(), WorklogError> {
+    let groups = runtime.worklog_service().find_duplicates()?;
+    if groups.is_empty() {
+        println!("No duplicate work log entries found");
+        return Ok(());
+    }
+
+    let mut extras: Vec<(IssueKey, String)> = Vec::new();
+    for group in &groups {
+        let (keep, rest) = group.split_first().expect("groups are never empty");
+        println!(
+            "{} {} {} {:?}",
+            keep.issue_key,
+            keep.started.format("%Y-%m-%d %H:%M"),
+            keep.timeSpent,
+            keep.comment.as_deref().unwrap_or_default()
+        );
+        println!("  keep   {}", keep.id);
+        for duplicate in rest {
+            println!("  delete {}", duplicate.id);
+            extras.push((duplicate.issue_key.clone(), duplicate.id.clone()));
+        }
+    }
+
+    if !duplicates.delete {
+        println!(
+            "{} duplicate entries found, use --delete to delete them",
+            extras.len()
+        );
+        return Ok(());
+    }
+    let report = runtime
+        .worklog_service()
+        .bulk_delete_worklogs(&extras)
+        .await?;
+    println!("Deleted {} duplicate entries", report.succeeded().count());
+    for (issue_key, worklog_id, reason) in &report.failed {
+        eprintln!("Failed to delete {issue_key} work log {worklog_id}: {reason}");
+    }
+    Ok(())
+}
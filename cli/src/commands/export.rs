@@ -0,0 +1,58 @@
+use chrono::{Days, Local};
+use jira::models::core::IssueKey;
+use worklog::{date, error::WorklogError};
+
+use crate::{
+    cli::{Export, ExportFormat},
+    get_runtime,
+};
+
+/// Exports your work logs of the local database to a file in the requested format
+pub(crate) fn execute(export: &Export) -> Result<(), WorklogError> {
+    let runtime = get_runtime();
+    let start_after = match export.start_after.as_deref() {
+        Some(s) => date::str_to_date_time(s)
+            .map_err(|e| WorklogError::BadInput(format!("Unable to parse '{s}': {e}")))?,
+        None => Local::now() - Days::new(30),
+    };
+    let issue_keys: Vec<IssueKey> = export
+        .issues
+        .iter()
+        .map(|key| IssueKey::from(key.as_str()))
+        .collect();
+    let user = runtime.user_service().find_current_user()?;
+    let worklogs =
+        runtime
+            .worklog_service()
+            .find_worklogs_after(start_after, &issue_keys, &[user])?;
+
+    match export.format {
+        ExportFormat::Xlsx => export_xlsx(&worklogs, export)?,
+    }
+    println!(
+        "Exported {} work log entries to {}",
+        worklogs.len(),
+        export.output.display()
+    );
+    Ok(())
+}
+
+#[cfg(feature = "xlsx")]
+fn export_xlsx(
+    worklogs: &[worklog::types::LocalWorklog],
+    export: &Export,
+) -> Result<(), WorklogError> {
+    let file = std::fs::File::create(&export.output)
+        .map_err(|e| WorklogError::CreateFile(format!("{}: {e}", export.output.display())))?;
+    worklog::export::export_xlsx(worklogs, file)
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn export_xlsx(
+    _worklogs: &[worklog::types::LocalWorklog],
+    _export: &Export,
+) -> Result<(), WorklogError> {
+    Err(WorklogError::Export(
+        "timesheet was built without Excel support, rebuild it with '--features xlsx'".to_string(),
+    ))
+}
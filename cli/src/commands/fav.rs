@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use jira::models::core::IssueKey;
+use worklog::error::WorklogError;
+use worklog::ApplicationRuntime;
+
+use crate::cli::FavCommand;
+
+pub(crate) async fn execute(
+    runtime: &ApplicationRuntime,
+    cmd: FavCommand,
+) -> Result<(), WorklogError> {
+    let issue_service = runtime.issue_service();
+    match cmd {
+        FavCommand::Add(add) => {
+            let issue_key = IssueKey::from(add.issue.as_str());
+            issue_service
+                .add_favorite(&issue_key, add.alias.as_deref())
+                .await?;
+            println!("Added {issue_key} to the favorites");
+        }
+        FavCommand::Rm(rm) => {
+            let issue_key = issue_service.remove_favorite(&rm.favorite)?;
+            println!("Removed {issue_key} from the favorites");
+        }
+        FavCommand::List => {
+            let favorites = issue_service.favorites()?;
+            if favorites.is_empty() {
+                println!(
+                    "No favorites, add one with 'timesheet fav add -i <issue> --alias <alias>'"
+                );
+                return Ok(());
+            }
+            let keys: Vec<IssueKey> = favorites.iter().map(|f| f.issue_key.clone()).collect();
+            let summaries: HashMap<IssueKey, String> = issue_service
+                .get_issues_filtered_by_keys(&keys)?
+                .into_iter()
+                .map(|issue| (issue.issue_key, issue.summary))
+                .collect();
+            // Every issue worked on, to find when work was last started on each favorite
+            let last_started: HashMap<IssueKey, String> = runtime
+                .worklog_service()
+                .most_recent_issues(issue_service.all_issue_keys()?.len())?
+                .into_iter()
+                .map(|(issue, started)| (issue.issue_key, started.format("%Y-%m-%d").to_string()))
+                .collect();
+            for (position, favorite) in (1..).zip(&favorites) {
+                println!(
+                    "{position:>3} {:<12} {:<12} {:<40} {}",
+                    favorite.issue_key,
+                    favorite.alias.as_deref().unwrap_or(""),
+                    summaries
+                        .get(&favorite.issue_key)
+                        .map_or("", String::as_str),
+                    last_started
+                        .get(&favorite.issue_key)
+                        .map_or("never worked on", String::as_str)
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use chrono::{Datelike, Days, Local, TimeZone};
+use worklog::error::WorklogError;
+
+use crate::{cli::Gaps, get_runtime};
+
+/// Lists the working days of the current week, or month, with less time logged than required.
+/// The required time defaults to the working hours per day configured in Jira.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) async fn execute(gaps: Gaps) -> Result<(), WorklogError> {
+    let runtime = get_runtime();
+    let now = Local::now();
+    let first_day = if gaps.month {
+        now.date_naive().with_day(1).unwrap()
+    } else {
+        now.date_naive() - Days::new(u64::from(now.weekday().num_days_from_monday()))
+    };
+    let start = Local
+        .from_local_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .unwrap_or(now);
+
+    let hours = match gaps.hours {
+        Some(hours) => hours,
+        None => {
+            runtime
+                .jira_client()
+                .get_time_tracking_options()
+                .await?
+                .workingHoursPerDay
+        }
+    };
+    let min_seconds_per_day = (hours * 3600.0) as i64;
+
+    let current_user = runtime.user_service().find_current_user()?;
+    let days = runtime
+        .worklog_service()
+        .find_gaps(
+            start,
+            now,
+            min_seconds_per_day,
+            &runtime.holidays,
+            &current_user,
+        )
+        .await?;
+    if days.is_empty() {
+        println!("At least {hours}h has been logged on every working day since {first_day}");
+    } else {
+        println!("Working days since {first_day} with less than {hours}h logged:");
+        for day in days {
+            println!("  {} {day}", day.weekday());
+        }
+    }
+    Ok(())
+}
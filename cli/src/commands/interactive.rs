@@ -0,0 +1,247 @@
+use std::fmt::Write as _;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use chrono::{Local, NaiveDate};
+use jira::models::core::IssueKey;
+use worklog::date::TimeSpent;
+use worklog::error::WorklogError;
+use worklog::types::JiraIssueInfo;
+use worklog::ApplicationRuntime;
+
+use crate::cli::Add;
+
+/// Number of issues suggested when the issue is given as text rather than a key
+const MAX_SUGGESTIONS: usize = 10;
+
+/// Prompts for the issue, date, duration and comment of a single entry, which are filled in
+/// to `add`. Refuses to run without a terminal, rather than waiting for input which never comes.
+pub(crate) fn prompt_entry(
+    runtime: &ApplicationRuntime,
+    add: &mut Add,
+) -> Result<(), WorklogError> {
+    if !io::stdin().is_terminal() {
+        return Err(WorklogError::BadInput(
+            "--interactive requires a terminal, use --issue and --durations, or --stdin when piping"
+                .to_string(),
+        ));
+    }
+    let issue_service = runtime.issue_service();
+    let issues = issue_service.get_issues_filtered_by_keys(&issue_service.all_issue_keys()?)?;
+
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout();
+    let issue_key = prompt(
+        &mut input,
+        &mut output,
+        "Issue key, or text to search for",
+        |answer| validate_issue_key(answer, &issues),
+    )?;
+    let date = prompt(
+        &mut input,
+        &mut output,
+        "Date, like 2024-05-31 [today]",
+        |answer| validate_date(answer, Local::now().date_naive()),
+    )?;
+    let duration = prompt(
+        &mut input,
+        &mut output,
+        "Duration, like 1h 30m or 1,5h",
+        validate_duration,
+    )?;
+    let comment = prompt(&mut input, &mut output, "Comment []", |answer| {
+        Ok(validate_comment(answer))
+    })?;
+
+    add.issues = vec![issue_key.to_string()];
+    add.durations = vec![duration];
+    add.started = date.map(|date| date.format("%Y-%m-%d").to_string());
+    add.comment = comment;
+    Ok(())
+}
+
+/// Asks the question until the answer is accepted by `validate`, which explains why an
+/// answer is rejected.
+fn prompt<T>(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+    validate: impl Fn(&str) -> Result<T, String>,
+) -> Result<T, WorklogError> {
+    let terminal_error =
+        |err: io::Error| WorklogError::BadInput(format!("Unable to prompt for input: {err}"));
+    loop {
+        write!(output, "{question}: ").map_err(terminal_error)?;
+        output.flush().map_err(terminal_error)?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer).map_err(terminal_error)? == 0 {
+            return Err(WorklogError::BadInput(
+                "No more input, no work log entry was added".to_string(),
+            ));
+        }
+        match validate(answer.trim()) {
+            Ok(value) => return Ok(value),
+            Err(reason) => writeln!(output, "{reason}").map_err(terminal_error)?,
+        }
+    }
+}
+
+/// Accepts an issue key like `TIME-94`. Any other text is searched for in the keys and
+/// summaries of the issues in the local database, which are suggested in the reason
+/// for rejecting the answer.
+pub(crate) fn validate_issue_key(
+    answer: &str,
+    issues: &[JiraIssueInfo],
+) -> Result<IssueKey, String> {
+    if answer.is_empty() {
+        return Err("The issue is required".to_string());
+    }
+    if !answer.contains(char::is_whitespace) {
+        let issue_key = IssueKey::from(answer);
+        if issue_key.number().is_some()
+            && issue_key
+                .project_key()
+                .starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            return Ok(issue_key);
+        }
+    }
+    let suggestions = suggest_issues(answer, issues);
+    if suggestions.is_empty() {
+        return Err(format!(
+            "'{answer}' is not an issue key, and no known issue matches it"
+        ));
+    }
+    let mut reason = format!("'{answer}' is not an issue key, did you mean one of:");
+    for issue in suggestions {
+        let _ = write!(reason, "\n  {} {}", issue.issue_key, issue.summary);
+    }
+    Err(reason)
+}
+
+/// The issues whose key or summary contains the text, ignoring case
+pub(crate) fn suggest_issues<'a>(
+    text: &str,
+    issues: &'a [JiraIssueInfo],
+) -> Vec<&'a JiraIssueInfo> {
+    let text = text.to_lowercase();
+    issues
+        .iter()
+        .filter(|issue| {
+            issue.issue_key.value().to_lowercase().contains(&text)
+                || issue.summary.to_lowercase().contains(&text)
+        })
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+/// Accepts an ISO 8601 date no later than today. An empty answer means today, with the work
+/// ending now, which is given as `None`.
+pub(crate) fn validate_date(answer: &str, today: NaiveDate) -> Result<Option<NaiveDate>, String> {
+    if answer.is_empty() {
+        return Ok(None);
+    }
+    let date = NaiveDate::parse_from_str(answer, "%Y-%m-%d")
+        .map_err(|_| format!("'{answer}' is not a date like 2024-05-31"))?;
+    if date > today {
+        return Err(format!("{date} is in the future"));
+    }
+    Ok(Some(date))
+}
+
+/// Accepts a duration like `1h 30m`, `1,5h` or `1d`
+pub(crate) fn validate_duration(answer: &str) -> Result<String, String> {
+    let duration = answer.replace(' ', "");
+    // The actual length of days and weeks is given by Jira when the entry is added
+    match TimeSpent::from_str(&duration, 7.5, 5.0) {
+        Ok(time_spent) if time_spent.time_spent_seconds > 0 => Ok(duration),
+        _ => Err(format!(
+            "'{answer}' is not a duration like 1h 30m, 1,5h or 1d"
+        )),
+    }
+}
+
+/// An empty comment is no comment
+pub(crate) fn validate_comment(answer: &str) -> Option<String> {
+    (!answer.is_empty()).then(|| answer.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(key: &str, summary: &str) -> JiraIssueInfo {
+        JiraIssueInfo {
+            issue_key: IssueKey::from(key),
+            summary: summary.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_issue_key() {
+        let issues = [issue("TIME-94", "Meetings"), issue("TIME-40", "Training")];
+        assert_eq!(
+            validate_issue_key("time-94", &issues),
+            Ok(IssueKey::from("TIME-94"))
+        );
+        assert!(validate_issue_key("", &issues).is_err());
+
+        let reason = validate_issue_key("meet", &issues).unwrap_err();
+        assert!(reason.contains("TIME-94 Meetings"), "{reason}");
+        assert!(!reason.contains("TIME-40"), "{reason}");
+        assert!(validate_issue_key("holiday", &issues)
+            .unwrap_err()
+            .contains("no known issue"));
+    }
+
+    #[test]
+    fn test_validate_date() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 31).unwrap();
+        assert_eq!(validate_date("", today), Ok(None));
+        assert_eq!(
+            validate_date("2024-05-30", today),
+            Ok(NaiveDate::from_ymd_opt(2024, 5, 30))
+        );
+        assert!(validate_date("2024-06-01", today).is_err());
+        assert!(validate_date("31.05.2024", today).is_err());
+    }
+
+    #[test]
+    fn test_validate_duration() {
+        assert_eq!(validate_duration("1h 30m"), Ok("1h30m".to_string()));
+        assert_eq!(validate_duration("1,5h"), Ok("1,5h".to_string()));
+        assert!(validate_duration("").is_err());
+        assert!(validate_duration("soon").is_err());
+    }
+
+    #[test]
+    fn test_validate_comment() {
+        assert_eq!(validate_comment(""), None);
+        assert_eq!(validate_comment("Meeting"), Some("Meeting".to_string()));
+    }
+
+    #[test]
+    fn test_prompt_repeats_until_valid_and_stops_at_end_of_input() {
+        let mut output = Vec::new();
+        let duration = prompt(
+            &mut "soon\n2h\n".as_bytes(),
+            &mut output,
+            "Duration",
+            validate_duration,
+        );
+        assert_eq!(duration.unwrap(), "2h");
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("'soon' is not a duration"));
+
+        assert!(matches!(
+            prompt(
+                &mut "".as_bytes(),
+                &mut Vec::new(),
+                "Duration",
+                validate_duration
+            ),
+            Err(WorklogError::BadInput(_))
+        ));
+    }
+}
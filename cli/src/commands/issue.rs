@@ -0,0 +1,56 @@
+use jira::models::core::IssueKey;
+use jira::models::project::JiraProjectKey;
+use worklog::error::WorklogError;
+use worklog::ApplicationRuntime;
+
+use crate::cli::IssueCommand;
+
+pub(crate) async fn execute(
+    runtime: &ApplicationRuntime,
+    cmd: IssueCommand,
+) -> Result<(), WorklogError> {
+    match cmd {
+        IssueCommand::Create(create) => {
+            let project_key = create.project.to_uppercase();
+            let components = runtime.component_service().components_for_new_issue(
+                &project_key,
+                &create.components,
+                &runtime.default_components,
+            )?;
+            let created = runtime
+                .jira_client()
+                .create_issue(
+                    &JiraProjectKey { key: project_key },
+                    &create.summary,
+                    create.description,
+                    components,
+                )
+                .await?;
+            println!("Created {}", created.key);
+        }
+        IssueCommand::Delete(delete) => {
+            let issue_key = IssueKey::from(delete.issue.as_str());
+            if !delete.yes {
+                return Err(WorklogError::BadInput(format!(
+                    "Deleting {issue_key} from Jira along with all of its work logs cannot be undone, add --yes to confirm"
+                )));
+            }
+            let worklogs = runtime.issue_service().delete_issue(&issue_key).await?;
+            println!("Deleted {issue_key} and {worklogs} local work log entries");
+        }
+        IssueCommand::Pin(pin) => {
+            let issue_key = IssueKey::from(pin.issue.as_str());
+            runtime.issue_service().set_pinned(&issue_key, true).await?;
+            println!("Pinned {issue_key}");
+        }
+        IssueCommand::Unpin(pin) => {
+            let issue_key = IssueKey::from(pin.issue.as_str());
+            runtime
+                .issue_service()
+                .set_pinned(&issue_key, false)
+                .await?;
+            println!("Unpinned {issue_key}");
+        }
+    }
+    Ok(())
+}
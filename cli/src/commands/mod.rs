@@ -1,3 +1,12 @@
+pub(crate) mod cache;
+pub(crate) mod complete;
 pub(crate) mod configuration;
+pub(crate) mod doctor;
+pub(crate) mod duplicates;
+pub(crate) mod export;
+pub(crate) mod fav;
+pub(crate) mod gaps;
+pub(crate) mod interactive;
+pub(crate) mod issue;
 pub(crate) mod status;
 pub(crate) mod stop_timer;
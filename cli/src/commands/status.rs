@@ -1,11 +1,13 @@
+use std::collections::{HashMap, HashSet};
 use std::process::exit;
 
-use chrono::{Datelike, Days, Local};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveTime};
 use jira::models::core::IssueKey;
 use log::debug;
+use worklog::config::DisplayTimeZone;
 use worklog::date;
 use worklog::error::WorklogError;
-use worklog::types::LocalWorklog;
+use worklog::types::{JiraIssueInfo, LocalWorklog};
 use worklog::ApplicationRuntime;
 
 use crate::{cli::Status, get_runtime, table_report_weekly::table_report_weekly};
@@ -15,34 +17,54 @@ pub async fn execute(status: Status) -> Result<(), WorklogError> {
     let runtime = get_runtime();
     let worklog_service = runtime.worklog_service();
 
-    let start_after = match status
-        .start_after
-        .map(|s| date::str_to_date_time(&s).unwrap())
-    {
-        None => Local::now().checked_sub_days(Days::new(30)),
-        Some(date) => Some(date),
+    let start_after = match (status.date, &status.start_after) {
+        (Some(date), _) => date
+            .and_time(NaiveTime::MIN)
+            .and_local_timezone(Local)
+            .earliest(),
+        (None, Some(s)) => Some(date::str_to_date_time(s).unwrap()),
+        (None, None) => Local::now().checked_sub_days(Days::new(30)),
     };
 
     let mut jira_keys_to_report = Vec::<IssueKey>::new();
-    if let Some(keys) = status.issues {
-        jira_keys_to_report.extend(keys.into_iter().map(IssueKey::from));
+    if let Some(keys) = &status.issues {
+        jira_keys_to_report.extend(keys.iter().map(|key| IssueKey::from(key.as_str())));
     }
 
-    eprintln!(
-        "Locating local work log entries after {}",
-        start_after.expect("Must specify --after ")
-    );
+    let worklogs = if let (true, Some(date)) = (status.live, status.date) {
+        eprintln!("Retrieving your work log entries of {date} from Jira");
+        live_worklogs(&runtime, date, &jira_keys_to_report).await?
+    } else {
+        let start_after = start_after.expect("Must specify --after ");
+        local_worklogs(&runtime, &status, start_after, &jira_keys_to_report)?
+    };
 
-    // Retrieves the data from the DBMS, which we will use to create the reports
-    let worklogs = if status.all_users {
-        worklog_service.find_worklogs_after(start_after.unwrap(), &jira_keys_to_report, &[])?
+    let worklogs = if let Some(tag) = &status.tag {
+        let tagged: HashSet<String> = worklog_service.find_by_tag(tag)?.into_iter().collect();
+        worklogs
+            .into_iter()
+            .filter(|wl| tagged.contains(&wl.id))
+            .collect()
     } else {
-        let user = runtime.user_service().find_current_user()?;
-        worklog_service.find_worklogs_after(start_after.unwrap(), &jira_keys_to_report, &[user])?
+        worklogs
+    };
+
+    let worklogs = if status.layout.rollup {
+        let mut keys: Vec<IssueKey> = worklogs.iter().map(|wl| wl.issue_key.clone()).collect();
+        keys.sort();
+        keys.dedup();
+        let issues = runtime.issue_service().get_issues_filtered_by_keys(&keys)?;
+        roll_up_to_parents(worklogs, &issues)
+    } else {
+        worklogs
     };
 
     eprintln!("Found {} local worklog entries", worklogs.len());
     let count_before = worklogs.iter().len();
+    if count_before == 0 && status.live {
+        eprintln!("No work log entries of yours found in Jira");
+        return Ok(());
+    }
     if count_before == 0 {
         eprintln!(
             r"ERROR: No data available in your local database for report generation.
@@ -56,12 +78,12 @@ pub async fn execute(status: Status) -> Result<(), WorklogError> {
         );
         exit(2);
     }
-    issue_and_entry_report(&worklogs);
+    issue_and_entry_report(&worklogs, runtime.display_tz);
     println!();
     assert_eq!(worklogs.len(), count_before);
 
     // Prints the report
-    table_report_weekly(&worklogs);
+    table_report_weekly(&worklogs, runtime.display_tz, status.layout.decimal);
 
     // Prints the status of the active timer
     match get_runtime().timer_service.get_active_timer() {
@@ -71,8 +93,12 @@ pub async fn execute(status: Status) -> Result<(), WorklogError> {
                 .num_seconds();
             let hours = elapsed_seconds / 3600;
             let minutes = (elapsed_seconds % 3600) / 60;
+            let summary = timer
+                .summary_snapshot
+                .map(|summary| format!(" - '{summary}'"))
+                .unwrap_or_default();
             println!(
-                "Active timer for {}, started at {} and current elapsed time is {:02}h {:02}m",
+                "Active timer for {}{summary}, started at {} and current elapsed time is {:02}h {:02}m",
                 timer.issue_key,
                 timer.started_at.format("%Y-%m-%d %H:%M"),
                 hours,
@@ -94,6 +120,51 @@ pub async fn execute(status: Status) -> Result<(), WorklogError> {
     Ok(())
 }
 
+/// Retrieves the work logs of the current user started on `date` straight from Jira,
+/// limited to the supplied issues, if any. Nothing is written to the local database.
+async fn live_worklogs(
+    runtime: &ApplicationRuntime,
+    date: NaiveDate,
+    issue_keys: &[IssueKey],
+) -> Result<Vec<LocalWorklog>, WorklogError> {
+    let worklogs = runtime
+        .jira_client()
+        .get_current_user_worklogs_on_date(date)
+        .await?;
+    Ok(worklogs
+        .iter()
+        .filter(|(issue_key, _)| issue_keys.is_empty() || issue_keys.contains(issue_key))
+        .map(|(issue_key, worklog)| LocalWorklog::from_worklog(worklog, issue_key))
+        .collect())
+}
+
+/// Retrieves the work logs started after `start_after` from the local database, limited to
+/// the supplied issues, if any, and to the `--date` and users of `status`.
+fn local_worklogs(
+    runtime: &ApplicationRuntime,
+    status: &Status,
+    start_after: DateTime<Local>,
+    issue_keys: &[IssueKey],
+) -> Result<Vec<LocalWorklog>, WorklogError> {
+    eprintln!("Locating local work log entries after {start_after}");
+
+    // Retrieves the data from the DBMS, which we will use to create the reports
+    let worklog_service = runtime.worklog_service();
+    let worklogs = if status.all_users {
+        worklog_service.find_worklogs_after(start_after, issue_keys, &[])?
+    } else {
+        let user = runtime.user_service().find_current_user()?;
+        worklog_service.find_worklogs_after(start_after, issue_keys, &[user])?
+    };
+    Ok(match status.date {
+        Some(date) => worklogs
+            .into_iter()
+            .filter(|wl| wl.started.date_naive() == date)
+            .collect(),
+        None => worklogs,
+    })
+}
+
 #[allow(dead_code)]
 fn print_info_about_time_codes(
     runtime: &ApplicationRuntime,
@@ -120,7 +191,24 @@ fn print_info_about_time_codes(
     }
 }
 
-fn issue_and_entry_report(entries: &[LocalWorklog]) {
+/// Replaces the issue key of work logs registered on subtasks with the key of the parent issue
+fn roll_up_to_parents(worklogs: Vec<LocalWorklog>, issues: &[JiraIssueInfo]) -> Vec<LocalWorklog> {
+    let parents: HashMap<&IssueKey, &IssueKey> = issues
+        .iter()
+        .filter_map(|issue| issue.parent_key.as_ref().map(|p| (&issue.issue_key, p)))
+        .collect();
+    worklogs
+        .into_iter()
+        .map(|mut worklog| {
+            if let Some(parent) = parents.get(&worklog.issue_key) {
+                worklog.issue_key = (*parent).clone();
+            }
+            worklog
+        })
+        .collect()
+}
+
+fn issue_and_entry_report(entries: &[LocalWorklog], display_tz: DisplayTimeZone) {
     println!(
         "{:8} {:7} {:7} {:<7} {:22} {:10} Comment",
         "Issue", "IssueId", "Id", "Weekday", "Started", "Time spent",
@@ -133,18 +221,66 @@ fn issue_and_entry_report(entries: &[LocalWorklog]) {
     });
 
     for e in &status_entries {
+        let started = e.started_in(display_tz);
         println!(
             "{:8} {:7} {:7} {:<7} {:22} {:10} {}",
             e.issue_key,
             e.issueId,
             e.id,
-            format!("{}", e.started.weekday()),
-            format!(
-                "{}",
-                e.started.with_timezone(&Local).format("%Y-%m-%d %H:%M %z")
-            ),
+            format!("{}", started.weekday()),
+            format!("{}", started.format("%Y-%m-%d %H:%M %z")),
             date::seconds_to_hour_and_min(e.timeSpentSeconds),
             e.comment.as_deref().unwrap_or("")
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn worklog(id: &str, issue_key: &str, seconds: i32) -> LocalWorklog {
+        let now = Local::now();
+        LocalWorklog::builder(IssueKey::from(issue_key), id, seconds)
+            .author("Ola Dunk")
+            .created(now)
+            .updated(now)
+            .started(now)
+            .issue_id(1)
+            .build()
+    }
+
+    #[test]
+    fn test_roll_up_to_parents() {
+        let worklogs = vec![
+            worklog("1", "TIME-1", 3600),
+            worklog("2", "TIME-2", 1800),
+            worklog("3", "TIME-3", 900),
+        ];
+        let issues = vec![
+            JiraIssueInfo {
+                issue_key: IssueKey::from("TIME-2"),
+                summary: "Subtask of TIME-1".to_string(),
+                parent_key: Some(IssueKey::from("TIME-1")),
+                ..Default::default()
+            },
+            JiraIssueInfo {
+                issue_key: IssueKey::from("TIME-3"),
+                summary: "No parent".to_string(),
+                parent_key: None,
+                ..Default::default()
+            },
+        ];
+
+        let mut totals = BTreeMap::<String, i32>::new();
+        for wl in roll_up_to_parents(worklogs, &issues) {
+            *totals.entry(wl.issue_key.to_string()).or_default() += wl.timeSpentSeconds;
+        }
+
+        assert_eq!(
+            totals,
+            BTreeMap::from([("TIME-1".to_string(), 5400), ("TIME-3".to_string(), 900)])
+        );
+    }
+}
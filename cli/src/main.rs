@@ -17,11 +17,31 @@
 //! timesheet add -i PROJ-123 -d 4h -s 2024-02-01 -c "Implemented feature X"
 //! ```
 //!
+//! Add a batch of work logs supplied as a JSON array:
+//! ```bash
+//! echo '[{"issue_key": "PROJ-123", "duration": "4h", "comment": "Implemented feature X"}]' | timesheet add --stdin
+//! ```
+//!
+//! Add work logs to several issues with the same start and comment:
+//! ```bash
+//! timesheet add -i PROJ-123=1h -i PROJ-124=1h -s 2024-02-01 -c "Retrospective"
+//! ```
+//!
+//! Add a work log to the second issue of `timesheet fav list`:
+//! ```bash
+//! timesheet add -f 2 -d 1h
+//! ```
+//!
 //! Add multiple work logs for different days:
 //! ```bash
 //! timesheet add -i PROJ-123 -d Mon:4h Tue:3.5h Wed:6h
 //! ```
 //!
+//! Be prompted for the issue, date, duration and comment:
+//! ```bash
+//! timesheet add --interactive
+//! ```
+//!
 //! ### Deleting Work Logs
 //! ```bash
 //! timesheet del -i PROJ-123 -w 12345
@@ -44,11 +64,45 @@
 //! timesheet sync -p PROJ TIME --all-users
 //! ```
 //!
-//! ### Listing Time Codes from Jira project TIME
-//! List all time codes from Jira project named `TIME`:
+//! ### Finding Days Without Work Logs
+//! List the working days of the current month with less than a full day logged:
+//! ```bash
+//! timesheet gaps --month
+//! ```
+//!
+//! ### Removing Duplicate Work Logs
+//! List the work logs recorded more than once, then delete all but the earliest of each:
+//! ```bash
+//! timesheet duplicates
+//! timesheet duplicates --delete
+//! ```
+//!
+//! ### Deleting an Issue
+//! Delete an issue from Jira along with its work logs, timers and favorite in the local database:
+//! ```bash
+//! timesheet issue delete PROJ-123 --yes
+//! ```
+//!
+//! ### Exporting an Excel Timesheet
+//! Requires the `xlsx` feature, i.e. `cargo install --features xlsx`:
+//! ```bash
+//! timesheet export --format xlsx -o timesheet.xlsx -a 2024-01-01
+//! ```
+//!
+//! ### Listing Time Codes
+//! List the time codes located by `time_code_jql` in the configuration file, which defaults
+//! to the unresolved issues of the Jira project named `TIME`:
 //!
 //! ```bash
 //! timesheet codes
+//! # Grouped by component
+//! timesheet codes --by-component
+//! ```
+//!
+//! ### Diagnosing Problems
+//! Check the configuration, Jira connection, local database and clock:
+//! ```bash
+//! timesheet doctor
 //! ```
 //!
 //! ## Time Format
@@ -60,11 +114,15 @@
 use chrono::Local;
 use clap::Parser;
 use cli::{Command, LogLevel, Opts};
-use commands::{configuration, status};
+use commands::{
+    cache, complete, configuration, doctor, duplicates, export, fav, gaps, interactive, issue,
+    status,
+};
 use env_logger::Env;
 use log::debug;
 use std::env;
 use std::fs::File;
+use std::io::{self, Read};
 use std::process::exit;
 
 use worklog::{
@@ -87,27 +145,45 @@ async fn main() -> Result<(), WorklogError> {
 
     #[allow(clippy::match_wildcard_for_single_variants)]
     match opts.cmd {
-        Command::Add(add_cmd) => {
-            let or: &worklog::OperationResult = &get_runtime()
-                .execute(Operation::Add(add_cmd.into()))
+        Command::Add(add_cmd) if add_cmd.batch.stdin => {
+            add_from_stdin(add_cmd.batch.atomic, add_cmd.force).await?;
+        }
+        Command::Add(mut add_cmd) => {
+            if add_cmd.interactive {
+                interactive::prompt_entry(&get_runtime(), &mut add_cmd)?;
+            }
+            if let Some(favorite) = add_cmd.favorite.take() {
+                let issue_key = get_runtime().issue_service().resolve_favorite(&favorite)?;
+                add_cmd.issues = vec![issue_key.to_string()];
+            }
+            if let Some(entries) = add_cmd.issue_duration_entries()? {
+                add_batch(
+                    operation::add::AddBatch::from_entries(entries, true, add_cmd.force),
+                    &add_cmd.tags,
+                )
                 .await?;
-            match or {
-                worklog::OperationResult::Added(items) => {
-                    for item in items {
-                        println!(
-                            "Added work log entry Id: {} Time spent: {} Time spent in seconds: {} Comment: {}",
-                            &item.id,
-                            &item.timeSpent,
-                            &item.timeSpentSeconds,
-                            &item.comment.as_deref().unwrap_or("")
-                        );
-                        println!(
-                            "To delete entry: timesheet del -i {} -w {}",
-                            &item.issue_key, &item.id
-                        );
+            } else {
+                let or: &worklog::OperationResult = &get_runtime()
+                    .execute(Operation::Add(add_cmd.into()))
+                    .await?;
+                match or {
+                    worklog::OperationResult::Added(items) => {
+                        for item in items {
+                            println!(
+                                "Added work log entry Id: {} Time spent: {} Time spent in seconds: {} Comment: {}",
+                                &item.id,
+                                &item.timeSpent,
+                                &item.timeSpentSeconds,
+                                &item.comment.as_deref().unwrap_or("")
+                            );
+                            println!(
+                                "To delete entry: timesheet del -i {} -w {}",
+                                &item.issue_key, &item.id
+                            );
+                        }
                     }
+                    _ => panic!("This should never happen!"),
                 }
-                _ => panic!("This should never happen!"),
             }
         }
 
@@ -128,10 +204,18 @@ async fn main() -> Result<(), WorklogError> {
         Command::Config(config) => {
             configuration::execute(config.cmd);
         } // end Config
-        Command::Codes => {
+        Command::Codes(codes_opts) => {
             let operation_result: &worklog::OperationResult =
                 &get_runtime().execute(Operation::Codes).await?;
             match operation_result {
+                worklog::OperationResult::IssueSummaries(issues) if codes_opts.by_component => {
+                    for (component, issues) in operation::codes::group_by_component(issues) {
+                        println!("{component}");
+                        for issue in issues {
+                            println!("  {} {}", issue.key, issue.fields.summary);
+                        }
+                    }
+                }
                 worklog::OperationResult::IssueSummaries(issues) => {
                     for issue in issues {
                         println!("{} {}", issue.key, issue.fields.summary);
@@ -145,49 +229,123 @@ async fn main() -> Result<(), WorklogError> {
                 .execute(Operation::Sync(sync_cmd.into()))
                 .await?;
             match operation_result {
-                OperationResult::Synchronised => {}
+                OperationResult::Synchronised(report) => {
+                    println!(
+                        "Synchronised: {} pushed, {} created, {} updated, {} deleted",
+                        report.pushed, report.created, report.updated, report.deleted
+                    );
+                }
                 _ => {
                     unimplemented!()
                 }
             }
         }
+        Command::Cache(cache_cmd) => {
+            cache::execute(&get_runtime(), cache_cmd.cmd)?;
+        }
+        Command::Doctor(doctor_opts) => {
+            doctor::execute(doctor_opts).await;
+        }
+        Command::Gaps(gaps_opts) => {
+            gaps::execute(gaps_opts).await?;
+        }
+        Command::Export(export_opts) => {
+            export::execute(&export_opts)?;
+        }
+        Command::Fav(fav_cmd) => {
+            fav::execute(&get_runtime(), fav_cmd.cmd).await?;
+        }
+        Command::Duplicates(duplicates_opts) => {
+            duplicates::execute(&get_runtime(), &duplicates_opts).await?;
+        }
+        Command::Issue(issue_cmd) => {
+            issue::execute(&get_runtime(), issue_cmd.cmd).await?;
+        }
+        Command::Complete(complete_cmd) => {
+            complete::execute(&get_runtime(), &complete_cmd.cmd)?;
+        }
         Command::Start(start_opts) => {
             // TODO: refactor this into a separate module `commands::start_timer`
+            if start_opts.issue.is_none() && !start_opts.resume {
+                suggest_recent_issues(&get_runtime())?;
+                exit(2);
+            }
             // Determine the start time
-            let start = match start_opts.start {
-                None => Local::now(),
-                Some(supplied_dt_string) => date::str_to_date_time(&supplied_dt_string)
+            let start = match (start_opts.start, start_opts.timezone) {
+                (None, _) => Local::now(),
+                (Some(supplied_dt_string), Some(time_zone)) => date::parse_time_zone(&time_zone)
+                    .and_then(|tz| date::str_to_date_time_in(&supplied_dt_string, &tz))
+                    .unwrap_or_else(|err| {
+                        eprintln!("{err}");
+                        exit(1);
+                    }),
+                (Some(supplied_dt_string), None) => date::str_to_date_time(&supplied_dt_string)
                     .unwrap_or_else(|err| {
                         eprintln!("Unable to parse date/time: {err}");
                         exit(1);
                     }),
             };
 
+            // A favorite may be given by its alias in place of the issue key
+            let issue = start_opts.issue.map(|issue| {
+                get_runtime()
+                    .issue_service()
+                    .resolve_favorite(&issue)
+                    .map_or(issue, |issue_key| issue_key.to_string())
+            });
+            let Some(issue) = issue else {
+                match get_runtime()
+                    .timer_service
+                    .resume_timer(start, start_opts.comment)
+                    .await
+                {
+                    Ok((timer, issue)) => println!(
+                        "Resumed work on issue {} - '{}' with timer id {:?} at {}",
+                        issue.issue_key,
+                        issue.summary,
+                        timer.id.as_ref().unwrap(),
+                        timer.started_at.format("%Y-%m-%d %H:%M")
+                    ),
+                    Err(e) => {
+                        eprintln!("Unable to resume work. Cause: {e}");
+                        exit(1);
+                    }
+                }
+                return Ok(());
+            };
+
+            match get_runtime()
+                .issue_service()
+                .ensure_issue(&IssueKey::new(&issue))
+                .await
+            {
+                Ok(issue_info) if !start_opts.force => {
+                    if let Some(warning) = issue_info.resolution_warning() {
+                        eprintln!("WARNING: {warning}");
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("Unable to start timer for issue {issue}. Cause: {e}");
+                    exit(1);
+                }
+            }
             match &get_runtime()
                 .timer_service
-                .start_timer(&start_opts.issue, start, start_opts.comment)
+                .start_timer(&issue, start, start_opts.comment)
                 .await
             {
                 Ok(timer) => {
-                    let issue_summary = &get_runtime()
-                        .issue_service
-                        .get_issues_filtered_by_keys(&[IssueKey::new(&timer.issue_key)])
-                        .ok()
-                        .and_then(|issues| issues.first().cloned())
-                        .unwrap();
                     println!(
                         "Started timer for issue {} - '{}' with id {:?} at {}",
-                        &start_opts.issue,
-                        &issue_summary.summary,
+                        &issue,
+                        timer.summary_snapshot.as_deref().unwrap_or_default(),
                         timer.id.as_ref().unwrap(),
                         timer.started_at.format("%Y-%m-%d %H:%M")
                     );
                 }
                 Err(e) => {
-                    println!(
-                        "Unable to start timer for issue {}. Cause: {e}",
-                        start_opts.issue
-                    );
+                    println!("Unable to start timer for issue {issue}. Cause: {e}");
                 }
             }
         }
@@ -205,6 +363,72 @@ async fn main() -> Result<(), WorklogError> {
     Ok(())
 }
 
+/// Adds the JSON array of entries read from stdin, printing the outcome of each entry
+async fn add_from_stdin(atomic: bool, force: bool) -> Result<(), WorklogError> {
+    let mut json = String::new();
+    io::stdin().read_to_string(&mut json)?;
+    let entries = operation::add::parse_entries(&json)?;
+
+    add_batch(
+        operation::add::AddBatch::from_entries(entries, atomic, force),
+        &[],
+    )
+    .await
+}
+
+/// Adds a batch of entries, printing the outcome of each entry as it is added, and tags the
+/// added ones
+async fn add_batch(
+    mut batch: operation::add::AddBatch,
+    tags: &[String],
+) -> Result<(), WorklogError> {
+    batch.progress = Some(Box::new(|index, result| match result {
+        Ok(item) => println!(
+            "Entry #{index}: added work log entry Id: {} to {} Time spent: {}",
+            &item.id, &item.issue_key, &item.timeSpent
+        ),
+        Err(err) => eprintln!("Entry #{index}: rejected, cause: {err}"),
+    }));
+    let runtime = get_runtime();
+    let operation_result = runtime.execute(Operation::AddBatch(batch)).await?;
+    let OperationResult::AddedBatch(report) = operation_result else {
+        panic!("This should never happen!");
+    };
+    if !tags.is_empty() {
+        for (_, item) in &report.added {
+            runtime.worklog_service().add_tags(&item.id, tags)?;
+        }
+    }
+    if !report.rejected.is_empty() {
+        exit(1);
+    }
+    Ok(())
+}
+
+/// Number of recently used issues suggested by `timesheet start` if no issue is given
+const RECENT_ISSUES_SUGGESTED: usize = 5;
+
+/// Lists the issues most recently worked on, to make it easy to resume work
+fn suggest_recent_issues(runtime: &ApplicationRuntime) -> Result<(), WorklogError> {
+    let recent_issues = runtime
+        .worklog_service()
+        .most_recent_issues(RECENT_ISSUES_SUGGESTED)?;
+    if recent_issues.is_empty() {
+        eprintln!("No issue given and no recent work found, use 'timesheet start -i <issue>'");
+        return Ok(());
+    }
+    eprintln!("No issue given, the most recently worked on issues are:");
+    for (issue, started) in recent_issues {
+        eprintln!(
+            "  timesheet start -i {:<12} # {} (last started {})",
+            issue.issue_key,
+            issue.summary,
+            started.format("%Y-%m-%d %H:%M")
+        );
+    }
+    Ok(())
+}
+
 /// Retrieves the application configuration file
 fn get_runtime() -> ApplicationRuntime {
     match ApplicationRuntime::new() {
@@ -255,9 +479,12 @@ impl From<cli::Add> for operation::add::Add {
     fn from(val: cli::Add) -> Self {
         operation::add::Add {
             durations: val.durations,
-            issue_key: val.issue,
+            issue_key: val.issues.into_iter().next().unwrap_or_default(),
             started: val.started,
             comment: val.comment,
+            timezone: val.timezone,
+            tags: val.tags,
+            force: val.force,
         }
     }
 }
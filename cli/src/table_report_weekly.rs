@@ -1,4 +1,4 @@
-use chrono::{DateTime, Datelike, Days, Duration, Local, NaiveDate};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Weekday};
 use log::debug;
 
 use jira::models::core::IssueKey;
@@ -6,11 +6,18 @@ use std::cmp;
 use std::collections::BTreeMap;
 use std::fmt::Write;
 use worklog::{
-    date::{self, seconds_to_hour_and_min},
+    config::DisplayTimeZone,
+    date::{format_seconds, seconds_to_hour_and_min, DurationStyle},
     types::LocalWorklog,
 };
 
-pub fn table_report_weekly(worklog_entries: &[LocalWorklog]) {
+/// Prints the weekly report, with the work logs bucketed into days of the supplied time zone.
+/// With `decimal`, the weekly totals are repeated in decimal hours in a column of their own.
+pub fn table_report_weekly(
+    worklog_entries: &[LocalWorklog],
+    display_tz: DisplayTimeZone,
+    decimal: bool,
+) {
     if worklog_entries.is_empty() {
         eprintln!("No worklog entries to create report from!");
         return;
@@ -23,14 +30,16 @@ pub fn table_report_weekly(worklog_entries: &[LocalWorklog]) {
         daily_totals_by_issue
             .entry(&entry.issue_key)
             .or_default()
-            .entry(entry.started.date_naive())
+            .entry(entry.started_in(display_tz).date_naive())
             .and_modify(|sum| *sum += entry.timeSpentSeconds)
             .or_insert(entry.timeSpentSeconds);
     }
 
-    if let Some((min_date, max_date)) = find_min_max_started(worklog_entries) {
-        let mut current_monday = date::first_date_in_week_for(min_date);
-        let last_date = date::last_date_in_week_for(max_date);
+    if let Some((min_started, max_started)) = find_min_max_started(worklog_entries) {
+        let min_date = display_tz.convert(&min_started).date_naive();
+        let max_date = display_tz.convert(&max_started).date_naive();
+        let mut current_monday = min_date.week(Weekday::Mon).first_day();
+        let last_date = max_date.week(Weekday::Mon).last_day();
 
         let mut grand_total = 0;
         while current_monday <= last_date {
@@ -43,16 +52,12 @@ pub fn table_report_weekly(worklog_entries: &[LocalWorklog]) {
             );
             println!("{week_label}");
 
-            print_weekly_table_header();
+            print_weekly_table_header(decimal);
             // Holds the total for each column (day) to be printed at the bottom of each week
             let mut daily_total_per_week = BTreeMap::<NaiveDate, i32>::new();
 
             for (key, daily_total_per_key) in &daily_totals_by_issue {
-                if !has_data_for_week(
-                    daily_total_per_key,
-                    current_monday.date_naive(),
-                    current_sunday.date_naive(),
-                ) {
+                if !has_data_for_week(daily_total_per_key, current_monday, current_sunday) {
                     continue;
                 }
 
@@ -61,8 +66,9 @@ pub fn table_report_weekly(worklog_entries: &[LocalWorklog]) {
                 print!("{:15}", key.to_string());
                 let daily_totals_for_this_key = print_and_accumulate_daily_totals(
                     daily_total_per_key,
-                    current_monday.date_naive(), // Start of current week
-                    current_sunday.date_naive(), // End of current week
+                    current_monday, // Start of current week
+                    current_sunday, // End of current week
+                    decimal,
                 );
 
                 // Add the daily totals for the current key into the current week
@@ -75,17 +81,22 @@ pub fn table_report_weekly(worklog_entries: &[LocalWorklog]) {
             }
 
             // All keys for this week have been printed, now show the weekly total
-            print_single_dashed_line();
-            let week_total =
-                print_week_total(&current_monday, current_sunday, &mut daily_total_per_week);
+            print_single_dashed_line(decimal);
+            let week_total = print_week_total(
+                current_monday,
+                current_sunday,
+                &mut daily_total_per_week,
+                decimal,
+            );
             grand_total += week_total;
-            current_monday += Duration::weeks(1);
+            current_monday = current_monday + Days::new(7);
         }
         println!(
-            "Grand total for period from {} to {}: {} ",
+            "Grand total for period from {} to {}: {} ({})",
             min_date.format("%Y-%m-%d"),
             max_date.format("%Y-%m-%d"),
-            seconds_to_hour_and_min(grand_total)
+            format_seconds(i64::from(grand_total), DurationStyle::HoursAndMinutes),
+            format_seconds(i64::from(grand_total), DurationStyle::Decimal)
         );
     }
     debug!("Table report done");
@@ -114,6 +125,7 @@ fn print_and_accumulate_daily_totals(
     daily_total_per_key: &BTreeMap<NaiveDate, i32>,
     start_date: NaiveDate,
     end_date: NaiveDate,
+    decimal: bool,
 ) -> BTreeMap<NaiveDate, i32> {
     let mut outputs = String::new();
     let mut current_date = Some(start_date);
@@ -146,27 +158,27 @@ fn print_and_accumulate_daily_totals(
     }
 
     println!(
-        "{} {:5}",
+        "{} {:5}{}",
         outputs,
-        seconds_to_hour_and_min(time_code_weekly_total)
+        seconds_to_hour_and_min(time_code_weekly_total),
+        decimal_column(time_code_weekly_total, decimal)
     );
 
     daily_total_current_week
 }
 
 fn print_week_total(
-    current_monday: &DateTime<Local>,
-    sunday: DateTime<Local>,
+    current_monday: NaiveDate,
+    sunday: NaiveDate,
     total_per_week_day: &mut BTreeMap<NaiveDate, i32>,
+    decimal: bool,
 ) -> i32 {
     print!("{:15}", "Week total");
-    let mut current_date = *current_monday;
+    let mut current_date = current_monday;
     let mut week_total = 0;
 
     while current_date <= sunday {
-        let seconds = total_per_week_day
-            .get(&current_date.date_naive())
-            .unwrap_or(&0);
+        let seconds = total_per_week_day.get(&current_date).unwrap_or(&0);
         week_total += *seconds;
         let output = if *seconds > 0 {
             seconds_to_hour_and_min(*seconds)
@@ -174,12 +186,12 @@ fn print_week_total(
             "-".to_string()
         };
         print!(" {output:^5}");
-        current_date += Duration::days(1); // Move to the next day
+        current_date = current_date + Days::new(1); // Move to the next day
     }
     print!(" {:^5}", seconds_to_hour_and_min(week_total));
-    println!();
+    println!("{}", decimal_column(week_total, decimal));
 
-    print_double_dashed_line();
+    print_double_dashed_line(decimal);
 
     println!();
 
@@ -205,25 +217,63 @@ fn find_min_max_started(worklogs: &[LocalWorklog]) -> Option<(DateTime<Local>, D
     Some(min_max)
 }
 
-fn print_weekly_table_header() {
+/// Width of the column holding the weekly totals in decimal hours
+const DECIMAL_COLUMN_WIDTH: usize = 7;
+
+/// The weekly total in decimal hours, if requested, including the leading separator
+fn decimal_column(seconds: i32, decimal: bool) -> String {
+    if decimal {
+        format!(
+            " {:>DECIMAL_COLUMN_WIDTH$}",
+            format_seconds(i64::from(seconds), DurationStyle::Decimal)
+        )
+    } else {
+        String::new()
+    }
+}
+
+fn print_weekly_table_header(decimal: bool) {
     println!(
-        "{:15} {:>5} {:>5} {:>5} {:>5} {:>5} {:>5} {:>5} {:>5}",
-        "Time code", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun", "Total"
+        "{:15} {:>5} {:>5} {:>5} {:>5} {:>5} {:>5} {:>5} {:>5}{}",
+        "Time code",
+        "Mon",
+        "Tue",
+        "Wed",
+        "Thu",
+        "Fri",
+        "Sat",
+        "Sun",
+        "Total",
+        if decimal {
+            format!(" {:>DECIMAL_COLUMN_WIDTH$}", "Hours")
+        } else {
+            String::new()
+        }
     );
 
-    print_single_dashed_line();
+    print_single_dashed_line(decimal);
 }
 
-fn print_single_dashed_line() {
+fn print_single_dashed_line(decimal: bool) {
     println!(
-        "{:-<15} ----- ----- ----- ----- ----- ----- ----- -----",
-        ""
+        "{:-<15} ----- ----- ----- ----- ----- ----- ----- -----{}",
+        "",
+        if decimal {
+            format!(" {:-<DECIMAL_COLUMN_WIDTH$}", "")
+        } else {
+            String::new()
+        }
     );
 }
-fn print_double_dashed_line() {
+fn print_double_dashed_line(decimal: bool) {
     println!(
-        "{:=<15} ===== ===== ===== ===== ===== ===== ===== =====",
-        ""
+        "{:=<15} ===== ===== ===== ===== ===== ===== ===== ====={}",
+        "",
+        if decimal {
+            format!(" {:=<DECIMAL_COLUMN_WIDTH$}", "")
+        } else {
+            String::new()
+        }
     );
 }
 
@@ -233,48 +283,36 @@ mod tests {
     use chrono::{Days, Local};
     use jira::models::core::IssueKey;
     use std::ops::Sub;
+    use worklog::config::DisplayTimeZone;
     use worklog::types::LocalWorklog;
 
     #[test]
     fn test_find_min_max_started() {
         let now = Local::now();
         let worklogs = vec![
-            LocalWorklog {
-                issue_key: IssueKey::from("ISSUE-1"),
-                id: "1".to_string(),
-                author: "user1".to_string(),
-                created: now,
-                updated: now,
-                started: now - chrono::Duration::days(2),
-                timeSpent: "1h".to_string(),
-                timeSpentSeconds: 3600,
-                issueId: 101,
-                comment: Some("Worklog 1".to_string()),
-            },
-            LocalWorklog {
-                issue_key: IssueKey::from("ISSUE-2"),
-                id: "2".to_string(),
-                author: "user2".to_string(),
-                created: now,
-                updated: now,
-                started: now - chrono::Duration::days(1),
-                timeSpent: "2h".to_string(),
-                timeSpentSeconds: 7200,
-                issueId: 102,
-                comment: Some("Worklog 2".to_string()),
-            },
-            LocalWorklog {
-                issue_key: IssueKey::from("ISSUE-3"),
-                id: "3".to_string(),
-                author: "user3".to_string(),
-                created: now,
-                updated: now,
-                started: now,
-                timeSpent: "30m".to_string(),
-                timeSpentSeconds: 1800,
-                issueId: 103,
-                comment: None,
-            },
+            LocalWorklog::builder(IssueKey::from("ISSUE-1"), "1", 3600)
+                .author("user1")
+                .created(now)
+                .updated(now)
+                .started(now - chrono::Duration::days(2))
+                .issue_id(101)
+                .comment("Worklog 1")
+                .build(),
+            LocalWorklog::builder(IssueKey::from("ISSUE-2"), "2", 7200)
+                .author("user2")
+                .created(now)
+                .updated(now)
+                .started(now - chrono::Duration::days(1))
+                .issue_id(102)
+                .comment("Worklog 2")
+                .build(),
+            LocalWorklog::builder(IssueKey::from("ISSUE-3"), "3", 1800)
+                .author("user3")
+                .created(now)
+                .updated(now)
+                .started(now)
+                .issue_id(103)
+                .build(),
         ];
 
         let early = now.sub(Days::new(2));
@@ -289,6 +327,6 @@ mod tests {
 
     #[test]
     fn test_table_report_weekly() {
-        table_report_weekly(&[]);
+        table_report_weekly(&[], DisplayTimeZone::Local, false);
     }
 }
@@ -37,7 +37,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .map(|i| i.key.clone())
         .collect::<Vec<IssueKey>>();
 
-    jira.chunked_work_logs(&keys.iter().take(2).cloned().collect(), start_after)
+    jira.chunked_work_logs(&keys.iter().take(2).cloned().collect(), start_after, None)
         .await?;
     println!(
         "Finished fetching worklogs for 2 issues in {:.2?}ms",
@@ -51,7 +51,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     let start_fetch_all = Instant::now();
 
-    let final_result = jira.chunked_work_logs(&keys, start_after).await?;
+    let final_result = jira.chunked_work_logs(&keys, start_after, None).await?;
     println!(
         "Finished fetching all the worklogs in {:.2?}",
         start_fetch_all.elapsed().as_millis()
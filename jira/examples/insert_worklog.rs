@@ -1,5 +1,6 @@
 use std::env;
 
+use jira::models::core::IssueKey;
 use jira::{Credentials, Jira};
 
 #[tokio::main]
@@ -15,7 +16,13 @@ async fn main() {
 
         println!("Executing ...");
         let _r = jira_client
-            .insert_worklog("TIME-94", dt, 27000, "Rubbish comment")
+            .insert_worklog(
+                &IssueKey::from("TIME-94"),
+                dt,
+                27000,
+                "Rubbish comment",
+                true,
+            )
             .await;
     } else {
         panic!("Missing env var JIRA_HOST, JIRA_USER or JIRA_TOKEN")
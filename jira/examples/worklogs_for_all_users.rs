@@ -57,7 +57,7 @@ async fn fetch_worklogs_for_issues2(
         let jira_client = jira.clone(); // Clone only once per async block
         async move {
             jira_client
-                .get_work_logs_for_issue(&issue_summary.key, start_after)
+                .get_work_logs_for_issue(&issue_summary.key, start_after, None)
                 .await
         }
     });
@@ -25,10 +25,13 @@
 //! ).expect("Failed to create Jira client");
 //
 //
-use crate::{Credentials, Jira};
+use crate::models::user::UserIdentity;
+use crate::{Credentials, Jira, OAuthToken, RefreshFn};
 use log::debug;
+use reqwest::redirect::{Attempt, Policy};
 use reqwest::Client;
 use std::env;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use thiserror::Error;
 use url::Url;
@@ -64,12 +67,55 @@ impl JiraEnvVars {
 
 pub const DEFAULT_API_VERSION: &str = "latest";
 
+/// The `User-Agent` sent with every request, unless overridden with [`JiraBuilder::user_agent`]
+pub const DEFAULT_USER_AGENT: &str = concat!("timesheet/", env!("CARGO_PKG_VERSION"));
+
+/// The request timeout, unless overridden with [`JiraBuilder::timeout`]
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The number of retries after http 429, unless overridden with [`JiraBuilder::max_retries`]
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// The number of redirects followed, like the default policy of reqwest
+const MAX_REDIRECTS: usize = 10;
+
+/// Follows redirects within the same host and port, to which the credentials are sent
+/// along. Redirects elsewhere, like to the canonical host of the Jira instance, are
+/// refused with [`JiraError::CrossHostRedirect`](crate::JiraError::CrossHostRedirect),
+/// rather than silently dropping the credentials and failing with http 401.
+fn redirect_policy() -> Policy {
+    Policy::custom(|attempt: Attempt| {
+        let from = &attempt.previous()[0];
+        if attempt.url().host_str() != from.host_str()
+            || attempt.url().port_or_known_default() != from.port_or_known_default()
+        {
+            let error = crate::JiraError::CrossHostRedirect {
+                from: from.to_string(),
+                to: attempt.url().to_string(),
+            };
+            attempt.error(error)
+        } else if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error(format!("more than {MAX_REDIRECTS} redirects"))
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
 /// Builder for creating Jira client instances with flexible configuration options
 pub struct JiraBuilder {
     host: Option<String>,
     api_version: Option<String>,
     credentials: Option<Credentials>,
     timeout: Option<Duration>,
+    http2_prior_knowledge: bool,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    user_agent: Option<String>,
+    time_tracking_precheck: bool,
+    validate_search_fields: bool,
+    user_identity: Option<UserIdentity>,
+    max_retries: u32,
 }
 
 impl Default for JiraBuilder {
@@ -86,7 +132,15 @@ impl JiraBuilder {
             host: None,
             api_version: None,
             credentials: None,
-            timeout: None,
+            timeout: Some(DEFAULT_TIMEOUT),
+            http2_prior_knowledge: false,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            user_agent: None,
+            time_tracking_precheck: true,
+            validate_search_fields: false,
+            user_identity: None,
+            max_retries: DEFAULT_MAX_RETRIES,
         }
     }
 
@@ -103,7 +157,8 @@ impl JiraBuilder {
         self
     }
 
-    /// Sets the API version (default is "3")
+    /// Sets the version of the REST API, like `2` for Jira Server instances which do not
+    /// expose `latest`. Defaults to [`DEFAULT_API_VERSION`]
     #[must_use]
     pub fn api_version(mut self, version: impl Into<String>) -> Self {
         self.api_version = Some(version.into());
@@ -124,18 +179,97 @@ impl JiraBuilder {
         self
     }
 
+    /// Sets OAuth 2.0 (3LO) authentication, refreshing the access token with `refresh_fn`
+    /// when it has expired or is rejected
+    #[must_use]
+    pub fn oauth(mut self, token: OAuthToken, refresh_fn: RefreshFn) -> Self {
+        self.credentials = Some(Credentials::oauth(token, refresh_fn));
+        self
+    }
+
     /// Sets a request timeout
     #[must_use]
     pub fn timeout_seconds(self, seconds: u64) -> Self {
         self.timeout(seconds)
     }
 
+    /// Sets the timeout of each request, which defaults to [`DEFAULT_TIMEOUT`]. A request
+    /// which times out fails with `JiraError::Timeout`
     #[must_use]
     pub fn timeout(mut self, seconds: u64) -> Self {
         self.timeout = Some(Duration::from_secs(seconds));
         self
     }
 
+    /// Speaks HTTP/2 without negotiating it first.
+    ///
+    /// By default HTTP/2 is used whenever the server offers it during the TLS (ALPN)
+    /// handshake, falling back to HTTP/1.1 otherwise. Only enable this for instances
+    /// known to speak h2c (HTTP/2 over plain text), as requests to anything else will fail.
+    #[must_use]
+    pub fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Sets how long idle connections are kept alive in the pool before being closed
+    #[must_use]
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept alive per host
+    #[must_use]
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Sets the `User-Agent` identifying this client in the logs of Jira,
+    /// the default is [`DEFAULT_USER_AGENT`]
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Verifies time tracking is available for the project, before a work log entry is added.
+    /// The outcome is cached by project key. Enabled by default, disable it to save the extra
+    /// round trips when time tracking is known to be available.
+    #[must_use]
+    pub fn time_tracking_precheck(mut self, enabled: bool) -> Self {
+        self.time_tracking_precheck = enabled;
+        self
+    }
+
+    /// Warns about the fields requested by a JQL search which are unknown to Jira, like a
+    /// misspelled custom field in the configuration, which Jira silently leaves out of the
+    /// issues found. Disabled by default, as it costs an extra call to retrieve the fields,
+    /// which are cached afterwards.
+    #[must_use]
+    pub fn validate_search_fields(mut self, enabled: bool) -> Self {
+        self.validate_search_fields = enabled;
+        self
+    }
+
+    /// Identifies users by the supplied identity, rather than detecting it from the
+    /// deployment type reported by the server. See [`Jira::user_identity`]
+    #[must_use]
+    pub fn user_identity(mut self, user_identity: UserIdentity) -> Self {
+        self.user_identity = Some(user_identity);
+        self
+    }
+
+    /// The number of times a request is retried when Jira responds with http 429 Too Many
+    /// Requests. Waits as long as given by the `Retry-After` header, otherwise the wait is
+    /// doubled for each retry. Zero disables retries.
+    #[must_use]
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
     /// Attempts to load configuration from environment variables
     #[must_use]
     pub fn from_env(self) -> Self {
@@ -191,11 +325,25 @@ impl JiraBuilder {
         let host_url = Url::parse(&host).map_err(JiraBuilderError::UrlParseError)?;
 
         // Create the HTTP client with a proper configuration
-        let mut client_builder = Client::builder();
+        let mut client_builder = Client::builder()
+            .user_agent(
+                self.user_agent
+                    .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            )
+            .redirect(redirect_policy());
 
         if let Some(timeout) = self.timeout {
             client_builder = client_builder.timeout(timeout);
         }
+        if self.http2_prior_knowledge {
+            client_builder = client_builder.http2_prior_knowledge();
+        }
+        if let Some(idle_timeout) = self.pool_idle_timeout {
+            client_builder = client_builder.pool_idle_timeout(idle_timeout);
+        }
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            client_builder = client_builder.pool_max_idle_per_host(max_idle);
+        }
 
         let client = client_builder
             .build()
@@ -207,6 +355,15 @@ impl JiraBuilder {
             api: format!("rest/api/{api_version}"),
             credentials,
             client,
+            global_settings: Arc::default(),
+            time_tracking_by_project: Arc::default(),
+            time_tracking_precheck: self.time_tracking_precheck,
+            validate_search_fields: self.validate_search_fields,
+            fields: Arc::default(),
+            user_identity: Arc::new(Mutex::new(self.user_identity)),
+            request_timeout: None,
+            stats: Arc::default(),
+            max_retries: self.max_retries,
         };
         debug!("Created Jira client: {jira:#?}");
 
@@ -243,3 +400,82 @@ impl JiraBuilder {
         Self::new().from_env().build()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn builder_applies_pool_settings() -> Result<(), Box<dyn std::error::Error>> {
+        let mut server = Server::new_async().await;
+        let _myself = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://norn.atlassian.net/rest/api/3/user?accountId=abc",
+                "accountId": "abc", "displayName": "Ola", "emailAddress": "ola@dunk.com", "timeZone": "Europe/Oslo"}"#,
+            )
+            .create_async()
+            .await;
+
+        let builder = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .pool_idle_timeout(Duration::from_secs(30))
+            .pool_max_idle_per_host(2);
+        assert_eq!(builder.pool_idle_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(builder.pool_max_idle_per_host, Some(2));
+        assert!(!builder.http2_prior_knowledge);
+
+        let jira = builder.build()?;
+        let user = jira.get_current_user().await?;
+        assert_eq!(user.account_id, "abc");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn requests_carry_the_user_agent() -> Result<(), Box<dyn std::error::Error>> {
+        let mut server = Server::new_async().await;
+        let default_agent = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/serverInfo").as_str(),
+            )
+            .match_header("user-agent", DEFAULT_USER_AGENT)
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let custom_agent = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .match_header("user-agent", "acme-timesheet/1.0")
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let jira = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .build()?;
+        jira.get::<serde_json::Value>("/serverInfo").await?;
+        default_agent.assert_async().await;
+
+        let jira = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .user_agent("acme-timesheet/1.0")
+            .build()?;
+        jira.get::<serde_json::Value>("/myself").await?;
+        custom_agent.assert_async().await;
+        assert!(DEFAULT_USER_AGENT.starts_with("timesheet/"));
+        Ok(())
+    }
+}
@@ -5,56 +5,131 @@
 //! Many of the types have been declared specifically for the purpose of work log management,
 //! and are hence not generic.
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
-    fmt::{self, Formatter},
+    fmt::{self, Formatter, Write as _},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
 };
 
-use chrono::{DateTime, Days, Local, NaiveDateTime, TimeZone};
-use futures::{stream, StreamExt};
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use futures::{stream, StreamExt, TryStreamExt};
 use log::{debug, warn};
 use models::{
     project::{JiraProjectsPage, Project},
-    user::User,
-    worklog::{Insert, Worklog, WorklogsPage},
+    user::{User, UserIdentity},
+    worklog::{
+        DeleteReport, Insert, PropertyKeys, UpdatedWorklogsPage, Worklog, WorklogIds,
+        WorklogProperty, WorklogsPage,
+    },
 };
 use reqwest::{
-    header::{ACCEPT, CONTENT_TYPE},
-    Client, Method, RequestBuilder, StatusCode,
+    header::{HeaderMap, ACCEPT, CONTENT_TYPE, DATE, RETRY_AFTER},
+    Client, Method, RequestBuilder, Response, StatusCode,
 };
 
 pub use crate::builder::{JiraBuilder, JiraBuilderError};
 use crate::models::core::IssueKey;
+use crate::models::field::FieldDefinition;
 use crate::models::issue::{
-    ComponentId, IssueSummary, IssueType, IssuesResponse, NewIssue, NewIssueFields,
-    NewIssueResponse,
+    CommentResponse, ComponentId, IssueSummary, IssueType, IssuesResponse, JqlSearchRequest,
+    JqlSearchResult, NewIssue, NewIssueFields, NewIssueResponse,
 };
-use crate::models::project::{Component, JiraProjectKey};
-use crate::models::setting::{GlobalSettings, TimeTrackingConfiguration};
+use crate::models::jql::Jql;
+use crate::models::project::{Component, ComponentsResponse, JiraProjectKey};
+use crate::models::setting::{GlobalSettings, ServerInfo, TimeTrackingConfiguration};
+pub use crate::oauth::{OAuthToken, RefreshFn};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use url::{ParseError, Url};
 
 pub mod models;
 
 pub mod builder;
+pub mod oauth;
 
 type Result<T> = std::result::Result<T, JiraError>;
 
 const MAX_RESULTS: i32 = 100; // Value of Jira `maxResults` variable when fetching data
+const MAX_WORKLOG_IDS: usize = 1000; // Maximum number of ids accepted by `/worklog/list`
+const MAX_GET_URL_LENGTH: usize = 2048; // Longer JQL searches are sent with POST to avoid http 414
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1); // Doubled for each retry after http 429
+const MAX_BACKOFF: Duration = Duration::from_secs(60); // Caps the backoff, including `Retry-After`
 
-#[derive(Serialize, Deserialize, Debug)]
+/// The fields to request when searching for issues to deserialize into an `IssueSummary`
+pub const ISSUE_SUMMARY_FIELDS: [&str; 8] = [
+    "id",
+    "key",
+    "summary",
+    "components",
+    "parent",
+    "status",
+    "resolution",
+    "assignee",
+];
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct Errors {
     #[serde(rename = "errorMessages")]
     pub error_messages: Vec<String>,
+    /// Messages by field name. Some endpoints supply an array of `{errorMessage}` objects rather
+    /// than a map, in which case the messages are keyed by their `field`, or their position.
+    #[serde(default, deserialize_with = "deserialize_errors")]
     pub errors: Option<BTreeMap<String, String>>,
 }
 
+/// The shapes of the `errors` of an error body
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorsShape {
+    Map(BTreeMap<String, String>),
+    List(Vec<ErrorEntry>),
+}
+
+#[derive(Deserialize)]
+struct ErrorEntry {
+    #[serde(rename = "errorMessage")]
+    error_message: String,
+    field: Option<String>,
+}
+
+fn deserialize_errors<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<BTreeMap<String, String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        Option::<ErrorsShape>::deserialize(deserializer)?.map(|shape| match shape {
+            ErrorsShape::Map(errors) => errors,
+            ErrorsShape::List(entries) => entries
+                .into_iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    (
+                        entry.field.unwrap_or_else(|| index.to_string()),
+                        entry.error_message,
+                    )
+                })
+                .collect(),
+        }),
+    )
+}
+
 #[derive(Debug)]
 pub enum JiraError {
     Unauthorized,
+    /// Authenticated, but not permitted to access the resource
+    Forbidden {
+        message: String,
+    },
     MethodNotAllowed,
     NotFound(String),
-    Fault { code: StatusCode, errors: Errors },
+    Fault {
+        code: StatusCode,
+        errors: Errors,
+        /// Identifies the request when contacting Atlassian support
+        request_id: Option<String>,
+    },
     RequiredParameter(String),
     DeleteFailed(StatusCode),
     WorklogNotFound(String, String),
@@ -65,6 +140,54 @@ pub enum JiraError {
     UriTooLong(String),
     BuilderError(JiraBuilderError),
     WorklogDurationTooShort(i32),
+    TokenRefresh(String),
+    /// Work can not be logged, as time tracking is disabled for the project
+    TimeTrackingDisabled(String),
+    /// Work can not be logged, as the issue is in a state which may not be edited, like done
+    IssueNotEditable {
+        key: IssueKey,
+    },
+    /// The response lacks a `Date` header, or the value could not be parsed
+    InvalidDateHeader(String),
+    /// The response body of `endpoint` could not be deserialized into `type_name`
+    Deserialize {
+        endpoint: String,
+        type_name: &'static str,
+        source: serde_json::Error,
+    },
+    /// No response was received from Jira
+    Unreachable(UnreachableCause, reqwest::Error),
+    /// Jira did not respond within the timeout of the client
+    Timeout(reqwest::Error),
+    /// Jira kept responding with http 429 Too Many Requests, after the retries were exhausted
+    RateLimited {
+        /// How long Jira asked to wait before the next request, if supplied
+        retry_after: Option<Duration>,
+    },
+    /// Jira redirected the request to another host, or port, which the credentials are not
+    /// sent to. The configured URL of Jira should be the one redirected to.
+    CrossHostRedirect {
+        from: String,
+        to: String,
+    },
+}
+
+/// Why no response was received from Jira
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnreachableCause {
+    /// The host name could not be resolved
+    Dns,
+    /// The connection was refused or could not be established
+    Connect,
+}
+
+impl fmt::Display for UnreachableCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UnreachableCause::Dns => write!(f, "the host name could not be resolved"),
+            UnreachableCause::Connect => write!(f, "the connection could not be established"),
+        }
+    }
 }
 
 impl From<JiraBuilderError> for JiraError {
@@ -97,16 +220,71 @@ impl fmt::Display for JiraError {
             Fault {
                 ref code,
                 ref errors,
-            } => writeln!(f, "Jira Client Error ({code}):\n{errors:#?}"),
-            Unauthorized => todo!(),
-            MethodNotAllowed => todo!(),
+                ref request_id,
+            } => {
+                writeln!(f, "Jira Client Error ({code}):\n{errors:#?}")?;
+                if let Some(request_id) = request_id {
+                    writeln!(
+                        f,
+                        "When contacting Atlassian support, reference request id {request_id}"
+                    )?;
+                }
+                Ok(())
+            }
+            Unauthorized => writeln!(
+                f,
+                "Jira rejected the credentials (401), verify the user and API token of the configuration"
+            ),
+            Forbidden { message } => writeln!(
+                f,
+                "Jira denied access, you lack the permission required: {message}"
+            ),
+            MethodNotAllowed => write!(
+                f,
+                "Jira does not allow this method for the resource (405), verify the URL of the Jira instance"
+            ),
             NotFound(url) => writeln!(f, "Not found: '{url}'"),
-            UnexpectedStatus => todo!(),
+            UnexpectedStatus => write!(f, "Jira responded with an unexpected HTTP status"),
             UriTooLong(uri) => write!(f, "URI too long: {uri} "),
             BuilderError(e) => write!(f, "JiraBuilderError: {e}"),
             WorklogDurationTooShort(d) => {
                 write!(f, "Worklog duration too short: {d} seconds")
             }
+            TokenRefresh(reason) => write!(f, "Unable to refresh the OAuth access token: {reason}"),
+            TimeTrackingDisabled(project_key) => write!(
+                f,
+                "Time tracking is not available for project {project_key}, work can not be logged"
+            ),
+            IssueNotEditable { key } => write!(
+                f,
+                "Issue {key} is not editable, probably as it is done, hence work can not be logged. \
+                 Reopen the issue, or ask for permission to log work on done issues"
+            ),
+            InvalidDateHeader(value) => {
+                write!(f, "Jira did not supply a valid Date header: '{value}'")
+            }
+            Deserialize {
+                endpoint,
+                type_name,
+                source,
+            } => write!(
+                f,
+                "Unable to deserialize the response of {endpoint} into {type_name}: {source}"
+            ),
+            Unreachable(cause, e) => write!(f, "Unable to reach Jira, {cause}: {e}"),
+            Timeout(e) => write!(f, "Jira did not respond within the timeout: {e}"),
+            RateLimited { retry_after } => {
+                write!(f, "Jira is rate limiting the requests")?;
+                match retry_after {
+                    Some(retry_after) => write!(f, ", retry in {} seconds", retry_after.as_secs()),
+                    None => write!(f, ", retry later"),
+                }
+            }
+            CrossHostRedirect { from, to } => write!(
+                f,
+                "Jira redirected {from} to another host, {to}, verify the URL of the Jira instance, which probably should be {}",
+                Url::parse(to).map_or_else(|_| to.clone(), |to| to.origin().ascii_serialization())
+            ),
         }
     }
 }
@@ -115,8 +293,14 @@ impl Error for JiraError {
     // Ref: https://stackoverflow.com/questions/62869360/should-an-error-with-a-source-include-that-source-in-the-display-output
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            JiraError::RequiredParameter(_) => None,
-            _ => self.source(),
+            JiraError::Deserialize { source, .. } => Some(source),
+            JiraError::RequestError(e) | JiraError::Unreachable(_, e) | JiraError::Timeout(e) => {
+                Some(e)
+            }
+            JiraError::SerializationError(e) => Some(e),
+            JiraError::ParseError(e) => Some(e),
+            JiraError::BuilderError(e) => Some(e),
+            _ => None,
         }
     }
 }
@@ -129,7 +313,21 @@ impl From<ParseError> for JiraError {
 
 impl From<reqwest::Error> for JiraError {
     fn from(error: reqwest::Error) -> JiraError {
-        JiraError::RequestError(error)
+        // The redirect policy of the builder refuses redirects to other hosts
+        if let Some(JiraError::CrossHostRedirect { from, to }) = error
+            .source()
+            .and_then(|source| source.downcast_ref::<JiraError>())
+        {
+            return JiraError::CrossHostRedirect {
+                from: from.clone(),
+                to: to.clone(),
+            };
+        }
+        if error.is_timeout() {
+            JiraError::Timeout(error)
+        } else {
+            JiraError::RequestError(error)
+        }
     }
 }
 
@@ -139,14 +337,45 @@ impl From<serde_json::error::Error> for JiraError {
     }
 }
 
-#[derive(Clone, Debug)]
+/// The secrets are redacted from the `Debug` output, hence the credentials, and the `Jira`
+/// client holding them, may safely be logged
+#[derive(Clone)]
 pub enum Credentials {
     Anonymous,
     Basic(String, String),
     Bearer(String),
+    /// OAuth 2.0 (3LO) access token, which is refreshed using `refresh_fn` when it expires.
+    /// The token is shared by all clones of the `Jira` client.
+    OAuth {
+        token: Arc<Mutex<OAuthToken>>,
+        refresh_fn: RefreshFn,
+    },
+}
+
+/// Replaces the secrets of the credentials
+const REDACTED: &str = "****";
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::Anonymous => write!(f, "Anonymous"),
+            Credentials::Basic(user, _) => write!(f, "Basic({user:?}, {REDACTED})"),
+            Credentials::Bearer(_) => write!(f, "Bearer({REDACTED})"),
+            Credentials::OAuth { .. } => write!(f, "OAuth({REDACTED})"),
+        }
+    }
 }
 
 impl Credentials {
+    /// Creates OAuth 2.0 credentials from an access token and the function used to refresh it
+    #[must_use]
+    pub fn oauth(token: OAuthToken, refresh_fn: RefreshFn) -> Credentials {
+        Credentials::OAuth {
+            token: Arc::new(Mutex::new(token)),
+            refresh_fn,
+        }
+    }
+
     fn apply(&self, request: RequestBuilder) -> RequestBuilder {
         match self {
             Credentials::Anonymous => request,
@@ -154,8 +383,200 @@ impl Credentials {
                 request.basic_auth(user.to_owned(), Some(pass.to_owned()))
             }
             Credentials::Bearer(ref token) => request.bearer_auth(token.to_owned()),
+            Credentials::OAuth { token, .. } => {
+                let access_token = lock_token(token).access_token.clone();
+                request.bearer_auth(access_token)
+            }
+        }
+    }
+
+    /// Refreshes an OAuth access token which has expired, or is about to
+    async fn refresh_if_expired(&self) -> Result<()> {
+        if let Credentials::OAuth { token, .. } = self {
+            if lock_token(token).is_expired(Utc::now()) {
+                self.refresh().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Obtains a new OAuth access token, returning `false` for credentials which cannot be refreshed
+    async fn refresh(&self) -> Result<bool> {
+        let Credentials::OAuth { token, refresh_fn } = self else {
+            return Ok(false);
+        };
+        let refresh_token = lock_token(token).refresh_token.clone();
+        debug!("Refreshing the OAuth access token");
+        let refreshed = refresh_fn
+            .call(refresh_token)
+            .await
+            .map_err(|e| JiraError::TokenRefresh(e.to_string()))?;
+        *lock_token(token) = refreshed;
+        Ok(true)
+    }
+}
+
+/// The token is never locked across an await point, hence a poisoned lock is still usable
+fn lock_token(token: &Mutex<OAuthToken>) -> MutexGuard<'_, OAuthToken> {
+    token.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The id Atlassian assigns to each request, which their support asks for
+fn request_id(headers: &HeaderMap) -> Option<String> {
+    ["X-ARequestId", "X-Trace"]
+        .into_iter()
+        .find_map(|name| headers.get(name)?.to_str().ok())
+        .map(ToString::to_string)
+}
+
+/// The rate limit reported by Jira, allowing callers to slow down before they are throttled.
+/// See [`Jira::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JiraStats {
+    /// Number of responses received from Jira
+    pub responses: u64,
+    /// The number of requests left before being throttled, as given by the
+    /// `X-RateLimit-Remaining` header of the most recent response supplying it
+    pub rate_limit_remaining: Option<u64>,
+    /// How long to wait before the next request, as given by the `Retry-After` header of
+    /// the most recent response. Only supplied when the request was throttled.
+    pub retry_after: Option<Duration>,
+}
+
+impl JiraStats {
+    fn update(&mut self, headers: &HeaderMap) {
+        let header = |name| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+        self.responses += 1;
+        if let Some(remaining) = header("X-RateLimit-Remaining") {
+            self.rate_limit_remaining = Some(remaining);
+        }
+        self.retry_after = header(RETRY_AFTER.as_str()).map(Duration::from_secs);
+    }
+}
+
+/// Maps a client error status of the response to the `JiraError` describing it, along with
+/// the id of the request, other responses are returned as is
+async fn error_for_status(url: &Url, response: Response) -> Result<Response> {
+    let status = response.status();
+    if !status.is_client_error() {
+        return Ok(response);
+    }
+    let request_id = request_id(response.headers());
+    let body = &response.text().await?;
+    debug!("status {status:?} body '{body:?}'");
+    Err(match status {
+        StatusCode::UNAUTHORIZED => JiraError::Unauthorized,
+        StatusCode::FORBIDDEN => JiraError::Forbidden {
+            message: forbidden_message(body),
+        },
+        StatusCode::METHOD_NOT_ALLOWED => JiraError::MethodNotAllowed,
+        StatusCode::NOT_FOUND => JiraError::NotFound(url.to_string()),
+        StatusCode::URI_TOO_LONG => JiraError::UriTooLong(url.to_string()),
+        _ => {
+            eprintln!("ERROR: http GET returned {status} for {url}, reason:{body}");
+            JiraError::Fault {
+                code: status,
+                errors: serde_json::from_str::<Errors>(body)?,
+                request_id,
+            }
         }
+    })
+}
+
+/// Classifies a failed connection as a DNS or connect failure, other errors, like timeouts,
+/// are converted as usual
+fn unreachable(err: reqwest::Error) -> JiraError {
+    if !err.is_connect() {
+        return JiraError::from(err);
+    }
+    // reqwest does not tell resolver failures apart, hence the causes are inspected
+    let mut source = err.source();
+    let mut is_dns = false;
+    while let Some(cause) = source {
+        let message = cause.to_string();
+        is_dns |= message.contains("dns error") || message.contains("failed to lookup");
+        source = cause.source();
+    }
+    let cause = if is_dns {
+        UnreachableCause::Dns
+    } else {
+        UnreachableCause::Connect
+    };
+    JiraError::Unreachable(cause, err)
+}
+
+/// Is this the http 400 returned when logging work on an issue which may not be edited?
+/// Jira only tells by the message, like "You can not edit the issue as it is in a
+/// non-editable workflow state."
+fn is_not_editable(error: &JiraError) -> bool {
+    let JiraError::Fault { code, errors, .. } = error else {
+        return false;
+    };
+    *code == StatusCode::BAD_REQUEST
+        && errors
+            .error_messages
+            .iter()
+            .chain(errors.errors.iter().flat_map(BTreeMap::values))
+            .map(|message| message.to_lowercase())
+            .any(|message| message.contains("not editable") || message.contains("non-editable"))
+}
+
+/// The reason given by Jira for denying access, falling back to the raw body if it holds no error messages
+fn forbidden_message(body: &str) -> String {
+    match serde_json::from_str::<Errors>(body) {
+        Ok(errors) if !errors.error_messages.is_empty() => errors.error_messages.join(", "),
+        _ => body.trim().to_string(),
+    }
+}
+
+/// The query string suppressing the notifications Jira sends to the watchers of an issue,
+/// empty if they should be notified
+fn notify_users_query(notify_users: bool) -> &'static str {
+    if notify_users {
+        ""
+    } else {
+        "?notifyUsers=false"
+    }
+}
+
+/// Formats the start of a work log the way Jira needs it.
+/// Note! The formatting in Jira is based on the time zone of the user. Remember to change it
+/// if you fly across the ocean :-)
+fn worklog_started(started: DateTime<Local>) -> String {
+    started.format("%Y-%m-%dT%H:%M:%S.%3f%z").to_string()
+}
+
+/// The body of a comment holding plain text, as Atlassian Document Format for version 3 of
+/// the REST API, which refuses plain text. Every line becomes a paragraph.
+fn comment_body(text: &str, document_format: bool) -> serde_json::Value {
+    if !document_format {
+        return serde_json::json!({ "body": text });
     }
+    let paragraphs: Vec<serde_json::Value> = text
+        .lines()
+        .map(|line| {
+            // Empty text nodes are not allowed, unlike empty paragraphs
+            let content: Vec<serde_json::Value> = if line.is_empty() {
+                vec![]
+            } else {
+                vec![serde_json::json!({ "type": "text", "text": line })]
+            };
+            serde_json::json!({ "type": "paragraph", "content": content })
+        })
+        .collect();
+    serde_json::json!({
+        "body": { "type": "doc", "version": 1, "content": paragraphs }
+    })
+}
+
+/// Midnight at the start of `date` in the local time zone, or midnight UTC if it does not exist
+/// locally, due to a daylight saving time transition
+fn start_of_local_day(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .map_or_else(|| midnight.and_utc(), |start| start.with_timezone(&Utc))
 }
 
 ///
@@ -184,6 +605,24 @@ pub struct Jira {
     api: String,
     credentials: Credentials,
     pub client: Client,
+    /// The global settings rarely change, hence they are retrieved once and shared by all clones
+    global_settings: Arc<Mutex<Option<GlobalSettings>>>,
+    /// Whether time tracking is available, by project key, as found by the pre-check of `insert_worklog`
+    time_tracking_by_project: Arc<Mutex<HashMap<String, bool>>>,
+    /// Verify time tracking is available before adding a work log entry
+    time_tracking_precheck: bool,
+    /// Warn about unknown fields requested by a JQL search
+    validate_search_fields: bool,
+    /// The fields of the issues, retrieved once when needed and shared by all clones
+    fields: Arc<Mutex<Option<Vec<FieldDefinition>>>>,
+    /// How users are identified, detected once unless given to the builder
+    user_identity: Arc<Mutex<Option<UserIdentity>>>,
+    /// Overrides the timeout of the HTTP client for the requests of this instance
+    request_timeout: Option<Duration>,
+    /// Shared by all clones, as they share the rate limit
+    stats: Arc<Mutex<JiraStats>>,
+    /// Number of times a request is retried when Jira responds with http 429
+    max_retries: u32,
 }
 
 impl Jira {
@@ -224,6 +663,51 @@ impl Jira {
             .build()?)
     }
 
+    /// Returns a clone of this client, which applies the supplied timeout to each request
+    /// rather than the timeout given to the builder. Use it for the occasional slow call:
+    ///
+    /// ```rust,no_run
+    /// # use jira::Jira;
+    /// # use std::time::Duration;
+    /// # async fn example(jira: &Jira) -> Result<(), jira::JiraError> {
+    /// let projects = jira
+    ///     .with_timeout(Duration::from_secs(120))
+    ///     .get_projects(vec!["TIME".to_string()])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_timeout(&self, timeout: Duration) -> Jira {
+        Jira {
+            request_timeout: Some(timeout),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a client for the same Jira instance acting as the user of the supplied
+    /// credentials, like when switching profile. What is cached about the instance is
+    /// copied, while the caches depending on the user, like which projects have time
+    /// tracking available and the rate limit, start out empty and are not shared with
+    /// this client.
+    #[must_use]
+    pub fn with_credentials(&self, credentials: Credentials) -> Jira {
+        fn copy<T: Clone>(cache: &Arc<Mutex<T>>) -> Arc<Mutex<T>> {
+            Arc::new(Mutex::new(
+                cache.lock().unwrap_or_else(PoisonError::into_inner).clone(),
+            ))
+        }
+        Jira {
+            credentials,
+            global_settings: copy(&self.global_settings),
+            fields: copy(&self.fields),
+            user_identity: copy(&self.user_identity),
+            time_tracking_by_project: Arc::default(),
+            stats: Arc::default(),
+            ..self.clone()
+        }
+    }
+
     async fn request<D>(
         &self,
         method: Method,
@@ -235,7 +719,94 @@ impl Jira {
         D: DeserializeOwned,
     {
         let url = self.host.join(&format!("{}{endpoint}", self.api))?;
+        let response = self
+            .request_raw(method, &url, query_params.as_ref(), body)
+            .await?;
+        let response = error_for_status(&url, response).await?;
+
+        let status = response.status();
+        let body = &response.text().await?;
+        debug!("status {status:?} body '{body:?}'");
+        let data = if body.is_empty() { "null" } else { body };
+        serde_json::from_str::<D>(data).map_err(|source| JiraError::Deserialize {
+            endpoint: endpoint.to_string(),
+            type_name: std::any::type_name::<D>(),
+            source,
+        })
+    }
+
+    /// Sends a request along the path shared by all calls: an expired or revoked OAuth token
+    /// is refreshed, the rate limit is recorded and rate limited requests are retried.
+    /// The response is returned regardless of its status, see [`error_for_status`].
+    ///
+    /// Failures to resolve the host name or to connect are reported as
+    /// `JiraError::Unreachable`.
+    async fn request_raw(
+        &self,
+        method: Method,
+        url: &Url,
+        query_params: Option<&Vec<(String, String)>>,
+        body: Option<Vec<u8>>,
+    ) -> Result<Response> {
+        self.credentials.refresh_if_expired().await?;
+        let mut attempt = 0;
+        loop {
+            let mut response = self
+                .build_request(method.clone(), url, query_params, body.clone())
+                .send()
+                .await
+                .map_err(unreachable)?;
+
+            // An access token may be revoked before it expires, so refresh it and retry once
+            if response.status() == StatusCode::UNAUTHORIZED && self.credentials.refresh().await? {
+                response = self
+                    .build_request(method.clone(), url, query_params, body.clone())
+                    .send()
+                    .await
+                    .map_err(unreachable)?;
+            }
+            let retry_after = {
+                let mut stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+                stats.update(response.headers());
+                stats.retry_after
+            };
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+            if attempt == self.max_retries {
+                return Err(JiraError::RateLimited { retry_after });
+            }
+            let backoff = retry_after
+                .unwrap_or_else(|| INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)))
+                .min(MAX_BACKOFF);
+            attempt += 1;
+            debug!(
+                "Rate limited by Jira, retry {attempt} of {} in {backoff:?}",
+                self.max_retries
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// A snapshot of the rate limit reported by Jira in the responses received so far
+    #[must_use]
+    pub fn stats(&self) -> JiraStats {
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
 
+    fn build_request(
+        &self,
+        method: Method,
+        url: &Url,
+        query_params: Option<&Vec<(String, String)>>,
+        body: Option<Vec<u8>>,
+    ) -> RequestBuilder {
+        // The request itself is not logged, as its headers hold the credentials
+        debug!("request {method} '{url}'");
         let mut request = self
             .client
             .request(method, url.clone())
@@ -244,7 +815,11 @@ impl Jira {
 
         // Apply query parameters if provided
         if let Some(params) = query_params {
-            request = request.query(&params);
+            request = request.query(params);
+        }
+
+        if let Some(timeout) = self.request_timeout {
+            request = request.timeout(timeout);
         }
 
         request = self.credentials.apply(request);
@@ -252,30 +827,7 @@ impl Jira {
         if let Some(body) = body {
             request = request.body(body);
         }
-        debug!("request '{request:?}'");
-
-        let response = request.send().await?;
-
-        let status = response.status();
-        let body = &response.text().await?;
-        debug!("status {status:?} body '{body:?}'");
-        match status {
-            StatusCode::UNAUTHORIZED => Err(JiraError::Unauthorized),
-            StatusCode::METHOD_NOT_ALLOWED => Err(JiraError::MethodNotAllowed),
-            StatusCode::NOT_FOUND => Err(JiraError::NotFound(url.to_string())),
-            StatusCode::URI_TOO_LONG => Err(JiraError::UriTooLong(url.to_string())),
-            client_err if client_err.is_client_error() => {
-                eprintln!("ERROR: http GET returned {status} for {url}, reason:{body}");
-                Err(JiraError::Fault {
-                    code: status,
-                    errors: serde_json::from_str::<Errors>(body)?,
-                })
-            }
-            _ => {
-                let data = if body.is_empty() { "null" } else { body };
-                Ok(serde_json::from_str::<D>(data)?)
-            }
-        }
+        request
     }
 
     ///
@@ -320,12 +872,24 @@ impl Jira {
             .await
     }
 
+    async fn put<D, S>(&self, endpoint: &str, body: S) -> Result<D>
+    where
+        D: DeserializeOwned,
+        S: Serialize,
+    {
+        let data = serde_json::to_string::<S>(&body)?;
+        self.request::<D>(Method::PUT, endpoint, None, Some(data.into_bytes()))
+            .await
+    }
+
     /// Fetches issues from Jira using a specified JQL query and response fields.
     ///
     /// This function sends a JQL query to the Jira server to retrieve issues that
     /// match the specified criteria. This supports pagination and will continue
     /// fetching until all issues are retrieved.
     ///
+    /// Queries which would make the request URL excessively long, like a large
+    /// `issueKey in (...)` list, are sent in the body of a `POST /search/jql` request.
     ///
     /// # Parameters
     /// - `jql`: A reference to a string containing the JQL query.
@@ -339,6 +903,7 @@ impl Jira {
     /// # Errors
     /// This function may return:
     /// * `JiraError::Unauthorized` if the authorization fails.
+    /// * `JiraError::Forbidden` if the user lacks the permission to search.
     /// * `JiraError::MethodNotAllowed` if the HTTP method is not allowed.
     /// * `JiraError::NotFound` if the resource could not be located.
     /// * `JiraError::UriTooLong` if the request URI is excessively long.
@@ -348,38 +913,153 @@ impl Jira {
     where
         T: DeserializeOwned,
     {
+        Ok(self.fetch_with_jql_limited(jql, fields, None).await?.issues)
+    }
+
+    /// Same as [`Jira::fetch_with_jql`], for a query composed with the [`Jql`] builder, which
+    /// quotes and escapes the values of the query.
+    ///
+    /// # Errors
+    /// Same as [`Jira::fetch_with_jql`]
+    pub async fn search<T>(&self, jql: &Jql, fields: Vec<&str>) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.fetch_with_jql(&jql.to_string(), fields).await
+    }
+
+    /// Same as [`Jira::fetch_with_jql`], but stops paginating once `max_issues` issues
+    /// have been retrieved. This protects interactive use against accidentally broad
+    /// JQL queries, which could otherwise pull tens of thousands of issues into memory.
+    ///
+    /// If Jira reports a `total` larger than `max_issues`, a warning is logged before
+    /// any further pages are fetched.
+    ///
+    /// # Returns
+    /// A `JqlSearchResult` holding at most `max_issues` issues, with `truncated` set
+    /// if more issues were available, along with the requested fields unknown to Jira.
+    ///
+    /// # Errors
+    /// Same as [`Jira::fetch_with_jql`]
+    pub async fn fetch_with_jql_limited<T>(
+        &self,
+        jql: &str,
+        fields: Vec<&str>,
+        max_issues: Option<usize>,
+    ) -> Result<JqlSearchResult<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let mut unknown_fields = Vec::new();
+        if self.validate_search_fields {
+            match self.unknown_fields(&fields).await {
+                Ok(unknown) => {
+                    if !unknown.is_empty() {
+                        warn!(
+                            "Jira knows no field named {}, which is left out of the issues of '{jql}'",
+                            unknown.join(", ")
+                        );
+                    }
+                    unknown_fields = unknown;
+                }
+                Err(e) => warn!("Unable to validate the fields of '{jql}': {e}"),
+            }
+        }
         let jql_encoded = urlencoding::encode(jql);
         let mut results: Vec<T> = Vec::new();
+        let mut truncated = false;
+
+        // The next page token is not included, as we want to use the same method for all pages
+        let use_post = self.host.as_str().len()
+            + self.api.len()
+            + format!(
+                "/search/jql?jql={}&fields={}&maxResults={}",
+                jql_encoded,
+                fields.join(","),
+                MAX_RESULTS
+            )
+            .len()
+            > MAX_GET_URL_LENGTH;
 
         let mut next_page_token = None;
         loop {
-            let resource = if let Some(token) = next_page_token {
-                format!(
-                    "/search/jql?jql={}&fields={}&maxResults={}&nextPageToken={}",
-                    jql_encoded,
-                    fields.join(","),
-                    MAX_RESULTS,
-                    token
-                )
+            let page_size = match max_issues {
+                Some(max) => {
+                    MAX_RESULTS.min(i32::try_from(max - results.len()).unwrap_or(MAX_RESULTS))
+                }
+                None => MAX_RESULTS,
+            };
+            let response: IssuesResponse<T> = if use_post {
+                let search = JqlSearchRequest {
+                    jql: jql.to_string(),
+                    fields: fields.iter().map(ToString::to_string).collect(),
+                    max_results: page_size,
+                    next_page_token: next_page_token.take(),
+                };
+                debug!("http post '/search/jql' {search:?}");
+                self.post("/search/jql", search).await?
             } else {
-                format!(
-                    "/search/jql?jql={}&fields={}&maxResults={}",
-                    jql_encoded,
-                    fields.join(","),
-                    MAX_RESULTS
-                )
+                self.get_jql_page(&jql_encoded, &fields, page_size, next_page_token.take())
+                    .await?
             };
-            debug!("http get '{resource:?}'");
-            let response: IssuesResponse<T> = self.get(&resource).await?;
+            if let (Some(max), Some(total), true) = (max_issues, response.total, results.is_empty())
+            {
+                if usize::try_from(total).is_ok_and(|total| total > max) {
+                    warn!("JQL '{jql}' matches {total} issues, only the first {max} will be retrieved");
+                }
+            }
             results.extend(response.issues);
 
+            if let Some(max) = max_issues {
+                if results.len() >= max {
+                    truncated = results.len() > max || response.next_page_token.is_some();
+                    results.truncate(max);
+                    break;
+                }
+            }
+
             if let Some(token) = response.next_page_token {
                 next_page_token = Some(token);
             } else {
                 break;
             }
         }
-        Ok(results)
+        Ok(JqlSearchResult {
+            issues: results,
+            truncated,
+            unknown_fields,
+        })
+    }
+
+    /// Retrieves a single page of a JQL search using http GET
+    async fn get_jql_page<T>(
+        &self,
+        jql_encoded: &str,
+        fields: &[&str],
+        page_size: i32,
+        next_page_token: Option<String>,
+    ) -> Result<IssuesResponse<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let resource = if let Some(token) = next_page_token {
+            format!(
+                "/search/jql?jql={}&fields={}&maxResults={}&nextPageToken={}",
+                jql_encoded,
+                fields.join(","),
+                page_size,
+                token
+            )
+        } else {
+            format!(
+                "/search/jql?jql={}&fields={}&maxResults={}",
+                jql_encoded,
+                fields.join(","),
+                page_size
+            )
+        };
+        debug!("http get '{resource:?}'");
+        self.get(&resource).await
     }
 
     /// Searches for Jira issues where `worklogAuthor` IS NOT EMPTY
@@ -394,7 +1074,9 @@ impl Jira {
     ///
     /// # Errors
     /// Returns an error if:
-    /// * Both `projects` and `issue_keys` are empty.
+    /// * Both `projects` and `issue_keys` are empty, which is reported as
+    ///   `JiraError::RequiredParameter`. Use [`Jira::get_all_issue_summaries`] to retrieve
+    ///   the issues of all projects.
     /// * Network requests fail.
     /// * Parsing the response fails.
     ///
@@ -406,41 +1088,73 @@ impl Jira {
         issue_key_filter: &[IssueKey],
         all_users: bool,
     ) -> Result<Vec<IssueSummary>> {
-        if project_filter.is_empty() && issue_key_filter.is_empty() {
-            warn!("No projects or issue keys provided");
-            return Ok(vec![]);
-        }
-
-        let mut jql = String::new();
+        Ok(self
+            .get_issue_summaries_limited(project_filter, issue_key_filter, all_users, None)
+            .await?
+            .issues)
+    }
 
-        if !project_filter.is_empty() {
-            jql = format!("project in ({})", project_filter.join(","));
-        }
-        if !issue_key_filter.is_empty() {
-            // creates a comma-separated list of issue the keys
-            let keys_spec = issue_key_filter
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(",");
-
-            if jql.is_empty() {
-                // No Project clause, so only add the issue keys
-                jql.push_str(format!("issueKey in ({keys_spec})").as_str());
-            } else {
-                // Appends the set of issue keys, after project filter
-                let s = format!("{jql} and issueKey in ({keys_spec})");
-                jql = s;
+    /// Same as [`Jira::get_issue_summaries`], but retrieves at most `max_issues` issues.
+    ///
+    /// # Errors
+    /// Same as [`Jira::get_issue_summaries`]
+    pub async fn get_issue_summaries_limited(
+        &self,
+        project_filter: &[&str],
+        issue_key_filter: &[IssueKey],
+        all_users: bool,
+        max_issues: Option<usize>,
+    ) -> Result<JqlSearchResult<IssueSummary>> {
+        let scope = match (project_filter.is_empty(), issue_key_filter.is_empty()) {
+            (true, true) => {
+                return Err(JiraError::RequiredParameter(
+                    "project_filter or issue_key_filter".to_string(),
+                ))
             }
-        }
-        if all_users {
-            jql.push_str(" AND worklogAuthor is not EMPTY ");
+            (false, false) => {
+                Jql::project_in(project_filter).and(Jql::issue_key_in(issue_key_filter))
+            }
+            (false, true) => Jql::project_in(project_filter),
+            (true, false) => Jql::issue_key_in(issue_key_filter),
+        };
+        self.search_issue_summaries(Some(scope), all_users, max_issues)
+            .await
+    }
+
+    /// Retrieves the issues of all projects on which work has been logged, by anyone if
+    /// `all_users` is set, otherwise by the current user. This may be a large number of issues
+    /// on a busy Jira instance, hence `max_issues` should be given for interactive use.
+    ///
+    /// # Errors
+    /// Returns an error if the network requests fail, or the response could not be parsed.
+    pub async fn get_all_issue_summaries(
+        &self,
+        all_users: bool,
+        max_issues: Option<usize>,
+    ) -> Result<JqlSearchResult<IssueSummary>> {
+        self.search_issue_summaries(None, all_users, max_issues)
+            .await
+    }
+
+    /// Searches the issues within the `scope` on which work has been logged
+    async fn search_issue_summaries(
+        &self,
+        scope: Option<Jql>,
+        all_users: bool,
+        max_issues: Option<usize>,
+    ) -> Result<JqlSearchResult<IssueSummary>> {
+        let worklog_author = if all_users {
+            Jql::worklog_author_is_not_empty()
         } else {
-            jql.push_str(" AND worklogAuthor=currentUser() ");
-        }
+            Jql::worklog_author_is_current_user()
+        };
+        let jql = match scope {
+            Some(scope) => scope.and(worklog_author),
+            None => worklog_author,
+        };
         debug!("search_issues() :- Composed this JQL: {jql}");
 
-        self.fetch_with_jql(&jql, vec!["id", "key", "summary", "components"])
+        self.fetch_with_jql_limited(&jql.to_string(), ISSUE_SUMMARY_FIELDS.to_vec(), max_issues)
             .await
     }
 
@@ -464,8 +1178,8 @@ impl Jira {
     /// This function may return:
     /// * `JiraError::Unauthorized` if authentication fails
     /// * `JiraError::NotFound` if the issue with the given key does not exist
-    /// * `JiraError::RequestError` for network-related issues
-    /// * `JiraError::SerializationError` if response parsing fails
+    /// * `JiraError::Unreachable` or `JiraError::RequestError` for network-related issues
+    /// * `JiraError::Deserialize` if response parsing fails
     ///
     /// # Examples
     ///
@@ -482,7 +1196,7 @@ impl Jira {
     pub async fn get_issue_summary(&self, issue_key: &IssueKey) -> Result<IssueSummary> {
         // Construct the endpoint with the `fields` parameter for minimal data retrieval
         let endpoint = format!(
-            "/issue/{}?fields=id,key,summary,components",
+            "/issue/{}?fields=id,key,summary,components,parent,status,resolution,assignee",
             issue_key.as_str()
         );
 
@@ -577,6 +1291,10 @@ impl Jira {
     /// provided project key. Components in Jira are used to organize and classify issues
     /// within a project.
     ///
+    /// The pages of the paginated `/project/{key}/component` endpoint are retrieved until the
+    /// last one. Servers lacking this endpoint are queried with `/project/{key}/components`,
+    /// which returns all the components at once.
+    ///
     /// # Arguments
     ///
     /// * `project_key` - A reference to a string slice that specifies the key of the Jira project.
@@ -602,9 +1320,31 @@ impl Jira {
     /// }
     /// ```
     pub async fn get_components(&self, project_key: &str) -> Result<Vec<Component>> {
-        let url = format!("/project/{project_key}/components?componentSource=auto");
-        let components = self.get::<Vec<Component>>(&url).await?;
-
+        let mut components = Vec::new();
+        let mut start_at = 0;
+        loop {
+            let url = format!(
+                "/project/{project_key}/component?startAt={start_at}&maxResults={MAX_RESULTS}&componentSource=auto"
+            );
+            let page = match self.get::<ComponentsResponse>(&url).await {
+                Ok(ComponentsResponse::Page(page)) => page,
+                Ok(ComponentsResponse::All(all)) => return Ok(all),
+                Err(JiraError::NotFound(_)) if start_at == 0 => {
+                    let url = format!("/project/{project_key}/components?componentSource=auto");
+                    return self.get::<Vec<Component>>(&url).await;
+                }
+                Err(e) => return Err(e),
+            };
+            let received = page.values.len();
+            components.extend(page.values);
+            let total_reached = page
+                .total
+                .is_some_and(|total| components.len() >= usize::try_from(total).unwrap_or(0));
+            if received == 0 || page.is_last.unwrap_or(total_reached) {
+                break;
+            }
+            start_at += received;
+        }
         Ok(components)
     }
 
@@ -613,11 +1353,14 @@ impl Jira {
     ///
     /// This function fetches paginated work logs for a Jira issue by querying the Jira API.
     /// It continues retrieving work logs until no more pages are available.
+    /// Work logs added while the pages are fetched shift the following pages, hence a work log
+    /// may be received twice. Such duplicates are dropped by their `id`.
     ///
     /// # Arguments
     ///
-    /// * `issue_key` - The key of the Jira issue for which work logs are being retrieved.
+    /// * `issue_key` - The key or numeric id of the Jira issue for which work logs are being retrieved.
     /// * `started_after` - A `NaiveDateTime` indicating the cutoff time for the work logs to retrieve.
+    /// * `started_before` - Only work logs started before this point in time are retrieved, if given.
     ///
     /// # Returns
     ///
@@ -626,6 +1369,7 @@ impl Jira {
     /// # Errors
     ///
     /// This function returns an error if:
+    /// * The `issue_key` is empty, in which case `JiraError::RequiredParameter` is returned.
     /// * Network requests to retrieve worklogs fail.
     /// * Parsing the Jira API responses fails.
     ///
@@ -636,47 +1380,71 @@ impl Jira {
     /// let issue_key = "ISSUE-123".to_string();
     /// let started_after = NaiveDateTime::parse_from_str("2023-01-01 00:00:00", "%Y-%m-%d %H:%M:%S")?;
     /// let worklogs = jira_client
-    ///     .get_worklogs_for_issue(issue_key, started_after)
+    ///     .get_worklogs_for_issue(issue_key, started_after, None)
     ///     .await?;
     /// for worklog in worklogs {
     ///     println!("Worklog author: {}, time spent: {}", worklog.author, worklog.time_spent);
     /// }
     /// ```
-    /// # Panics
-    ///
-    /// This function will panic if:
-    /// - The `issue_key` is empty, as it is required to specify an issue key.
-    ///
-    /// Ensure that the `issue_key` parameter is properly provided before calling this method.
     #[allow(
         clippy::cast_sign_loss,
         clippy::cast_possible_truncation,
         clippy::cast_possible_wrap
     )]
-    pub async fn get_work_logs_for_issue(
+    pub async fn get_work_logs_for_issue<K>(
         &self,
-        issue_key: &IssueKey,
+        issue_key: K,
         started_after: NaiveDateTime,
-    ) -> Result<Vec<Worklog>> {
-        assert!(!issue_key.is_empty(), "Must specify an issue key");
+        started_before: Option<NaiveDateTime>,
+    ) -> Result<Vec<Worklog>>
+    where
+        K: AsRef<str>,
+    {
+        let issue_key = issue_key.as_ref();
+        if issue_key.trim().is_empty() {
+            return Err(JiraError::RequiredParameter("issue_key".to_string()));
+        }
         let mut resource_name =
-            Self::compose_work_logs_url(issue_key.as_str(), 0, 5000, started_after);
+            Self::compose_work_logs_url(issue_key, 0, 5000, started_after, started_before);
         let mut worklogs: Vec<Worklog> = Vec::<Worklog>::new();
+        let mut received_ids = HashSet::new();
 
         debug!("Retrieving work logs for {issue_key}");
         // Loops through the result pages until last page received
         loop {
-            let mut worklog_page = self.get::<WorklogsPage>(&resource_name).await?;
-            let is_last_page = worklog_page.worklogs.len() < worklog_page.max_results;
+            let worklog_page = self.get::<WorklogsPage>(&resource_name).await?;
+            for skipped in &worklog_page.skipped {
+                warn!(
+                    "Skipped work log {} of {issue_key}: {}",
+                    skipped.id.as_deref().unwrap_or("without id"),
+                    skipped.reason
+                );
+            }
+            let received = worklog_page.worklogs.len() + worklog_page.skipped.len();
+            // Prefers `isLast` when supplied, but an empty page is always the last one
+            let is_last_page = received == 0
+                || worklog_page
+                    .is_last
+                    .unwrap_or(received < worklog_page.max_results);
             if !is_last_page {
                 resource_name = Self::compose_work_logs_url(
-                    issue_key.as_str(),
-                    worklog_page.startAt + worklog_page.worklogs.len(),
+                    issue_key,
+                    worklog_page.startAt + received,
                     worklog_page.max_results,
                     started_after,
+                    started_before,
                 );
             }
-            worklogs.append(&mut worklog_page.worklogs);
+            for worklog in worklog_page.worklogs {
+                if received_ids.insert(worklog.id.clone()) {
+                    worklogs.push(worklog);
+                } else {
+                    debug!(
+                        "Dropped work log {} of {issue_key} received twice",
+                        worklog.id
+                    );
+                }
+            }
             if is_last_page {
                 break;
             }
@@ -702,17 +1470,92 @@ impl Jira {
     /// # Errors
     ///
     /// This function may return the following kinds of errors:
-    /// - Errors originating from the `self.get` method, such as network-related issues, unauthorized access, or resource not found.
+    /// - `JiraError::WorklogNotFound` if Jira holds no such worklog for the issue.
+    /// - Errors originating from the `self.get` method, such as network-related issues or unauthorized access.
     /// - Deserialization errors if the response from the Jira API does not match the expected `Worklog` structure.
+    pub async fn get_worklog(&self, issue_id: &str, worklog_id: &str) -> Result<Worklog> {
+        let resource = format!("/issue/{issue_id}/worklog/{worklog_id}");
+        match self.get::<Worklog>(&resource).await {
+            Err(JiraError::NotFound(_)) => Err(JiraError::WorklogNotFound(
+                issue_id.to_string(),
+                worklog_id.to_string(),
+            )),
+            result => result,
+        }
+    }
+
+    /// Retrieves a specific worklog for a given issue, see [`Jira::get_worklog`]
     ///
-    /// These errors will be encapsulated in the `Result` type based on the error-handling mechanism of the `reqwest` library or the custom error type `JiraError` used in the `self.get` implementation.
+    /// # Errors
+    /// See [`Jira::get_worklog`]
+    #[deprecated(note = "use `get_worklog` instead")]
     pub async fn get_work_log_by_issue_and_id(
         &self,
         issue_id: &str,
         worklog_id: &str,
     ) -> Result<Worklog> {
-        let resource = format!("/issue/{issue_id}/worklog/{worklog_id}");
-        self.get::<Worklog>(&resource).await
+        self.get_worklog(issue_id, worklog_id).await
+    }
+
+    /// Retrieves the ids of the work logs, of all issues visible to the user, which were created
+    /// or updated since the supplied point in time. Jira returns them a page at a time, hence
+    /// many changes are retrieved with several requests. Use [`Jira::get_worklogs_bulk`] to
+    /// retrieve the work logs themselves.
+    ///
+    /// Note that Jira leaves out the changes of the last minute, which are listed later.
+    ///
+    /// # Errors
+    /// Any error returned by [`Jira::get`]
+    pub async fn get_updated_worklog_ids(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        let mut since = since.timestamp_millis();
+        let mut ids = Vec::new();
+        loop {
+            let page: UpdatedWorklogsPage =
+                self.get(&format!("/worklog/updated?since={since}")).await?;
+            debug!(
+                "get_updated_worklog_ids() :- {} work logs changed since {since}",
+                page.values.len()
+            );
+            ids.extend(page.values.iter().map(|v| v.worklog_id.to_string()));
+            // Guards against looping forever, should Jira not move on to the next page
+            if page.last_page || page.values.is_empty() || page.until <= since {
+                break;
+            }
+            since = page.until;
+        }
+        Ok(ids)
+    }
+
+    /// Retrieves the work logs with the supplied ids, like the ones given by
+    /// [`Jira::get_updated_worklog_ids`], regardless of their issues. Ids which do not exist,
+    /// or are not visible to the user, are left out. Ids which are not numeric are skipped
+    /// with a warning.
+    ///
+    /// # Errors
+    /// Any error returned when posting to `/worklog/list`
+    pub async fn get_worklogs_bulk(&self, ids: &[String]) -> Result<Vec<Worklog>> {
+        let ids: Vec<u64> = ids
+            .iter()
+            .filter_map(|id| {
+                if let Ok(id) = id.trim().parse() {
+                    Some(id)
+                } else {
+                    warn!("Skipping work log id '{id}', which is not numeric");
+                    None
+                }
+            })
+            .collect();
+        let mut worklogs = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(MAX_WORKLOG_IDS) {
+            let body = WorklogIds {
+                ids: chunk.to_vec(),
+            };
+            worklogs.extend(
+                self.post::<Vec<Worklog>, WorklogIds>("/worklog/list", body)
+                    .await?,
+            );
+        }
+        Ok(worklogs)
     }
 
     /// Retrieves all worklogs for the currently authenticated user associated with a specific issue.
@@ -725,40 +1568,146 @@ impl Jira {
     /// - `issue_key`: A string slice representing the key of the Jira issue.
     /// - `started_after`: An optional `DateTime<Local>` object representing the timestamp after which
     ///   worklogs should be included. If omitted, defaults to approximately one month prior to the current date.
+    /// - `started_before`: An optional `DateTime<Local>` object representing the timestamp before which
+    ///   worklogs should be included. If omitted, there is no upper bound.
     ///
     /// # Returns
     /// - Returns a `Result` containing a vector of `Worklog` objects authored by the currently authenticated user on success.
     /// - If the operation fails, it returns an appropriate error, such as network issues or Jira API-related errors.
     ///
     /// # Errors
-    ///
-    ///
-    /// # Panics
-    /// This function will panic if `issue_key` is an empty string.
+    /// - `JiraError::RequiredParameter` if `issue_key` is empty
+    /// - Any error returned by [`Jira::get_work_logs_for_issue`] or [`Jira::get_current_user`]
     pub async fn get_work_logs_for_current_user(
         &self,
         issue_key: &str,
         started_after: Option<DateTime<Local>>,
+        started_before: Option<DateTime<Local>>,
     ) -> Result<Vec<Worklog>> {
-        assert!(!issue_key.is_empty(), "Must specify an issue key");
-        let date_time = started_after.unwrap_or_else(|| {
-            // Defaults to a month (approx)
-            Local::now().checked_sub_days(Days::new(30)).unwrap()
-        });
-        let naive_date_time = DateTime::from_timestamp_millis(date_time.timestamp_millis())
-            .unwrap()
-            .naive_local();
+        if issue_key.trim().is_empty() {
+            return Err(JiraError::RequiredParameter("issue_key".to_string()));
+        }
+        // Defaults to a month (approx)
+        let date_time = started_after.unwrap_or_else(|| Local::now() - Days::new(30));
+        let naive_date_time = date_time.naive_utc();
         let result = self
-            .get_work_logs_for_issue(&IssueKey::new(issue_key), naive_date_time)
+            .get_work_logs_for_issue(
+                issue_key,
+                naive_date_time,
+                started_before.map(|before| before.naive_utc()),
+            )
             .await?;
         debug!("Work logs retrieved, filtering them for current user ....");
         let current_user = self.get_current_user().await?;
+        let identity = self.user_identity().await?;
         Ok(result
             .into_iter()
-            .filter(|wl| wl.author.accountId == current_user.account_id)
+            .filter(|wl| identity.matches(&wl.author, &current_user))
             .collect())
     }
 
+    /// Retrieves the work logs of the currently authenticated user across all issues, without
+    /// having to know the issues up front.
+    ///
+    /// The issues are located with the JQL `worklogAuthor = currentUser() AND worklogDate >= ...`,
+    /// after which the work logs of up to 10 issues are fetched concurrently.
+    ///
+    /// # Parameters
+    /// - `started_after`: Only work logs started at or after this point in time are included
+    /// - `project_filter`: Keys of the projects to search, all projects if empty
+    ///
+    /// # Returns
+    /// The work logs paired with the key of their issue.
+    ///
+    /// # Errors
+    /// - Any error returned by [`Jira::fetch_with_jql`], [`Jira::get_work_logs_for_issue`]
+    ///   or [`Jira::get_current_user`]
+    pub async fn get_current_user_worklogs(
+        &self,
+        started_after: DateTime<Utc>,
+        project_filter: &[&str],
+    ) -> Result<Vec<(IssueKey, Worklog)>> {
+        let mut jql = Jql::worklog_author_is_current_user()
+            .and(Jql::worklog_date_from(started_after.date_naive()));
+        if !project_filter.is_empty() {
+            jql = jql.and(Jql::project_in(project_filter));
+        }
+        self.get_current_user_worklogs_with_jql(&jql, started_after, None)
+            .await
+    }
+
+    /// Retrieves the work logs of the currently authenticated user started on `date`, which
+    /// is a date in the local time zone, straight from Jira rather than the local database.
+    ///
+    /// Jira evaluates `worklogDate` in the time zone of the instance, which need not be the
+    /// local one. The issues are therefore searched for the day before and after `date` as
+    /// well, after which only the work logs started on the local `date` are kept.
+    ///
+    /// # Errors
+    /// - Any error returned by [`Jira::fetch_with_jql`], [`Jira::get_work_logs_for_issue`]
+    ///   or [`Jira::get_current_user`]
+    pub async fn get_current_user_worklogs_on_date(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Vec<(IssueKey, Worklog)>> {
+        let jql = Jql::worklog_author_is_current_user().and(Jql::worklog_date_between(
+            date.pred_opt().unwrap_or(date),
+            date.succ_opt().unwrap_or(date),
+        ));
+        let started_after = start_of_local_day(date);
+        let started_before = start_of_local_day(date.succ_opt().unwrap_or(NaiveDate::MAX));
+        self.get_current_user_worklogs_with_jql(&jql, started_after, Some(started_before))
+            .await
+    }
+
+    /// Fetches the work logs of the current user of the issues matching `jql`, which were
+    /// started at or after `started_after` and before `started_before`, if given.
+    /// The work logs of up to 10 issues are fetched concurrently.
+    async fn get_current_user_worklogs_with_jql(
+        &self,
+        jql: &Jql,
+        started_after: DateTime<Utc>,
+        started_before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(IssueKey, Worklog)>> {
+        let issues: Vec<IssueSummary> = self
+            .fetch_with_jql(&jql.to_string(), vec!["summary", "components"])
+            .await?;
+        debug!(
+            "Found {} issues with work logs by current user",
+            issues.len()
+        );
+        let current_user = self.get_current_user().await?;
+        let identity = self.user_identity().await?;
+
+        let mut pages = stream::iter(issues)
+            .map(|issue| async move {
+                let worklogs = self
+                    .get_work_logs_for_issue(
+                        &issue.key,
+                        started_after.naive_utc(),
+                        started_before.map(|before| before.naive_utc()),
+                    )
+                    .await;
+                (issue.key, worklogs)
+            })
+            .buffer_unordered(10);
+
+        let mut result = Vec::new();
+        while let Some((issue_key, worklogs)) = pages.next().await {
+            result.extend(
+                worklogs?
+                    .into_iter()
+                    .filter(|wl| {
+                        wl.started >= started_after
+                            && started_before.is_none_or(|before| wl.started < before)
+                            && identity.matches(&wl.author, &current_user)
+                    })
+                    .map(|wl| (issue_key.clone(), wl)),
+            );
+        }
+        Ok(result)
+    }
+
     fn project_search_resource(start_at: i32, project_keys: Vec<String>) -> String {
         // It seems 50 is the max value of maxResults
         let mut resource = format!("/project/search?maxResults=50&startAt={start_at}");
@@ -776,14 +1725,23 @@ impl Jira {
         start_at: usize,
         max_results: usize,
         started_after: NaiveDateTime,
+        started_before: Option<NaiveDateTime>,
     ) -> String {
-        format!(
+        let mut url = format!(
             "/issue/{}/worklog?startAt={}&maxResults={}&startedAfter={}",
             issue_key,
             start_at,
             max_results,
             Local.from_utc_datetime(&started_after).timestamp_millis()
-        )
+        );
+        if let Some(started_before) = started_before {
+            let _ = write!(
+                url,
+                "&startedBefore={}",
+                Local.from_utc_datetime(&started_before).timestamp_millis()
+            );
+        }
+        url
     }
 
     /// Inserts a worklog for a specific issue in Jira.
@@ -796,6 +1754,7 @@ impl Jira {
     /// - `started`: The starting date and time of the worklog, formatted as `DateTime<Local>`.
     /// - `time_spent_seconds`: The duration of the worklog in seconds.
     /// - `comment`: A description or comment about the work performed.
+    /// - `notify_users`: Whether the watchers of the issue are notified, which is what Jira does by default.
     ///
     /// # Returns
     /// - `Ok(Worklog)` if the operation succeeds, containing the created worklog entry.
@@ -810,6 +1769,12 @@ impl Jira {
     /// This function may return:
     /// - An error related to network communication if the server cannot be reached.
     /// - Validation errors if the input data or formatting does not meet Jira's requirements.
+    /// - `JiraError::TimeTrackingDisabled` if time tracking is not available for the project of
+    ///   the issue. This is verified before the entry is posted, unless disabled with
+    ///   [`JiraBuilder::time_tracking_precheck`](crate::builder::JiraBuilder::time_tracking_precheck).
+    /// - `JiraError::IssueNotEditable` if Jira refuses work logged on the issue in its current
+    ///   state, like done or in a closed sprint. Transitioning the issue to an open status
+    ///   (#31) may be required.
     ///
     /// # Example
     /// ```rust,ignore
@@ -819,31 +1784,73 @@ impl Jira {
     /// let time_spent_seconds = 3600; // 1 hour
     /// let comment = "Worked on improving project documentation.";
     ///
-    /// match instance.insert_worklog("ISSUE-123", started, time_spent_seconds, comment).await {
+    /// let issue_key = IssueKey::from("ISSUE-123");
+    /// match instance.insert_worklog(&issue_key, started, time_spent_seconds, comment, true).await {
     ///     Ok(worklog) => println!("Successfully inserted worklog: {:?}", worklog),
     ///     Err(e) => eprintln!("Error inserting worklog: {:?}", e),
     /// }
     /// ```
     pub async fn insert_worklog(
         &self,
-        issue_id: &str,
+        issue_key: &IssueKey,
         started: DateTime<Local>,
         time_spent_seconds: i32,
         comment: &str,
+        notify_users: bool,
     ) -> Result<Worklog> {
-        // This is how Jira needs it.
-        // Note! The formatting in Jira is based on the time zone of the user. Remember to change it
-        // if you fly across the ocean :-)
-        // Move this into a function
-        let start = started.format("%Y-%m-%dT%H:%M:%S.%3f%z");
+        if issue_key.is_empty() {
+            return Err(JiraError::RequiredParameter("issue_key".to_string()));
+        }
+        if self.time_tracking_precheck {
+            self.ensure_time_tracking_enabled(issue_key).await?;
+        }
         let worklog_entry = Insert {
             timeSpentSeconds: time_spent_seconds,
             comment: comment.to_string(),
-            started: start.to_string(),
+            started: worklog_started(started),
         };
 
-        let url = format!("/issue/{issue_id}/worklog");
-        self.post::<Worklog, Insert>(&url, worklog_entry).await
+        let url = format!(
+            "/issue/{issue_key}/worklog{}",
+            notify_users_query(notify_users)
+        );
+        self.post::<Worklog, Insert>(&url, worklog_entry)
+            .await
+            .map_err(|err| {
+                if is_not_editable(&err) {
+                    JiraError::IssueNotEditable {
+                        key: issue_key.clone(),
+                    }
+                } else {
+                    err
+                }
+            })
+    }
+
+    /// Same as [`Jira::insert_worklog`], for callers holding the issue id or key as a string.
+    ///
+    /// # Errors
+    /// Same as [`Jira::insert_worklog`]
+    #[deprecated(note = "use `insert_worklog` with an `IssueKey`")]
+    pub async fn insert_worklog_by_id(
+        &self,
+        issue_id: &str,
+        started: DateTime<Local>,
+        time_spent_seconds: i32,
+        comment: &str,
+        notify_users: bool,
+    ) -> Result<Worklog> {
+        if issue_id.trim().is_empty() {
+            return Err(JiraError::RequiredParameter("issue_id".to_string()));
+        }
+        self.insert_worklog(
+            &IssueKey::from(issue_id),
+            started,
+            time_spent_seconds,
+            comment,
+            notify_users,
+        )
+        .await
     }
 
     /// Creates a new issue in Jira.
@@ -887,9 +1894,7 @@ impl Jira {
     ) -> Result<NewIssueResponse> {
         let new_issue = NewIssue {
             fields: NewIssueFields {
-                project: JiraProjectKey {
-                    key: jira_project_key.key,
-                },
+                project: jira_project_key.clone(),
                 issuetype: IssueType {
                     name: "Task".to_string(),
                 },
@@ -908,14 +1913,173 @@ impl Jira {
         Ok(result)
     }
 
+    /// Same as [`Jira::create_issue`], but returns an existing unresolved issue with the same
+    /// summary in the project rather than creating a duplicate. This makes scripts which create
+    /// issues safe to re-run after a crash.
+    ///
+    /// The existing issue is searched for with `project = X AND summary ~ "..."`. Note that `~`
+    /// is a fuzzy text search, which ignores case, punctuation and word order, and may match
+    /// issues whose summary merely contains the words. The candidates are therefore compared
+    /// with the summary, ignoring case and surrounding whitespace, and a new issue is created
+    /// unless one of them matches. Issues which were recently created may not yet be indexed
+    /// for searching by Jira, hence two calls in quick succession may still both create an issue.
+    ///
+    /// # Errors
+    /// Same as [`Jira::fetch_with_jql`] and [`Jira::create_issue`]
+    pub async fn find_or_create_issue(
+        &self,
+        jira_project_key: &JiraProjectKey,
+        summary: &str,
+        description: Option<String>,
+        components: Vec<ComponentId>,
+    ) -> Result<NewIssueResponse> {
+        let jql = format!(
+            "project = \"{}\" AND summary ~ \"{}\" AND resolution is EMPTY ORDER BY created",
+            jira_project_key.key,
+            summary.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        let candidates: Vec<IssueSummary> = self
+            .fetch_with_jql(&jql, vec!["id", "key", "summary"])
+            .await?;
+        if let Some(existing) = candidates.into_iter().find(|issue| {
+            issue
+                .fields
+                .summary
+                .trim()
+                .eq_ignore_ascii_case(summary.trim())
+        }) {
+            debug!("Found existing issue {} for '{summary}'", existing.key);
+            return Ok(NewIssueResponse {
+                id: existing.id,
+                key: existing.key,
+            });
+        }
+        self.create_issue(jira_project_key, summary, description, components)
+            .await
+    }
+
+    /// Updates the fields of an existing issue, like its summary or description.
+    ///
+    /// # Parameters
+    /// - `issue_key`: The key of the issue to update
+    /// - `fields`: The fields to set, keyed by field name, i.e. `{"summary": "New summary"}`
+    ///
+    /// # Errors
+    /// This function may return:
+    /// - `JiraError::RequiredParameter` if `fields` is empty
+    /// - `JiraError::NotFound` if the issue does not exist
+    /// - `JiraError::Fault` if Jira rejects the update, for instance a field which is not on the edit screen
+    /// - Network-related errors originating from the `reqwest` library
+    pub async fn update_issue(
+        &self,
+        issue_key: &IssueKey,
+        fields: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        if fields.is_empty() {
+            return Err(JiraError::RequiredParameter("fields".to_string()));
+        }
+        let url = format!("/issue/{}", issue_key.as_str());
+        let body = serde_json::json!({ "fields": fields });
+        let result = self.put::<Option<serde_json::Value>, _>(&url, body).await;
+        match result {
+            Err(JiraError::NotFound(_)) => Err(JiraError::NotFound(issue_key.to_string())),
+            other_result => other_result.map(|_| ()),
+        }
+    }
+
+    /// Changes the summary of an existing issue.
+    ///
+    /// # Errors
+    /// See [`Jira::update_issue`]
+    pub async fn set_summary(&self, issue_key: &IssueKey, summary: &str) -> Result<()> {
+        let mut fields = serde_json::Map::new();
+        fields.insert("summary".to_string(), summary.into());
+        self.update_issue(issue_key, fields).await
+    }
+
+    /// Changes the description of an existing issue.
+    ///
+    /// # Errors
+    /// See [`Jira::update_issue`]
+    pub async fn set_description(&self, issue_key: &IssueKey, description: &str) -> Result<()> {
+        let mut fields = serde_json::Map::new();
+        fields.insert("description".to_string(), description.into());
+        self.update_issue(issue_key, fields).await
+    }
+
+    /// Adds a comment to an issue, like why work on it has stopped.
+    ///
+    /// The text is sent as Atlassian Document Format if the client uses version 3 of the REST
+    /// API, see [`JiraBuilder::api_version`], and as plain text otherwise.
+    ///
+    /// # Errors
+    /// - `JiraError::RequiredParameter` if the issue key or the text is empty.
+    /// - `JiraError::NotFound` if the issue does not exist, or any other error returned by Jira.
+    pub async fn add_comment(&self, issue_key: &IssueKey, body: &str) -> Result<CommentResponse> {
+        if issue_key.is_empty() {
+            return Err(JiraError::RequiredParameter("issue_key".to_string()));
+        }
+        if body.trim().is_empty() {
+            return Err(JiraError::RequiredParameter("body".to_string()));
+        }
+        let document_format = self.api.ends_with("/3");
+        self.post(
+            &format!("/issue/{issue_key}/comment"),
+            comment_body(body, document_format),
+        )
+        .await
+    }
+
+    /// Changes the start, time spent and comment of an existing worklog, which keeps its id,
+    /// unlike deleting and inserting it again.
+    ///
+    /// # Parameters
+    /// - `issue_key`: The key or numeric id of the issue to which the worklog belongs.
+    /// - `worklog_id`: The ID of the worklog to be changed.
+    /// - `started`, `time_spent_seconds` and `comment`: The new values, see [`Jira::insert_worklog`].
+    /// - `notify_users`: Whether the watchers of the issue are notified, which is what Jira does by default.
+    ///
+    /// # Errors
+    /// - `JiraError::RequiredParameter` if the issue key or worklog id is empty.
+    /// - `JiraError::NotFound` if the worklog does not exist, and otherwise the same errors as
+    ///   [`Jira::insert_worklog`].
+    pub async fn update_worklog(
+        &self,
+        issue_key: &IssueKey,
+        worklog_id: &str,
+        started: DateTime<Local>,
+        time_spent_seconds: i32,
+        comment: &str,
+        notify_users: bool,
+    ) -> Result<Worklog> {
+        if issue_key.is_empty() {
+            return Err(JiraError::RequiredParameter("issue_key".to_string()));
+        }
+        if worklog_id.trim().is_empty() {
+            return Err(JiraError::RequiredParameter("worklog_id".to_string()));
+        }
+        let worklog_entry = Insert {
+            timeSpentSeconds: time_spent_seconds,
+            comment: comment.to_string(),
+            started: worklog_started(started),
+        };
+
+        let url = format!(
+            "/issue/{issue_key}/worklog/{worklog_id}{}",
+            notify_users_query(notify_users)
+        );
+        self.put::<Worklog, Insert>(&url, worklog_entry).await
+    }
+
     /// Deletes an existing worklog associated with a specific issue.
     ///
     /// This function interacts with the Jira server to delete a worklog entry
-    /// by its corresponding issue ID and worklog ID.
+    /// by its corresponding issue key and worklog ID.
     ///
     /// # Parameters
-    /// - `issue_id`: The ID of the issue to which the worklog belongs.
+    /// - `issue_key`: The key or numeric id of the issue to which the worklog belongs.
     /// - `worklog_id`: The ID of the worklog to be deleted.
+    /// - `notify_users`: Whether the watchers of the issue are notified, which is what Jira does by default.
     ///
     /// # Returns
     /// - Returns `Ok(())` on successful deletion of the worklog.
@@ -928,12 +2092,137 @@ impl Jira {
     ///     - API-related errors, such as authentication failures or resource not found.
     ///     - Deserialization errors if the response from the Jira API does not match the expected `Worklog` structure.
     /// - Any other errors that may occur during internal processing, encapsulated as a `JiraError`.
-    pub async fn delete_worklog(&self, issue_id: String, worklog_id: String) -> Result<()> {
-        let url = format!("/issue/{}/worklog/{}", &issue_id, &worklog_id);
+    pub async fn delete_worklog(
+        &self,
+        issue_key: &IssueKey,
+        worklog_id: &str,
+        notify_users: bool,
+    ) -> Result<()> {
+        let url = format!(
+            "/issue/{issue_key}/worklog/{worklog_id}{}",
+            notify_users_query(notify_users)
+        );
         let _ = self.delete::<Option<Worklog>>(&url).await?;
         Ok(())
     }
 
+    /// Same as [`Jira::delete_worklog`], for callers holding the issue id or key as a string.
+    ///
+    /// # Errors
+    /// Same as [`Jira::delete_worklog`]
+    #[deprecated(note = "use `delete_worklog` with an `IssueKey`")]
+    pub async fn delete_worklog_by_id(
+        &self,
+        issue_id: String,
+        worklog_id: String,
+        notify_users: bool,
+    ) -> Result<()> {
+        if issue_id.trim().is_empty() {
+            return Err(JiraError::RequiredParameter("issue_id".to_string()));
+        }
+        self.delete_worklog(&IssueKey::from(issue_id), &worklog_id, notify_users)
+            .await
+    }
+
+    /// Sets a property on a work log, replacing the value of an existing property with the same key.
+    ///
+    /// # Parameters
+    /// - `issue_id`: The id or key of the issue holding the work log
+    /// - `worklog_id`: The id of the work log
+    /// - `key`: The key of the property
+    /// - `value`: The value of the property, any JSON value
+    ///
+    /// # Errors
+    /// This function may return:
+    /// - `JiraError::NotFound` if the issue or the work log does not exist
+    /// - `JiraError::Fault` if Jira rejects the property, for instance if the value is too large
+    /// - Network-related errors originating from the `reqwest` library
+    pub async fn set_worklog_property(
+        &self,
+        issue_id: &str,
+        worklog_id: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<()> {
+        let url = format!(
+            "/issue/{issue_id}/worklog/{worklog_id}/properties/{}",
+            urlencoding::encode(key)
+        );
+        let _ = self
+            .put::<Option<serde_json::Value>, _>(&url, value)
+            .await?;
+        Ok(())
+    }
+
+    /// Retrieves the value of a work log property.
+    ///
+    /// # Errors
+    /// This function may return:
+    /// - `JiraError::NotFound` if the issue, the work log or the property does not exist
+    /// - Network-related errors originating from the `reqwest` library
+    pub async fn get_worklog_property(
+        &self,
+        issue_id: &str,
+        worklog_id: &str,
+        key: &str,
+    ) -> Result<serde_json::Value> {
+        let url = format!(
+            "/issue/{issue_id}/worklog/{worklog_id}/properties/{}",
+            urlencoding::encode(key)
+        );
+        let property = self.get::<WorklogProperty>(&url).await?;
+        Ok(property.value)
+    }
+
+    /// Lists the keys of the properties set on a work log.
+    ///
+    /// # Errors
+    /// This function may return:
+    /// - `JiraError::NotFound` if the issue or the work log does not exist
+    /// - Network-related errors originating from the `reqwest` library
+    pub async fn list_worklog_property_keys(
+        &self,
+        issue_id: &str,
+        worklog_id: &str,
+    ) -> Result<Vec<String>> {
+        let url = format!("/issue/{issue_id}/worklog/{worklog_id}/properties");
+        let property_keys = self.get::<PropertyKeys>(&url).await?;
+        Ok(property_keys.keys.into_iter().map(|k| k.key).collect())
+    }
+
+    /// Deletes a batch of work logs, issuing up to 10 concurrent requests.
+    ///
+    /// Work logs which no longer exist in Jira are reported as `not_found` rather than as
+    /// failures, which makes it safe to repeat the call.
+    ///
+    /// # Parameters
+    /// - `items`: The issue key and the work log id of each work log to delete
+    ///
+    /// # Errors
+    /// The individual failures are collected in the `DeleteReport`; this function currently
+    /// always returns `Ok`.
+    pub async fn bulk_delete_worklogs(&self, items: &[(IssueKey, String)]) -> Result<DeleteReport> {
+        let mut outcomes = stream::iter(items)
+            .map(|(issue_key, worklog_id)| async move {
+                let result = self.delete_worklog(issue_key, worklog_id, true).await;
+                (issue_key.clone(), worklog_id.clone(), result)
+            })
+            .buffer_unordered(10);
+
+        let mut report = DeleteReport::default();
+        while let Some((issue_key, worklog_id, result)) = outcomes.next().await {
+            match result {
+                Ok(()) => report.deleted.push((issue_key, worklog_id)),
+                Err(JiraError::NotFound(_)) => report.not_found.push((issue_key, worklog_id)),
+                Err(err) => {
+                    warn!("Unable to delete work log {worklog_id} of {issue_key}: {err:?}");
+                    report.failed.push((issue_key, worklog_id, err));
+                }
+            }
+        }
+        Ok(report)
+    }
+
     /// Deletes an existing Jira issue.
     ///
     /// This function interacts with the Jira server to delete a specified issue
@@ -990,6 +2279,8 @@ impl Jira {
     /// such as the `estimates` field used, time tracking provider, and other
     /// related details.
     ///
+    /// The configuration is retrieved once and cached for the lifetime of this client and its clones.
+    ///
     /// # Returns
     /// - Returns a `Result` containing the `TimeTrackingConfiguration` object on success.
     /// - Returns an appropriate error if the operation fails, such as network issues or
@@ -1003,8 +2294,176 @@ impl Jira {
     ///     - Deserialization errors if the response from the Jira API does not match the expected `Worklog` structure.
     /// - Any other errors that may occur during internal processing, encapsulated as a `JiraError`.
     pub async fn get_time_tracking_options(&self) -> Result<TimeTrackingConfiguration> {
+        Ok(self.get_global_settings().await?.timeTrackingConfiguration)
+    }
+
+    /// Retrieves the global settings of the Jira instance, which are cached after the first call.
+    ///
+    /// # Errors
+    /// Returns a `JiraError` if the request fails or the response could not be deserialized
+    pub async fn get_global_settings(&self) -> Result<GlobalSettings> {
+        if let Some(cached) = self.cached_global_settings().clone() {
+            return Ok(cached);
+        }
         let global_settings = self.get::<GlobalSettings>("/configuration").await?;
-        Ok(global_settings.timeTrackingConfiguration)
+        *self.cached_global_settings() = Some(global_settings.clone());
+        Ok(global_settings)
+    }
+
+    /// Retrieves the system and custom fields of the issues, which are cached after the first call.
+    ///
+    /// # Errors
+    /// Returns a `JiraError` if the request fails or the response could not be deserialized
+    pub async fn get_fields(&self) -> Result<Vec<FieldDefinition>> {
+        if let Some(cached) = self
+            .fields
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+        {
+            return Ok(cached);
+        }
+        let fields = self.get::<Vec<FieldDefinition>>("/field").await?;
+        *self.fields.lock().unwrap_or_else(PoisonError::into_inner) = Some(fields.clone());
+        Ok(fields)
+    }
+
+    /// The fields to request from a JQL search which are unknown to Jira, see
+    /// [`JiraBuilder::validate_search_fields`]. Fields are given by id, key or name, optionally
+    /// excluded with a leading `-`, besides `*all`, `*navigable`, `id` and `key`.
+    ///
+    /// # Errors
+    /// Same as [`Jira::get_fields`]
+    pub async fn unknown_fields(&self, fields: &[&str]) -> Result<Vec<String>> {
+        let known = self.get_fields().await?;
+        Ok(fields
+            .iter()
+            .map(|field| field.trim().trim_start_matches('-'))
+            .filter(|field| !matches!(*field, "*all" | "*navigable" | "id" | "key"))
+            .filter(|field| !known.iter().any(|known| known.is_named(field)))
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    /// The lock is never held across an await point, hence a poisoned lock is still usable
+    fn cached_global_settings(&self) -> MutexGuard<'_, Option<GlobalSettings>> {
+        self.global_settings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Verifies that work may be logged on the issue, i.e. time tracking is enabled for the
+    /// instance and the `timetracking` field is available for the issue.
+    /// The outcome is cached by project key.
+    async fn ensure_time_tracking_enabled(&self, issue_key: &IssueKey) -> Result<()> {
+        let project_key = issue_key.project_key().to_string();
+        let cached = self
+            .time_tracking_by_project
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&project_key)
+            .copied();
+        let enabled = if let Some(enabled) = cached {
+            enabled
+        } else {
+            let enabled = self.get_global_settings().await?.time_tracking_enabled()
+                && self
+                    .get::<serde_json::Value>(&format!("/issue/{issue_key}?fields=timetracking"))
+                    .await?
+                    .pointer("/fields/timetracking")
+                    .is_some();
+            self.time_tracking_by_project
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(project_key.clone(), enabled);
+            enabled
+        };
+        if enabled {
+            Ok(())
+        } else {
+            Err(JiraError::TimeTrackingDisabled(project_key))
+        }
+    }
+
+    /// How users are identified by this Jira instance, `accountId` on Jira Cloud and the user
+    /// name on Jira Server and Data Center. Detected from the deployment type of the server info
+    /// once, unless given to [`JiraBuilder::user_identity`].
+    ///
+    /// # Errors
+    /// Returns a `JiraError` if the server info could not be retrieved
+    pub async fn user_identity(&self) -> Result<UserIdentity> {
+        if let Some(identity) = *self
+            .user_identity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            return Ok(identity);
+        }
+        let server_info = self.get_server_info().await?;
+        let identity = UserIdentity::from_deployment_type(server_info.deploymentType.as_deref());
+        debug!("user_identity() :- {identity:?} for {server_info:?}");
+        *self
+            .user_identity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(identity);
+        Ok(identity)
+    }
+
+    /// Retrieves information about the Jira server, like the version and the current server time.
+    ///
+    /// # Errors
+    /// Returns a `JiraError` if the request fails or the response could not be deserialized
+    pub async fn get_server_info(&self) -> Result<ServerInfo> {
+        self.get::<ServerInfo>("/serverInfo").await
+    }
+
+    /// Retrieves the current time of the Jira server from the `Date` header of the response
+    /// to the inexpensive `/serverInfo` call.
+    ///
+    /// # Errors
+    /// Returns a `JiraError` if the request fails or the `Date` header is missing or invalid
+    pub async fn get_server_time(&self) -> Result<DateTime<Utc>> {
+        let url = self.host.join(&format!("{}/serverInfo", self.api))?;
+        let response = self.request_raw(Method::GET, &url, None, None).await?;
+        let response = error_for_status(&url, response).await?;
+        let date = response
+            .headers()
+            .get(DATE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        DateTime::parse_from_rfc2822(date)
+            .map(|server_time| server_time.with_timezone(&Utc))
+            .map_err(|_| JiraError::InvalidDateHeader(date.to_string()))
+    }
+
+    /// Computes how far the local clock is ahead of the Jira server clock, a negative value
+    /// meaning the local clock is behind.
+    ///
+    /// The resolution is one second, as given by the `Date` header.
+    ///
+    /// # Errors
+    /// Returns a `JiraError` if the server time could not be retrieved
+    pub async fn clock_skew(&self) -> Result<chrono::Duration> {
+        let server_time = self.get_server_time().await?;
+        Ok(Utc::now() - server_time)
+    }
+
+    /// Measures the round-trip time of the inexpensive `/serverInfo` call.
+    ///
+    /// The pooled connection of the client is reused, hence only the first ping includes
+    /// the time needed to establish the connection. Any response counts, regardless of the status,
+    /// except http 429 Too Many Requests which is retried like any other request.
+    ///
+    /// # Errors
+    /// Returns `JiraError::Unreachable` if the host name could not be resolved or the connection
+    /// could not be established, and `JiraError::Timeout` if the request timed out
+    pub async fn ping(&self) -> Result<Duration> {
+        let url = self.host.join(&format!("{}/serverInfo", self.api))?;
+        let started = Instant::now();
+        let response = self.request_raw(Method::GET, &url, None, None).await?;
+        let round_trip = started.elapsed();
+        debug!("ping() :- {} after {round_trip:?}", response.status());
+        Ok(round_trip)
     }
 
     ///
@@ -1022,6 +2481,7 @@ impl Jira {
     ///   for which worklogs should be retrieved.
     /// - `start_after_naive_date_time`: A `NaiveDateTime` instance representing the cutoff point
     ///   for retrieving worklogs. Only worklogs created or updated after this date-time will be fetched.
+    /// - `started_before`: Only worklogs started before this date-time are fetched, if given.
     ///
     /// # Returns
     /// - Returns a `Result` containing a `Vec<Worklog>` on success.
@@ -1038,9 +2498,12 @@ impl Jira {
         &self,
         issue_keys: &Vec<IssueKey>,
         start_after_naive_date_time: NaiveDateTime,
+        started_before: Option<NaiveDateTime>,
     ) -> Result<Vec<Worklog>> {
         let futures = stream::iter(issue_keys)
-            .map(|key| self.get_work_logs_for_issue(key, start_after_naive_date_time))
+            .map(|key| {
+                self.get_work_logs_for_issue(key, start_after_naive_date_time, started_before)
+            })
             .buffer_unordered(10);
 
         let issue_worklogs: Vec<_> = futures
@@ -1050,15 +2513,71 @@ impl Jira {
 
         Ok(issue_worklogs)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::builder::DEFAULT_API_VERSION;
-    use mockito::Server;
 
-    #[tokio::test]
+    /// Retrieves the work logs of the issues concurrently, like [`Jira::chunked_work_logs`],
+    /// but fails if the work logs of any of the issues could not be retrieved, rather than
+    /// silently leaving them out.
+    ///
+    /// # Errors
+    /// Returns the first `JiraError` encountered while retrieving the work logs
+    pub async fn try_chunked_work_logs(
+        &self,
+        issue_keys: &[IssueKey],
+        start_after_naive_date_time: NaiveDateTime,
+        started_before: Option<NaiveDateTime>,
+    ) -> Result<Vec<Worklog>> {
+        stream::iter(issue_keys)
+            .map(|key| {
+                self.get_work_logs_for_issue(key, start_after_naive_date_time, started_before)
+            })
+            .buffer_unordered(10)
+            .try_concat()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::DEFAULT_API_VERSION;
+    use mockito::{Mock, Server};
+
+    /// Mocks the server info, which tells how users are identified
+    async fn mock_server_info(server: &mut mockito::ServerGuard, deployment_type: &str) -> Mock {
+        server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/serverInfo").as_str(),
+            )
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"baseUrl": "https://norn.atlassian.net", "version": "1001.0.0",
+                    "deploymentType": "{deployment_type}"}}"#
+            ))
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn user_identity_is_detected_once_unless_forced() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let server_info = mock_server_info(&mut server, "Server").await.expect(1);
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+        assert_eq!(client.user_identity().await?, UserIdentity::Name);
+        assert_eq!(client.clone().user_identity().await?, UserIdentity::Name);
+        server_info.assert_async().await;
+
+        let forced = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .user_identity(UserIdentity::AccountId)
+            .build()?;
+        assert_eq!(forced.user_identity().await?, UserIdentity::AccountId);
+        server_info.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
     async fn fetch_myself_success() -> Result<()> {
         let mut server = Server::new_async().await;
         let url = server.url();
@@ -1116,9 +2635,8 @@ mod tests {
         if let Err(unauth) = client.get_current_user().await {
             #[allow(clippy::single_match_else)]
             match unauth {
-                JiraError::Fault { code, errors } => {
-                    assert_eq!(code, 403);
-                    assert_eq!(errors.error_messages[0], "foo");
+                JiraError::Forbidden { message } => {
+                    assert_eq!(message, "foo");
                 }
                 _ => panic!(),
             }
@@ -1128,4 +2646,1966 @@ mod tests {
 
         Ok(())
     }
+
+    fn issue_page_body(range: std::ops::Range<usize>, next_page_token: Option<&str>) -> String {
+        let issues = range
+            .map(|n| {
+                format!(
+                    r#"{{"id": "{n}", "key": "TIME-{n}", "fields": {{"summary": "Issue {n}", "components": []}}}}"#
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        match next_page_token {
+            Some(token) => {
+                format!(r#"{{"issues": [{issues}], "total": 300, "nextPageToken": "{token}"}}"#)
+            }
+            None => format!(r#"{{"issues": [{issues}], "total": 300}}"#),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_with_jql_limited_truncates() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let path = format!("/rest/api/{DEFAULT_API_VERSION}/search/jql");
+
+        let first_page = server
+            .mock("GET", path.as_str())
+            .match_query(mockito::Matcher::Regex(
+                "^jql=[^&]*&fields=[^&]*&maxResults=100$".to_string(),
+            ))
+            .with_status(200)
+            .with_body(issue_page_body(1..101, Some("page2")))
+            .create_async()
+            .await;
+        let remaining_pages = server
+            .mock("GET", path.as_str())
+            .match_query(mockito::Matcher::Regex("nextPageToken".to_string()))
+            .with_status(200)
+            .with_body(issue_page_body(101..201, Some("page3")))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = Jira::new(
+            url,
+            Credentials::Basic("foo@bar.com".to_string(), String::new()),
+        )?;
+        let result: JqlSearchResult<IssueSummary> = client
+            .fetch_with_jql_limited("project = TIME", vec!["key", "summary"], Some(100))
+            .await?;
+
+        assert_eq!(result.issues.len(), 100);
+        assert!(result.truncated);
+        first_page.assert_async().await;
+        remaining_pages.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_with_jql_uses_post_for_long_queries() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let path = format!("/rest/api/{DEFAULT_API_VERSION}/search/jql");
+
+        let get_mock = server
+            .mock("GET", path.as_str())
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        let post_mock = server
+            .mock("POST", path.as_str())
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"fields": ["id", "key", "summary", "components", "parent", "status", "resolution", "assignee"], "maxResults": 100}"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(issue_page_body(1..3, None))
+            .create_async()
+            .await;
+
+        let client = Jira::new(
+            url,
+            Credentials::Basic("foo@bar.com".to_string(), String::new()),
+        )?;
+        let keys = (1..=500)
+            .map(|n| IssueKey::new(&format!("TIME-{n}")))
+            .collect::<Vec<_>>();
+        let issues = client.get_issue_summaries(&[], &keys, true).await?;
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].key, IssueKey::from("TIME-1"));
+        post_mock.assert_async().await;
+        get_mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn current_user_work_logs_data_center_authors() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _server_info = mock_server_info(&mut server, "Server").await;
+        let url = server.url();
+        let _myself = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                "self": "foo",
+                "name": "jdoe",
+                "key": "JIRAUSER10100",
+                "emailAddress": "jdoe@bar.com",
+                "displayName": "John Doe",
+                "timeZone": "Europe/Oslo"
+            }"#,
+            )
+            .create_async()
+            .await;
+        let _worklogs = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(
+                r#"{
+                "startAt": 0, "maxResults": 5000, "total": 2,
+                "worklogs": [
+                    {"id": "1", "issueId": "10", "timeSpent": "1h", "timeSpentSeconds": 3600,
+                     "created": "2024-01-01T08:00:00.000+0000", "updated": "2024-01-01T08:00:00.000+0000",
+                     "started": "2024-01-01T08:00:00.000+0000",
+                     "author": {"name": "jdoe", "key": "JIRAUSER10100", "displayName": "John Doe"}},
+                    {"id": "2", "issueId": "10", "timeSpent": "1h", "timeSpentSeconds": 3600,
+                     "created": "2024-01-01T08:00:00.000+0000", "updated": "2024-01-01T08:00:00.000+0000",
+                     "started": "2024-01-01T08:00:00.000+0000",
+                     "author": {"name": "ola", "key": "JIRAUSER10200", "displayName": "Ola Dunk"}}
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = Jira::new(
+            url,
+            Credentials::Basic("jdoe@bar.com".to_string(), String::new()),
+        )?;
+        let worklogs = client
+            .get_work_logs_for_current_user("TIME-1", None, None)
+            .await?;
+
+        assert_eq!(worklogs.len(), 1);
+        assert_eq!(worklogs[0].id, "1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn work_logs_for_empty_issue_key_is_an_error() -> Result<()> {
+        let client = Jira::new("http://localhost", Credentials::Anonymous)?;
+
+        let result = client
+            .get_work_logs_for_issue("", Local::now().naive_utc(), None)
+            .await;
+        assert!(matches!(result, Err(JiraError::RequiredParameter(p)) if p == "issue_key"));
+
+        let result = client.get_work_logs_for_current_user(" ", None, None).await;
+        assert!(matches!(result, Err(JiraError::RequiredParameter(p)) if p == "issue_key"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn server_info_holds_server_time() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _server_info = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/serverInfo").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{
+                "baseUrl": "https://norn.atlassian.net",
+                "version": "1001.0.0-SNAPSHOT",
+                "deploymentType": "Cloud",
+                "serverTime": "2024-05-20T10:11:12.345+0000"
+            }"#,
+            )
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let server_info = client.get_server_info().await?;
+        assert_eq!(server_info.deploymentType.as_deref(), Some("Cloud"));
+        assert_eq!(
+            server_info.serverTime.map(|t| t.to_rfc3339()),
+            Some("2024-05-20T10:11:12.345+00:00".to_string())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn components_are_retrieved_across_pages() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let endpoint = format!("/rest/api/{DEFAULT_API_VERSION}/project/TIME/component");
+        let first = server
+            .mock("GET", endpoint.as_str())
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "0".into()))
+            .with_status(200)
+            .with_body(
+                r#"{"startAt": 0, "maxResults": 2, "total": 3, "isLast": false,
+                    "values": [{"id": "1", "name": "Backend"}, {"id": "2", "name": "Frontend"}]}"#,
+            )
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", endpoint.as_str())
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "2".into()))
+            .with_status(200)
+            .with_body(
+                r#"{"startAt": 2, "maxResults": 2, "total": 3, "isLast": true,
+                    "values": [{"id": "3", "name": "Infrastructure"}]}"#,
+            )
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let components = client.get_components("TIME").await?;
+
+        first.assert_async().await;
+        second.assert_async().await;
+        let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Backend", "Frontend", "Infrastructure"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn components_are_retrieved_until_total_without_is_last() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let endpoint = format!("/rest/api/{DEFAULT_API_VERSION}/project/TIME/component");
+        let first = server
+            .mock("GET", endpoint.as_str())
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "0".into()))
+            .with_status(200)
+            .with_body(
+                r#"{"startAt": 0, "maxResults": 1, "total": 2,
+                    "values": [{"id": "1", "name": "Backend"}]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", endpoint.as_str())
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "1".into()))
+            .with_status(200)
+            .with_body(
+                r#"{"startAt": 1, "maxResults": 1, "total": 2,
+                    "values": [{"id": "2", "name": "Frontend"}]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let components = client.get_components("TIME").await?;
+
+        // No third page is requested once the total is reached
+        first.assert_async().await;
+        second.assert_async().await;
+        let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Backend", "Frontend"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn components_fall_back_to_unpaginated_endpoint() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _paginated = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/project/TIME/component").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+        let _all = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/project/TIME/components").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"[{"id": "1", "name": "Backend"}]"#)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let components = client.get_components("TIME").await?;
+        assert_eq!(components.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn errors_deserialized_from_map_and_array() {
+        let from_map: Errors = serde_json::from_str(
+            r#"{"errorMessages": [], "errors": {"timeLogging": "Time tracking is disabled"}}"#,
+        )
+        .unwrap();
+        let from_array: Errors = serde_json::from_str(
+            r#"{"errorMessages": [],
+                "errors": [{"errorMessage": "Time tracking is disabled", "field": "timeLogging"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(from_map, from_array);
+
+        let without_field: Errors = serde_json::from_str(
+            r#"{"errorMessages": ["Bad request"], "errors": [{"errorMessage": "First"}, {"errorMessage": "Second"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            without_field.errors,
+            Some(BTreeMap::from([
+                ("0".to_string(), "First".to_string()),
+                ("1".to_string(), "Second".to_string())
+            ]))
+        );
+
+        let without_errors: Errors =
+            serde_json::from_str(r#"{"errorMessages": ["Bad request"]}"#).unwrap();
+        assert_eq!(without_errors.errors, None);
+    }
+
+    #[tokio::test]
+    async fn per_call_timeout_overrides_default() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _server_info = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/serverInfo").as_str(),
+            )
+            .with_status(200)
+            .with_chunked_body(|w| {
+                std::thread::sleep(Duration::from_millis(500));
+                w.write_all(
+                    br#"{"baseUrl": "https://norn.atlassian.net", "version": "1001.0.0",
+                        "deploymentType": "Cloud"}"#,
+                )
+            })
+            .expect(2)
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .timeout(5)
+            .build()?;
+
+        let result = client
+            .with_timeout(Duration::from_millis(100))
+            .get_server_info()
+            .await;
+        assert!(matches!(&result, Err(JiraError::Timeout(_))), "{result:?}");
+        // The default timeout of the builder is kept by the original client
+        let server_info = client.get_server_info().await?;
+        assert_eq!(server_info.deploymentType.as_deref(), Some("Cloud"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deserialize_error_holds_endpoint_and_type() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _server_info = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/serverInfo").as_str(),
+            )
+            .with_status(200)
+            .with_body(r#"{"serverTime": 42}"#)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let result = client.get_server_info().await;
+        let Err(JiraError::Deserialize {
+            endpoint,
+            type_name,
+            ..
+        }) = &result
+        else {
+            panic!("Expected a deserialize error, got {result:?}");
+        };
+        assert_eq!(endpoint, "/serverInfo");
+        assert!(type_name.ends_with("ServerInfo"), "{type_name}");
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("/serverInfo"), "{message}");
+        assert!(message.contains("ServerInfo"), "{message}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ping_measures_round_trip() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let server_info = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/serverInfo").as_str(),
+            )
+            .with_status(200)
+            .with_body("{}")
+            .expect(2)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        assert!(client.ping().await? < Duration::from_secs(1));
+        assert!(client.ping().await? < Duration::from_secs(1));
+        server_info.assert_async().await;
+        assert_eq!(client.stats().responses, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ping_reports_refused_connection() -> Result<()> {
+        // Nothing listens on the port once the listener has been dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .expect("Unable to bind a local port")
+            .local_addr()
+            .expect("Unable to resolve the local port")
+            .port();
+        let client = Jira::new(format!("http://127.0.0.1:{port}"), Credentials::Anonymous)?;
+
+        let result = client.ping().await;
+        assert!(
+            matches!(
+                &result,
+                Err(JiraError::Unreachable(UnreachableCause::Connect, _))
+            ),
+            "{result:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn clock_skew_is_computed_from_date_header() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let server_time = Utc::now() - chrono::Duration::minutes(10);
+        let _server_info = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/serverInfo").as_str(),
+            )
+            .with_status(200)
+            .with_header("date", &server_time.to_rfc2822())
+            .with_body("{}")
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        assert_eq!(
+            client.get_server_time().await?.timestamp(),
+            server_time.timestamp()
+        );
+        let skew = client.clock_skew().await?.num_seconds();
+        assert!((600..=605).contains(&skew), "unexpected skew {skew}s");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn oauth_token_is_refreshed_and_request_retried_on_unauthorized() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let path = format!("/rest/api/{DEFAULT_API_VERSION}/myself");
+        let rejected = server
+            .mock("GET", path.as_str())
+            .match_header("authorization", "Bearer revoked")
+            .with_status(401)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("GET", path.as_str())
+            .match_header("authorization", "Bearer refreshed")
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://norn.atlassian.net/rest/api/3/user?accountId=abc",
+                "accountId": "abc", "displayName": "Ola", "emailAddress": "ola@dunk.com",
+                "timeZone": "Europe/Oslo"}"#,
+            )
+            .create_async()
+            .await;
+
+        let refresh_count = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&refresh_count);
+        let refresh_fn = RefreshFn::new(move |refresh_token| {
+            *counter.lock().unwrap() += 1;
+            async move {
+                assert_eq!(refresh_token, "refresh-1");
+                Ok(OAuthToken {
+                    access_token: "refreshed".to_string(),
+                    refresh_token: "refresh-2".to_string(),
+                    expires_at: Utc::now() + chrono::TimeDelta::hours(1),
+                })
+            }
+        });
+        let token = OAuthToken {
+            access_token: "revoked".to_string(),
+            refresh_token: "refresh-1".to_string(),
+            expires_at: Utc::now() + chrono::TimeDelta::hours(1),
+        };
+        let client = Jira::new(server.url(), Credentials::oauth(token, refresh_fn))?;
+
+        let user = client.get_current_user().await?;
+        assert_eq!(user.account_id, "abc");
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+        assert_eq!(*refresh_count.lock().unwrap(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn current_user_worklogs_across_projects() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _server_info = mock_server_info(&mut server, "Cloud").await;
+        let _search = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/search/jql").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "jql".to_string(),
+                "worklogAuthor = currentUser() AND worklogDate >= \"2024-02-01\" AND project in (\"TIME\", \"ABC\")"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"{"issues": [
+                    {"id": "1", "key": "TIME-1", "fields": {"summary": "Meetings", "components": []}},
+                    {"id": "2", "key": "ABC-2", "fields": {"summary": "Coding", "components": []}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        let _myself = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://norn.atlassian.net/rest/api/3/user?accountId=me",
+                "accountId": "me", "displayName": "Me", "emailAddress": "me@dunk.com",
+                "timeZone": "Europe/Oslo"}"#,
+            )
+            .create_async()
+            .await;
+        let worklog = |id: &str, account_id: &str, issue_id: &str| {
+            format!(
+                r#"{{"id": "{id}", "author": {{"accountId": "{account_id}", "displayName": "{account_id}"}},
+                "started": "2024-02-02T08:00:00.000+0000", "timeSpent": "1h",
+                "timeSpentSeconds": 3600, "issueId": "{issue_id}"}}"#
+            )
+        };
+        let mut worklog_mocks = Vec::new();
+        for (key, body) in [
+            (
+                "TIME-1",
+                format!(
+                    "{},{}",
+                    worklog("10", "me", "1"),
+                    worklog("11", "other", "1")
+                ),
+            ),
+            ("ABC-2", worklog("20", "me", "2")),
+        ] {
+            worklog_mocks.push(
+                server
+                    .mock(
+                        "GET",
+                        format!("/rest/api/{DEFAULT_API_VERSION}/issue/{key}/worklog").as_str(),
+                    )
+                    .match_query(mockito::Matcher::Any)
+                    .with_status(200)
+                    .with_body(format!(
+                        r#"{{"startAt": 0, "maxResults": 5000, "total": 1, "worklogs": [{body}]}}"#
+                    ))
+                    .create_async()
+                    .await,
+            );
+        }
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let started_after = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        let mut worklogs = client
+            .get_current_user_worklogs(started_after, &["TIME", "ABC"])
+            .await?;
+        worklogs.sort_by(|a, b| a.1.id.cmp(&b.1.id));
+
+        for mock in worklog_mocks {
+            mock.assert_async().await;
+        }
+        let found: Vec<(String, String)> = worklogs
+            .into_iter()
+            .map(|(key, wl)| (key.to_string(), wl.id))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("TIME-1".to_string(), "10".to_string()),
+                ("ABC-2".to_string(), "20".to_string())
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn current_user_worklogs_on_date() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _server_info = mock_server_info(&mut server, "Cloud").await;
+        let search = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/search/jql").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "jql".to_string(),
+                "worklogAuthor = currentUser() AND worklogDate >= \"2024-02-14\" AND worklogDate <= \"2024-02-16\""
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"{"issues": [
+                    {"id": "1", "key": "TIME-1", "fields": {"summary": "Meetings", "components": []}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        let _myself = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://norn.atlassian.net/rest/api/3/user?accountId=me",
+                "accountId": "me", "displayName": "Me", "emailAddress": "me@dunk.com",
+                "timeZone": "Europe/Oslo"}"#,
+            )
+            .create_async()
+            .await;
+        // Work logs just before, during and just after the 15th in the local time zone
+        let date = NaiveDate::from_ymd_opt(2024, 2, 15).unwrap();
+        let worklog = |id: &str, started: DateTime<Local>| {
+            format!(
+                r#"{{"id": "{id}", "author": {{"accountId": "me", "displayName": "Me"}},
+                "started": "{}", "timeSpent": "1h", "timeSpentSeconds": 3600, "issueId": "1"}}"#,
+                started.format("%Y-%m-%dT%H:%M:%S%.3f%z")
+            )
+        };
+        let midnight = date
+            .and_time(NaiveTime::MIN)
+            .and_local_timezone(Local)
+            .unwrap();
+        let body = [
+            worklog("10", midnight - chrono::Duration::minutes(30)),
+            worklog("11", midnight),
+            worklog("12", midnight + chrono::Duration::hours(23)),
+            worklog("13", midnight + chrono::Duration::hours(24)),
+        ]
+        .join(",");
+        let _worklogs = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"startAt": 0, "maxResults": 5000, "total": 4, "worklogs": [{body}]}}"#
+            ))
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let worklogs = client.get_current_user_worklogs_on_date(date).await?;
+
+        search.assert_async().await;
+        let mut ids: Vec<String> = worklogs.into_iter().map(|(_, wl)| wl.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["11".to_string(), "12".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fault_holds_request_id() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _issue = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .with_header("X-ARequestId", "8f1c2d3e-request")
+            .with_body(r#"{"errorMessages": ["Field 'parent' is unknown"], "errors": {}}"#)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let error = client
+            .get_issue_summary(&IssueKey::from("TIME-1"))
+            .await
+            .unwrap_err();
+
+        let JiraError::Fault {
+            code, request_id, ..
+        } = &error
+        else {
+            panic!("Expected a fault, got {error:?}");
+        };
+        assert_eq!(code.as_u16(), 400);
+        assert_eq!(request_id.as_deref(), Some("8f1c2d3e-request"));
+        assert!(error
+            .to_string()
+            .contains("reference request id 8f1c2d3e-request"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_issue_puts_fields() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let update = server
+            .mock(
+                "PUT",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1").as_str(),
+            )
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "fields": {"summary": "Renamed"}
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        client
+            .set_summary(&IssueKey::from("TIME-1"), "Renamed")
+            .await?;
+        update.assert_async().await;
+
+        assert!(matches!(
+            client
+                .update_issue(&IssueKey::from("TIME-1"), serde_json::Map::new())
+                .await,
+            Err(JiraError::RequiredParameter(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn worklog_property_round_trip() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let property_url =
+            format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/10/properties/billing");
+        let value = serde_json::json!({"code": "B-42", "billable": true});
+        let set = server
+            .mock("PUT", property_url.as_str())
+            .match_body(mockito::Matcher::Json(value.clone()))
+            .with_status(201)
+            .create_async()
+            .await;
+        let _get = server
+            .mock("GET", property_url.as_str())
+            .with_status(200)
+            .with_body(serde_json::json!({"key": "billing", "value": value}).to_string())
+            .create_async()
+            .await;
+        let _keys = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/10/properties")
+                    .as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"keys": [{"self": "http://localhost/properties/billing", "key": "billing"}]}"#,
+            )
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        client
+            .set_worklog_property("TIME-1", "10", "billing", &value)
+            .await?;
+        set.assert_async().await;
+
+        assert_eq!(
+            client
+                .get_worklog_property("TIME-1", "10", "billing")
+                .await?,
+            value
+        );
+        assert_eq!(
+            client.list_worklog_property_keys("TIME-1", "10").await?,
+            vec!["billing".to_string()]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn insert_and_delete_worklog_honour_notify_users() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let worklog_body = r#"{"id": "10", "started": "2024-02-01T08:00:00.000+0000",
+            "timeSpentSeconds": 3600, "issueId": "1"}"#;
+        let silent_insert = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "notifyUsers".into(),
+                "false".into(),
+            ))
+            .with_status(201)
+            .with_body(worklog_body)
+            .create_async()
+            .await;
+        let notifying_insert = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::Missing)
+            .with_status(201)
+            .with_body(worklog_body)
+            .create_async()
+            .await;
+        let silent_delete = server
+            .mock(
+                "DELETE",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/10").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "notifyUsers".into(),
+                "false".into(),
+            ))
+            .with_status(204)
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .time_tracking_precheck(false)
+            .build()?;
+
+        let issue_key = IssueKey::from("TIME-1");
+        client
+            .insert_worklog(&issue_key, Local::now(), 3600, "Silent", false)
+            .await?;
+        client
+            .insert_worklog(&issue_key, Local::now(), 3600, "Notifying", true)
+            .await?;
+        client.delete_worklog(&issue_key, "10", false).await?;
+
+        silent_insert.assert_async().await;
+        notifying_insert.assert_async().await;
+        silent_delete.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn insert_worklog_fails_early_when_time_tracking_is_disabled() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let configuration = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/configuration").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"votingEnabled": true, "watchingEnabled": true, "unassignedIssuesAllowed": false,
+                "subTasksEnabled": true, "issueLinkingEnabled": true, "timeTrackingEnabled": false,
+                "attachmentsEnabled": true, "timeTrackingConfiguration": {"workingHoursPerDay": 8.0,
+                "workingDaysPerWeek": 5.0, "timeFormat": "pretty", "defaultUnit": "minute"}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let insert = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        for issue in ["TIME-1", "time-2"] {
+            match client
+                .insert_worklog(&IssueKey::from(issue), Local::now(), 3600, "Blocked", true)
+                .await
+            {
+                Err(JiraError::TimeTrackingDisabled(project_key)) => {
+                    assert_eq!(project_key, "TIME");
+                }
+                other => panic!("Expected JiraError::TimeTrackingDisabled, got {other:?}"),
+            }
+        }
+        configuration.assert_async().await;
+        insert.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_worklog_maps_not_found_to_worklog_not_found() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _missing = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/42").as_str(),
+            )
+            .with_status(404)
+            .with_body(r#"{"errorMessages": ["Cannot find worklog with id: 42."], "errors": {}}"#)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        match client.get_worklog("TIME-1", "42").await {
+            Err(JiraError::WorklogNotFound(issue_id, worklog_id)) => {
+                assert_eq!(issue_id, "TIME-1");
+                assert_eq!(worklog_id, "42");
+            }
+            other => panic!("Expected JiraError::WorklogNotFound, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unauthorized_and_forbidden_are_distinct() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _unauthorized = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(401)
+            .create_async()
+            .await;
+        let _forbidden = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .with_body(r#"{"errorMessages": ["You do not have the permission to see the specified issue."], "errors": {}}"#)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        assert!(matches!(
+            client.get_current_user().await,
+            Err(JiraError::Unauthorized)
+        ));
+        match client.get_issue_summary(&IssueKey::from("TIME-1")).await {
+            Err(JiraError::Forbidden { message }) => assert_eq!(
+                message,
+                "You do not have the permission to see the specified issue."
+            ),
+            other => panic!("Expected JiraError::Forbidden, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn time_tracking_options_are_cached() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let configuration = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/configuration").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"votingEnabled": true, "watchingEnabled": true, "unassignedIssuesAllowed": false,
+                "subTasksEnabled": true, "issueLinkingEnabled": true, "timeTrackingEnabled": true,
+                "attachmentsEnabled": true, "timeTrackingConfiguration": {"workingHoursPerDay": 8.0,
+                "workingDaysPerWeek": 5.0, "timeFormat": "pretty", "defaultUnit": "minute"}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let first = client.get_time_tracking_options().await?;
+        let second = client.clone().get_time_tracking_options().await?;
+        assert_eq!(first, second);
+        assert_eq!(second.seconds_per_day(), 28_800);
+        configuration.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bulk_delete_worklogs_treats_not_found_as_deleted() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let mut mocks = Vec::new();
+        for (worklog_id, status) in [("1", 204), ("2", 404), ("3", 403)] {
+            mocks.push(
+                server
+                    .mock(
+                        "DELETE",
+                        format!(
+                            "/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/{worklog_id}"
+                        )
+                        .as_str(),
+                    )
+                    .with_status(status)
+                    .with_body(if status == 403 {
+                        r#"{"errorMessages": ["You do not have permission to delete the worklog"]}"#
+                    } else {
+                        ""
+                    })
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let items: Vec<(IssueKey, String)> = ["1", "2", "3"]
+            .iter()
+            .map(|id| (IssueKey::from("TIME-1"), (*id).to_string()))
+            .collect();
+        let report = client.bulk_delete_worklogs(&items).await?;
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        assert_eq!(report.deleted, vec![items[0].clone()]);
+        assert_eq!(report.not_found, vec![items[1].clone()]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].1, "3");
+        assert_eq!(report.succeeded().count(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn find_or_create_issue_returns_existing_issue() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let search = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/search/jql").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "jql".to_string(),
+                "project = \"TIME\" AND summary ~ \"Say \\\"cheese\\\"\" AND resolution is EMPTY ORDER BY created"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"{"issues": [
+                    {"id": "1", "key": "TIME-1", "fields": {"summary": "Say cheese twice", "components": []}},
+                    {"id": "2", "key": "TIME-2", "fields": {"summary": "say \"cheese\"", "components": []}}
+                ]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let create = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue").as_str(),
+            )
+            .expect(0)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let issue = client
+            .find_or_create_issue(
+                &JiraProjectKey {
+                    key: "TIME".to_string(),
+                },
+                "Say \"cheese\"",
+                None,
+                vec![],
+            )
+            .await?;
+
+        assert_eq!(issue.key, IssueKey::from("TIME-2"));
+        assert_eq!(issue.id, "2");
+        search.assert_async().await;
+        create.assert_async().await;
+        Ok(())
+    }
+
+    #[test]
+    fn credentials_debug_redacts_secrets() -> Result<()> {
+        let bearer = Credentials::Bearer("s3cr3t-t0k3n".to_string());
+        assert_eq!(format!("{bearer:?}"), "Bearer(****)");
+
+        let basic = Credentials::Basic("ola@dunk.com".to_string(), "p4ssw0rd".to_string());
+        assert_eq!(format!("{basic:?}"), r#"Basic("ola@dunk.com", ****)"#);
+
+        let jira = Jira::new("http://localhost", bearer)?;
+        assert!(!format!("{jira:?}").contains("s3cr3t-t0k3n"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_issue_summaries_requires_a_filter() -> Result<()> {
+        let client = Jira::new("http://localhost", Credentials::Anonymous)?;
+        let result = client.get_issue_summaries(&[], &[], true).await;
+        assert!(
+            matches!(&result, Err(JiraError::RequiredParameter(name)) if name.contains("project_filter")),
+            "Expected a missing filter to be refused, got {result:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_all_issue_summaries_searches_every_project() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let search = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/search/jql").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "jql".to_string(),
+                "worklogAuthor = currentUser()".to_string(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"{"issues": [
+                    {"id": "1", "key": "TIME-1", "fields": {"summary": "Meetings", "components": []}},
+                    {"id": "2", "key": "ABC-2", "fields": {"summary": "Coding", "components": []}}
+                ]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let result = client.get_all_issue_summaries(false, None).await?;
+
+        search.assert_async().await;
+        assert_eq!(result.issues.len(), 2);
+        assert!(!result.truncated);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn insert_and_delete_worklog_by_issue_key() -> Result<()> {
+        let mut server = Server::new_async().await;
+        // Jira accepts the numeric id of the issue in place of the key
+        let insert = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/10001/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::Missing)
+            .with_status(201)
+            .with_body(
+                r#"{"id": "20", "started": "2024-02-01T08:00:00.000+0000",
+                "timeSpentSeconds": 1800, "issueId": "10001"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let delete = server
+            .mock(
+                "DELETE",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/20").as_str(),
+            )
+            .with_status(204)
+            .expect(2)
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .time_tracking_precheck(false)
+            .build()?;
+
+        let worklog = client
+            .insert_worklog(&IssueKey::from("10001"), Local::now(), 1800, "", true)
+            .await?;
+        assert_eq!(worklog.id, "20");
+        client
+            .delete_worklog(&IssueKey::from("time-1"), &worklog.id, true)
+            .await?;
+        #[allow(deprecated)]
+        client
+            .delete_worklog_by_id("TIME-1".to_string(), worklog.id, true)
+            .await?;
+
+        insert.assert_async().await;
+        delete.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn worklog_mutations_require_an_issue() -> Result<()> {
+        let client = Jira::new("http://localhost:1", Credentials::Anonymous)?;
+        assert!(matches!(
+            client
+                .insert_worklog(&IssueKey::default(), Local::now(), 60, "", true)
+                .await,
+            Err(JiraError::RequiredParameter(_))
+        ));
+        assert!(matches!(
+            client
+                .insert_worklog_by_id(" ", Local::now(), 60, "", true)
+                .await,
+            Err(JiraError::RequiredParameter(_))
+        ));
+        assert!(matches!(
+            client
+                .delete_worklog_by_id(String::new(), "1".to_string(), true)
+                .await,
+            Err(JiraError::RequiredParameter(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_work_logs_for_issue_drops_worklogs_received_twice() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let worklog = |id: &str| {
+            format!(
+                r#"{{"id": "{id}", "started": "2024-02-02T08:00:00.000+0000", "timeSpent": "1h",
+                "timeSpentSeconds": 3600, "issueId": "1"}}"#
+            )
+        };
+        // A work log added between the requests shifts "11" from the first to the second page
+        let first_page = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "0".into()))
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"startAt": 0, "maxResults": 2, "total": 3, "worklogs": [{},{}]}}"#,
+                worklog("10"),
+                worklog("11")
+            ))
+            .create_async()
+            .await;
+        let second_page = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "2".into()))
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"startAt": 2, "maxResults": 2, "total": 4, "worklogs": [{}]}}"#,
+                worklog("11")
+            ))
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let worklogs = client
+            .get_work_logs_for_issue("TIME-1", Local::now().naive_utc(), None)
+            .await?;
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+        let ids: Vec<&str> = worklogs.iter().map(|wl| wl.id.as_str()).collect();
+        assert_eq!(ids, vec!["10", "11"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_work_logs_for_issue_stops_at_is_last() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let worklog = |id: &str| {
+            format!(
+                r#"{{"id": "{id}", "started": "2024-02-02T08:00:00.000+0000", "timeSpent": "1h",
+                "timeSpentSeconds": 3600, "issueId": "1"}}"#
+            )
+        };
+        // A full page, which would otherwise be followed by a request for the next one
+        let page = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "startAt".into(),
+                "0".into(),
+            ))
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"startAt": 0, "maxResults": 2, "total": 2, "isLast": true, "worklogs": [{},{}]}}"#,
+                worklog("10"),
+                worklog("11")
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let next_page = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::UrlEncoded("startAt".into(), "2".into()))
+            .expect(0)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let worklogs = client
+            .get_work_logs_for_issue("TIME-1", Local::now().naive_utc(), None)
+            .await?;
+
+        page.assert_async().await;
+        next_page.assert_async().await;
+        assert_eq!(worklogs.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stats_reflect_rate_limit_headers() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let myself = format!("/rest/api/{DEFAULT_API_VERSION}/myself");
+        // Surfaces the 429 response below, rather than retrying it
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .max_retries(0)
+            .build()?;
+        assert_eq!(client.stats(), JiraStats::default());
+
+        let _ok = server
+            .mock("GET", myself.as_str())
+            .with_status(200)
+            .with_header("X-RateLimit-Remaining", "42")
+            .with_body(
+                r#"{"self": "https://norn.atlassian.net/rest/api/3/user?accountId=abc",
+                "accountId": "abc", "displayName": "Ola", "emailAddress": "ola@dunk.com",
+                "timeZone": "Europe/Oslo"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        client.get_current_user().await?;
+        let stats = client.stats();
+        assert_eq!(stats.responses, 1);
+        assert_eq!(stats.rate_limit_remaining, Some(42));
+        assert_eq!(stats.retry_after, None);
+
+        let _throttled = server
+            .mock("GET", myself.as_str())
+            .with_status(429)
+            .with_header("X-RateLimit-Remaining", "0")
+            .with_header("Retry-After", "30")
+            .with_body(r#"{"errorMessages": ["Rate limit exceeded"]}"#)
+            .create_async()
+            .await;
+        assert!(client.clone().get_current_user().await.is_err());
+        // Clones share the stats
+        let stats = client.stats();
+        assert_eq!(stats.responses, 2);
+        assert_eq!(stats.rate_limit_remaining, Some(0));
+        assert_eq!(stats.retry_after, Some(Duration::from_secs(30)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_is_retried_when_rate_limited() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let myself = format!("/rest/api/{DEFAULT_API_VERSION}/myself");
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let throttled = server
+            .mock("GET", myself.as_str())
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", myself.as_str())
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://norn.atlassian.net/rest/api/3/user?accountId=abc",
+                "accountId": "abc", "displayName": "Ola", "emailAddress": "ola@dunk.com",
+                "timeZone": "Europe/Oslo"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        assert_eq!(client.get_current_user().await?.account_id, "abc");
+        throttled.assert_async().await;
+        ok.assert_async().await;
+        assert_eq!(client.stats().responses, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_gives_up_when_retries_are_exhausted() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .max_retries(1)
+            .build()?;
+
+        let throttled = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(2)
+            .create_async()
+            .await;
+        assert!(matches!(
+            client.get_current_user().await,
+            Err(JiraError::RateLimited {
+                retry_after: Some(retry_after)
+            }) if retry_after == Duration::ZERO
+        ));
+        throttled.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_path_has_configured_api_version() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .api_version("2")
+            .build()?;
+
+        let myself = server
+            .mock("GET", "/rest/api/2/myself")
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://jira.example.com/rest/api/2/user?username=ola",
+                "accountId": "abc", "displayName": "Ola", "emailAddress": "ola@dunk.com",
+                "timeZone": "Europe/Oslo"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        client.get_current_user().await?;
+        myself.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_worklog_puts_new_values() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let started = Local.with_ymd_and_hms(2024, 2, 1, 9, 0, 0).unwrap();
+        let update = server
+            .mock(
+                "PUT",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/10").as_str(),
+            )
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "timeSpentSeconds": 5400,
+                "comment": "Longer meeting",
+                "started": started.format("%Y-%m-%dT%H:%M:%S.%3f%z").to_string(),
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"id": "10", "started": "2024-02-01T08:00:00.000+0000",
+                "timeSpentSeconds": 5400, "issueId": "1", "comment": "Longer meeting"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+        let issue_key = IssueKey::from("TIME-1");
+
+        let worklog = client
+            .update_worklog(&issue_key, "10", started, 5400, "Longer meeting", true)
+            .await?;
+        assert_eq!(worklog.id, "10");
+        assert_eq!(worklog.timeSpentSeconds, 5400);
+        update.assert_async().await;
+
+        assert!(matches!(
+            client
+                .update_worklog(&issue_key, " ", started, 5400, "", true)
+                .await,
+            Err(JiraError::RequiredParameter(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_credentials_acts_as_the_new_user() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let myself = format!("/rest/api/{DEFAULT_API_VERSION}/myself");
+        let user = |account_id: &str| {
+            format!(
+                r#"{{"self": "https://norn.atlassian.net/rest/api/3/user?accountId={account_id}",
+                "accountId": "{account_id}", "displayName": "{account_id}",
+                "emailAddress": "{account_id}@dunk.com", "timeZone": "Europe/Oslo"}}"#
+            )
+        };
+        let _old = server
+            .mock("GET", myself.as_str())
+            .match_header("authorization", "Bearer old-token")
+            .with_status(200)
+            .with_body(user("old"))
+            .create_async()
+            .await;
+        let new = server
+            .mock("GET", myself.as_str())
+            .match_header("authorization", "Bearer new-token")
+            .with_status(200)
+            .with_body(user("new"))
+            .expect(1)
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Bearer("old-token".to_string()))
+            .build()?;
+        client
+            .time_tracking_by_project
+            .lock()
+            .unwrap()
+            .insert("TIME".to_string(), true);
+        assert_eq!(client.get_current_user().await?.account_id, "old");
+
+        let switched = client.with_credentials(Credentials::Bearer("new-token".to_string()));
+        assert_eq!(switched.get_current_user().await?.account_id, "new");
+        new.assert_async().await;
+        assert!(switched.time_tracking_by_project.lock().unwrap().is_empty());
+        assert_eq!(switched.stats().responses, 1);
+
+        // The original client is left as it was
+        assert_eq!(client.get_current_user().await?.account_id, "old");
+        assert!(client.time_tracking_by_project.lock().unwrap()["TIME"]);
+        assert_eq!(client.stats().responses, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_updated_worklog_ids_follows_the_pages() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let updated = format!("/rest/api/{DEFAULT_API_VERSION}/worklog/updated");
+        let since = DateTime::from_timestamp_millis(1_438_013_600_000).unwrap();
+        let first = server
+            .mock("GET", updated.as_str())
+            .match_query(mockito::Matcher::UrlEncoded(
+                "since".into(),
+                "1438013600000".into(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"{"values": [{"worklogId": 103, "updatedTime": 1438013671562, "properties": []},
+                    {"worklogId": 104, "updatedTime": 1438013693136, "properties": []}],
+                "since": 1438013600000, "until": 1438013693136, "lastPage": false,
+                "nextPage": "https://norn.atlassian.net/rest/api/3/worklog/updated?since=1438013693136"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let last = server
+            .mock("GET", updated.as_str())
+            .match_query(mockito::Matcher::UrlEncoded(
+                "since".into(),
+                "1438013693136".into(),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"{"values": [{"worklogId": 105, "updatedTime": 1438013700000, "properties": []}],
+                "since": 1438013693136, "until": 1438013700000, "lastPage": true}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let ids = client.get_updated_worklog_ids(since).await?;
+        assert_eq!(ids, ["103", "104", "105"]);
+        first.assert_async().await;
+        last.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_worklogs_bulk_posts_the_ids() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let list = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/worklog/list").as_str(),
+            )
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"ids": [103, 105]}),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"[{"id": "103", "started": "2024-02-01T08:00:00.000+0000",
+                    "timeSpentSeconds": 3600, "issueId": "1"},
+                {"id": "105", "started": "2024-02-02T08:00:00.000+0000",
+                    "timeSpentSeconds": 1800, "issueId": "2"}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let worklogs = client
+            .get_worklogs_bulk(&["103".to_string(), "bogus".to_string(), "105".to_string()])
+            .await?;
+        assert_eq!(
+            worklogs.iter().map(|w| w.id.as_str()).collect::<Vec<_>>(),
+            ["103", "105"]
+        );
+        list.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn slow_response_times_out() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _slow = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(200)
+            .with_chunked_body(|w| {
+                std::thread::sleep(Duration::from_millis(1500));
+                w.write_all(b"{}")
+            })
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .timeout(1)
+            .build()?;
+
+        let result = client.get_current_user().await;
+        assert!(matches!(&result, Err(JiraError::Timeout(_))), "{result:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unknown_search_fields_are_found_once_fields_are_cached() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let fields = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/field").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"[{"id": "summary", "key": "summary", "name": "Summary", "custom": false},
+                {"id": "customfield_10010", "key": "customfield_10010", "name": "Time code",
+                    "custom": true}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let search = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/search/jql").as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"issues": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .validate_search_fields(true)
+            .build()?;
+
+        // The unknown field is reported, without failing the search
+        let result: JqlSearchResult<IssueSummary> = client
+            .fetch_with_jql_limited("project = TIME", vec!["key", "summary", "sumary"], None)
+            .await?;
+        assert!(result.issues.is_empty());
+        assert_eq!(result.unknown_fields, ["sumary"]);
+        assert_eq!(
+            client
+                .unknown_fields(&[
+                    "summary",
+                    "customfield_10010",
+                    "time code",
+                    "-sumary",
+                    "*all"
+                ])
+                .await?,
+            ["sumary"]
+        );
+        fields.assert_async().await;
+        search.assert_async().await;
+        Ok(())
+    }
+
+    #[test]
+    fn status_errors_are_displayed() {
+        for (error, context) in [
+            (JiraError::Unauthorized, "401"),
+            (JiraError::MethodNotAllowed, "405"),
+            (JiraError::UnexpectedStatus, "unexpected HTTP status"),
+        ] {
+            let message = error.to_string();
+            assert!(message.contains(context), "{message}");
+        }
+    }
+
+    #[tokio::test]
+    async fn error_source_is_the_underlying_error() {
+        let request_error = reqwest::Client::new()
+            .get("not a url")
+            .send()
+            .await
+            .unwrap_err();
+        let error = JiraError::from(request_error);
+        assert!(matches!(error, JiraError::RequestError(_)));
+        assert!(error.source().is_some());
+
+        let error = JiraError::from(serde_json::from_str::<User>("{").unwrap_err());
+        assert!(error.source().is_some());
+        assert!(JiraError::Unauthorized.source().is_none());
+    }
+
+    #[tokio::test]
+    async fn same_host_redirects_are_followed_with_the_credentials() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _moved = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(302)
+            .with_header("Location", "/jira/rest/api/latest/myself")
+            .create_async()
+            .await;
+        let _myself = server
+            .mock("GET", "/jira/rest/api/latest/myself")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://norn.atlassian.net/rest/api/3/user?accountId=abc",
+                "accountId": "abc", "displayName": "Ola", "emailAddress": "ola@dunk.com",
+                "timeZone": "Europe/Oslo"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Bearer("secret".to_string()))
+            .build()?;
+
+        assert_eq!(client.get_current_user().await?.account_id, "abc");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cross_host_redirect_is_a_descriptive_error() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let canonical = Server::new_async().await;
+        let _moved = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .with_status(301)
+            .with_header(
+                "Location",
+                &format!("{}/rest/api/latest/myself", canonical.url()),
+            )
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Bearer("secret".to_string()))
+            .build()?;
+
+        let err = client.get_current_user().await.unwrap_err();
+        let JiraError::CrossHostRedirect { ref to, .. } = err else {
+            panic!("Expected a cross host redirect, got {err:?}");
+        };
+        assert!(to.starts_with(&canonical.url()), "{to}");
+        assert!(
+            err.to_string().contains(&canonical.url()),
+            "The configured URL should be corrected: {err}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_work_logs_for_issue_is_bounded_by_started_before() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let started_after = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let started_before = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        let worklogs = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded(
+                    "startedAfter".into(),
+                    started_after.timestamp_millis().to_string(),
+                ),
+                mockito::Matcher::UrlEncoded(
+                    "startedBefore".into(),
+                    started_before.timestamp_millis().to_string(),
+                ),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"startAt": 0, "maxResults": 5000, "total": 0, "worklogs": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let result = client
+            .get_work_logs_for_issue(
+                "TIME-1",
+                started_after.naive_utc(),
+                Some(started_before.naive_utc()),
+            )
+            .await?;
+
+        worklogs.assert_async().await;
+        assert!(result.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn insert_worklog_on_non_editable_issue_is_a_typed_error() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let _worklog = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .with_status(400)
+            .with_body(
+                r#"{"errorMessages": ["You can not edit the issue as it is in a non-editable workflow state."],
+                "errors": {}}"#,
+            )
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .time_tracking_precheck(false)
+            .build()?;
+
+        let err = client
+            .insert_worklog(&IssueKey::from("TIME-1"), Local::now(), 3600, "", true)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, JiraError::IssueNotEditable { key } if key.as_str() == "TIME-1"),
+            "Expected IssueNotEditable, got {err:?}"
+        );
+        assert!(err.to_string().contains("TIME-1 is not editable"));
+
+        // Other faults are left as they are
+        let _invalid = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-2/worklog").as_str(),
+            )
+            .with_status(400)
+            .with_body(r#"{"errorMessages": [], "errors": {"timeLogged": "Invalid time duration entered."}}"#)
+            .create_async()
+            .await;
+        let err = client
+            .insert_worklog(&IssueKey::from("TIME-2"), Local::now(), 0, "", true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, JiraError::Fault { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_comment_posts_document_format_to_version_3() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let comment = server
+            .mock("POST", "/rest/api/3/issue/TIME-1/comment")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "body": {"type": "doc", "version": 1, "content": [
+                    {"type": "paragraph", "content": [{"type": "text", "text": "Blocked by review"}]},
+                    {"type": "paragraph", "content": []},
+                    {"type": "paragraph", "content": [{"type": "text", "text": "Waiting for Ola"}]}
+                ]}
+            })))
+            .with_status(201)
+            .with_body(r#"{"id": "10000", "created": "2024-02-01T08:00:00.000+0000"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .api_version("3")
+            .build()?;
+
+        let response = client
+            .add_comment(
+                &IssueKey::from("TIME-1"),
+                "Blocked by review\n\nWaiting for Ola",
+            )
+            .await?;
+
+        comment.assert_async().await;
+        assert_eq!(response.id, "10000");
+        assert_eq!(
+            response.created,
+            Utc.with_ymd_and_hms(2024, 2, 1, 8, 0, 0).unwrap()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_comment_posts_plain_text_to_version_2() -> Result<()> {
+        let mut server = Server::new_async().await;
+        let comment = server
+            .mock(
+                "POST",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/comment").as_str(),
+            )
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"body": "Blocked by review"}),
+            ))
+            .with_status(201)
+            .with_body(r#"{"id": "10000", "created": "2024-02-01T08:00:00.000+0000"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        client
+            .add_comment(&IssueKey::from("TIME-1"), "Blocked by review")
+            .await?;
+        comment.assert_async().await;
+
+        let err = client
+            .add_comment(&IssueKey::from("TIME-1"), " ")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, JiraError::RequiredParameter(p) if p == "body"));
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
+use crate::models::issue::{IssueStatus, Resolution};
 use crate::models::project::Component;
 use serde::{
     de::{self, Visitor},
@@ -10,18 +11,45 @@ use std::{
 };
 
 /// Represents the author (user) of a worklog item
-#[derive(Debug, Deserialize, Serialize, PartialOrd, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Default)]
 #[allow(non_snake_case)]
 pub struct Author {
+    /// Empty on older Jira Data Center instances, which identify users by `name` and `key`
+    #[serde(default)]
     pub accountId: String,
     pub emailAddress: Option<String>,
     pub displayName: String,
+    /// User name, only supplied by Jira Data Center
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// User key, only supplied by Jira Data Center
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq, Eq, Hash, Ord)]
 pub struct Fields {
     pub summary: String,
     pub components: Vec<Component>,
+    /// The parent of a subtask
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<IssueRef>,
+    /// Only supplied if the `status` field is requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<IssueStatus>,
+    /// Empty for unresolved issues, or if the `resolution` field is not requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<Resolution>,
+    /// Empty for unassigned issues, or if the `assignee` field is not requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<Author>,
+}
+
+/// A reference to another issue, like the parent of a subtask
+#[derive(Clone, Debug, Deserialize, Serialize, PartialOrd, PartialEq, Eq, Hash, Ord)]
+pub struct IssueRef {
+    pub id: String,
+    pub key: IssueKey,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -64,6 +92,28 @@ impl IssueKey {
         &self.value
     }
 
+    /// The key of the project the issue belongs to, i.e. `TIME` of `TIME-148`.
+    ///
+    /// The key is split at the last dash, hence `AB-CD-12` belongs to project `AB-CD`.
+    /// A key without a dash, like an issue id, is returned as is.
+    #[must_use]
+    pub fn project_key(&self) -> &str {
+        match self.value.rsplit_once('-') {
+            Some((project_key, _)) if !project_key.is_empty() => project_key,
+            _ => &self.value,
+        }
+    }
+
+    /// The sequence number of the issue within its project, i.e. `148` of `TIME-148`.
+    /// Returns `None` unless the key is a project key followed by a dash and a number.
+    #[must_use]
+    pub fn number(&self) -> Option<u64> {
+        match self.value.rsplit_once('-') {
+            Some((project_key, number)) if !project_key.is_empty() => number.parse().ok(),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn len(&self) -> usize {
         self.value.len()
@@ -74,6 +124,12 @@ impl IssueKey {
     }
 }
 
+impl AsRef<str> for IssueKey {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
 impl Hash for IssueKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.value.hash(state);
@@ -173,4 +229,80 @@ mod tests {
         let k1 = IssueKey::from("time-147");
         assert_eq!(k1.to_string(), "TIME-147".to_string());
     }
+
+    #[test]
+    fn test_project_key_and_number() {
+        let key = IssueKey::from("time-148");
+        assert_eq!(key.project_key(), "TIME");
+        assert_eq!(key.number(), Some(148));
+
+        // Split at the last dash
+        let key = IssueKey::from("AB-CD-12");
+        assert_eq!(key.project_key(), "AB-CD");
+        assert_eq!(key.number(), Some(12));
+    }
+
+    #[test]
+    fn test_project_key_and_number_of_malformed_keys() {
+        let key = IssueKey::from("TIME");
+        assert_eq!(key.project_key(), "TIME");
+        assert_eq!(key.number(), None);
+
+        let key = IssueKey::from("TIME-abc");
+        assert_eq!(key.project_key(), "TIME");
+        assert_eq!(key.number(), None);
+
+        let key = IssueKey::from("-12");
+        assert_eq!(key.project_key(), "-12");
+        assert_eq!(key.number(), None);
+    }
+
+    #[test]
+    fn test_fields_with_parent() {
+        let fields: Fields = serde_json::from_str(
+            r#"{"summary": "Subtask", "components": [],
+                "parent": {"id": "10000", "key": "TIME-1", "fields": {"summary": "Parent"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(fields.parent.unwrap().key, IssueKey::from("TIME-1"));
+
+        let fields: Fields =
+            serde_json::from_str(r#"{"summary": "Standalone", "components": []}"#).unwrap();
+        assert!(fields.parent.is_none());
+    }
+
+    #[test]
+    fn test_fields_with_status_and_resolution() {
+        let fields: Fields = serde_json::from_str(
+            r#"{"summary": "Closed", "components": [],
+                "status": {"id": "6", "name": "Closed", "statusCategory": {"key": "done"}},
+                "resolution": {"id": "1", "name": "Done"}}"#,
+        )
+        .unwrap();
+        assert_eq!(fields.status.unwrap().name, "Closed");
+        assert_eq!(fields.resolution.unwrap().name, "Done");
+
+        let fields: Fields = serde_json::from_str(
+            r#"{"summary": "Open", "components": [], "status": {"name": "To Do"}, "resolution": null}"#,
+        )
+        .unwrap();
+        assert!(fields.resolution.is_none());
+    }
+
+    #[test]
+    fn test_fields_with_assignee() {
+        let fields: Fields = serde_json::from_str(
+            r#"{"summary": "Assigned", "components": [],
+                "assignee": {"self": "https://norn.atlassian.net/rest/api/2/user?accountId=abc",
+                    "accountId": "abc", "displayName": "Ola Dunk", "active": true}}"#,
+        )
+        .unwrap();
+        assert_eq!(fields.assignee.unwrap().displayName, "Ola Dunk");
+
+        let fields: Fields = serde_json::from_str(
+            r#"{"summary": "Unassigned", "components": [], "assignee": null}"#,
+        )
+        .unwrap();
+        assert!(fields.assignee.is_none());
+    }
 }
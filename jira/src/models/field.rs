@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// A system or custom field of the issues, as returned by `/field`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldDefinition {
+    /// Like `summary` or `customfield_10010`, which is used to request the field
+    pub id: String,
+    /// Only supplied by Jira Cloud, where it equals the id for most fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub custom: bool,
+}
+
+impl FieldDefinition {
+    /// Is this the field requested as `field`? The id and key are compared as is, while
+    /// the name is compared ignoring case.
+    #[must_use]
+    pub fn is_named(&self, field: &str) -> bool {
+        self.id == field
+            || self.key.as_deref() == Some(field)
+            || self.name.eq_ignore_ascii_case(field)
+    }
+}
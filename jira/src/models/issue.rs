@@ -1,6 +1,7 @@
 use super::{core::IssueKey, worklog::Worklog};
 use crate::models::core::Fields;
 use crate::models::project::JiraProjectKey;
+use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize};
 
@@ -14,6 +15,8 @@ where
     pub issues: Vec<T>,
     #[serde(rename = "nextPageToken")] // Ensure field matches the JSON representation
     pub next_page_token: Option<String>,
+    /// Total number of matching issues, only supplied by some Jira versions
+    pub total: Option<i64>,
 }
 
 impl<T> IssuesResponse<T> where T: DeserializeOwned {}
@@ -33,16 +36,42 @@ where
             issues: Vec<T>,
             #[serde(rename = "nextPageToken")]
             next_page_token: Option<String>,
+            total: Option<i64>,
         }
 
         let internal = InternalIssuesResponse::deserialize(deserializer)?;
         Ok(IssuesResponse {
             issues: internal.issues,
             next_page_token: internal.next_page_token,
+            total: internal.total,
         })
     }
 }
 
+/// Body of the `POST /search/jql` request, used when the JQL query is too
+/// long to be sent in the query string.
+#[derive(Debug, Serialize)]
+pub struct JqlSearchRequest {
+    pub jql: String,
+    pub fields: Vec<String>,
+    #[serde(rename = "maxResults")]
+    pub max_results: i32,
+    #[serde(rename = "nextPageToken", skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+/// The result of a JQL search, which may have been cut short if the
+/// number of matching issues exceeded the requested maximum.
+#[derive(Debug)]
+pub struct JqlSearchResult<T> {
+    pub issues: Vec<T>,
+    /// `true` if more issues matched the query than were returned
+    pub truncated: bool,
+    /// The requested fields which Jira does not know of, and hence are absent from the
+    /// issues. Only found if `validate_search_fields` is enabled for the client.
+    pub unknown_fields: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialOrd, PartialEq, Ord, Eq)]
 #[allow(clippy::module_name_repetitions)]
 pub struct IssueSummary {
@@ -82,6 +111,26 @@ pub struct Issue {
     pub fields: Fields,
 }
 
+/// The workflow status of an issue, like `To Do` or `In Progress`
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq, Eq, Hash, Ord)]
+#[allow(clippy::module_name_repetitions)]
+pub struct IssueStatus {
+    pub name: String,
+}
+
+/// How an issue was resolved, like `Done` or `Won't Do`
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq, Eq, Hash, Ord)]
+pub struct Resolution {
+    pub name: String,
+}
+
+/// The comment added to an issue, see [`Jira::add_comment`](crate::Jira::add_comment)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentResponse {
+    pub id: String,
+    pub created: DateTime<Utc>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NewIssueResponse {
     pub id: String,
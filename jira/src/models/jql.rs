@@ -0,0 +1,186 @@
+//! Composes JQL queries from clauses, quoting and escaping the values supplied.
+//!
+//! ```
+//! use jira::models::jql::Jql;
+//!
+//! let jql = Jql::project_in(&["TIME", "ABC"]).and(Jql::worklog_author_is_current_user());
+//! assert_eq!(
+//!     jql.to_string(),
+//!     r#"project in ("TIME", "ABC") AND worklogAuthor = currentUser()"#
+//! );
+//! ```
+use crate::models::core::IssueKey;
+use chrono::NaiveDate;
+use std::fmt::{self, Display, Formatter};
+
+/// How the clauses of a query are combined, to decide where parentheses are needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    /// A single clause
+    None,
+    And,
+    Or,
+}
+
+/// A JQL query. Values are always quoted, hence user input can not alter the structure
+/// of the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jql {
+    query: String,
+    operator: Operator,
+}
+
+impl Jql {
+    fn clause(query: String) -> Self {
+        Jql {
+            query,
+            operator: Operator::None,
+        }
+    }
+
+    /// Issues of the projects, which must not be empty
+    #[must_use]
+    pub fn project_in<S: AsRef<str>>(projects: &[S]) -> Self {
+        Self::clause(format!("project in ({})", quote_all(projects)))
+    }
+
+    /// The issues, of which there must be at least one
+    #[must_use]
+    pub fn issue_key_in(issue_keys: &[IssueKey]) -> Self {
+        Self::clause(format!("issueKey in ({})", quote_all(issue_keys)))
+    }
+
+    /// Issues on which the current user has logged work
+    #[must_use]
+    pub fn worklog_author_is_current_user() -> Self {
+        Self::clause("worklogAuthor = currentUser()".to_string())
+    }
+
+    /// Issues on which anyone has logged work
+    #[must_use]
+    pub fn worklog_author_is_not_empty() -> Self {
+        Self::clause("worklogAuthor is not EMPTY".to_string())
+    }
+
+    /// Issues with work logged between `start` and `end`, both inclusive
+    #[must_use]
+    pub fn worklog_date_between(start: NaiveDate, end: NaiveDate) -> Self {
+        Self::clause(format!(
+            "worklogDate >= {} AND worklogDate <= {}",
+            quote(&start.format("%Y-%m-%d").to_string()),
+            quote(&end.format("%Y-%m-%d").to_string())
+        ))
+        .with_operator(Operator::And)
+    }
+
+    /// Issues with work logged on or after `start`
+    #[must_use]
+    pub fn worklog_date_from(start: NaiveDate) -> Self {
+        Self::clause(format!(
+            "worklogDate >= {}",
+            quote(&start.format("%Y-%m-%d").to_string())
+        ))
+    }
+
+    /// Issues matching both this and the `other` query
+    #[must_use]
+    pub fn and(self, other: Jql) -> Self {
+        self.combine(other, Operator::And)
+    }
+
+    /// Issues matching either this or the `other` query
+    #[must_use]
+    pub fn or(self, other: Jql) -> Self {
+        self.combine(other, Operator::Or)
+    }
+
+    fn with_operator(mut self, operator: Operator) -> Self {
+        self.operator = operator;
+        self
+    }
+
+    fn combine(self, other: Self, operator: Operator) -> Self {
+        let keyword = if operator == Operator::And {
+            "AND"
+        } else {
+            "OR"
+        };
+        Jql {
+            query: format!(
+                "{} {keyword} {}",
+                self.into_operand(operator),
+                other.into_operand(operator)
+            ),
+            operator,
+        }
+    }
+
+    /// The query as an operand of `operator`, in parentheses if it combines clauses differently
+    fn into_operand(self, operator: Operator) -> String {
+        if self.operator == Operator::None || self.operator == operator {
+            self.query
+        } else {
+            format!("({})", self.query)
+        }
+    }
+}
+
+impl Display for Jql {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.query)
+    }
+}
+
+/// Quotes a value, escaping backslashes and double quotes within it
+#[must_use]
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn quote_all<S: AsRef<str>>(values: &[S]) -> String {
+    values
+        .iter()
+        .map(|value| quote(value.as_ref()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_escapes_quotes_and_backslashes() {
+        let jql = Jql::project_in(&[r#"Bob's "secret" project"#, r"back\slash"]);
+        assert_eq!(
+            jql.to_string(),
+            r#"project in ("Bob's \"secret\" project", "back\\slash")"#
+        );
+    }
+
+    #[test]
+    fn test_composition_of_clauses() {
+        let jql = Jql::project_in(&["TIME"])
+            .or(Jql::issue_key_in(&[
+                IssueKey::from("KT-1"),
+                IssueKey::from("KT-2"),
+            ]))
+            .and(Jql::worklog_author_is_current_user())
+            .and(Jql::worklog_date_between(
+                NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+            ));
+        assert_eq!(
+            jql.to_string(),
+            r#"(project in ("TIME") OR issueKey in ("KT-1", "KT-2")) AND worklogAuthor = currentUser() AND worklogDate >= "2024-02-01" AND worklogDate <= "2024-02-29""#
+        );
+
+        // AND binds tighter than OR, but the parentheses make it explicit
+        let jql = Jql::worklog_author_is_not_empty()
+            .or(Jql::project_in(&["A"]).and(Jql::project_in(&["B"])));
+        assert_eq!(
+            jql.to_string(),
+            r#"worklogAuthor is not EMPTY OR (project in ("A") AND project in ("B"))"#
+        );
+    }
+}
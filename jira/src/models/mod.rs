@@ -1,5 +1,7 @@
 pub mod core;
+pub mod field;
 pub mod issue;
+pub mod jql;
 pub mod project;
 pub mod setting;
 pub mod user;
@@ -34,9 +34,9 @@ pub struct Project {
 }
 
 // TODO: elaborate on JiraProjectKey
-#[derive(Serialize, Debug, Clone, Copy)]
+#[derive(Serialize, Debug, Clone)]
 pub struct JiraProjectKey {
-    pub key: &'static str,
+    pub key: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq, Eq, Hash, Ord)]
@@ -44,3 +44,24 @@ pub struct Component {
     pub id: String,
     pub name: String,
 }
+
+/// A page of the components of a project, as returned by `/project/{key}/component`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ComponentsPage {
+    #[serde(alias = "startAt")]
+    pub start_at: i32,
+    #[serde(alias = "maxResults")]
+    pub max_results: i32,
+    pub total: Option<i32>,
+    #[serde(alias = "isLast")]
+    pub is_last: Option<bool>,
+    pub values: Vec<Component>,
+}
+
+/// Components are returned as pages by Jira Cloud, while older servers return them all at once
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ComponentsResponse {
+    Page(ComponentsPage),
+    All(Vec<Component>),
+}
@@ -1,7 +1,8 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Represents the global Jira settings
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct GlobalSettings {
@@ -15,8 +16,16 @@ pub struct GlobalSettings {
     pub timeTrackingConfiguration: TimeTrackingConfiguration,
 }
 
+impl GlobalSettings {
+    /// Whether time tracking is enabled for the Jira instance
+    #[must_use]
+    pub fn time_tracking_enabled(&self) -> bool {
+        self.timeTrackingEnabled
+    }
+}
+
 /// Represents the time tracking configuration settings retrieved from Jira
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct TimeTrackingConfiguration {
     /// Holds the number of work hours per day, typically 7.5 in Norway
@@ -28,3 +37,53 @@ pub struct TimeTrackingConfiguration {
     /// What is the default unit
     pub defaultUnit: String,
 }
+
+impl TimeTrackingConfiguration {
+    /// The number of seconds in a working day, i.e. what `1d` amounts to
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn seconds_per_day(&self) -> i32 {
+        (self.workingHoursPerDay * 3600.0).round() as i32
+    }
+
+    /// The number of seconds in a working week, i.e. what `1w` amounts to
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn seconds_per_week(&self) -> i32 {
+        (self.workingDaysPerWeek * self.workingHoursPerDay * 3600.0).round() as i32
+    }
+}
+
+/// Represents the information about the Jira server, returned by `/serverInfo`
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ServerInfo {
+    pub baseUrl: String,
+    pub version: String,
+    /// Either `Cloud` or `Server`
+    pub deploymentType: Option<String>,
+    /// The current time on the Jira server
+    pub serverTime: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configuration(working_hours_per_day: f32) -> TimeTrackingConfiguration {
+        TimeTrackingConfiguration {
+            workingHoursPerDay: working_hours_per_day,
+            workingDaysPerWeek: 5.0,
+            timeFormat: "pretty".to_string(),
+            defaultUnit: "minute".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_seconds_per_day_and_week() {
+        assert_eq!(configuration(7.5).seconds_per_day(), 27_000);
+        assert_eq!(configuration(7.5).seconds_per_week(), 135_000);
+        assert_eq!(configuration(8.0).seconds_per_day(), 28_800);
+        assert_eq!(configuration(8.0).seconds_per_week(), 144_000);
+    }
+}
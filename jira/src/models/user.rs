@@ -1,10 +1,12 @@
+use crate::models::core::Author;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct User {
     #[serde(alias = "self")]
     pub self_url: String,
-    #[serde(alias = "accountId")]
+    /// Empty on older Jira Data Center instances
+    #[serde(alias = "accountId", default)]
     pub account_id: String,
     #[serde(alias = "emailAddress")]
     pub email_address: String,
@@ -12,4 +14,135 @@ pub struct User {
     pub display_name: String,
     #[serde(alias = "timeZone")]
     pub time_zone: String,
+    /// User name, only supplied by Jira Data Center
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// User key, only supplied by Jira Data Center
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+/// The identifier used for users, which depends on the deployment type of Jira.
+/// Jira Cloud identifies users by `accountId`, while Jira Server and Data Center use
+/// the user `name` and `key`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserIdentity {
+    AccountId,
+    Name,
+}
+
+impl UserIdentity {
+    /// Selects the identity from the `deploymentType` of the server info, only `Cloud` uses
+    /// the `accountId`. Older Jira Server versions do not supply the deployment type at all.
+    #[must_use]
+    pub fn from_deployment_type(deployment_type: Option<&str>) -> Self {
+        match deployment_type {
+            Some(deployment_type) if deployment_type.eq_ignore_ascii_case("cloud") => {
+                UserIdentity::AccountId
+            }
+            _ => UserIdentity::Name,
+        }
+    }
+
+    /// The identifier of the user, `None` if the user lacks it
+    #[must_use]
+    pub fn of_user(self, user: &User) -> Option<&str> {
+        match self {
+            UserIdentity::AccountId => Some(user.account_id.as_str()).filter(|id| !id.is_empty()),
+            UserIdentity::Name => user.name.as_deref(),
+        }
+    }
+
+    /// The identifier of the author, `None` if the author lacks it
+    #[must_use]
+    pub fn of_author(self, author: &Author) -> Option<&str> {
+        match self {
+            UserIdentity::AccountId => Some(author.accountId.as_str()).filter(|id| !id.is_empty()),
+            UserIdentity::Name => author.name.as_deref(),
+        }
+    }
+
+    /// Is the author the supplied user? On Jira Server and Data Center the user `key` is
+    /// compared if available on both sides, as it survives renaming the user.
+    #[must_use]
+    pub fn matches(self, author: &Author, user: &User) -> bool {
+        if self == UserIdentity::Name {
+            if let (Some(author_key), Some(user_key)) = (&author.key, &user.key) {
+                return author_key == user_key;
+            }
+        }
+        matches!((self.of_author(author), self.of_user(user)), (Some(a), Some(u)) if a == u)
+    }
+
+    /// The query parameter identifying a user in the REST API, like `/user?accountId=...`
+    #[must_use]
+    pub fn query_parameter(self) -> &'static str {
+        match self {
+            UserIdentity::AccountId => "accountId",
+            UserIdentity::Name => "username",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn author(json: &str) -> Author {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_cloud_identifies_users_by_account_id() {
+        let identity = UserIdentity::from_deployment_type(Some("Cloud"));
+        assert_eq!(identity, UserIdentity::AccountId);
+        assert_eq!(identity.query_parameter(), "accountId");
+
+        let user = User {
+            account_id: "abc".to_string(),
+            name: Some("ola".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(identity.of_user(&user), Some("abc"));
+        assert!(identity.matches(
+            &author(r#"{"accountId": "abc", "displayName": "Ola"}"#),
+            &user
+        ));
+        // The name is ignored on Cloud
+        assert!(!identity.matches(
+            &author(r#"{"accountId": "def", "displayName": "Ola", "name": "ola"}"#),
+            &user
+        ));
+        assert!(!identity.matches(&author(r#"{"displayName": "Ola"}"#), &User::default()));
+    }
+
+    #[test]
+    fn test_server_identifies_users_by_name() {
+        for deployment_type in [Some("Server"), None] {
+            assert_eq!(
+                UserIdentity::from_deployment_type(deployment_type),
+                UserIdentity::Name
+            );
+        }
+        let identity = UserIdentity::Name;
+        assert_eq!(identity.query_parameter(), "username");
+
+        let user = User {
+            name: Some("ola".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(identity.of_user(&user), Some("ola"));
+        assert!(identity.matches(&author(r#"{"displayName": "Ola", "name": "ola"}"#), &user));
+        assert!(!identity.matches(&author(r#"{"displayName": "Kari", "name": "kari"}"#), &user));
+        // The key takes precedence, as it is kept when the user is renamed
+        let renamed = User {
+            name: Some("ola.dunk".to_string()),
+            key: Some("JIRAUSER1".to_string()),
+            ..Default::default()
+        };
+        assert!(identity.matches(
+            &author(r#"{"displayName": "Ola", "name": "ola", "key": "JIRAUSER1"}"#),
+            &renamed
+        ));
+    }
 }
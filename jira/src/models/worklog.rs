@@ -1,29 +1,90 @@
-use super::core::Author;
+use super::core::{Author, IssueKey};
+use crate::JiraError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// A page of work logs. Work logs which could not be parsed are collected in `skipped`,
+/// rather than failing the entire page.
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "RawWorklogsPage")]
 #[allow(non_snake_case)]
 pub struct WorklogsPage {
     pub startAt: usize,
     #[serde(alias = "maxResults")]
     pub max_results: usize,
     pub total: usize,
+    /// Only supplied by some Jira instances, in which case it tells whether more pages follow
+    #[serde(rename = "isLast", default, skip_serializing_if = "Option::is_none")]
+    pub is_last: Option<bool>,
     pub worklogs: Vec<Worklog>,
+    #[serde(skip_serializing)]
+    pub skipped: Vec<SkippedWorklog>,
 }
 
+/// A work log of a page, which could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedWorklog {
+    /// The id of the work log, if present
+    pub id: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct RawWorklogsPage {
+    startAt: usize,
+    #[serde(alias = "maxResults")]
+    max_results: usize,
+    total: usize,
+    #[serde(rename = "isLast", default)]
+    is_last: Option<bool>,
+    worklogs: Vec<serde_json::Value>,
+}
+
+impl From<RawWorklogsPage> for WorklogsPage {
+    fn from(raw: RawWorklogsPage) -> Self {
+        let mut worklogs = Vec::with_capacity(raw.worklogs.len());
+        let mut skipped = Vec::new();
+        for value in raw.worklogs {
+            let id = value.get("id").and_then(|id| id.as_str()).map(String::from);
+            match serde_json::from_value::<Worklog>(value) {
+                Ok(worklog) => worklogs.push(worklog),
+                Err(e) => skipped.push(SkippedWorklog {
+                    id,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        WorklogsPage {
+            startAt: raw.startAt,
+            max_results: raw.max_results,
+            total: raw.total,
+            is_last: raw.is_last,
+            worklogs,
+            skipped,
+        }
+    }
+}
+
+/// A work log entry. Only the fields required to identify and place the work log in time
+/// are mandatory, as Jira omits some of the others for work logs created by certain integrations.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd)]
 #[allow(non_snake_case)]
 pub struct Worklog {
     pub id: String,
     // "557058:189520f0-d1fb-4a0d-b555-bc44ec1f4ebc"
+    #[serde(default)]
     pub author: Author,
+    #[serde(default)]
     pub created: DateTime<Utc>,
+    #[serde(default)]
     pub updated: DateTime<Utc>,
     pub started: DateTime<Utc>,
+    #[serde(default)]
     pub timeSpent: String,
     pub timeSpentSeconds: i32,
     pub issueId: String, // Numeric FK to issue
+    #[serde(default)]
     pub comment: Option<String>,
 }
 
@@ -34,3 +95,103 @@ pub struct Insert {
     pub started: String,
     pub timeSpentSeconds: i32,
 }
+
+/// A property of a work log, holding an arbitrary JSON value which Jira preserves
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorklogProperty {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+/// A page of the ids of work logs changed since a point in time, as returned by
+/// `/worklog/updated`
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdatedWorklogsPage {
+    pub values: Vec<UpdatedWorklog>,
+    /// Milliseconds since the epoch of the last change on the page, which is where the
+    /// next page starts
+    pub until: i64,
+    #[serde(rename = "lastPage", default = "default_last_page")]
+    pub last_page: bool,
+}
+
+/// Only pages known to be followed by others are not the last one
+fn default_last_page() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdatedWorklog {
+    #[serde(rename = "worklogId")]
+    pub worklog_id: u64,
+}
+
+/// The ids of the work logs to retrieve with `/worklog/list`
+#[derive(Debug, Serialize)]
+pub(crate) struct WorklogIds {
+    pub ids: Vec<u64>,
+}
+
+/// The keys of the properties of a work log
+#[derive(Debug, Deserialize)]
+pub(crate) struct PropertyKeys {
+    pub keys: Vec<PropertyKey>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PropertyKey {
+    pub key: String,
+}
+
+/// Outcome of deleting a batch of work logs, each identified by the issue key and the work log id
+#[derive(Debug, Default)]
+pub struct DeleteReport {
+    pub deleted: Vec<(IssueKey, String)>,
+    /// Work logs which did not exist in Jira, which is treated as a successful deletion
+    pub not_found: Vec<(IssueKey, String)>,
+    pub failed: Vec<(IssueKey, String, JiraError)>,
+}
+
+impl DeleteReport {
+    /// All the work logs which are no longer present in Jira
+    pub fn succeeded(&self) -> impl Iterator<Item = &(IssueKey, String)> {
+        self.deleted.iter().chain(self.not_found.iter())
+    }
+
+    #[must_use]
+    pub fn has_failures(&self) -> bool {
+        !self.failed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_with_incomplete_worklogs() {
+        let page: WorklogsPage = serde_json::from_str(
+            r#"{"startAt": 0, "maxResults": 5000, "total": 3, "worklogs": [
+                {"id": "1", "author": {"accountId": "abc", "displayName": "Ola"},
+                 "created": "2024-02-01T08:00:00.000+0000", "updated": "2024-02-01T08:00:00.000+0000",
+                 "started": "2024-02-01T08:00:00.000+0000", "timeSpent": "1h",
+                 "timeSpentSeconds": 3600, "issueId": "10", "comment": "Meeting"},
+                {"id": "2", "started": "2024-02-01T09:00:00.000+0000",
+                 "timeSpentSeconds": 1800, "issueId": "10"},
+                {"id": "3", "timeSpentSeconds": 1800, "issueId": "10"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(page.worklogs.len(), 2);
+        let incomplete = &page.worklogs[1];
+        assert_eq!(incomplete.id, "2");
+        assert!(incomplete.comment.is_none());
+        assert!(incomplete.timeSpent.is_empty());
+        assert_eq!(incomplete.author, Author::default());
+
+        assert_eq!(page.skipped.len(), 1);
+        assert_eq!(page.skipped[0].id.as_deref(), Some("3"));
+        assert!(page.skipped[0].reason.contains("started"));
+    }
+}
@@ -0,0 +1,62 @@
+//! Support for OAuth 2.0 (3LO) access tokens, which expire and must be refreshed.
+//!
+//! The refreshing itself is left to the caller, who supplies a [`RefreshFn`] exchanging
+//! the refresh token for a new [`OAuthToken`].
+use std::fmt::{self, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// Tokens expiring within this many seconds are refreshed before being used
+const EXPIRY_MARGIN_SECONDS: i64 = 30;
+
+/// An OAuth 2.0 access token along with the refresh token used to obtain a new one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OAuthToken {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl OAuthToken {
+    /// Has the token expired, or is it about to?
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - TimeDelta::seconds(EXPIRY_MARGIN_SECONDS) <= now
+    }
+}
+
+pub type RefreshFuture = Pin<
+    Box<dyn Future<Output = Result<OAuthToken, Box<dyn std::error::Error + Send + Sync>>> + Send>,
+>;
+
+/// Exchanges a refresh token for a new [`OAuthToken`], typically by calling
+/// `https://auth.atlassian.com/oauth/token`
+#[derive(Clone)]
+pub struct RefreshFn(Arc<dyn Fn(String) -> RefreshFuture + Send + Sync>);
+
+impl RefreshFn {
+    pub fn new<F, Fut>(refresh: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<OAuthToken, Box<dyn std::error::Error + Send + Sync>>>
+            + Send
+            + 'static,
+    {
+        RefreshFn(Arc::new(move |refresh_token| {
+            Box::pin(refresh(refresh_token))
+        }))
+    }
+
+    pub(crate) fn call(&self, refresh_token: String) -> RefreshFuture {
+        (self.0)(refresh_token)
+    }
+}
+
+impl fmt::Debug for RefreshFn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("RefreshFn")
+    }
+}
@@ -13,7 +13,7 @@ async fn test_create_issue() -> Result<(), Box<dyn std::error::Error>> {
     let new_issue = jira_client
         .create_issue(
             &JiraProjectKey {
-                key: TEST_PROJECT_KEY,
+                key: TEST_PROJECT_KEY.to_string(),
             },
             "Test issue",
             Some("Test description".to_string()),
@@ -35,7 +35,7 @@ async fn create_multiple_issues_fast_version() -> Result<(), Box<dyn std::error:
     let issue_keys = test_data::create_batch_of_issues(
         10,
         JiraProjectKey {
-            key: TEST_PROJECT_KEY,
+            key: TEST_PROJECT_KEY.to_string(),
         },
     )
     .await?;
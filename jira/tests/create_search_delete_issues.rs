@@ -34,7 +34,7 @@ async fn search_issues_test() -> Result<(), Box<dyn std::error::Error>> {
     let issues = test_data::create_batch_of_issues(
         3,
         JiraProjectKey {
-            key: TEST_PROJECT_KEY,
+            key: TEST_PROJECT_KEY.to_string(),
         },
     )
     .await?;
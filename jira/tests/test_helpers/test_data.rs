@@ -70,7 +70,7 @@ pub async fn create_batch_of_issues(
     let jira_client = jira_client::create();
 
     let first_component = jira_client
-        .get_components(jira_project_key.key)
+        .get_components(&jira_project_key.key)
         .await?
         .into_iter()
         .next()
@@ -80,7 +80,7 @@ pub async fn create_batch_of_issues(
     for _ in 0..qty {
         issue_futures.push(create_issue_task(
             jira_client.clone(),
-            jira_project_key,
+            jira_project_key.clone(),
             first_component.clone(),
         ));
     }
@@ -146,10 +146,11 @@ pub async fn add_random_work_logs_to_issues(
             for i in 0..worklog_qty_range.end {
                 let worklog = jira_client
                     .insert_worklog(
-                        jira_key.as_str(),
+                        jira_key,
                         random_datetime(),
                         random_number_seconds_in_steps_of_900(),
                         "Test worklog",
+                        true,
                     )
                     .await;
                 debug!("Added worklog {}/{}", i, worklog_qty_range.end);
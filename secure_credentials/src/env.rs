@@ -0,0 +1,66 @@
+use crate::CredentialStore;
+use std::env;
+use std::error::Error;
+
+/// The environment variable holding the token, unless another one is given to
+/// [`EnvCredentialStore::new`]
+pub const TOKEN_ENV_VAR: &str = "TIMESHEET_TOKEN";
+
+/// Reads the token from an environment variable, for platforms lacking a keychain and for
+/// running in containers. The same token is returned for every service and account, and
+/// the store is read only.
+#[derive(Debug, Clone)]
+pub struct EnvCredentialStore {
+    variable: String,
+}
+
+impl EnvCredentialStore {
+    #[must_use]
+    pub fn new(variable: &str) -> Self {
+        EnvCredentialStore {
+            variable: variable.to_string(),
+        }
+    }
+}
+
+impl Default for EnvCredentialStore {
+    fn default() -> Self {
+        EnvCredentialStore::new(TOKEN_ENV_VAR)
+    }
+}
+
+impl CredentialStore for EnvCredentialStore {
+    fn store(&self, _service: &str, _account: &str, _token: &str) -> Result<(), Box<dyn Error>> {
+        Err(format!("The token can not be stored in ${}", self.variable).into())
+    }
+
+    fn retrieve(&self, _service: &str, _account: &str) -> Result<String, Box<dyn Error>> {
+        match env::var(&self.variable) {
+            Ok(token) if !token.trim().is_empty() => Ok(token),
+            _ => Err(format!("${} holds no token", self.variable).into()),
+        }
+    }
+
+    fn delete(&self, _service: &str, _account: &str) -> Result<(), Box<dyn Error>> {
+        Err(format!("The token can not be deleted from ${}", self.variable).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_is_read_from_the_variable() {
+        // A variable of its own, as the tests run concurrently
+        let variable = format!("TIMESHEET_TEST_TOKEN_{}", std::process::id());
+        let store = EnvCredentialStore::new(&variable);
+        assert!(store.retrieve("service", "ola").is_err());
+
+        env::set_var(&variable, "a-token");
+        assert_eq!(store.retrieve("service", "ola").unwrap(), "a-token");
+        assert!(store.store("service", "ola", "another-token").is_err());
+        assert!(store.delete("service", "ola").is_err());
+        env::remove_var(&variable);
+    }
+}
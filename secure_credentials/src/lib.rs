@@ -1,2 +1,37 @@
+//! Storage of secret tokens, like the Jira access token, outside of the configuration file.
+//!
+//! The stores implement [`CredentialStore`], hence the application is not tied to a
+//! particular keychain, and may be tested with the in-memory [`MockStore`].
+use std::error::Error;
+
+mod env;
 #[cfg(target_os = "macos")]
 pub mod macos;
+mod mock;
+
+pub use env::{EnvCredentialStore, TOKEN_ENV_VAR};
+pub use mock::MockStore;
+
+/// A store of secret tokens, each identified by a `service` and the user `account`
+pub trait CredentialStore {
+    /// Stores the `token`, replacing the one already stored for `service` and `account`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the token could not be written to the store
+    fn store(&self, service: &str, account: &str, token: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Retrieves the token stored for `service` and `account`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if there is no such token, or the store could not be read
+    fn retrieve(&self, service: &str, account: &str) -> Result<String, Box<dyn Error>>;
+
+    /// Deletes the token stored for `service` and `account`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if there is no such token, or it could not be deleted
+    fn delete(&self, service: &str, account: &str) -> Result<(), Box<dyn Error>>;
+}
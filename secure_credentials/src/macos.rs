@@ -1,6 +1,26 @@
+use crate::CredentialStore;
 use security_framework::os::macos::keychain::SecKeychain;
 use security_framework::os::macos::passwords::find_generic_password;
 use std::error::Error;
+
+/// The default macOS keychain of the user
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeychainStore;
+
+impl CredentialStore for KeychainStore {
+    fn store(&self, service: &str, account: &str, token: &str) -> Result<(), Box<dyn Error>> {
+        store_secure_token(service, account, token)
+    }
+
+    fn retrieve(&self, service: &str, account: &str) -> Result<String, Box<dyn Error>> {
+        get_secure_token(service, account)
+    }
+
+    fn delete(&self, service: &str, account: &str) -> Result<(), Box<dyn Error>> {
+        delete_secure_token(service, account)
+    }
+}
+
 /// Store the `token` into the macos Keychain for the provided `service` with the user account
 /// identified by `account`
 ///
@@ -33,3 +53,14 @@ pub fn get_secure_token(service: &str, account: &str) -> Result<String, Box<dyn
 
     Ok(password_str)
 }
+
+/// Deletes the secure token associated with `service` and `account`
+///
+/// # Errors
+///
+/// Returns `Err` if there is no such token in the keychain
+pub fn delete_secure_token(service: &str, account: &str) -> Result<(), Box<dyn Error>> {
+    let (_, item) = find_generic_password(None, service, account)?;
+    item.delete();
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::CredentialStore;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Mutex, PoisonError};
+
+/// Keeps the tokens in memory, for tests which must not touch a keychain
+#[derive(Debug, Default)]
+pub struct MockStore {
+    tokens: Mutex<HashMap<(String, String), String>>,
+}
+
+impl MockStore {
+    /// A store holding the token of `service` and `account`
+    #[must_use]
+    pub fn with_token(service: &str, account: &str, token: &str) -> Self {
+        let store = MockStore::default();
+        store.insert(service, account, token);
+        store
+    }
+
+    fn insert(&self, service: &str, account: &str, token: &str) {
+        self.tokens
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                (service.to_string(), account.to_string()),
+                token.to_string(),
+            );
+    }
+}
+
+impl CredentialStore for MockStore {
+    fn store(&self, service: &str, account: &str, token: &str) -> Result<(), Box<dyn Error>> {
+        self.insert(service, account, token);
+        Ok(())
+    }
+
+    fn retrieve(&self, service: &str, account: &str) -> Result<String, Box<dyn Error>> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&(service.to_string(), account.to_string()))
+            .cloned()
+            .ok_or_else(|| format!("No token for {service} and {account}").into())
+    }
+
+    fn delete(&self, service: &str, account: &str) -> Result<(), Box<dyn Error>> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&(service.to_string(), account.to_string()))
+            .map(|_| ())
+            .ok_or_else(|| format!("No token for {service} and {account}").into())
+    }
+}
@@ -1,15 +1,21 @@
 use axum::routing::{get, post};
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Query, State},
+    http::{header::CONTENT_TYPE, StatusCode},
     response::{IntoResponse, Json, Response},
     Router,
 };
-use chrono::{Duration, Local};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use worklog::{error::WorklogError, types::LocalWorklog, ApplicationRuntime};
+use worklog::{
+    error::WorklogError,
+    types::{LocalWorklog, Timer},
+    ApplicationRuntime, ReadConn,
+};
 
 use serde_json::json;
 
@@ -31,6 +37,10 @@ impl IntoResponse for ServerError {
         let status_code = match self {
             ServerError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
             ServerError::BadRequest => StatusCode::BAD_REQUEST,
+            ServerError::WorklogError(WorklogError::NoActiveTimer) => StatusCode::NOT_FOUND,
+            ServerError::WorklogError(WorklogError::TimerDurationTooSmall(_)) => {
+                StatusCode::BAD_REQUEST
+            }
             ServerError::WorklogError(_worklog_error) => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
@@ -42,11 +52,11 @@ impl IntoResponse for ServerError {
 async fn get_worklogs(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<LocalWorklog>>, ServerError> {
-    // TODO: Consider removing this as the ApplicationRuntime should be thread safe now.
-    let runtime = state.runtime.lock().await;
+    // Reads do not wait for the writers, which hold the runtime
+    let read_conn = state.reads.get();
 
-    let keys = runtime.issue_service().find_unique_keys()?;
-    let worklogs = runtime.worklog_service().find_worklogs_after(
+    let keys = read_conn.find_unique_keys()?;
+    let worklogs = read_conn.find_worklogs_after(
         Local::now()
             .checked_sub_signed(Duration::days(365))
             .unwrap(),
@@ -58,25 +68,223 @@ async fn get_worklogs(
     Ok(Json(worklogs))
 }
 
-// Handler to handle POST requests to /worklog/timesheet
-async fn post_worklog(Json(payload): Json<LocalWorklog>) -> impl IntoResponse {
-    // Here you can process the timesheet data, such as saving it to a database
-    // For now, we will just print the received data
-    println!("Received timesheet data: {payload:?}");
+// Handler to handle POST requests to /api/worklogs
+async fn post_worklog(
+    State(state): State<AppState>,
+    Json(payload): Json<LocalWorklog>,
+) -> Result<impl IntoResponse, ServerError> {
+    // Writes go through the runtime, one at a time
+    let runtime = state.runtime.lock().await;
+    runtime.worklog_service().add_entry(&payload).await?;
 
     // Return a success message
-    (
+    Ok((
         StatusCode::OK,
         Json(json!({
             "message": "Timesheet successfully updated",
         })),
+    ))
+}
+
+/// A timer along with the time spent on it, up to now if it is still running
+#[derive(Debug, Serialize, Deserialize)]
+struct TimerView {
+    #[serde(flatten)]
+    timer: Timer,
+    duration_seconds: i64,
+}
+
+impl From<Timer> for TimerView {
+    fn from(timer: Timer) -> Self {
+        let duration_seconds =
+            (timer.stopped_at.unwrap_or_else(Local::now) - timer.started_at).num_seconds();
+        Self {
+            timer,
+            duration_seconds,
+        }
+    }
+}
+
+/// The active timer, if any, kept apart from the timers which have been stopped
+#[derive(Debug, Serialize, Deserialize)]
+struct TimersResponse {
+    active: Option<TimerView>,
+    timers: Vec<TimerView>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimersQuery {
+    /// Only timers started at or after this time are listed, the default is 30 days ago
+    since: Option<DateTime<Local>>,
+}
+
+async fn get_timers(
+    State(state): State<AppState>,
+    Query(query): Query<TimersQuery>,
+) -> Result<Json<TimersResponse>, ServerError> {
+    let read_conn = state.reads.get();
+
+    let since = query
+        .since
+        .unwrap_or_else(|| Local::now() - Duration::days(30));
+    let (active, stopped): (Vec<Timer>, Vec<Timer>) = read_conn
+        .find_timers_after(since)?
+        .into_iter()
+        .partition(Timer::is_active);
+    // The active timer is included even if it was started before `since`
+    let active = match active.into_iter().next() {
+        Some(timer) => Some(timer),
+        None => read_conn.find_active_timer()?,
+    };
+
+    Ok(Json(TimersResponse {
+        active: active.map(TimerView::from),
+        timers: stopped.into_iter().map(TimerView::from).collect(),
+    }))
+}
+
+/// Gauges exposed by `GET /metrics`, for dashboards
+#[derive(Debug, Default)]
+struct Metrics {
+    worklogs: i64,
+    logged_seconds_7d: i64,
+    logged_seconds_30d: i64,
+    unsynced_timers: usize,
+    database_bytes: u64,
+}
+
+impl Metrics {
+    fn collect(read_conn: &ReadConn) -> Result<Self, ServerError> {
+        let now = Local::now();
+        let week_ago = now - Duration::days(7);
+        let worklogs_30d = read_conn.find_worklogs_after(now - Duration::days(30), &[], &[])?;
+        let unsynced_timers = read_conn
+            .find_timers_after(DateTime::UNIX_EPOCH.with_timezone(&Local))?
+            .iter()
+            .filter(|timer| !timer.synced && !timer.is_active())
+            .count();
+        Ok(Self {
+            worklogs: read_conn.count_worklogs()?,
+            logged_seconds_7d: worklogs_30d
+                .iter()
+                .filter(|worklog| worklog.started > week_ago)
+                .map(|worklog| i64::from(worklog.timeSpentSeconds))
+                .sum(),
+            logged_seconds_30d: worklogs_30d
+                .iter()
+                .map(|worklog| i64::from(worklog.timeSpentSeconds))
+                .sum(),
+            unsynced_timers,
+            database_bytes: read_conn.database_size()?,
+        })
+    }
+
+    /// Formats the gauges in the Prometheus text exposition format
+    fn to_prometheus(&self) -> String {
+        let gauge = |name: &str, help: &str, samples: &[(&str, String)]| {
+            let mut text = format!("# HELP {name} {help}\n# TYPE {name} gauge\n");
+            for (labels, value) in samples {
+                text.push_str(&format!("{name}{labels} {value}\n"));
+            }
+            text
+        };
+        [
+            gauge(
+                "timesheet_worklogs",
+                "Number of work log entries in the local database",
+                &[("", self.worklogs.to_string())],
+            ),
+            gauge(
+                "timesheet_logged_seconds",
+                "Time logged in the trailing window",
+                &[
+                    ("{window=\"7d\"}", self.logged_seconds_7d.to_string()),
+                    ("{window=\"30d\"}", self.logged_seconds_30d.to_string()),
+                ],
+            ),
+            gauge(
+                "timesheet_unsynced_timers",
+                "Number of stopped timers not yet logged in Jira",
+                &[("", self.unsynced_timers.to_string())],
+            ),
+            gauge(
+                "timesheet_database_bytes",
+                "Size of the local database",
+                &[("", self.database_bytes.to_string())],
+            ),
+        ]
+        .concat()
+    }
+}
+
+async fn get_metrics(State(state): State<AppState>) -> Result<Response, ServerError> {
+    let metrics = Metrics::collect(state.reads.get())?;
+    Ok((
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.to_prometheus(),
     )
+        .into_response())
+}
+
+async fn stop_timer(State(state): State<AppState>) -> Result<Json<TimerView>, ServerError> {
+    let runtime = state.runtime.lock().await;
+    let timer = runtime
+        .timer_service()
+        .stop_active_timer(Local::now(), None)?;
+    Ok(Json(TimerView::from(timer)))
+}
+
+/// Number of read-only database connections serving the GET handlers
+const READ_POOL_SIZE: usize = 4;
+
+/// Read-only database connections, handed out in turn
+struct ReadPool {
+    connections: Vec<ReadConn>,
+    next: AtomicUsize,
+}
+
+impl ReadPool {
+    fn new(runtime: &ApplicationRuntime, size: usize) -> Result<Self, WorklogError> {
+        let connections = (0..size)
+            .map(|_| runtime.snapshot_read())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            connections,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    fn get(&self) -> &ReadConn {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        &self.connections[index]
+    }
 }
 
 #[derive(Clone)]
 struct AppState {
-    // TODO: consider removing this, as ApplicationRuntime is now thread safe
+    /// The write path, serialising the operations modifying the local database
     runtime: Arc<Mutex<ApplicationRuntime>>,
+    reads: Arc<ReadPool>,
+}
+
+impl AppState {
+    fn new(runtime: ApplicationRuntime) -> Result<Self, WorklogError> {
+        Ok(Self {
+            reads: Arc::new(ReadPool::new(&runtime, READ_POOL_SIZE)?),
+            runtime: Arc::new(Mutex::new(runtime)),
+        })
+    }
+}
+
+fn app(state: AppState) -> Router {
+    Router::new()
+        .route("/api/worklogs", get(get_worklogs))
+        .route("/api/worklogs", post(post_worklog))
+        .route("/api/timers", get(get_timers))
+        .route("/api/timers/stop", post(stop_timer))
+        .route("/metrics", get(get_metrics))
+        //.route("/api/tracking", get(get_tracking_candidates))
+        .with_state(state)
 }
 
 #[tokio::main]
@@ -85,16 +293,9 @@ async fn main() -> Result<(), ServerError> {
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
-    let state = AppState {
-        runtime: Arc::new(Mutex::new(ApplicationRuntime::new()?)),
-    };
+    let state = AppState::new(ApplicationRuntime::new()?)?;
 
-    let app = Router::new()
-        .route("/api/worklogs", get(get_worklogs))
-        .route("/api/worklogs", post(post_worklog))
-        //.route("/api/tracking", get(get_tracking_candidates))
-        .with_state(state)
-        .layer(cors);
+    let app = app(state).layer(cors);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], 4000));
     println!("Server running on http://{addr}");
@@ -104,3 +305,164 @@ async fn main() -> Result<(), ServerError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Method, Request};
+    use jira::models::core::{Fields, IssueKey};
+    use jira::models::issue::IssueSummary;
+    use jira::{Credentials, Jira};
+    use tower::ServiceExt;
+    use worklog::ApplicationRuntimeBuilder;
+
+    /// Creates the state of an in-memory database holding the issue TIME-1
+    fn test_state() -> AppState {
+        let jira = Jira::new("http://localhost", Credentials::Anonymous).unwrap();
+        let runtime = ApplicationRuntimeBuilder::new()
+            .use_in_memory_db()
+            .use_jira_client(jira)
+            .build()
+            .unwrap();
+        runtime
+            .issue_service()
+            .add_jira_issues(&[IssueSummary {
+                id: "1".to_string(),
+                key: IssueKey::from("TIME-1"),
+                fields: Fields {
+                    summary: "Time".to_string(),
+                    ..Default::default()
+                },
+            }])
+            .unwrap();
+        AppState::new(runtime).unwrap()
+    }
+
+    async fn send(state: &AppState, method: Method, uri: &str) -> (StatusCode, Vec<u8>) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = app(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    async fn start_timer(state: &AppState, started_at: DateTime<Local>) {
+        let runtime = state.runtime.lock().await;
+        runtime
+            .timer_service()
+            .start_timer("TIME-1", started_at, None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_timers_returns_active_timer_distinctly() {
+        let state = test_state();
+        let now = Local::now();
+        start_timer(&state, now - Duration::hours(3)).await;
+        state
+            .runtime
+            .lock()
+            .await
+            .timer_service()
+            .stop_active_timer(now - Duration::hours(2), None)
+            .unwrap();
+        start_timer(&state, now - Duration::hours(1)).await;
+
+        let (status, body) = send(&state, Method::GET, "/api/timers").await;
+        assert_eq!(status, StatusCode::OK);
+        let response: TimersResponse = serde_json::from_slice(&body).unwrap();
+        let active = response.active.expect("No active timer");
+        assert!(active.timer.is_active());
+        assert!(active.duration_seconds >= 3600);
+        assert_eq!(response.timers.len(), 1);
+        assert_eq!(response.timers[0].duration_seconds, 3600);
+
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(
+            DateTime::parse_from_rfc3339(json["active"]["started_at"].as_str().unwrap()).is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_timer_stops_the_active_timer() {
+        let state = test_state();
+        start_timer(&state, Local::now() - Duration::hours(1)).await;
+
+        let (status, body) = send(&state, Method::POST, "/api/timers/stop").await;
+        assert_eq!(status, StatusCode::OK);
+        let stopped: TimerView = serde_json::from_slice(&body).unwrap();
+        assert!(!stopped.timer.is_active());
+        assert!(stopped.duration_seconds >= 3600);
+
+        let (status, body) = send(&state, Method::GET, "/api/timers").await;
+        assert_eq!(status, StatusCode::OK);
+        let response: TimersResponse = serde_json::from_slice(&body).unwrap();
+        assert!(response.active.is_none());
+        assert_eq!(response.timers.len(), 1);
+
+        // There is no active timer left to stop
+        let (status, _) = send(&state, Method::POST, "/api/timers/stop").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_post_worklog_is_read_back() {
+        let state = test_state();
+        let worklog = LocalWorklog::builder(IssueKey::from("TIME-1"), "10", 3600)
+            .author("Ola Nordmann")
+            .issue_id(1)
+            .build();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/worklogs")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(&worklog).unwrap()))
+            .unwrap();
+        let response = app(state.clone()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let (status, body) = send(&state, Method::GET, "/api/worklogs").await;
+        assert_eq!(status, StatusCode::OK);
+        let worklogs: Vec<LocalWorklog> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(worklogs.len(), 1);
+        assert_eq!(worklogs[0].id, "10");
+        assert_eq!(worklogs[0].timeSpentSeconds, 3600);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_in_prometheus_format() {
+        let state = test_state();
+        let now = Local::now();
+        start_timer(&state, now - Duration::hours(2)).await;
+        state
+            .runtime
+            .lock()
+            .await
+            .timer_service()
+            .stop_active_timer(now - Duration::hours(1), None)
+            .unwrap();
+
+        let (status, body) = send(&state, Method::GET, "/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        let text = String::from_utf8(body).unwrap();
+        for name in [
+            "timesheet_worklogs",
+            "timesheet_logged_seconds",
+            "timesheet_unsynced_timers",
+            "timesheet_database_bytes",
+        ] {
+            assert!(
+                text.contains(&format!("# TYPE {name} gauge\n")),
+                "No {name} in {text}"
+            );
+        }
+        assert!(text.contains("timesheet_logged_seconds{window=\"7d\"} "));
+        assert!(text.contains("\ntimesheet_unsynced_timers 1\n"), "{text}");
+    }
+}
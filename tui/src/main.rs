@@ -6,7 +6,11 @@ use ratatui::{
     DefaultTerminal,
 };
 use std::error::Error;
-use worklog::{types::LocalWorklog, ApplicationRuntime, ApplicationRuntimeBuilder};
+use worklog::{
+    date::{format_seconds, DurationStyle},
+    types::LocalWorklog,
+    ApplicationRuntime, ApplicationRuntimeBuilder,
+};
 
 use chrono::{
     offset::TimeZone, DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Weekday,
@@ -87,7 +91,8 @@ fn fetch_weekly_data(
             }))
             .await;
     */
-    let mut all_local = match runtime
+    // Ordered by the time the work was started
+    let all_local = match runtime
         .worklog_service()
         .find_worklogs_after(start_of_week, &[], &[])
     {
@@ -97,10 +102,13 @@ fn fetch_weekly_data(
         }
     };
 
-    all_local.sort_by_key(|e| e.started);
     map_to_week_view(&all_local)
 }
 
+fn hours_and_minutes(seconds: u32) -> String {
+    format_seconds(i64::from(seconds), DurationStyle::HoursAndMinutes)
+}
+
 #[allow(clippy::unused_async)]
 async fn run(mut terminal: DefaultTerminal) -> Result<(), Box<dyn Error>> {
     let runtime = ApplicationRuntimeBuilder::new().build()?;
@@ -117,20 +125,16 @@ async fn run(mut terminal: DefaultTerminal) -> Result<(), Box<dyn Error>> {
                 cells.extend(
                     times
                         .iter()
-                        .map(|&time_spent| format!("{} hours", time_spent / 3600)),
+                        .map(|&time_spent| hours_and_minutes(time_spent)),
                 );
-                cells.push(format!("{} hours", row_sum / 3600));
+                cells.push(hours_and_minutes(*row_sum));
                 Row::new(cells)
             })
             .collect();
 
         let mut footer_cells = vec!["Total".to_string()];
-        footer_cells.extend(
-            column_sums
-                .iter()
-                .map(|&sum| format!("{} hours", sum / 3600)),
-        );
-        footer_cells.push(format!("{} hours", row_sums / 3600));
+        footer_cells.extend(column_sums.iter().map(|&sum| hours_and_minutes(sum)));
+        footer_cells.push(hours_and_minutes(row_sums));
 
         terminal.draw(|frame| {
             let widths = [
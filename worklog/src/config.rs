@@ -1,16 +1,20 @@
 use crate::error::WorklogError;
 use anyhow::Result;
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
 use directories;
 use directories::ProjectDirs;
+use jira::Credentials;
+use log::{debug, warn};
+use secure_credentials::CredentialStore;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::{fmt, str::FromStr};
 
-#[cfg(target_os = "macos")]
-use log::debug;
-
-#[cfg(target_os = "macos")]
+/// The service of the Jira access token in the credential store
 pub const KEYCHAIN_SERVICE_NAME: &str = "com.norn.timesheet.jira";
 
 /// Application configuration struct
@@ -24,6 +28,168 @@ pub struct AppConfiguration {
     /// is an old version, which does not have an `application_data` section
     #[serde(default = "default_application_data")]
     pub application_data: ApplicationData,
+
+    /// Rules for the work log comments, defaults to the limits imposed by Jira
+    #[serde(default)]
+    pub comment: CommentConfiguration,
+
+    /// Time zone used when presenting work logs, defaults to the local time zone
+    #[serde(default)]
+    pub display_tz: DisplayTimeZone,
+
+    /// JQL locating the issues used as time codes, listed by `timesheet codes`
+    #[serde(default = "default_time_code_jql")]
+    pub time_code_jql: String,
+
+    /// Remove an issue from the local database when its last work log entry is deleted,
+    /// unless the issue is a favorite or has been pinned with `timesheet issue pin`
+    #[serde(default)]
+    pub prune_orphan_issues: bool,
+
+    /// Public holidays and other days off, written as `["2024-12-25", "2024-12-26"]`,
+    /// which are not counted as working days
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holidays: Vec<NaiveDate>,
+
+    /// Names of the components given to new issues of a project, unless components are
+    /// specified, written as `TIME = ["Backend"]` in the `default_components` section
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub default_components: BTreeMap<String, Vec<String>>,
+}
+
+/// The time codes used by Norns, held in the Jira project `TIME`
+pub const DEFAULT_TIME_CODE_JQL: &str = "project = TIME AND resolution = Unresolved";
+
+/// The time zone in which work logs are presented and bucketed into days and weeks.
+/// Written as `local`, `utc` or an IANA time zone name like `Europe/Oslo` in the Toml file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum DisplayTimeZone {
+    /// The time zone of this computer
+    #[default]
+    Local,
+    Utc,
+    /// A named time zone, typically the one of the Jira user profile
+    Named(Tz),
+}
+
+impl DisplayTimeZone {
+    /// Converts the supplied point in time into this time zone
+    #[must_use]
+    pub fn convert<T: TimeZone>(&self, dt: &DateTime<T>) -> DateTime<FixedOffset> {
+        match self {
+            DisplayTimeZone::Local => dt.with_timezone(&chrono::Local).fixed_offset(),
+            DisplayTimeZone::Utc => dt.with_timezone(&Utc).fixed_offset(),
+            DisplayTimeZone::Named(tz) => dt.with_timezone(tz).fixed_offset(),
+        }
+    }
+}
+
+impl FromStr for DisplayTimeZone {
+    type Err = WorklogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(DisplayTimeZone::Local),
+            "utc" => Ok(DisplayTimeZone::Utc),
+            _ => s.parse::<Tz>().map(DisplayTimeZone::Named).map_err(|_| {
+                WorklogError::BadInput(format!(
+                    "Unknown time zone '{s}', use 'local', 'utc' or an IANA name like 'Europe/Oslo'"
+                ))
+            }),
+        }
+    }
+}
+
+impl TryFrom<String> for DisplayTimeZone {
+    type Error = WorklogError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<DisplayTimeZone> for String {
+    fn from(value: DisplayTimeZone) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for DisplayTimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayTimeZone::Local => write!(f, "local"),
+            DisplayTimeZone::Utc => write!(f, "utc"),
+            DisplayTimeZone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+/// Maximum length of a Jira text field, which applies to work log comments
+pub const JIRA_MAX_COMMENT_LENGTH: usize = 32_767;
+
+/// What to do with comments exceeding the maximum length
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentPolicy {
+    /// Refuse to add the work log entry
+    #[default]
+    Reject,
+    /// Cut the comment down to the maximum length and issue a warning
+    Truncate,
+}
+
+/// Holds the configuration for the `comment` section of the Toml file
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct CommentConfiguration {
+    /// Maximum number of characters in a work log comment
+    pub max_length: usize,
+    pub policy: CommentPolicy,
+}
+
+impl Default for CommentConfiguration {
+    fn default() -> Self {
+        CommentConfiguration {
+            max_length: JIRA_MAX_COMMENT_LENGTH,
+            policy: CommentPolicy::default(),
+        }
+    }
+}
+
+impl CommentConfiguration {
+    /// Applies the comment policy to the supplied comment, ensuring the same comment
+    /// is written to Jira and the local database.
+    ///
+    /// Returns the comment along with a notice to tell the user, if the comment was truncated.
+    ///
+    /// # Errors
+    /// Returns `WorklogError::CommentTooLong` if the comment is too long and the policy is
+    /// `CommentPolicy::Reject`
+    pub fn enforce(
+        &self,
+        comment: Option<String>,
+    ) -> Result<(Option<String>, Option<String>), WorklogError> {
+        let Some(comment) = comment else {
+            return Ok((None, None));
+        };
+        let length = comment.chars().count();
+        if length <= self.max_length {
+            return Ok((Some(comment), None));
+        }
+        match self.policy {
+            CommentPolicy::Reject => Err(WorklogError::CommentTooLong {
+                length,
+                max_length: self.max_length,
+            }),
+            CommentPolicy::Truncate => Ok((
+                Some(comment.chars().take(self.max_length).collect()),
+                Some(format!(
+                    "Comment of {length} characters truncated to {} characters",
+                    self.max_length
+                )),
+            )),
+        }
+    }
 }
 
 /// Holds the configuration for the `application_data` section of the Toml file
@@ -46,6 +212,26 @@ pub struct JiraClientConfiguration {
     pub url: String,
     pub user: String,
     pub token: String,
+    /// Overrides the default `User-Agent` of `timesheet/<version>` sent to Jira
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Version of the REST API, like `2` for Jira Server instances lacking `latest`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+    /// How the token is sent to Jira, `basic` unless a Personal Access Token is used
+    #[serde(default)]
+    pub auth_type: AuthType,
+}
+
+/// How the token of the configuration authenticates with Jira
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthType {
+    /// The user and the API token of Jira Cloud
+    #[default]
+    Basic,
+    /// A Personal Access Token of Jira Data Center, which identifies the user by itself
+    Bearer,
 }
 
 impl JiraClientConfiguration {
@@ -54,6 +240,15 @@ impl JiraClientConfiguration {
     pub fn has_valid_jira_token(&self) -> bool {
         !(self.token.contains("secret") || self.token == JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN)
     }
+
+    /// The credentials of the user and token, as given by the `auth_type`
+    #[must_use]
+    pub fn credentials(&self) -> Credentials {
+        match self.auth_type {
+            AuthType::Basic => Credentials::Basic(self.user.clone(), self.token.clone()),
+            AuthType::Bearer => Credentials::Bearer(self.token.clone()),
+        }
+    }
 }
 
 /// Filename holding the application configuration parameters
@@ -68,55 +263,75 @@ pub fn worklog_file() -> PathBuf {
     project_dirs().data_dir().join("worklog.db")
 }
 
-/// Loads and returns the application configuration, with platform-specific keychain integration, c
-/// currently only for macOS.
-///
-/// On macOS, this function performs additional keychain-related operations:
-/// - If a valid Jira token exists in the configuration file but not in the keychain,
-///   it migrates the token to the keychain
-/// - Retrieves the Jira token from the keychain and merges it into the configuration
+/// The store of the Jira access token, the macOS keychain
+#[cfg(all(target_os = "macos", feature = "keychain"))]
+#[must_use]
+pub fn credential_store() -> Box<dyn CredentialStore> {
+    Box::new(secure_credentials::macos::KeychainStore)
+}
+
+/// The store of the Jira access token. There is no keychain on this platform, hence the
+/// token is read from the `TIMESHEET_TOKEN` environment variable, if set.
+#[cfg(not(all(target_os = "macos", feature = "keychain")))]
+#[must_use]
+pub fn credential_store() -> Box<dyn CredentialStore> {
+    Box::new(secure_credentials::EnvCredentialStore::default())
+}
+
+/// Loads and returns the application configuration, with the Jira token looked up in the
+/// credential `store`, see [`credential_store`].
 ///
-/// On other platforms, this behaves the same as `load_no_keychain_lookup()`.
+/// - If a valid Jira token exists in the configuration file but not in the store,
+///   it is moved to the store, provided the store accepts it
+/// - The Jira token of the store is merged into the configuration
 ///
 /// # Errors
 ///
 /// Returns `WorklogError` if:
 /// - The configuration file cannot be read or parsed
-/// - The configuration file cannot be created during token migration on macOS
+/// - The configuration file cannot be written when moving the token to the store
 ///
 /// # Example
 ///
 /// ```no_run
 /// use worklog::config;
 ///
-/// let app_config = config::load_with_keychain_lookup()
+/// let app_config = config::load_with_keychain_lookup(config::credential_store().as_ref())
 ///     .expect("Failed to load configuration");
 /// println!("Jira URL: {}", app_config.jira.url);
 /// ```
-#[allow(unused_mut)]
-pub fn load_with_keychain_lookup() -> Result<AppConfiguration, WorklogError> {
+pub fn load_with_keychain_lookup(
+    store: &dyn CredentialStore,
+) -> Result<AppConfiguration, WorklogError> {
     // Loads the plain configuration file without a keychain lookup
     let (config_path, mut app_config) = load_no_keychain_lookup()?;
+    merge_credential_store(&mut app_config, &config_path, store)?;
+    Ok(app_config)
+}
 
-    #[cfg(target_os = "macos")]
-    if cfg!(target_os = "macos") {
-        // If the loaded configuration file holds a valid Jira token, migrate it to
-        // the macOS Key Chain
-        if app_config.jira.has_valid_jira_token()
-            && secure_credentials::macos::get_secure_token(
-                KEYCHAIN_SERVICE_NAME,
-                &app_config.jira.user,
-            )
+/// Moves a valid token of the configuration file at `config_path` into the `store`, unless
+/// already there, and merges the token of the `store` into the configuration
+fn merge_credential_store(
+    app_config: &mut AppConfiguration,
+    config_path: &Path,
+    store: &dyn CredentialStore,
+) -> Result<(), WorklogError> {
+    if app_config.jira.has_valid_jira_token()
+        && store
+            .retrieve(KEYCHAIN_SERVICE_NAME, &app_config.jira.user)
             .is_err()
-        {
-            create_configuration_file(&app_config, &config_path)
-                .map_err(|_src_err| WorklogError::ConfigFileCreation { path: config_path })?;
+    {
+        let mut migrated = app_config.clone();
+        if store_jira_token_in_keychain(&mut migrated, store) {
+            save_atomic(&migrated, config_path).map_err(|_src_err| {
+                WorklogError::ConfigFileCreation {
+                    path: config_path.to_path_buf(),
+                }
+            })?;
         }
-
-        // Merges the Jira token from the Keychain into the Application configuration
-        merge_jira_token_from_keychain(&mut app_config);
     }
-    Ok(app_config)
+    merge_jira_token_from_keychain(app_config, store);
+    Ok(())
 }
 
 /// Loads the application configuration from the configuration file without performing any keychain lookups.
@@ -154,9 +369,10 @@ pub fn load_no_keychain_lookup() -> Result<(PathBuf, AppConfiguration), WorklogE
     Ok((config_path, app_config))
 }
 
+/// Saves the configuration, with the Jira token moved to the `store` if it accepts it
 #[allow(clippy::missing_errors_doc)]
-pub fn save(cfg: &AppConfiguration) -> Result<()> {
-    create_configuration_file(cfg, &configuration_file())
+pub fn save(cfg: &AppConfiguration, store: &dyn CredentialStore) -> Result<()> {
+    create_configuration_file(cfg, &configuration_file(), store)
 }
 
 #[allow(clippy::missing_errors_doc)]
@@ -169,6 +385,10 @@ pub fn application_config_to_string(cfg: &AppConfiguration) -> Result<String> {
     Ok(toml::to_string::<AppConfiguration>(cfg)?)
 }
 
+fn default_time_code_jql() -> String {
+    DEFAULT_TIME_CODE_JQL.to_string()
+}
+
 fn default_application_data() -> ApplicationData {
     ApplicationData::default()
 }
@@ -225,8 +445,11 @@ pub fn read_data(path: &Path) -> Result<AppConfiguration, WorklogError> {
     })
 }
 
-#[allow(unused_mut)]
-fn create_configuration_file(cfg: &AppConfiguration, path: &PathBuf) -> Result<()> {
+fn create_configuration_file(
+    cfg: &AppConfiguration,
+    path: &Path,
+    store: &dyn CredentialStore,
+) -> Result<()> {
     let directory = path.parent().unwrap();
     if !directory.try_exists()? {
         fs::create_dir_all(directory)?;
@@ -234,35 +457,125 @@ fn create_configuration_file(cfg: &AppConfiguration, path: &PathBuf) -> Result<(
 
     let mut cfg_updated = cfg.clone();
 
-    #[cfg(target_os = "macos")]
-    if cfg!(target_os = "macos") {
-        debug!("MacOs: Moving security token into the keychain");
-        migrate_jira_token_into_keychain(&mut cfg_updated);
+    if cfg_updated.jira.has_valid_jira_token()
+        && !store_jira_token_in_keychain(&mut cfg_updated, store)
+    {
+        if store
+            .retrieve(KEYCHAIN_SERVICE_NAME, &cfg_updated.jira.user)
+            .is_ok_and(|token| token == cfg_updated.jira.token)
+        {
+            // The token came from a read-only store, like the environment, and is not
+            // persisted. The token of the configuration file, if any, is kept instead.
+            debug!(
+                "Keeping the Jira access token of the read-only credential store out of the file"
+            );
+            cfg_updated.jira.token = read_data(path).map_or_else(
+                |_| JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN.to_string(),
+                |previous| previous.jira.token,
+            );
+        } else {
+            warn!(
+                "No keychain available, the Jira access token is stored in the configuration file"
+            );
+            eprintln!(
+                "WARNING: No keychain available, the Jira access token is stored in plain text in {}",
+                path.to_string_lossy()
+            );
+        }
     }
 
-    let mut file = File::create(path)?;
-    let toml = application_config_to_string(&cfg_updated)?;
+    save_atomic(&cfg_updated, path)
+}
+
+/// Writes the configuration to `path` without ever leaving a truncated file behind.
+///
+/// The TOML is written to a temporary file in the same directory, which is then renamed
+/// over the target. The previous contents, if any, are kept in a `.bak` file next to it,
+/// see [`write_backup`].
+///
+/// # Errors
+/// Returns an error if the configuration cannot be serialized or any of the file
+/// operations fail.
+pub fn save_atomic(cfg: &AppConfiguration, path: &Path) -> Result<()> {
+    let toml = application_config_to_string(cfg)?;
+
+    let tmp_path = sibling_path(path, "tmp");
+    let mut file = create_private_file(&tmp_path)?;
     file.write_all(toml.as_bytes())?;
+    file.sync_all()?;
+
+    if path.try_exists()? {
+        write_backup(path, &sibling_path(path, "bak"), &cfg.jira.token)?;
+    }
+    fs::rename(&tmp_path, path)?;
 
     Ok(())
 }
 
-/// Sets the Jira Access Security Token in the macOS Key Chain
-/// See also the `security` command.
+/// Copies the configuration at `path` to `backup`, which only the owner may read. A valid
+/// Jira token other than `token`, i.e. one not kept in plain text by the configuration
+/// about to be saved, is replaced with the placeholder, so that a token moved to the
+/// credential store does not linger on disk.
+fn write_backup(path: &Path, backup: &Path, token: &str) -> Result<()> {
+    let contents = match read_data(path) {
+        Ok(mut previous)
+            if previous.jira.has_valid_jira_token() && previous.jira.token != token =>
+        {
+            previous.jira.token = JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN.to_string();
+            application_config_to_string(&previous)?.into_bytes()
+        }
+        _ => fs::read(path)?,
+    };
+
+    let mut file = create_private_file(backup)?;
+    file.write_all(&contents)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Creates or truncates a file which only the owner may read, as it may hold the Jira token.
+/// The permissions of an existing file, like one left behind by an interrupted save, are
+/// tightened as well.
+fn create_private_file(path: &Path) -> io::Result<File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        Ok(file)
+    }
+    #[cfg(not(unix))]
+    options.open(path)
+}
+
+/// Appends `extension` to the file name of `path`, i.e. `config.toml` becomes `config.toml.bak`
+fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+/// Merges the Jira access token of the `store` into the configuration. On macOS the token
+/// may be added to the keychain with the `security` command:
 /// `
-/// security add-generic-password -s com.norn.timesheet \
+/// security add-generic-password -s com.norn.timesheet.jira \
 ///   -a your-emailk@whereever.com -w secure_token_goes_here
 /// `
-#[cfg(target_os = "macos")]
-fn merge_jira_token_from_keychain(config: &mut AppConfiguration) {
-    use log::warn;
-
-    debug!("MacOS: retrieving the Jira access token from the keychain ...");
-    match secure_credentials::macos::get_secure_token(KEYCHAIN_SERVICE_NAME, &config.jira.user) {
+fn merge_jira_token_from_keychain(config: &mut AppConfiguration, store: &dyn CredentialStore) {
+    debug!("Retrieving the Jira access token from the credential store ...");
+    match store.retrieve(KEYCHAIN_SERVICE_NAME, &config.jira.user) {
         Ok(token) => {
-            debug!("Found Jira access token in keychain and injected it");
+            debug!("Found Jira access token in the credential store and injected it");
             config.jira.token = token;
         }
+        // The token of the configuration file is used
+        Err(err) if config.jira.has_valid_jira_token() => {
+            debug!("No Jira access token in the credential store: {err}");
+        }
         Err(err) => {
             warn!(
                 "No Jira Access Token in keychain for {} and {}",
@@ -280,34 +593,40 @@ fn merge_jira_token_from_keychain(config: &mut AppConfiguration) {
 
 const JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN: &str = "*** stored in macos keychain ***";
 
-#[cfg(target_os = "macos")]
-fn migrate_jira_token_into_keychain(app_config: &mut AppConfiguration) {
-    match secure_credentials::macos::store_secure_token(
+/// Moves the Jira access token into the `store`, replacing it in the configuration with a
+/// placeholder. Returns `false`, leaving the token in place, if the store could not be written.
+fn store_jira_token_in_keychain(
+    app_config: &mut AppConfiguration,
+    store: &dyn CredentialStore,
+) -> bool {
+    debug!("Moving the security token into the credential store");
+    match store.store(
         KEYCHAIN_SERVICE_NAME,
         &app_config.jira.user,
         &app_config.jira.token,
     ) {
         Ok(()) => {
             debug!(
-                "Jira access token stored into the Keychain under {} and {}",
+                "Jira access token stored under {} and {}",
                 KEYCHAIN_SERVICE_NAME, app_config.jira.user
             );
-            debug!("MacOs: Removing the security token from the config file");
+            // This will ensure the jira security token in the config file on disk contains
+            // a useless placeholder
+            debug!("Removing the security token from the config file");
+            app_config.jira.token = JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN.to_string();
+            true
         }
-        #[allow(unused_variables)]
         Err(error) => {
-            panic!("Unable to store the Jira access token into the MacOS keychain {error}");
+            debug!("Unable to store the Jira access token: {error}");
+            false
         }
     }
-    // a useless placeholder
-    // This will ensure the jira security token in the config file on disk contains
-    debug!("MacOs: Removing the security token from the config file");
-    app_config.jira.token = JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN.to_string();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use secure_credentials::{EnvCredentialStore, MockStore};
 
     #[test]
     fn toml_parsing() {
@@ -341,6 +660,7 @@ mod tests {
             app_config.application_data.local_worklog,
             worklog_file().to_string_lossy()
         );
+        assert_eq!(app_config.time_code_jql, DEFAULT_TIME_CODE_JQL);
     }
 
     #[ignore = "Cannot access the keychain from a non-interactive test"]
@@ -350,7 +670,7 @@ mod tests {
 
         let cfg = generate_config_for_test();
 
-        create_configuration_file(&cfg, &tmp_config_file)?;
+        create_configuration_file(&cfg, &tmp_config_file, credential_store().as_ref())?;
         if let Ok(result) = read_data(&tmp_config_file) {
             // Don't compare the jira.token field as this may vary depending on operating system
             assert!(
@@ -365,16 +685,356 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_save_atomic_keeps_backup() -> Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("timesheet-save-atomic-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let config_file = dir.join("config.toml");
+
+        let mut cfg = generate_config_for_test();
+        save_atomic(&cfg, &config_file)?;
+        assert!(!dir.join("config.toml.bak").exists());
+
+        cfg.jira.user = "ola".to_string();
+        save_atomic(&cfg, &config_file)?;
+
+        assert!(!dir.join("config.toml.tmp").exists());
+        assert_eq!(read_data(&config_file)?.jira.user, "ola");
+        assert_eq!(
+            read_data(&dir.join("config.toml.bak"))?.jira.user,
+            "steinar"
+        );
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[cfg(all(target_os = "macos", feature = "keychain"))]
+    #[ignore = "Cannot access the keychain from a non-interactive test"]
+    #[test]
+    fn test_token_is_stored_in_keychain() -> Result<()> {
+        let tmp_config_file =
+            std::env::temp_dir().join(format!("test-config-{}.toml", std::process::id()));
+
+        let mut cfg = generate_config_for_test();
+        cfg.jira.user = format!("timesheet-test-{}@example.com", std::process::id());
+        cfg.jira.token = "a-token-for-the-keychain".to_string();
+
+        create_configuration_file(
+            &cfg,
+            &tmp_config_file,
+            &secure_credentials::macos::KeychainStore,
+        )?;
+
+        let on_disk = read_data(&tmp_config_file)?;
+        assert_eq!(on_disk.jira.token, JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN);
+        let in_keychain =
+            secure_credentials::macos::get_secure_token(KEYCHAIN_SERVICE_NAME, &cfg.jira.user)
+                .expect("Token not found in the keychain");
+        assert_eq!(in_keychain, cfg.jira.token);
+
+        fs::remove_file(&tmp_config_file)?;
+        Ok(())
+    }
+
+    #[cfg(not(all(target_os = "macos", feature = "keychain")))]
+    #[test]
+    fn test_token_stays_in_configuration_without_keychain() {
+        let mut cfg = generate_config_for_test();
+        cfg.jira.token = "a-token-for-the-file".to_string();
+        assert!(!store_jira_token_in_keychain(
+            &mut cfg,
+            credential_store().as_ref()
+        ));
+        assert_eq!(cfg.jira.token, "a-token-for-the-file");
+    }
+
+    #[test]
+    fn test_token_is_moved_to_the_credential_store() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("timesheet-store-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let config_file = dir.join("config.toml");
+        let mut cfg = generate_config_for_test();
+        cfg.jira.token = "a-token-for-the-store".to_string();
+        save_atomic(&cfg, &config_file)?;
+        let store = MockStore::default();
+
+        merge_credential_store(&mut cfg, &config_file, &store)?;
+
+        assert_eq!(cfg.jira.token, "a-token-for-the-store");
+        assert_eq!(
+            store
+                .retrieve(KEYCHAIN_SERVICE_NAME, &cfg.jira.user)
+                .unwrap(),
+            "a-token-for-the-store"
+        );
+        assert_eq!(
+            read_data(&config_file)?.jira.token,
+            JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN
+        );
+
+        let backup = dir.join("config.toml.bak");
+        assert!(!fs::read_to_string(&backup)?.contains("a-token-for-the-store"));
+        assert_eq!(read_data(&backup)?.jira.user, cfg.jira.user);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&backup)?.permissions().mode() & 0o777, 0o600);
+            assert_eq!(
+                fs::metadata(&config_file)?.permissions().mode() & 0o777,
+                0o600
+            );
+        }
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_of_the_credential_store_is_merged() -> Result<()> {
+        let config_file = std::env::temp_dir().join("timesheet-never-written.toml");
+        let mut cfg = generate_config_for_test();
+        cfg.jira.token = JIRA_TOKEN_STORED_IN_MACOS_KEYCHAIN.to_string();
+        let store = MockStore::with_token(KEYCHAIN_SERVICE_NAME, &cfg.jira.user, "stored-token");
+
+        merge_credential_store(&mut cfg, &config_file, &store)?;
+
+        assert_eq!(cfg.jira.token, "stored-token");
+        assert!(!config_file.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_stays_in_configuration_of_read_only_store() -> Result<()> {
+        let config_file = std::env::temp_dir().join("timesheet-never-written.toml");
+        let mut cfg = generate_config_for_test();
+        cfg.jira.token = "a-token-for-the-file".to_string();
+        let store = EnvCredentialStore::new("TIMESHEET_TEST_UNSET_TOKEN");
+
+        merge_credential_store(&mut cfg, &config_file, &store)?;
+
+        assert_eq!(cfg.jira.token, "a-token-for-the-file");
+        assert!(!config_file.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_of_read_only_store_is_not_saved() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("timesheet-read-only-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let config_file = dir.join("config.toml");
+        let mut cfg = generate_config_for_test();
+        cfg.jira.token = "a-token-of-the-file".to_string();
+        save_atomic(&cfg, &config_file)?;
+        std::env::set_var(
+            "TIMESHEET_TEST_READ_ONLY_TOKEN",
+            "a-token-of-the-environment",
+        );
+        let store = EnvCredentialStore::new("TIMESHEET_TEST_READ_ONLY_TOKEN");
+
+        merge_credential_store(&mut cfg, &config_file, &store)?;
+        assert_eq!(cfg.jira.token, "a-token-of-the-environment");
+        cfg.jira.url = "https://example.atlassian.net".to_string();
+        create_configuration_file(&cfg, &config_file, &store)?;
+
+        let on_disk = read_data(&config_file)?;
+        assert_eq!(on_disk.jira.url, "https://example.atlassian.net");
+        assert_eq!(on_disk.jira.token, "a-token-of-the-file");
+        assert!(!fs::read_to_string(&config_file)?.contains("a-token-of-the-environment"));
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
     fn generate_config_for_test() -> AppConfiguration {
         AppConfiguration {
             jira: JiraClientConfiguration {
                 url: "http".to_string(),
                 user: "steinar".to_string(),
                 token: "not_a_token".to_string(),
+                user_agent: None,
+                api_version: None,
+                auth_type: AuthType::default(),
             },
             application_data: ApplicationData {
                 local_worklog: "worklog.db".to_string(),
             },
+            comment: CommentConfiguration::default(),
+            display_tz: DisplayTimeZone::default(),
+            time_code_jql: DEFAULT_TIME_CODE_JQL.to_string(),
+            prune_orphan_issues: false,
+            holidays: vec![],
+            default_components: BTreeMap::new(),
         }
     }
+
+    #[test]
+    fn test_toml_parsing_comment_policy() {
+        let toml_str = r#"
+        [jira]
+        url = "http"
+        user = "steinar"
+        token = "rubbish"
+
+        [comment]
+        max_length = 10
+        policy = "truncate"
+        "#;
+
+        let app_config: AppConfiguration = toml::from_str(toml_str).unwrap();
+        assert_eq!(app_config.comment.max_length, 10);
+        assert_eq!(app_config.comment.policy, CommentPolicy::Truncate);
+    }
+
+    #[test]
+    fn test_toml_parsing_auth_type() {
+        let toml_str = r#"
+        [jira]
+        url = "http"
+        user = "steinar"
+        token = "rubbish"
+        auth_type = "bearer"
+        "#;
+
+        let app_config: AppConfiguration = toml::from_str(toml_str).unwrap();
+        assert_eq!(app_config.jira.auth_type, AuthType::Bearer);
+        assert!(matches!(
+            app_config.jira.credentials(),
+            Credentials::Bearer(token) if token == "rubbish"
+        ));
+        // Existing configurations use basic authentication
+        let app_config: AppConfiguration =
+            toml::from_str(&toml_str.replace(r#"auth_type = "bearer""#, "")).unwrap();
+        assert_eq!(app_config.jira.auth_type, AuthType::Basic);
+    }
+
+    #[test]
+    fn test_toml_parsing_holidays() {
+        let toml_str = r#"
+        holidays = ["2024-12-25", "2024-12-26"]
+
+        [jira]
+        url = "http"
+        user = "steinar"
+        token = "rubbish"
+        "#;
+
+        let app_config: AppConfiguration = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            app_config.holidays,
+            vec![
+                NaiveDate::from_ymd_opt(2024, 12, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 12, 26).unwrap()
+            ]
+        );
+        assert!(toml::to_string(&generate_config_for_test())
+            .unwrap()
+            .find("holidays")
+            .is_none());
+    }
+
+    #[test]
+    fn test_toml_parsing_display_tz() {
+        let toml_str = r#"
+        display_tz = "Europe/Oslo"
+
+        [jira]
+        url = "http"
+        user = "steinar"
+        token = "rubbish"
+        "#;
+
+        let app_config: AppConfiguration = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            app_config.display_tz,
+            DisplayTimeZone::Named(chrono_tz::Europe::Oslo)
+        );
+
+        let toml = application_config_to_string(&app_config).unwrap();
+        assert!(toml.contains(r#"display_tz = "Europe/Oslo""#));
+        assert!("Mars/Olympus".parse::<DisplayTimeZone>().is_err());
+    }
+
+    #[test]
+    fn test_over_length_comment_rejected() {
+        let comment_config = CommentConfiguration {
+            max_length: 5,
+            policy: CommentPolicy::Reject,
+        };
+        assert!(matches!(
+            comment_config.enforce(Some("Too long".to_string())),
+            Err(WorklogError::CommentTooLong {
+                length: 8,
+                max_length: 5
+            })
+        ));
+        assert_eq!(
+            comment_config.enforce(Some("Short".to_string())).unwrap(),
+            (Some("Short".to_string()), None)
+        );
+    }
+
+    #[test]
+    fn test_over_length_comment_truncated() {
+        let comment_config = CommentConfiguration {
+            max_length: 5,
+            policy: CommentPolicy::Truncate,
+        };
+        let (comment, notice) = comment_config
+            .enforce(Some("Blåbærsyltetøy".to_string()))
+            .unwrap();
+        assert_eq!(comment, Some("Blåbæ".to_string()));
+        assert_eq!(
+            notice.as_deref(),
+            Some("Comment of 14 characters truncated to 5 characters")
+        );
+        assert_eq!(comment_config.enforce(None).unwrap(), (None, None));
+    }
+
+    #[test]
+    fn test_toml_parsing_default_components() {
+        let toml_str = r#"
+        [jira]
+        url = "http"
+        user = "steinar"
+        token = "rubbish"
+
+        [default_components]
+        TIME = ["Backend", "Meetings"]
+        "#;
+
+        let app_config: AppConfiguration = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            app_config.default_components["TIME"],
+            vec!["Backend".to_string(), "Meetings".to_string()]
+        );
+        let serialized = toml::to_string(&app_config).unwrap();
+        assert_eq!(
+            toml::from_str::<AppConfiguration>(&serialized).unwrap(),
+            app_config
+        );
+        assert!(toml::to_string(&generate_config_for_test())
+            .unwrap()
+            .find("default_components")
+            .is_none());
+    }
+
+    #[test]
+    fn test_toml_parsing_api_version() {
+        let toml_str = r#"
+        [jira]
+        url = "http"
+        user = "steinar"
+        token = "rubbish"
+        api_version = "2"
+        "#;
+
+        let app_config: AppConfiguration = toml::from_str(toml_str).unwrap();
+        assert_eq!(app_config.jira.api_version.as_deref(), Some("2"));
+        assert!(toml::to_string(&generate_config_for_test())
+            .unwrap()
+            .find("api_version")
+            .is_none());
+    }
 }
@@ -1,7 +1,10 @@
+use crate::error::WorklogError;
 use anyhow::{bail, Context};
 use chrono::offset::TimeZone;
 use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
-use chrono::{Days, Month, NaiveDateTime, NaiveTime, ParseResult};
+use chrono::{Days, Month, NaiveDateTime, NaiveTime, ParseResult, Utc};
+use chrono_tz::Tz;
+use jira::models::setting::TimeTrackingConfiguration;
 
 use num_traits::cast::FromPrimitive;
 use regex::Regex;
@@ -18,26 +21,52 @@ use std::sync::LazyLock;
 ///
 #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 pub fn str_to_date_time(s: &str) -> ParseResult<DateTime<Local>> {
+    let naive_date_time = str_to_naive_date_time(s, Local::now().date_naive())?;
+    Ok(Local.from_local_datetime(&naive_date_time).unwrap())
+}
+
+/// Parses a date, a time or a datetime like [`str_to_date_time`], but interprets it as
+/// the wall clock time of the supplied time zone rather than the local one.
+///
+/// # Errors
+/// Returns `Error::InvalidInput` if `s` could not be parsed, or if it denotes a time
+/// which does not exist in the time zone, like during a daylight saving time transition.
+pub fn str_to_date_time_in(s: &str, tz: &Tz) -> Result<DateTime<Local>, Error> {
+    let today = Utc::now().with_timezone(tz).date_naive();
+    let naive_date_time = str_to_naive_date_time(s, today)
+        .map_err(|e| Error::InvalidInput(format!("Unable to parse '{s}': {e}")))?;
+    tz.from_local_datetime(&naive_date_time)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Local))
+        .ok_or_else(|| Error::InvalidInput(format!("{s} does not exist in time zone {tz}")))
+}
+
+/// Parses an IANA time zone name, like `Europe/Oslo`
+///
+/// # Errors
+/// Returns `Error::InvalidInput` if the name is not a known IANA time zone
+pub fn parse_time_zone(name: &str) -> Result<Tz, Error> {
+    name.parse::<Tz>().map_err(|_| {
+        Error::InvalidInput(format!(
+            "Unknown time zone '{name}', expected an IANA name like 'Europe/Oslo'"
+        ))
+    })
+}
+
+fn str_to_naive_date_time(s: &str, today: NaiveDate) -> ParseResult<NaiveDateTime> {
     static DATE_EXPR: LazyLock<Regex> =
         LazyLock::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap());
     static TIME_EXPR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d{1,2}:\d{2}$").unwrap());
-    static DATE_TIME_EXPR: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{1,2}:\d{2}$").unwrap());
 
     if DATE_EXPR.is_match(s) {
         let naive_date = NaiveDate::parse_from_str(s, "%Y-%m-%d")?;
-        let naive_date_time = naive_date.and_hms_opt(8, 0, 0).unwrap();
-        Ok(Local.from_local_datetime(&naive_date_time).unwrap())
+        Ok(naive_date.and_hms_opt(8, 0, 0).unwrap())
     } else if TIME_EXPR.is_match(s) {
-        let nt = NaiveTime::parse_from_str(s, "%H:%M").unwrap();
-        let local_now = Local::now().date_naive().and_time(nt);
-        Ok(Local.from_local_datetime(&local_now).unwrap())
-    } else if DATE_TIME_EXPR.is_match(s) {
-        let dt = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M").unwrap();
-        Ok(Local.from_local_datetime(&dt).unwrap())
+        let nt = NaiveTime::parse_from_str(s, "%H:%M")?;
+        Ok(today.and_time(nt))
     } else {
-        // TODO: don't panic, return an error
-        panic!("Unable to parse {s} into a DateTime<Local>");
+        // Either `2023-05-26T09:00`, or parsing with the most specific format yields the appropriate error
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M")
     }
 }
 
@@ -126,16 +155,42 @@ impl TimeSpent {
     ///
     /// - On success, returns a `TimeSpent` instance containing both the original string and the parsed duration in seconds.
     /// - On failure, returns a variant of `Error::InvalidInput` describing the error.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn from_str(
+        s: &str,
+        work_hours_per_day: f32,
+        working_days_per_week: f32,
+    ) -> Result<TimeSpent, Error> {
+        Self::parse_with(
+            s,
+            work_hours_per_day * 3600.0,
+            working_days_per_week * work_hours_per_day * 3600.0,
+        )
+    }
+
+    /// Same as [`TimeSpent::from_str`], but interprets days and weeks according to the time
+    /// tracking configuration of the Jira instance.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidInput` if the duration could not be parsed
+    #[allow(clippy::cast_precision_loss)]
+    pub fn parse(s: &str, time_tracking: &TimeTrackingConfiguration) -> Result<TimeSpent, Error> {
+        Self::parse_with(
+            s,
+            time_tracking.seconds_per_day() as f32,
+            time_tracking.seconds_per_week() as f32,
+        )
+    }
+
     #[allow(
-        clippy::missing_errors_doc,
         clippy::missing_panics_doc,
         clippy::cast_precision_loss,
         clippy::cast_possible_truncation
     )]
-    pub fn from_str(
+    fn parse_with(
         s: &str,
-        work_hours_per_day: f32,
-        working_days_per_week: f32,
+        seconds_per_day: f32,
+        seconds_per_week: f32,
     ) -> Result<TimeSpent, Error> {
         static TIME_SPEC: LazyLock<Regex> = LazyLock::new(|| {
             Regex::new(r"\b(?:(\d+(?:[.,]\d{1,2})?)w)?(?:(\d+(?:[.,]\d{1,2})?)d)?(?:(\d+(?:[.,]\d{1,2})?)h)?(?:(\d+)m)?\b"
@@ -162,8 +217,8 @@ impl TimeSpent {
                     .map_or(0, |m| m.as_str().parse::<u32>().unwrap_or(0));
 
                 println!("Parsed time: {days} days, {hours} hours, {minutes} minutes");
-                let seconds: f32 = weeks * working_days_per_week * work_hours_per_day * 3600.0
-                    + days * work_hours_per_day * 3600.0
+                let seconds: f32 = weeks * seconds_per_week
+                    + days * seconds_per_day
                     + hours * 3600.0
                     + minutes as f32 * 60.0;
                 Ok(TimeSpent {
@@ -255,6 +310,34 @@ pub fn last_date_in_week_for(dt: DateTime<Local>) -> DateTime<Local> {
     dt.add(Days::new(u64::from(days)))
 }
 
+/// Is the date a working day, given the number of working days per week starting on Monday?
+/// A `working_days_per_week` of 5.0 makes Saturday and Sunday the weekend.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+#[must_use]
+pub fn is_working_day(date: NaiveDate, working_days_per_week: f32) -> bool {
+    date.weekday().num_days_from_monday() < working_days_per_week.round() as u32
+}
+
+/// Counts the working days from `start` to `end`, both inclusive, skipping the weekend given by
+/// `working_days_per_week` (see [`is_working_day`]) and the supplied `holidays`.
+///
+/// A fractional `working_days_per_week` is rounded to the nearest whole number of days,
+/// hence 4.5 makes Monday to Friday working days. Returns 0 if `end` is before `start`.
+#[must_use]
+pub fn working_days_between(
+    start: NaiveDate,
+    end: NaiveDate,
+    working_days_per_week: f32,
+    holidays: &[NaiveDate],
+) -> u32 {
+    let count = start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .filter(|day| is_working_day(*day, working_days_per_week) && !holidays.contains(day))
+        .count();
+    u32::try_from(count).unwrap_or(u32::MAX)
+}
+
 /// Splits a vector of day names and durations separated by ':' into
 /// a vector of tuples, holding the Weekday and the duration
 /// Given for instance \["mon:1,5h"\] the resulting vector will be
@@ -317,6 +400,69 @@ pub fn is_new_week(current_week: u32, dt: &NaiveDate) -> bool {
     dt.iso_week().week() > current_week
 }
 
+/// How [`format_seconds`] presents a duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// Hours and minutes, like `1h 45m`
+    HoursAndMinutes,
+    /// Hours with two decimals, like `1.75h`
+    Decimal,
+}
+
+/// Formats a number of seconds as hours and minutes, or decimal hours, without losing the minutes
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_seconds(seconds: i64, style: DurationStyle) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.unsigned_abs();
+    match style {
+        DurationStyle::HoursAndMinutes => match (seconds / 3600, seconds % 3600 / 60) {
+            (0, minutes) => format!("{sign}{minutes}m"),
+            (hours, 0) => format!("{sign}{hours}h"),
+            (hours, minutes) => format!("{sign}{hours}h {minutes}m"),
+        },
+        DurationStyle::Decimal => format!("{sign}{:.2}h", seconds as f64 / 3600.0),
+    }
+}
+
+/// Converts a `timeSpent` string as formatted by Jira, like `1d 2h 30m`, back to seconds.
+/// Days and weeks are converted using the time tracking configuration of the Jira instance,
+/// since a Jira day is a working day rather than 24 hours.
+///
+/// # Errors
+/// Returns `WorklogError::InvalidInput` if the string is empty, or any of the space separated
+/// parts is not a whole number followed by one of the units `w`, `d`, `h` or `m`
+pub fn jira_time_spent_to_seconds(
+    time_spent: &str,
+    seconds_per_day: i32,
+    seconds_per_week: i32,
+) -> Result<i32, WorklogError> {
+    let invalid = || WorklogError::InvalidInput(format!("Invalid Jira time spent '{time_spent}'"));
+    if time_spent.trim().is_empty() {
+        return Err(invalid());
+    }
+    let mut seconds = 0i32;
+    for part in time_spent.split_whitespace() {
+        let (amount, unit_seconds) = if let Some(amount) = part.strip_suffix('w') {
+            (amount, seconds_per_week)
+        } else if let Some(amount) = part.strip_suffix('d') {
+            (amount, seconds_per_day)
+        } else if let Some(amount) = part.strip_suffix('h') {
+            (amount, 3600)
+        } else if let Some(amount) = part.strip_suffix('m') {
+            (amount, 60)
+        } else {
+            return Err(invalid());
+        };
+        let amount: i32 = amount.parse().map_err(|_| invalid())?;
+        seconds = amount
+            .checked_mul(unit_seconds)
+            .and_then(|part_seconds| seconds.checked_add(part_seconds))
+            .ok_or_else(invalid)?;
+    }
+    Ok(seconds)
+}
+
 #[must_use]
 pub fn seconds_to_hour_and_min(seconds: i32) -> String {
     let hour = seconds / 3600;
@@ -329,6 +475,31 @@ pub fn seconds_to_hour_and_min(seconds: i32) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_working_days_between() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 12, day).unwrap();
+        // Friday the 20th to Friday the 27th spans a weekend, Christmas Day and Boxing Day
+        let holidays = [date(25), date(26)];
+        assert_eq!(working_days_between(date(20), date(27), 5.0, &[]), 6);
+        assert_eq!(working_days_between(date(20), date(27), 5.0, &holidays), 4);
+        // Holidays on the weekend are not counted twice
+        assert_eq!(
+            working_days_between(date(20), date(27), 5.0, &[date(21)]),
+            6
+        );
+        assert_eq!(working_days_between(date(20), date(20), 5.0, &holidays), 1);
+        assert_eq!(working_days_between(date(27), date(20), 5.0, &holidays), 0);
+    }
+
+    #[test]
+    fn test_working_days_between_with_fractional_week() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 12, day).unwrap();
+        // Monday the 16th to Sunday the 22nd
+        assert_eq!(working_days_between(date(16), date(22), 4.5, &[]), 5);
+        assert_eq!(working_days_between(date(16), date(22), 4.4, &[]), 4);
+        assert_eq!(working_days_between(date(16), date(22), 6.0, &[]), 6);
+    }
+
     #[test]
     fn test_parse_hour_and_minutes_to_seconds() {
         let seconds = parse_hour_and_minutes_to_seconds("01:30").unwrap();
@@ -342,6 +513,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_str_to_date_time_in_time_zone() {
+        let oslo = parse_time_zone("Europe/Oslo").unwrap();
+        let new_york = parse_time_zone("America/New_York").unwrap();
+
+        let in_oslo = str_to_date_time_in("2024-02-01T09:00", &oslo).unwrap();
+        let in_new_york = str_to_date_time_in("2024-02-01T09:00", &new_york).unwrap();
+        assert_eq!(
+            in_oslo.with_timezone(&Utc).to_rfc3339(),
+            "2024-02-01T08:00:00+00:00"
+        );
+        assert_eq!(
+            in_new_york.with_timezone(&Utc).to_rfc3339(),
+            "2024-02-01T14:00:00+00:00"
+        );
+
+        assert!(parse_time_zone("Europe/Atlantis").is_err());
+        // 02:30 is skipped when daylight saving time starts in Oslo
+        assert!(str_to_date_time_in("2024-03-31T02:30", &oslo).is_err());
+    }
+
     #[test]
     fn test_as_date_time() {
         let dt = NaiveDateTime::parse_from_str("2023-05-25T08:00", "%Y-%m-%dT%H:%M").unwrap();
@@ -485,6 +677,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_time_spent_parse_per_instance() {
+        let mut time_tracking = TimeTrackingConfiguration {
+            workingHoursPerDay: 7.5,
+            workingDaysPerWeek: 5.0,
+            timeFormat: "pretty".to_string(),
+            defaultUnit: "minute".to_string(),
+        };
+        assert_eq!(
+            TimeSpent::parse("1d", &time_tracking)
+                .unwrap()
+                .time_spent_seconds,
+            27_000
+        );
+        assert_eq!(
+            TimeSpent::parse("1w", &time_tracking)
+                .unwrap()
+                .time_spent_seconds,
+            135_000
+        );
+
+        time_tracking.workingHoursPerDay = 8.0;
+        assert_eq!(
+            TimeSpent::parse("1d", &time_tracking)
+                .unwrap()
+                .time_spent_seconds,
+            28_800
+        );
+        assert_eq!(
+            TimeSpent::parse("1w1h", &time_tracking)
+                .unwrap()
+                .time_spent_seconds,
+            147_600
+        );
+    }
+
     #[test]
     fn parse_durations() {
         assert_eq!(
@@ -549,6 +777,26 @@ mod tests {
         println!("{hour}:{minutes}");
     }
 
+    #[test]
+    fn test_format_seconds() {
+        for (seconds, hours_and_minutes, decimal) in [
+            (0, "0m", "0.00h"),
+            (45 * 60, "45m", "0.75h"),
+            (3600 + 45 * 60, "1h 45m", "1.75h"),
+            (8 * 3600, "8h", "8.00h"),
+        ] {
+            assert_eq!(
+                format_seconds(seconds, DurationStyle::HoursAndMinutes),
+                hours_and_minutes
+            );
+            assert_eq!(format_seconds(seconds, DurationStyle::Decimal), decimal);
+        }
+        assert_eq!(
+            format_seconds(-5400, DurationStyle::HoursAndMinutes),
+            "-1h 30m"
+        );
+    }
+
     #[test]
     fn test_first_date_in_week_for() {
         let now = Local.with_ymd_and_hms(2024, 11, 22, 21, 36, 0);
@@ -568,4 +816,36 @@ mod tests {
             NaiveDate::from_ymd_opt(2024, 11, 24).unwrap()
         );
     }
+
+    #[test]
+    fn test_jira_time_spent_to_seconds() -> Result<(), WorklogError> {
+        // Jira defaults to 8 hour days and 5 day weeks
+        let (per_day, per_week) = (8 * 3600, 5 * 8 * 3600);
+        assert_eq!(
+            jira_time_spent_to_seconds("1d 2h 30m", per_day, per_week)?,
+            8 * 3600 + 2 * 3600 + 30 * 60
+        );
+        assert_eq!(
+            jira_time_spent_to_seconds("3w", per_day, per_week)?,
+            3 * per_week
+        );
+        assert_eq!(
+            jira_time_spent_to_seconds("45m", per_day, per_week)?,
+            45 * 60
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_jira_time_spent_to_seconds_malformed() {
+        for malformed in ["", "1x", "1.5h", "h", "2h30m", "1d -"] {
+            assert!(
+                matches!(
+                    jira_time_spent_to_seconds(malformed, 28_800, 144_000),
+                    Err(WorklogError::InvalidInput(_))
+                ),
+                "'{malformed}' should be rejected"
+            );
+        }
+    }
 }
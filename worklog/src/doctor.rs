@@ -0,0 +1,573 @@
+//! Diagnostic checks of the environment the application is running in.
+//!
+//! Every check is an independent function returning a [`CheckResult`], which holds
+//! the outcome and a hint on how to remedy the problem. [`run_checks`] executes them all.
+
+use crate::config::{self, AppConfiguration};
+use crate::date;
+use crate::error::WorklogError;
+use crate::repository::database_manager::{DatabaseConfig, DatabaseManager};
+use crate::repository::sqlite;
+use crate::types::SchemaIssue;
+use chrono::{DateTime, Utc};
+use jira::{Jira, JiraError, UnreachableCause};
+use rusqlite::{Connection, OpenFlags};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+/// Maximum difference in seconds between the local clock and the Jira server clock
+pub const MAX_CLOCK_SKEW_SECONDS: i64 = 60;
+
+/// Round-trip time to Jira above which the connection is reported as slow
+pub const MAX_JIRA_LATENCY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "PASS"),
+            CheckStatus::Warn => write!(f, "WARN"),
+            CheckStatus::Fail => write!(f, "FAIL"),
+        }
+    }
+}
+
+/// The outcome of a single diagnostic check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to remedy a failed check
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        CheckResult {
+            name,
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        CheckResult {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        CheckResult {
+            name,
+            status: CheckStatus::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    #[must_use]
+    pub fn is_failure(&self) -> bool {
+        self.status == CheckStatus::Fail
+    }
+}
+
+const CONFIG_UPDATE_HINT: &str =
+    "Use 'timesheet config update --url <url> --user <user> --token <token>' to create it";
+
+/// Checks the outcome of loading the configuration file
+#[must_use]
+pub fn check_configuration(
+    loaded: &Result<(PathBuf, AppConfiguration), WorklogError>,
+) -> CheckResult {
+    const NAME: &str = "Configuration";
+    match loaded {
+        Err(WorklogError::ApplicationConfig { path, .. }) => {
+            CheckResult::fail(NAME, format!("Unable to read {path}"), CONFIG_UPDATE_HINT)
+        }
+        Err(WorklogError::TomlParse { path, .. }) => CheckResult::fail(
+            NAME,
+            format!("Unable to parse {}", path.display()),
+            "Fix the syntax errors or remove the file and run 'timesheet config update'",
+        ),
+        Err(err) => CheckResult::fail(NAME, err.to_string(), CONFIG_UPDATE_HINT),
+        Ok((path, app_config)) => {
+            if let Err(err) = Url::parse(&app_config.jira.url) {
+                return CheckResult::fail(
+                    NAME,
+                    format!("Invalid Jira URL '{}': {err}", app_config.jira.url),
+                    "Use 'timesheet config update --url <url>' to fix it",
+                );
+            }
+            if app_config.jira.user.trim().is_empty() {
+                return CheckResult::fail(
+                    NAME,
+                    "No Jira user configured",
+                    "Use 'timesheet config update --user <user>' to fix it",
+                );
+            }
+            // On macOS, the token is kept in the keychain, which is checked separately
+            if !cfg!(all(target_os = "macos", feature = "keychain"))
+                && !app_config.jira.has_valid_jira_token()
+            {
+                return CheckResult::fail(
+                    NAME,
+                    "The Jira token is missing or invalid",
+                    "Use 'timesheet config update --token <token>' to fix it",
+                );
+            }
+            CheckResult::pass(NAME, format!("Loaded from {}", path.display()))
+        }
+    }
+}
+
+/// Checks the outcome of looking up the Jira token in the keychain.
+/// `None` indicates that no keychain is used on this platform.
+#[must_use]
+pub fn check_keychain(token_lookup: Option<&Result<String, String>>) -> CheckResult {
+    const NAME: &str = "Keychain";
+    match token_lookup {
+        None => CheckResult::pass(NAME, "Not used on this platform"),
+        Some(Ok(_)) => CheckResult::pass(NAME, "Jira token found in the keychain"),
+        Some(Err(err)) => CheckResult::fail(
+            NAME,
+            format!("No Jira token in the keychain: {err}"),
+            "Use 'timesheet config update --token <token>' to store it",
+        ),
+    }
+}
+
+/// Checks that the local database can be opened and holds the complete schema
+#[must_use]
+pub fn check_database(path: &Path) -> CheckResult {
+    const NAME: &str = "Database";
+    if !path.exists() {
+        return CheckResult::warn(
+            NAME,
+            format!("{} does not exist yet", path.display()),
+            "Run 'timesheet sync' to create and populate it",
+        );
+    }
+    let connection = match Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(connection) => connection,
+        Err(err) => {
+            return CheckResult::fail(
+                NAME,
+                format!("Unable to open {}: {err}", path.display()),
+                "Check the file permissions, or remove the file and run 'timesheet sync'",
+            )
+        }
+    };
+    match sqlite::verify_schema(&connection) {
+        Ok(issues) if issues.is_empty() => CheckResult::pass(
+            NAME,
+            format!(
+                "{} holds all {} tables",
+                path.display(),
+                sqlite::SCHEMA_TABLES.len()
+            ),
+        ),
+        Ok(issues) if issues.iter().all(|issue| matches!(issue, SchemaIssue::ExtraColumn { .. })) => {
+            CheckResult::warn(
+                NAME,
+                format!("Schema differs: {}", join(&issues)),
+                "The columns are unknown to this version and left as is, upgrade if this is unexpected",
+            )
+        }
+        Ok(issues) => CheckResult::fail(
+            NAME,
+            format!("Schema differs: {}", join(&issues)),
+            "Run 'timesheet doctor --repair' to add the missing tables and columns",
+        ),
+        Err(err) => CheckResult::fail(
+            NAME,
+            format!("Unable to read the schema of {}: {err}", path.display()),
+            "The file may be corrupt, remove it and run 'timesheet sync'",
+        ),
+    }
+}
+
+fn join(issues: &[SchemaIssue]) -> String {
+    issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Adds the tables and columns missing from the local database given in the configuration,
+/// returning what was repaired.
+///
+/// # Errors
+/// Returns a `WorklogError` if the configuration could not be loaded, or the database could
+/// not be opened or altered
+pub fn repair_database() -> Result<Vec<SchemaIssue>, WorklogError> {
+    let (_, app_config) = config::load_no_keychain_lookup()?;
+    let path = PathBuf::from(&app_config.application_data.local_worklog);
+    DatabaseManager::new(&DatabaseConfig::SqliteOnDisk { path })?.repair_schema()
+}
+
+/// Checks that Jira is reachable and accepts our credentials
+pub async fn check_jira_authentication(jira: &Jira) -> CheckResult {
+    const NAME: &str = "Jira";
+    match jira.get_current_user().await {
+        Ok(user) => CheckResult::pass(NAME, format!("Authenticated as {}", user.display_name)),
+        Err(JiraError::Unauthorized) => CheckResult::fail(
+            NAME,
+            "Jira rejected the credentials",
+            "Create a new API token and run 'timesheet config update --token <token>'",
+        ),
+        Err(JiraError::Forbidden { message }) => CheckResult::fail(
+            NAME,
+            format!("Jira accepted the credentials, but denied access: {message}"),
+            "Ask your Jira administrator for access, a new API token will not help",
+        ),
+        Err(err @ (JiraError::MethodNotAllowed | JiraError::UnexpectedStatus)) => {
+            CheckResult::fail(
+                NAME,
+                format!("Unexpected response from Jira: {err}"),
+                "Verify that the configured URL points to the Jira REST API",
+            )
+        }
+        Err(err) => CheckResult::fail(
+            NAME,
+            format!("Unable to reach Jira: {}", err.to_string().trim_end()),
+            "Verify the Jira URL and your network connection",
+        ),
+    }
+}
+
+/// Checks the round-trip time to Jira, as measured by [`Jira::ping`]
+#[must_use]
+pub fn check_jira_latency(latency: Result<Duration, JiraError>) -> CheckResult {
+    const NAME: &str = "Latency";
+    match latency {
+        Ok(latency) if latency > MAX_JIRA_LATENCY => CheckResult::warn(
+            NAME,
+            format!("Jira latency: {}ms", latency.as_millis()),
+            "Jira responds slowly, verify your network connection",
+        ),
+        Ok(latency) => CheckResult::pass(NAME, format!("Jira latency: {}ms", latency.as_millis())),
+        Err(JiraError::Unreachable(cause, _)) => CheckResult::fail(
+            NAME,
+            format!("Unable to reach Jira, {cause}"),
+            match cause {
+                UnreachableCause::Dns => {
+                    "Verify the host name of the Jira URL and your DNS settings"
+                }
+                UnreachableCause::Connect => "Verify the Jira URL and any proxy or firewall",
+            },
+        ),
+        Err(JiraError::Timeout(_)) => CheckResult::fail(
+            NAME,
+            "Unable to reach Jira, the request timed out",
+            "Verify your network connection",
+        ),
+        Err(err) => CheckResult::fail(
+            NAME,
+            format!("Unable to reach Jira: {}", err.to_string().trim_end()),
+            "Verify the Jira URL and your network connection",
+        ),
+    }
+}
+
+/// Checks the difference between the local clock and the time reported by the Jira server
+#[must_use]
+pub fn check_clock_skew(
+    local_time: DateTime<Utc>,
+    server_time: Option<DateTime<Utc>>,
+) -> CheckResult {
+    const NAME: &str = "Clock";
+    let Some(server_time) = server_time else {
+        return CheckResult::warn(
+            NAME,
+            "The Jira server did not report its time",
+            "Verify the Jira connection",
+        );
+    };
+    let skew = (local_time - server_time).num_seconds();
+    if skew.abs() > MAX_CLOCK_SKEW_SECONDS {
+        CheckResult::fail(
+            NAME,
+            format!("The local clock differs {skew}s from the Jira server"),
+            "Synchronise your system clock, or the work logs will be registered at the wrong time",
+        )
+    } else {
+        CheckResult::pass(NAME, format!("Within {skew}s of the Jira server"))
+    }
+}
+
+/// Number of work log ids listed by [`check_time_spent`]
+const MAX_LISTED_WORKLOGS: usize = 5;
+
+/// Checks that the `timeSpent` text of the work logs agrees with `timeSpentSeconds`, given
+/// as `(worklog id, time spent, time spent seconds)`. Jira rounds `timeSpent` to whole
+/// minutes, hence differences of less than a minute are ignored.
+#[must_use]
+pub fn check_time_spent(
+    worklogs: &[(String, String, i32)],
+    seconds_per_day: i32,
+    seconds_per_week: i32,
+) -> CheckResult {
+    const NAME: &str = "Time spent";
+    let mismatched: Vec<&str> = worklogs
+        .iter()
+        .filter(|(_, time_spent, time_spent_seconds)| {
+            date::jira_time_spent_to_seconds(time_spent, seconds_per_day, seconds_per_week)
+                .map_or(true, |seconds| (seconds - time_spent_seconds).abs() >= 60)
+        })
+        .map(|(id, _, _)| id.as_str())
+        .collect();
+    if mismatched.is_empty() {
+        return CheckResult::pass(
+            NAME,
+            format!("All {} work logs are consistent", worklogs.len()),
+        );
+    }
+    let mut ids = mismatched[..mismatched.len().min(MAX_LISTED_WORKLOGS)].join(", ");
+    if mismatched.len() > MAX_LISTED_WORKLOGS {
+        ids.push_str(", ...");
+    }
+    CheckResult::warn(
+        NAME,
+        format!(
+            "{} work logs where timeSpent disagrees with timeSpentSeconds: {ids}",
+            mismatched.len()
+        ),
+        "Run 'timesheet sync' to refresh them from Jira",
+    )
+}
+
+/// Reads the id, `timeSpent` and `timeSpentSeconds` of every work log in the local database
+fn read_time_spent(path: &Path) -> rusqlite::Result<Vec<(String, String, i32)>> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt =
+        connection.prepare("SELECT id, time_spent, time_spent_seconds FROM worklog ORDER BY id")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+    rows.collect()
+}
+
+/// Runs all the checks in sequence. Checks depending on the configuration are skipped if
+/// it could not be loaded.
+pub async fn run_checks() -> Vec<CheckResult> {
+    let loaded = config::load_no_keychain_lookup();
+    let mut results = vec![check_configuration(&loaded)];
+    let Ok((_, mut app_config)) = loaded else {
+        return results;
+    };
+
+    let token_lookup = keychain_token(&app_config);
+    results.push(check_keychain(token_lookup.as_ref()));
+    if let Some(Ok(token)) = token_lookup {
+        app_config.jira.token = token;
+    }
+
+    let database = PathBuf::from(&app_config.application_data.local_worklog);
+    results.push(check_database(&database));
+
+    match Jira::new(&app_config.jira.url, app_config.jira.credentials()) {
+        Ok(jira) => {
+            results.push(check_jira_authentication(&jira).await);
+            results.push(check_jira_latency(jira.ping().await));
+            let server_time = jira.get_server_time().await.ok();
+            results.push(check_clock_skew(Utc::now(), server_time));
+            if let (true, Ok(worklogs), Ok(time_tracking)) = (
+                database.exists(),
+                read_time_spent(&database),
+                jira.get_time_tracking_options().await,
+            ) {
+                results.push(check_time_spent(
+                    &worklogs,
+                    time_tracking.seconds_per_day(),
+                    time_tracking.seconds_per_week(),
+                ));
+            }
+        }
+        Err(err) => results.push(CheckResult::fail(
+            "Jira",
+            format!("Unable to create the Jira client: {err:?}"),
+            "Use 'timesheet config update --url <url>' to fix the Jira URL",
+        )),
+    }
+    results
+}
+
+#[cfg(all(target_os = "macos", feature = "keychain"))]
+fn keychain_token(app_config: &AppConfiguration) -> Option<Result<String, String>> {
+    Some(
+        secure_credentials::macos::get_secure_token(
+            config::KEYCHAIN_SERVICE_NAME,
+            &app_config.jira.user,
+        )
+        .map_err(|err| err.to_string()),
+    )
+}
+
+#[cfg(not(all(target_os = "macos", feature = "keychain")))]
+fn keychain_token(_app_config: &AppConfiguration) -> Option<Result<String, String>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ApplicationData, CommentConfiguration, DisplayTimeZone, JiraClientConfiguration,
+    };
+    use crate::repository::database_manager::{DatabaseConfig, DatabaseManager};
+    use chrono::Duration;
+    use jira::Credentials;
+    use mockito::Server;
+    use std::collections::BTreeMap;
+
+    fn app_config(url: &str) -> AppConfiguration {
+        AppConfiguration {
+            jira: JiraClientConfiguration {
+                url: url.to_string(),
+                user: "steinar".to_string(),
+                token: "a_valid_token".to_string(),
+                user_agent: None,
+                api_version: None,
+                auth_type: config::AuthType::default(),
+            },
+            application_data: ApplicationData::default(),
+            comment: CommentConfiguration::default(),
+            display_tz: DisplayTimeZone::default(),
+            time_code_jql: config::DEFAULT_TIME_CODE_JQL.to_string(),
+            prune_orphan_issues: false,
+            holidays: vec![],
+            default_components: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_check_configuration() {
+        let missing = Err(WorklogError::ApplicationConfig {
+            path: "timesheet.toml".to_string(),
+            source: std::io::Error::from(std::io::ErrorKind::NotFound),
+        });
+        let result = check_configuration(&missing);
+        assert!(result.is_failure());
+        assert!(result.hint.unwrap().contains("timesheet config update"));
+
+        let invalid_url = Ok((PathBuf::from("timesheet.toml"), app_config("not a url")));
+        assert!(check_configuration(&invalid_url).is_failure());
+
+        let valid = Ok((
+            PathBuf::from("timesheet.toml"),
+            app_config("https://norn.atlassian.net"),
+        ));
+        assert_eq!(check_configuration(&valid).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_check_keychain() {
+        assert_eq!(check_keychain(None).status, CheckStatus::Pass);
+        assert!(check_keychain(Some(&Err("not found".to_string()))).is_failure());
+    }
+
+    #[test]
+    fn test_check_clock_skew() {
+        let now = Utc::now();
+        assert_eq!(
+            check_clock_skew(now, Some(now - Duration::seconds(5))).status,
+            CheckStatus::Pass
+        );
+        assert!(check_clock_skew(now, Some(now + Duration::minutes(10))).is_failure());
+        assert_eq!(check_clock_skew(now, None).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn test_check_jira_latency() {
+        let result = check_jira_latency(Ok(std::time::Duration::from_millis(180)));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(result.message, "Jira latency: 180ms");
+        assert_eq!(
+            check_jira_latency(Ok(std::time::Duration::from_secs(5))).status,
+            CheckStatus::Warn
+        );
+        assert!(check_jira_latency(Err(JiraError::Unauthorized)).is_failure());
+    }
+
+    #[test]
+    fn test_check_database() -> Result<(), WorklogError> {
+        let path = std::env::temp_dir().join("doctor-test.db");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(check_database(&path).status, CheckStatus::Warn);
+
+        DatabaseManager::new(&DatabaseConfig::SqliteOnDisk { path: path.clone() })?;
+        let result = check_database(&path);
+        std::fs::remove_file(&path)?;
+        assert_eq!(result.status, CheckStatus::Pass, "{}", result.message);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_jira_authentication_unauthorized() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let _myself = server
+            .mock("GET", mockito::Matcher::Regex("/myself$".to_string()))
+            .with_status(401)
+            .create_async()
+            .await;
+        let jira = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let result = check_jira_authentication(&jira).await;
+        assert!(result.is_failure());
+        assert_eq!(result.message, "Jira rejected the credentials");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_jira_authentication_forbidden() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let _myself = server
+            .mock("GET", mockito::Matcher::Regex("/myself$".to_string()))
+            .with_status(403)
+            .with_body(r#"{"errorMessages": ["Site access denied"]}"#)
+            .create_async()
+            .await;
+        let jira = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let result = check_jira_authentication(&jira).await;
+        assert!(result.is_failure());
+        assert_eq!(
+            result.message,
+            "Jira accepted the credentials, but denied access: Site access denied"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_time_spent() {
+        let (per_day, per_week) = (28_800, 144_000);
+        let worklogs = vec![
+            ("1".to_string(), "1d 2h 30m".to_string(), 37_800),
+            // Rounded by Jira to whole minutes
+            ("2".to_string(), "1h 30m".to_string(), 5_430),
+        ];
+        assert_eq!(
+            check_time_spent(&worklogs, per_day, per_week).status,
+            CheckStatus::Pass
+        );
+
+        let mut worklogs = worklogs;
+        worklogs.push(("3".to_string(), "3w".to_string(), 3600));
+        worklogs.push(("4".to_string(), "bogus".to_string(), 3600));
+        let result = check_time_spent(&worklogs, per_day, per_week);
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.message.ends_with(": 3, 4"), "{}", result.message);
+    }
+}
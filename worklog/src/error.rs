@@ -55,6 +55,8 @@ pub enum WorklogError {
     ActiveTimerExists,
     #[error("No active timer")]
     NoActiveTimer,
+    #[error("No recent work found to resume")]
+    NothingToResume,
     #[error("Database lock error")]
     DatabaseLockError,
     #[error("Timer not found")]
@@ -73,6 +75,10 @@ pub enum WorklogError {
     IssueNotFoundInLocalDBMS(String),
     #[error("Missing worklog parent, issue: {0} does not exist.")]
     MissingWorklogParentIssue(IssueKey),
+    #[error("Comment is {length} characters long, the maximum is {max_length}")]
+    CommentTooLong { length: usize, max_length: usize },
+    #[error("Unable to export the work logs: {0}")]
+    Export(String),
 }
 
 impl From<rusqlite::Error> for WorklogError {
@@ -93,6 +99,13 @@ impl From<date::Error> for WorklogError {
     }
 }
 
+#[cfg(feature = "xlsx")]
+impl From<rust_xlsxwriter::XlsxError> for WorklogError {
+    fn from(err: rust_xlsxwriter::XlsxError) -> Self {
+        WorklogError::Export(format!("{err}"))
+    }
+}
+
 impl From<ParseError> for WorklogError {
     fn from(value: ParseError) -> Self {
         WorklogError::InvalidUrl(value)
@@ -0,0 +1,152 @@
+//! Export of work log entries to files, like the Excel timesheets requested by finance.
+//!
+//! The Excel export is only available with the `xlsx` feature enabled.
+
+use std::collections::BTreeMap;
+use std::io::{Seek, Write};
+
+use chrono::{Datelike, Days, IsoWeek, NaiveDate};
+use jira::models::core::IssueKey;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use crate::error::WorklogError;
+use crate::types::LocalWorklog;
+
+/// Hours logged per issue and weekday, `0` being Monday, for a single week
+type WeeklyGrid = BTreeMap<IssueKey, [f64; 7]>;
+
+/// Writes the work logs as an Excel workbook with one worksheet for each week.
+///
+/// Each worksheet has a header with the date range of the week, followed by a grid with the
+/// issues as rows and the weekdays as columns, holding the hours logged. The last row and
+/// column hold the totals.
+///
+/// # Errors
+/// Returns `WorklogError::Export` if the workbook could not be written
+pub fn export_xlsx<W>(worklogs: &[LocalWorklog], writer: W) -> Result<(), WorklogError>
+where
+    W: Write + Seek + Send,
+{
+    let mut weeks: BTreeMap<IsoWeek, WeeklyGrid> = BTreeMap::new();
+    for worklog in worklogs {
+        let started = worklog.started.date_naive();
+        let hours = weeks
+            .entry(started.iso_week())
+            .or_default()
+            .entry(worklog.issue_key.clone())
+            .or_insert([0.0; 7]);
+        hours[started.weekday().num_days_from_monday() as usize] +=
+            f64::from(worklog.timeSpentSeconds) / 3600.0;
+    }
+
+    let mut workbook = Workbook::new();
+    for (week, grid) in &weeks {
+        write_week(&mut workbook, *week, grid)?;
+    }
+    if weeks.is_empty() {
+        workbook
+            .add_worksheet()
+            .write_string(0, 0, "No work logs found")?;
+    }
+    workbook.save_to_writer(writer)?;
+    Ok(())
+}
+
+fn write_week(workbook: &mut Workbook, week: IsoWeek, grid: &WeeklyGrid) -> Result<(), XlsxError> {
+    let bold = Format::new().set_bold();
+    let hours_format = Format::new().set_num_format("0.00");
+    let monday = NaiveDate::from_isoywd_opt(week.year(), week.week(), chrono::Weekday::Mon)
+        .expect("An ISO week always has a Monday");
+    let sunday = monday + Days::new(6);
+
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(format!("{}-W{:02}", week.year(), week.week()))?;
+    worksheet.write_string_with_format(0, 0, format!("Timesheet {monday} - {sunday}"), &bold)?;
+
+    worksheet.write_string_with_format(2, 0, "Issue", &bold)?;
+    for (day, column) in (0..7u16).map(|day| (monday + Days::new(day.into()), day + 1)) {
+        worksheet.write_string_with_format(2, column, day.format("%a %d.%m").to_string(), &bold)?;
+    }
+    worksheet.write_string_with_format(2, 8, "Total", &bold)?;
+
+    let mut row = 3;
+    let mut day_totals = [0.0; 7];
+    for (issue_key, hours) in grid {
+        worksheet.write_string(row, 0, issue_key.value())?;
+        for (column, value) in (1u16..).zip(hours) {
+            if *value > 0.0 {
+                worksheet.write_number_with_format(row, column, *value, &hours_format)?;
+            }
+        }
+        worksheet.write_number_with_format(row, 8, hours.iter().sum::<f64>(), &hours_format)?;
+        for (total, value) in day_totals.iter_mut().zip(hours) {
+            *total += value;
+        }
+        row += 1;
+    }
+
+    worksheet.write_string_with_format(row, 0, "Total", &bold)?;
+    for (column, total) in (1u16..).zip(day_totals) {
+        worksheet.write_number_with_format(row, column, total, &hours_format)?;
+    }
+    worksheet.write_number_with_format(row, 8, day_totals.iter().sum::<f64>(), &hours_format)?;
+    worksheet.set_column_width(0, 14)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{open_workbook, Data, Reader, Xlsx};
+    use chrono::{Local, TimeZone};
+    use std::fs::File;
+
+    fn worklog(id: &str, issue_key: &str, started: &str, seconds: i32) -> LocalWorklog {
+        let started = Local
+            .from_local_datetime(
+                &chrono::NaiveDateTime::parse_from_str(started, "%Y-%m-%dT%H:%M").unwrap(),
+            )
+            .unwrap();
+        LocalWorklog::builder(IssueKey::from(issue_key), id, seconds)
+            .author("Ola Dunk")
+            .created(started)
+            .updated(started)
+            .started(started)
+            .issue_id(1)
+            .build()
+    }
+
+    #[test]
+    fn test_export_xlsx_weekly_grid_with_totals() -> Result<(), WorklogError> {
+        let worklogs = vec![
+            worklog("1", "TIME-1", "2024-02-05T08:00", 7200),
+            worklog("2", "TIME-1", "2024-02-06T08:00", 3600),
+            worklog("3", "TIME-2", "2024-02-06T10:00", 5400),
+            // The next week
+            worklog("4", "TIME-2", "2024-02-12T08:00", 1800),
+        ];
+        let path = std::env::temp_dir().join(format!("export_test_{}.xlsx", std::process::id()));
+        export_xlsx(&worklogs, File::create(&path)?)?;
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["2024-W06", "2024-W07"]);
+        let range = workbook.worksheet_range("2024-W06").unwrap();
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(
+            range.get_value((0, 0)),
+            Some(&Data::String(
+                "Timesheet 2024-02-05 - 2024-02-11".to_string()
+            ))
+        );
+        assert_eq!(
+            range.get_value((3, 0)),
+            Some(&Data::String("TIME-1".to_string()))
+        );
+        // Tuesday holds 1h on TIME-1 and 1,5h on TIME-2
+        assert_eq!(range.get_value((5, 2)), Some(&Data::Float(2.5)));
+        // Grand total of the week
+        assert_eq!(range.get_value((5, 8)), Some(&Data::Float(4.5)));
+        Ok(())
+    }
+}
@@ -40,7 +40,7 @@
 //!
 //! The new example demonstrates how to use the `ApplicationRuntimeBuilder` with its fluent interface to customize the runtime with specific configuration options before building it. This builder pattern gives users more flexibility compared to the simple `ApplicationRuntime::new()` approach in the original example.
 
-use crate::config::JiraClientConfiguration;
+use crate::config::{CommentConfiguration, DisplayTimeZone, JiraClientConfiguration};
 /// The `ApplicationRuntime` struct serves as the main runtime environment for the application,
 /// providing access to essential services such as issue management, user management, and
 /// worklog management. It facilitates communication with the Jira API and local worklog
@@ -56,31 +56,38 @@ use crate::config::JiraClientConfiguration;
 /// * `issue_service` - A shared instance of the `IssueService` for managing issues.
 /// * `component_service` - A shared instance of the `ComponentService` for managing components.
 use crate::error::WorklogError;
+pub use crate::repository::database_manager::ReadConn;
 use crate::repository::database_manager::{DatabaseConfig, DatabaseManager};
 use crate::service::component::ComponentService;
 use crate::service::issue::IssueService;
 pub use crate::service::timer::TimerService;
 use crate::service::user::UserService;
 use crate::service::worklog::WorkLogService;
+use chrono::{DateTime, Local, NaiveDate};
 use config::AppConfiguration;
 use jira::builder::JiraBuilder;
 use jira::models::issue::IssueSummary;
-use jira::{Credentials, Jira};
+use jira::Jira;
 use log::debug;
 use operation::{
-    add::{self, Add},
+    add::{self, Add, AddBatch, BatchReport},
     codes,
     del::{self, Del},
 };
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use types::LocalWorklog;
 
 pub mod config;
 pub mod date;
+pub mod doctor;
 pub mod error;
+#[cfg(feature = "xlsx")]
+pub mod export;
 pub mod operation;
+pub mod prelude;
 
 pub mod types;
 
@@ -111,10 +118,22 @@ pub struct ApplicationRuntime {
     pub issue_service: Arc<IssueService>,
     pub component_service: Arc<ComponentService>,
     pub timer_service: Arc<TimerService>,
+    pub comment_configuration: CommentConfiguration,
+    pub display_tz: DisplayTimeZone,
+    /// JQL locating the issues used as time codes
+    pub time_code_jql: String,
+    /// Remove the issue of a deleted work log entry, once no entries refer to it
+    pub prune_orphan_issues: bool,
+    /// Days off, which are not working days
+    pub holidays: Vec<NaiveDate>,
+    /// Names of the components given to new issues, by project key
+    pub default_components: BTreeMap<String, Vec<String>>,
+    database_manager: Arc<DatabaseManager>,
 }
 
 pub enum Operation {
     Add(Add),
+    AddBatch(AddBatch),
     Del(Del),
     Codes,
     Sync(operation::sync::Sync),
@@ -122,9 +141,10 @@ pub enum Operation {
 
 pub enum OperationResult {
     Added(Vec<LocalWorklog>),
+    AddedBatch(BatchReport),
     Deleted(String),
     IssueSummaries(Vec<IssueSummary>),
-    Synchronised,
+    Synchronised(operation::sync::SyncReport),
 }
 
 impl ApplicationRuntime {
@@ -179,6 +199,29 @@ impl ApplicationRuntime {
         self.timer_service.clone()
     }
 
+    /// Opens a read-only connection to the local database, for queries which should not
+    /// wait for writers to complete. See [`ReadConn`]
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the database could not be opened
+    pub fn snapshot_read(&self) -> Result<ReadConn, WorklogError> {
+        self.database_manager.snapshot_read()
+    }
+
+    /// Moves the work logs started before `cutoff` out of the local database, into the
+    /// archive database at `archive_path`. Returns the number of work logs moved.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the archive could not be written. See
+    /// [`DatabaseManager::archive_before`]
+    pub fn archive_before(
+        &self,
+        cutoff: DateTime<Local>,
+        archive_path: &Path,
+    ) -> Result<usize, WorklogError> {
+        self.database_manager.archive_before(cutoff, archive_path)
+    }
+
     /// Executes the specified `Operation` and returns the result.
     ///
     /// # Arguments
@@ -194,6 +237,7 @@ impl ApplicationRuntime {
     /// This function may return an error (`WorklogError`) in the following scenarios:
     ///
     /// - When adding worklogs fails during `Operation::Add`.
+    /// - When an atomic batch holds invalid entries during `Operation::AddBatch`.
     /// - When deleting a worklog entry fails during `Operation::Del`.
     /// - When fetching issue summaries fails during `Operation::Codes`.
     /// - When syncing worklogs with Jira fails during `Operation::Sync`.
@@ -218,6 +262,10 @@ impl ApplicationRuntime {
                 let worklogs = add::execute(self, &mut instructions).await?;
                 Ok(OperationResult::Added(worklogs))
             }
+            Operation::AddBatch(instructions) => {
+                let report = add::execute_batch(self, &instructions).await?;
+                Ok(OperationResult::AddedBatch(report))
+            }
             Operation::Del(instructions) => {
                 let id = del::execute(self, &instructions).await?;
                 Ok(OperationResult::Deleted(id))
@@ -227,8 +275,8 @@ impl ApplicationRuntime {
                 Ok(OperationResult::IssueSummaries(issues))
             }
             Operation::Sync(sync_cmd) => {
-                operation::sync::execute(self, &sync_cmd).await?;
-                Ok(OperationResult::Synchronised)
+                let report = operation::sync::execute(self, &sync_cmd).await?;
+                Ok(OperationResult::Synchronised(report))
             }
         }
     }
@@ -300,6 +348,7 @@ pub struct ApplicationRuntimeBuilder {
     config: AppConfiguration,
     use_in_memory_db: bool,       // Internal field to toggle in-memory mode.
     use_jira_test_instance: bool, // Internal field to toggle Jira test instance.
+    jira_client: Option<Jira>,    // Supplied Jira client, overrides the configuration.
 }
 
 impl Default for ApplicationRuntimeBuilder {
@@ -307,15 +356,25 @@ impl Default for ApplicationRuntimeBuilder {
         ApplicationRuntimeBuilder {
             use_in_memory_db: false,
             use_jira_test_instance: false,
+            jira_client: None,
             config: AppConfiguration {
                 jira: JiraClientConfiguration {
                     url: "https://norns.atlassian.net".to_string(),
                     user: "<USER>".to_string(),
                     token: "<PASSWORD>".to_string(),
+                    user_agent: None,
+                    api_version: None,
+                    auth_type: config::AuthType::default(),
                 },
                 application_data: config::ApplicationData {
                     local_worklog: "local_worklog.db".to_string(),
                 },
+                comment: config::CommentConfiguration::default(),
+                display_tz: config::DisplayTimeZone::default(),
+                time_code_jql: config::DEFAULT_TIME_CODE_JQL.to_string(),
+                prune_orphan_issues: false,
+                holidays: vec![],
+                default_components: BTreeMap::new(),
             },
         }
     }
@@ -417,6 +476,15 @@ impl ApplicationRuntimeBuilder {
         self
     }
 
+    /// Configures the `ApplicationRuntime` to use the supplied Jira client, rather than
+    /// creating one from the configuration file or the environment variables.
+    /// Typically used to run against a mocked Jira instance in tests.
+    #[must_use]
+    pub fn use_jira_client(mut self, jira_client: Jira) -> Self {
+        self.jira_client = Some(jira_client);
+        self
+    }
+
     /// Finalizes the construction of the `ApplicationRuntime` instance.
     ///
     /// This method initializes various components required by `ApplicationRuntime`, such as
@@ -451,7 +519,7 @@ impl ApplicationRuntimeBuilder {
     pub fn build(&mut self) -> Result<ApplicationRuntime, WorklogError> {
         let jira_client = self.create_jira_client()?;
 
-        let database_manager = &self.create_database_manager()?;
+        let database_manager = Arc::new(self.create_database_manager()?);
 
         let user_repo = database_manager.create_user_repository();
         let worklog_repo = database_manager.create_worklog_repository();
@@ -460,7 +528,7 @@ impl ApplicationRuntimeBuilder {
         let timer_repo = database_manager.create_timer_repository();
 
         let user_service = Arc::new(UserService::new(user_repo));
-        let issue_service = Arc::new(IssueService::new(issue_repo));
+        let issue_service = Arc::new(IssueService::new(issue_repo, jira_client.clone()));
         let worklog_service = Arc::new(WorkLogService::new(
             worklog_repo,
             issue_service.clone(),
@@ -472,6 +540,7 @@ impl ApplicationRuntimeBuilder {
             Arc::clone(&issue_service),
             Arc::clone(&worklog_service),
             jira_client.clone(),
+            self.config.comment.clone(),
         ));
 
         Ok(ApplicationRuntime {
@@ -481,6 +550,13 @@ impl ApplicationRuntimeBuilder {
             issue_service,
             component_service,
             timer_service,
+            comment_configuration: self.config.comment.clone(),
+            display_tz: self.config.display_tz,
+            time_code_jql: self.config.time_code_jql.clone(),
+            prune_orphan_issues: self.config.prune_orphan_issues,
+            holidays: self.config.holidays.clone(),
+            default_components: self.config.default_components.clone(),
+            database_manager,
         })
     }
 
@@ -489,9 +565,10 @@ impl ApplicationRuntimeBuilder {
     /// Note! If `!use_jira_test_instance`, the disk configuration file will be loaded into
     /// the `config` field.
     ///
-    /// This method handles two different scenarios for creating a Jira client:
-    /// 1. Using environment variables (when `use_jira_test_instance` is true)
-    /// 2. Using configuration file settings (when `use_jira_test_instance` is false)
+    /// This method handles three different scenarios for creating a Jira client:
+    /// 1. Using the client supplied with `use_jira_client`
+    /// 2. Using environment variables (when `use_jira_test_instance` is true)
+    /// 3. Using configuration file settings (when `use_jira_test_instance` is false)
     ///
     /// # Returns
     ///
@@ -507,24 +584,31 @@ impl ApplicationRuntimeBuilder {
     /// - `WorklogError::JiraError`: When the Jira client fails to initialize with provided credentials
     ///
     fn create_jira_client(&mut self) -> Result<Jira, WorklogError> {
-        if self.use_jira_test_instance {
+        if let Some(jira_client) = &self.jira_client {
+            Ok(jira_client.clone())
+        } else if self.use_jira_test_instance {
             // Use environment variables for test instance
             JiraBuilder::create_from_env().map_err(WorklogError::JiraBuildError)
         } else {
             // Load configuration from disk file to obtain Jira credentials
-            self.config = config::load_with_keychain_lookup()?;
+            self.config = config::load_with_keychain_lookup(config::credential_store().as_ref())?;
             self.create_jira_from_config()
         }
     }
 
     /// Helper method to create a Jira client from the current configuration
     fn create_jira_from_config(&self) -> Result<Jira, WorklogError> {
-        let credentials = Credentials::Basic(
-            self.config.jira.user.clone(),
-            self.config.jira.token.clone(),
-        );
-
-        Jira::new(&self.config.jira.url, credentials)
+        let mut builder = JiraBuilder::new()
+            .host(&self.config.jira.url)
+            .credentials(self.config.jira.credentials());
+        if let Some(user_agent) = &self.config.jira.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(api_version) = &self.config.jira.api_version {
+            builder = builder.api_version(api_version);
+        }
+        builder
+            .build()
             .map_err(|e| WorklogError::JiraError(e.to_string()))
     }
 
@@ -550,3 +634,40 @@ impl ApplicationRuntimeBuilder {
         Ok(database_manager)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jira::builder::DEFAULT_API_VERSION;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_bearer_auth_type_sends_the_token_as_bearer() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let myself = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/myself").as_str(),
+            )
+            .match_header("authorization", Matcher::Regex("^Bearer ".to_string()))
+            .with_status(200)
+            .with_body(
+                r#"{"self": "https://jira.example.com/rest/api/2/user?username=ola",
+                "accountId": "", "name": "ola", "key": "JIRAUSER10200", "displayName": "Ola",
+                "emailAddress": "ola@dunk.com", "timeZone": "Europe/Oslo"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let mut builder = ApplicationRuntimeBuilder::new().use_in_memory_db();
+        builder.config.jira.url = server.url();
+        builder.config.jira.token = "personal-access-token".to_string();
+        builder.config.jira.auth_type = config::AuthType::Bearer;
+        let jira_client = builder.create_jira_from_config()?;
+        let runtime = builder.use_jira_client(jira_client).build()?;
+
+        runtime.jira_client().get_current_user().await?;
+        myself.assert_async().await;
+        Ok(())
+    }
+}
@@ -14,6 +14,9 @@
 //!     issue_key: "PROJ-123".to_string(),
 //!     started: None,
 //!     comment: Some("Development work".to_string()),
+//!     timezone: None,
+//!     tags: vec!["billable".to_string()],
+//!     force: false,
 //! };
 //!
 //! // Add multiple worklog entries
@@ -22,6 +25,9 @@
 //!     issue_key: "PROJ-123".to_string(),
 //!     started: None,
 //!     comment: Some("Weekly work".to_string()),
+//!     timezone: Some("Europe/Oslo".to_string()),
+//!     tags: vec![],
+//!     force: false,
 //! };
 //! ```
 //!
@@ -36,24 +42,92 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{Datelike, Local, TimeZone, Weekday};
+use chrono_tz::Tz;
 use jira::{
-    models::{core::IssueKey, setting::TimeTrackingConfiguration},
+    models::{
+        core::IssueKey,
+        setting::TimeTrackingConfiguration,
+        user::{User, UserIdentity},
+    },
     Jira, JiraError,
 };
 use log::{debug, info};
+use serde::{Deserialize, Serialize};
 
-use crate::{date, error::WorklogError, types::LocalWorklog, ApplicationRuntime};
+use crate::{
+    config::CommentConfiguration,
+    date,
+    doctor::MAX_CLOCK_SKEW_SECONDS,
+    error::WorklogError,
+    types::{JiraIssueInfo, LocalWorklog},
+    ApplicationRuntime,
+};
 
 pub struct Add {
     pub durations: Vec<String>,
     pub issue_key: String,
     pub started: Option<String>,
     pub comment: Option<String>,
+    /// IANA time zone in which `started` is given, the local time zone if not supplied
+    pub timezone: Option<String>,
+    /// Local tags of the added entries, which are not sent to Jira
+    pub tags: Vec<String>,
+    /// Do not warn if the issue is resolved, or assigned to someone else
+    pub force: bool,
+}
+
+/// Invoked once per entry of a batch with the index of the entry, after the entry has been
+/// added to Jira and the local database, or has been rejected
+pub type BatchProgress = Box<dyn Fn(usize, Result<&LocalWorklog, &WorklogError>) + Send + Sync>;
+
+/// Adds a batch of work log entries, like the JSON array read by [`parse_entries`]
+pub struct AddBatch {
+    pub entries: Vec<AddEntry>,
+    /// Reject the entire batch if any of the entries are invalid
+    pub atomic: bool,
+    /// Do not warn about the entries logging time against resolved issues
+    pub force: bool,
+    /// Reports the outcome of each entry as soon as it is known
+    pub progress: Option<BatchProgress>,
+}
+
+impl AddBatch {
+    /// Creates a batch of the supplied entries, like the ones given as `ISSUE=DURATION` pairs
+    #[must_use]
+    pub fn from_entries(entries: Vec<AddEntry>, atomic: bool, force: bool) -> Self {
+        AddBatch {
+            entries,
+            atomic,
+            force,
+            progress: None,
+        }
+    }
+}
+
+/// A single work log entry of a JSON batch. A missing `issue_key` or `duration` is parsed as
+/// empty, so that the entry is rejected on its own rather than failing the entire batch.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AddEntry {
+    #[serde(default)]
+    pub issue_key: String,
+    /// Defaults to the duration before now, like `--started` of `timesheet add`
+    pub started: Option<String>,
+    /// Like `1h`, `7,5h` or `1d`
+    #[serde(default)]
+    pub duration: String,
+    pub comment: Option<String>,
+}
+
+/// Outcome of adding a batch of entries, each identified by its index in the JSON array
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub added: Vec<(usize, LocalWorklog)>,
+    pub rejected: Vec<(usize, WorklogError)>,
 }
 
 // Trait for Jira client operations needed by this module
 #[async_trait]
-pub trait JiraClient {
+pub trait JiraClient: Send + Sync {
     async fn get_time_tracking_options(&self) -> Result<TimeTrackingConfiguration, JiraError>;
     async fn insert_worklog(
         &self,
@@ -62,6 +136,11 @@ pub trait JiraClient {
         time_spent_seconds: i32,
         comment: &str,
     ) -> Result<jira::models::worklog::Worklog, JiraError>;
+
+    /// How far the local clock is ahead of the Jira server clock, if known
+    async fn clock_skew(&self) -> Option<chrono::Duration> {
+        None
+    }
 }
 
 // Implement the trait for the concrete Jira client
@@ -78,8 +157,21 @@ impl JiraClient for Jira {
         time_spent_seconds: i32,
         comment: &str,
     ) -> Result<jira::models::worklog::Worklog, JiraError> {
-        self.insert_worklog(issue_id, started, time_spent_seconds, comment)
-            .await
+        if issue_id.trim().is_empty() {
+            return Err(JiraError::RequiredParameter("issue_id".to_string()));
+        }
+        self.insert_worklog(
+            &IssueKey::from(issue_id),
+            started,
+            time_spent_seconds,
+            comment,
+            true,
+        )
+        .await
+    }
+
+    async fn clock_skew(&self) -> Option<chrono::Duration> {
+        self.clock_skew().await.ok()
     }
 }
 
@@ -99,6 +191,7 @@ impl JiraClient for Jira {
 /// * `WorklogError::BadInput` - When durations are empty or in invalid format
 /// * `WorklogError::JiraError` - When there are issues communicating with Jira
 /// * `WorklogError::TimeError` - When there are problems with time calculations
+/// * `WorklogError::CommentTooLong` - When the comment is too long and the comment policy is `reject`
 ///
 /// # Panics
 ///
@@ -125,6 +218,29 @@ pub async fn execute(
     // Ensure the issue key is always uppercase
     instructions.issue_key = instructions.issue_key.to_uppercase();
 
+    let issue = runtime
+        .issue_service()
+        .ensure_issue(&IssueKey::from(instructions.issue_key.as_str()))
+        .await?;
+    for warning in issue_warnings(runtime, &issue, instructions.force).await {
+        eprintln!("WARNING: {warning}");
+    }
+
+    let time_zone = instructions
+        .timezone
+        .as_deref()
+        .map(date::parse_time_zone)
+        .transpose()?;
+
+    // Jira and the local database must receive the same comment
+    let (comment, notice) = runtime
+        .comment_configuration
+        .enforce(instructions.comment.take())?;
+    if let Some(notice) = notice {
+        eprintln!("WARNING: {notice}");
+    }
+    instructions.comment = comment;
+
     debug!(
         "Length: {} and durations[0]={}",
         instructions.durations.len(),
@@ -143,6 +259,7 @@ pub async fn execute(
             instructions.issue_key.clone(),
             &instructions.durations[0],
             instructions.started.clone(),
+            time_zone.as_ref(),
             instructions.comment.clone(),
         )
         .await?;
@@ -158,6 +275,7 @@ pub async fn execute(
             time_tracking_options,
             instructions.issue_key.clone(),
             instructions.durations.clone(),
+            time_zone.as_ref(),
             instructions.comment.clone(),
         )
         .await?;
@@ -172,10 +290,230 @@ pub async fn execute(
         .worklog_service()
         .add_worklog_entries(&added_worklog_items)
         .await?;
+    if !instructions.tags.is_empty() {
+        for worklog in &added_worklog_items {
+            runtime
+                .worklog_service()
+                .add_tags(&worklog.id, &instructions.tags)?;
+        }
+    }
 
     Ok(added_worklog_items)
 }
 
+/// Adds a batch of work log entries.
+///
+/// Invalid entries are reported with their index in the batch, while the remaining entries
+/// are added, unless `atomic` is set.
+///
+/// Each entry is written to the local database, in a transaction of its own, as soon as Jira
+/// has accepted it. Committing every entry costs far less than the round trip to Jira for it,
+/// and ensures that the entries already added to Jira are kept locally if the batch is
+/// interrupted, hence the remaining entries may be added by re-running the batch without them.
+///
+/// # Errors
+///
+/// * `WorklogError::BadInput` - When `atomic` is set and any of the entries are invalid
+/// * `WorklogError::JiraError` - When the time tracking options could not be retrieved from Jira
+/// * `WorklogError::Sql` - When an added entry could not be written to the local database, in
+///   which case the remaining entries are not added
+pub async fn execute_batch(
+    runtime: &ApplicationRuntime,
+    instructions: &AddBatch,
+) -> Result<BatchReport, WorklogError> {
+    let mut entries = validate_entries(&instructions.entries);
+    // Retrieves the issues into the local database, which the added entries refer to
+    ensure_issues(runtime, &mut entries, instructions.force).await;
+    let client = runtime.jira_client();
+    let time_tracking_options = client.get_time_tracking_options().await?;
+
+    add_entries(
+        client,
+        &time_tracking_options,
+        &runtime.comment_configuration,
+        entries,
+        instructions.atomic,
+        &mut |index, result| {
+            if let Ok(worklog) = result {
+                runtime
+                    .worklog_service()
+                    .add_entries_of_cached_issues(std::slice::from_ref(worklog))?;
+            }
+            if let Some(progress) = &instructions.progress {
+                progress(index, result);
+            }
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Parses a JSON array of [`AddEntry`] objects, typically read from stdin
+///
+/// # Errors
+/// Returns `WorklogError::BadInput` if the input is not a JSON array of entries
+pub fn parse_entries(json: &str) -> Result<Vec<AddEntry>, WorklogError> {
+    serde_json::from_str(json)
+        .map_err(|e| WorklogError::BadInput(format!("Expected a JSON array of entries: {e}")))
+}
+
+/// Validates each entry, keeping the error of each invalid entry in its place
+fn validate_entries(entries: &[AddEntry]) -> Vec<Result<AddEntry, WorklogError>> {
+    entries.iter().cloned().map(validate_entry).collect()
+}
+
+/// The warnings about logging time against the issue, which is resolved or assigned to
+/// someone else. There are none if `force` is set.
+async fn issue_warnings(
+    runtime: &ApplicationRuntime,
+    issue: &JiraIssueInfo,
+    force: bool,
+) -> Vec<String> {
+    if force {
+        return Vec::new();
+    }
+    let mut warnings: Vec<String> = issue.resolution_warning().into_iter().collect();
+    if let (Ok(current_user), Ok(identity)) = (
+        runtime.user_service().find_current_user(),
+        runtime.jira_client().user_identity().await,
+    ) {
+        warnings.extend(assignee_warning(issue, &current_user, identity));
+    }
+    warnings
+}
+
+/// Warns if the issue is known to be assigned to someone other than the current user.
+/// Unassigned issues, and issues cached before the assignee was recorded, yield no warning.
+fn assignee_warning(
+    issue: &JiraIssueInfo,
+    current_user: &User,
+    identity: UserIdentity,
+) -> Option<String> {
+    issue
+        .assignee
+        .as_ref()
+        .filter(|assignee| !identity.matches(assignee, current_user))
+        .map(|assignee| {
+            format!(
+                "{} is assigned to {}, not you. Use --force to silence this warning",
+                issue.issue_key, assignee.displayName
+            )
+        })
+}
+
+/// Replaces the entries of issues which could not be retrieved with an error, and warns about
+/// the entries logging time against resolved issues, unless `force` is set
+async fn ensure_issues(
+    runtime: &ApplicationRuntime,
+    entries: &mut [Result<AddEntry, WorklogError>],
+    force: bool,
+) {
+    for (index, entry) in entries.iter_mut().enumerate() {
+        if let Ok(add_entry) = entry {
+            let issue_key = IssueKey::from(add_entry.issue_key.as_str());
+            match runtime.issue_service().ensure_issue(&issue_key).await {
+                Ok(issue) => {
+                    if let Some(warning) = issue.resolution_warning().filter(|_| !force) {
+                        eprintln!("WARNING: Entry #{index}: {warning}");
+                    }
+                }
+                Err(e) => *entry = Err(e),
+            }
+        }
+    }
+}
+
+fn validate_entry(mut entry: AddEntry) -> Result<AddEntry, WorklogError> {
+    if entry.issue_key.trim().is_empty() {
+        return Err(WorklogError::BadInput(
+            "issue_key may not be empty".to_string(),
+        ));
+    }
+    if entry.duration.trim().is_empty() {
+        return Err(WorklogError::BadInput(
+            "duration may not be empty".to_string(),
+        ));
+    }
+    if let Some(started) = &entry.started {
+        date::str_to_date_time(started).map_err(|e| {
+            WorklogError::BadInput(format!("Unable to parse started '{started}': {e}"))
+        })?;
+    }
+    // Ensure the issue key is always uppercase
+    entry.issue_key = entry.issue_key.to_uppercase();
+    Ok(entry)
+}
+
+/// Receives the outcome of each entry of a batch, see [`add_entries`]
+type OnEntryResult<'a> =
+    dyn FnMut(usize, Result<&LocalWorklog, &WorklogError>) -> Result<(), WorklogError> + Send + 'a;
+
+/// Adds the entries to Jira one by one, passing the outcome of each to `on_result` before the
+/// next entry is added. An error returned by `on_result` stops the batch.
+async fn add_entries(
+    client: &dyn JiraClient,
+    time_tracking_options: &TimeTrackingConfiguration,
+    comment_configuration: &CommentConfiguration,
+    entries: Vec<Result<AddEntry, WorklogError>>,
+    atomic: bool,
+    on_result: &mut OnEntryResult<'_>,
+) -> Result<BatchReport, WorklogError> {
+    // Applies the comment policy up front, so that it is covered by the atomic check
+    let entries: Vec<Result<AddEntry, WorklogError>> = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            entry.and_then(|mut entry| {
+                let (comment, notice) = comment_configuration.enforce(entry.comment.take())?;
+                if let Some(notice) = notice {
+                    eprintln!("WARNING: Entry #{index}: {notice}");
+                }
+                entry.comment = comment;
+                Ok(entry)
+            })
+        })
+        .collect();
+
+    if atomic {
+        let invalid: Vec<String> = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| entry.as_ref().err().map(|e| format!("#{index}: {e}")))
+            .collect();
+        if !invalid.is_empty() {
+            return Err(WorklogError::BadInput(format!(
+                "No entries added, as some are invalid: {}",
+                invalid.join(", ")
+            )));
+        }
+    }
+
+    let mut report = BatchReport::default();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let result = match entry {
+            Ok(entry) => {
+                add_single_entry(
+                    client,
+                    time_tracking_options,
+                    entry.issue_key,
+                    &entry.duration,
+                    entry.started,
+                    None,
+                    entry.comment,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        on_result(index, result.as_ref())?;
+        match result {
+            Ok(worklog) => report.added.push((index, worklog)),
+            Err(e) => report.rejected.push((index, e)),
+        }
+    }
+    Ok(report)
+}
+
 ///
 /// Handles list of durations specified with 3 letter abbreviations for the day name, followed by
 /// ':' and the numeric duration followed by the unit ('d'=day, 'h'=hour)
@@ -188,6 +526,7 @@ async fn add_multiple_entries(
     time_tracking_options: TimeTrackingConfiguration,
     issue: String,
     durations: Vec<String>,
+    time_zone: Option<&Tz>,
     comment: Option<String>,
 ) -> Result<Vec<LocalWorklog>, WorklogError> {
     // Parses the list of durations in the format XXX:nn,nnU, i.e. Mon:1,5h into Weekday, duration and unit
@@ -217,6 +556,7 @@ async fn add_multiple_entries(
             issue.to_string(),
             &duration,
             Some(started),
+            time_zone,
             comment.clone(),
         )
         .await?;
@@ -231,6 +571,7 @@ async fn add_single_entry(
     issue_key: String,
     duration: &str,
     started: Option<String>,
+    time_zone: Option<&Tz>,
     comment: Option<String>,
 ) -> Result<LocalWorklog, WorklogError> {
     debug!(
@@ -238,11 +579,7 @@ async fn add_single_entry(
         &issue_key, duration, started, comment
     );
     // Transforms strings like "1h", "1d", "1w" into number of seconds. Decimal point and full stop supported
-    let time_spent_seconds = match date::TimeSpent::from_str(
-        duration,
-        time_tracking_options.workingHoursPerDay,
-        time_tracking_options.workingDaysPerWeek,
-    ) {
+    let time_spent_seconds = match date::TimeSpent::parse(duration, time_tracking_options) {
         Ok(time_spent) => time_spent.time_spent_seconds,
         Err(e) => {
             return Err(WorklogError::BadInput(
@@ -254,12 +591,33 @@ async fn add_single_entry(
     };
     debug!("time spent in seconds: {time_spent_seconds}");
 
-    // If a starting point was given, transform it from string to a full DateTime<Local>
-    let starting_point = started
-        .as_ref()
-        .map(|dt| date::str_to_date_time(dt).unwrap());
+    // If a starting point was given, transform it from string to a full DateTime<Local>,
+    // reading it as the wall clock time of `time_zone` if supplied
+    let starting_point =
+        match (started.as_deref(), time_zone) {
+            (Some(dt), Some(tz)) => Some(date::str_to_date_time_in(dt, tz)?),
+            (Some(dt), None) => Some(date::str_to_date_time(dt).map_err(|e| {
+                WorklogError::BadInput(format!("Unable to parse started '{dt}': {e}"))
+            })?),
+            (None, _) => None,
+        };
     // Optionally calculates the starting point after which it is verified
-    let calculated_start = date::calculate_started_time(starting_point, time_spent_seconds)?;
+    let calculated_start = match date::calculate_started_time(starting_point, time_spent_seconds) {
+        Ok(start) => start,
+        Err(err @ date::Error::StartAndDurationExceedsNow { .. }) => {
+            // A local clock running behind the Jira server may wrongly reject the entry
+            if let Some(skew) = client.clock_skew().await {
+                if skew.num_seconds().abs() > MAX_CLOCK_SKEW_SECONDS {
+                    eprintln!(
+                        "WARNING: the local clock differs {}s from the Jira server, which may cause this error",
+                        skew.num_seconds()
+                    );
+                }
+            }
+            return Err(err.into());
+        }
+        Err(err) => return Err(err.into()),
+    };
 
     let result = client
         .insert_worklog(
@@ -318,6 +676,8 @@ mod tests {
                 accountId: "test-account".to_string(),
                 emailAddress: Some("test@example.com".to_string()),
                 displayName: "Test User".to_string(),
+                name: None,
+                key: None,
             },
             comment: Some("Test comment".to_string()),
             created: chrono::Utc::now(),
@@ -347,6 +707,7 @@ mod tests {
             "TEST-123".to_string(),
             "1h",
             None,
+            None,
             Some("Test comment".to_string()),
         )
         .await;
@@ -358,6 +719,39 @@ mod tests {
         assert_eq!(local_worklog.comment, Some("Test comment".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_add_single_entry_in_time_zone() {
+        let config = create_test_time_tracking_config();
+
+        for (time_zone, expected_utc) in [
+            ("Europe/Oslo", "2024-02-01T08:00:00+00:00"),
+            ("America/New_York", "2024-02-01T14:00:00+00:00"),
+        ] {
+            let mut mock_client = MockJiraClientImpl::new();
+            let expected_worklog = create_test_worklog("TEST-123", 3600);
+            mock_client
+                .expect_insert_worklog()
+                .withf(move |_, started, _, _| {
+                    started.with_timezone(&chrono::Utc).to_rfc3339() == expected_utc
+                })
+                .times(1)
+                .returning(move |_, _, _, _| Ok(expected_worklog.clone()));
+
+            let tz = date::parse_time_zone(time_zone).unwrap();
+            add_single_entry(
+                &mock_client,
+                &config,
+                "TEST-123".to_string(),
+                "1h",
+                Some("2024-02-01T09:00".to_string()),
+                Some(&tz),
+                None,
+            )
+            .await
+            .unwrap();
+        }
+    }
+
     #[tokio::test]
     async fn test_add_single_entry_invalid_duration() {
         let mock_client = MockJiraClientImpl::new();
@@ -370,6 +764,7 @@ mod tests {
             "invalid_duration",
             None,
             None,
+            None,
         )
         .await;
 
@@ -401,6 +796,7 @@ mod tests {
             "2h",
             Some("2024-01-15T09:00".to_string()),
             None,
+            None,
         )
         .await;
 
@@ -433,6 +829,7 @@ mod tests {
             config,
             "TEST-123".to_string(),
             durations,
+            None,
             Some("Weekly work".to_string()),
         )
         .await;
@@ -460,6 +857,7 @@ mod tests {
             "TEST-123".to_string(),
             "1h",
             None,
+            None,
             Some("Test comment".to_string()),
         )
         .await;
@@ -500,6 +898,7 @@ mod tests {
                 duration_str,
                 None,
                 None,
+                None,
             )
             .await;
 
@@ -511,4 +910,356 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_add_entries_from_json_reports_invalid_entries() {
+        let mut mock_client = MockJiraClientImpl::new();
+        let config = create_test_time_tracking_config();
+        let expected_worklog = create_test_worklog("TEST-123", 3600);
+
+        mock_client
+            .expect_insert_worklog()
+            .with(eq("TEST-123"), always(), eq(3600), eq("Scripted"))
+            .times(1)
+            .returning(move |_, _, _, _| Ok(expected_worklog.clone()));
+
+        let entries = validate_entries(
+            &parse_entries(
+                r#"[
+                    {"issue_key": "test-123", "duration": "1h", "comment": "Scripted"},
+                    {"duration": "2h"}
+                ]"#,
+            )
+            .unwrap(),
+        );
+        let report = add_entries(
+            &mock_client,
+            &config,
+            &CommentConfiguration::default(),
+            entries,
+            false,
+            &mut |_, _| Ok(()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.added.len(), 1);
+        assert_eq!(report.added[0].0, 0);
+        assert_eq!(report.added[0].1.timeSpentSeconds, 3600);
+        assert_eq!(report.rejected.len(), 1);
+        let (index, error) = &report.rejected[0];
+        assert_eq!(*index, 1);
+        assert!(error.to_string().contains("issue_key"), "{error}");
+
+        assert!(matches!(
+            parse_entries(r#"{"issue_key": "TEST-123", "duration": "1h"}"#),
+            Err(WorklogError::BadInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_add_entries_from_issue_duration_pairs() {
+        let mut mock_client = MockJiraClientImpl::new();
+        let config = create_test_time_tracking_config();
+
+        for (issue_key, seconds) in [("PROJ-1", 3600), ("PROJ-2", 1800)] {
+            let expected_worklog = create_test_worklog(issue_key, seconds);
+            mock_client
+                .expect_insert_worklog()
+                .with(eq(issue_key), always(), eq(seconds), eq("Retro"))
+                .times(1)
+                .returning(move |_, _, _, _| Ok(expected_worklog.clone()));
+        }
+
+        let entries: Vec<AddEntry> = [("proj-1", "1h"), ("proj-2", "30m")]
+            .into_iter()
+            .map(|(issue_key, duration)| AddEntry {
+                issue_key: issue_key.to_string(),
+                started: Some("2024-02-01T09:00".to_string()),
+                duration: duration.to_string(),
+                comment: Some("Retro".to_string()),
+            })
+            .collect();
+        let batch = AddBatch::from_entries(entries, true, false);
+        let report = add_entries(
+            &mock_client,
+            &config,
+            &CommentConfiguration::default(),
+            validate_entries(&batch.entries),
+            batch.atomic,
+            &mut |_, _| Ok(()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.added.len(), 2);
+        assert!(report.rejected.is_empty());
+        assert_eq!(report.added[0].1.issue_key, IssueKey::from("PROJ-1"));
+        assert_eq!(report.added[1].1.issue_key, IssueKey::from("PROJ-2"));
+    }
+
+    #[tokio::test]
+    async fn test_add_entries_atomic_rejects_entire_batch() {
+        let mock_client = MockJiraClientImpl::new();
+        let config = create_test_time_tracking_config();
+
+        let entries = validate_entries(
+            &parse_entries(
+                r#"[
+                    {"issue_key": "TEST-123", "duration": "1h"},
+                    {"issue_key": "TEST-123", "duration": "1h", "started": "yesterday-ish"}
+                ]"#,
+            )
+            .unwrap(),
+        );
+        let result = add_entries(
+            &mock_client,
+            &config,
+            &CommentConfiguration::default(),
+            entries,
+            true,
+            &mut |_, _| Ok(()),
+        )
+        .await;
+
+        assert!(matches!(result, Err(WorklogError::BadInput(msg)) if msg.contains("#1")));
+    }
+
+    #[tokio::test]
+    async fn test_add_batch_persists_each_entry_as_it_is_added() -> Result<(), WorklogError> {
+        use crate::ApplicationRuntimeBuilder;
+        use jira::builder::DEFAULT_API_VERSION;
+        use jira::models::{core::Fields, issue::IssueSummary};
+        use jira::{Credentials, JiraBuilder};
+        use mockito::{Matcher, Server};
+        use std::sync::{Arc, Mutex};
+
+        let mut server = Server::new_async().await;
+        let api = format!("/rest/api/{DEFAULT_API_VERSION}");
+        let _configuration = server
+            .mock("GET", format!("{api}/configuration").as_str())
+            .with_body(
+                r#"{"votingEnabled": true, "watchingEnabled": true, "unassignedIssuesAllowed": true,
+                    "subTasksEnabled": true, "issueLinkingEnabled": true, "timeTrackingEnabled": true,
+                    "attachmentsEnabled": true,
+                    "timeTrackingConfiguration": {"workingHoursPerDay": 7.5, "workingDaysPerWeek": 5.0,
+                        "timeFormat": "pretty", "defaultUnit": "hour"}}"#,
+            )
+            .create_async()
+            .await;
+        let started = (Local::now() - chrono::Duration::days(1)).format("%Y-%m-%dT%H:%M:%S%.3f%z");
+        let mut inserts = Vec::new();
+        for n in 1..=5 {
+            let mock = server
+                .mock("POST", format!("{api}/issue/TIME-{n}/worklog").as_str())
+                .match_query(Matcher::Any);
+            // The third entry is rejected by Jira
+            let mock = if n == 3 {
+                mock.with_status(500)
+                    .with_body(r#"{"errorMessages": ["Internal server error"]}"#)
+            } else {
+                mock.with_status(201).with_body(format!(
+                    r#"{{"id": "10{n}", "issueId": "{n}", "timeSpent": "1h", "timeSpentSeconds": 3600,
+                        "created": "{started}", "updated": "{started}", "started": "{started}",
+                        "author": {{"accountId": "abc", "displayName": "Ola Dunk"}}}}"#
+                ))
+            };
+            inserts.push(mock.expect(1).create_async().await);
+        }
+
+        let jira = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .time_tracking_precheck(false)
+            .build()
+            .map_err(WorklogError::JiraBuildError)?;
+        let runtime = Arc::new(
+            ApplicationRuntimeBuilder::new()
+                .use_in_memory_db()
+                .use_jira_client(jira)
+                .build()?,
+        );
+        runtime.issue_service().add_jira_issues(
+            &(1..=5)
+                .map(|n| IssueSummary {
+                    id: n.to_string(),
+                    key: IssueKey::from(format!("TIME-{n}")),
+                    fields: Fields::default(),
+                })
+                .collect::<Vec<_>>(),
+        )?;
+
+        // The ids of the locally stored entries when the third entry was rejected
+        let stored_at_rejection = Arc::new(Mutex::new(None));
+        let progress: BatchProgress = {
+            let runtime = Arc::clone(&runtime);
+            let stored_at_rejection = Arc::clone(&stored_at_rejection);
+            Box::new(move |index, result| {
+                if index == 2 && result.is_err() {
+                    let mut ids: Vec<String> = runtime
+                        .worklog_service()
+                        .find_worklogs_after(Local::now() - chrono::Duration::days(7), &[], &[])
+                        .unwrap()
+                        .into_iter()
+                        .map(|wl| wl.id)
+                        .collect();
+                    ids.sort();
+                    *stored_at_rejection.lock().unwrap() = Some(ids);
+                }
+            })
+        };
+        let entries: Vec<AddEntry> = (1..=5)
+            .map(|n| AddEntry {
+                issue_key: format!("TIME-{n}"),
+                started: None,
+                duration: "1h".to_string(),
+                comment: None,
+            })
+            .collect();
+        let mut batch = AddBatch::from_entries(entries, false, false);
+        batch.progress = Some(progress);
+
+        let report = execute_batch(&runtime, &batch).await?;
+
+        for insert in inserts {
+            insert.assert_async().await;
+        }
+        assert_eq!(report.added.len(), 4);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(
+            *stored_at_rejection.lock().unwrap(),
+            Some(vec!["101".to_string(), "102".to_string()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_assignee_warning_when_assigned_to_someone_else() {
+        let current_user = User {
+            account_id: "abc".to_string(),
+            display_name: "Ola Dunk".to_string(),
+            ..Default::default()
+        };
+        let mut issue = JiraIssueInfo {
+            issue_key: IssueKey::from("TIME-1"),
+            summary: "Someone else's work".to_string(),
+            assignee: Some(Author {
+                accountId: "def".to_string(),
+                displayName: "Ola Dunk".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        // Another user sharing the display name
+        let warning = assignee_warning(&issue, &current_user, UserIdentity::AccountId);
+        assert!(
+            matches!(&warning, Some(msg) if msg.contains("TIME-1") && msg.contains("Ola Dunk")),
+            "Expected a warning, got {warning:?}"
+        );
+
+        issue.assignee = Some(Author {
+            accountId: "abc".to_string(),
+            displayName: "Ola Dunk (renamed)".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            assignee_warning(&issue, &current_user, UserIdentity::AccountId),
+            None
+        );
+
+        issue.assignee = None;
+        assert_eq!(
+            assignee_warning(&issue, &current_user, UserIdentity::AccountId),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_to_resolved_issue_warns_unless_forced() -> Result<(), WorklogError> {
+        use crate::ApplicationRuntimeBuilder;
+        use jira::builder::DEFAULT_API_VERSION;
+        use jira::{Credentials, JiraBuilder};
+        use mockito::{Matcher, Server};
+
+        let mut server = Server::new_async().await;
+        let api = format!("/rest/api/{DEFAULT_API_VERSION}");
+        let _configuration = server
+            .mock("GET", format!("{api}/configuration").as_str())
+            .with_body(
+                r#"{"votingEnabled": true, "watchingEnabled": true, "unassignedIssuesAllowed": true,
+                    "subTasksEnabled": true, "issueLinkingEnabled": true, "timeTrackingEnabled": true,
+                    "attachmentsEnabled": true,
+                    "timeTrackingConfiguration": {"workingHoursPerDay": 7.5, "workingDaysPerWeek": 5.0,
+                        "timeFormat": "pretty", "defaultUnit": "hour"}}"#,
+            )
+            .create_async()
+            .await;
+        let _issue = server
+            .mock("GET", format!("{api}/issue/TIME-1").as_str())
+            .match_query(Matcher::Any)
+            .with_body(
+                r#"{"id": "1", "key": "TIME-1", "fields": {"summary": "Done and dusted",
+                    "components": [], "status": {"name": "Closed"}, "resolution": {"name": "Done"}}}"#,
+            )
+            .create_async()
+            .await;
+        let started = Local::now() - chrono::Duration::hours(1);
+        let started = started.format("%Y-%m-%dT%H:%M:%S%.3f%z");
+        let mut inserts = Vec::new();
+        for id in [10, 11] {
+            inserts.push(
+                server
+                    .mock("POST", format!("{api}/issue/TIME-1/worklog").as_str())
+                    .match_query(Matcher::Any)
+                    .with_status(201)
+                    .with_body(format!(
+                        r#"{{"id": "{id}", "issueId": "1", "timeSpent": "1h", "timeSpentSeconds": 3600,
+                            "created": "{started}", "updated": "{started}", "started": "{started}",
+                            "author": {{"accountId": "abc", "displayName": "Ola Dunk"}}}}"#
+                    ))
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let jira = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .time_tracking_precheck(false)
+            .build()
+            .map_err(WorklogError::JiraBuildError)?;
+        let runtime = ApplicationRuntimeBuilder::new()
+            .use_in_memory_db()
+            .use_jira_client(jira)
+            .build()?;
+
+        let mut add = Add {
+            durations: vec!["1h".to_string()],
+            issue_key: "time-1".to_string(),
+            started: None,
+            comment: None,
+            timezone: None,
+            tags: vec![],
+            force: false,
+        };
+        assert_eq!(execute(&runtime, &mut add).await?.len(), 1);
+        let issue = runtime
+            .issue_service()
+            .ensure_issue(&IssueKey::from("TIME-1"))
+            .await?;
+        let warnings = issue_warnings(&runtime, &issue, false).await;
+        assert!(
+            matches!(warnings.as_slice(), [msg] if msg.contains("TIME-1") && msg.contains("Done")),
+            "Expected a warning, got {warnings:?}"
+        );
+        assert!(issue_warnings(&runtime, &issue, true).await.is_empty());
+
+        add.force = true;
+        assert_eq!(execute(&runtime, &mut add).await?.len(), 1);
+        for insert in inserts {
+            insert.assert_async().await;
+        }
+        Ok(())
+    }
 }
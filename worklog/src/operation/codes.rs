@@ -1,14 +1,156 @@
+use std::collections::BTreeMap;
+
 use jira::models::issue::IssueSummary;
+use jira::{Jira, JiraError};
 
 use crate::{error::WorklogError, ApplicationRuntime};
 
+/// The group of the time codes without a component, see [`group_by_component`]
+pub const NO_COMPONENT: &str = "(no component)";
+
+/// Retrieves the time codes, sorted by issue key without duplicates
 pub(crate) async fn execute(
     runtime: &ApplicationRuntime,
 ) -> Result<Vec<IssueSummary>, WorklogError> {
-    let jira_client = runtime.jira_client();
-    let issues = jira_client
-        .get_issue_summaries(&["TIME"], &[], false)
-        .await?;
+    let time_codes = find_time_codes(runtime.jira_client(), &runtime.time_code_jql).await?;
+    Ok(sort_and_dedup(time_codes))
+}
+
+/// Sorts the issues by key, keeping the first of issues listed more than once, like when
+/// an issue matches several clauses of the JQL
+fn sort_and_dedup(mut issues: Vec<IssueSummary>) -> Vec<IssueSummary> {
+    issues.sort_by(|a, b| a.key.cmp(&b.key));
+    issues.dedup_by(|a, b| a.key == b.key);
+    issues
+}
+
+/// Groups the time codes by the names of their components, in the order of the supplied
+/// time codes. A time code with several components is listed in each of their groups, and
+/// those without any are grouped as [`NO_COMPONENT`].
+#[must_use]
+pub fn group_by_component(issues: &[IssueSummary]) -> BTreeMap<&str, Vec<&IssueSummary>> {
+    let mut groups: BTreeMap<&str, Vec<&IssueSummary>> = BTreeMap::new();
+    for issue in issues {
+        if issue.fields.components.is_empty() {
+            groups.entry(NO_COMPONENT).or_default().push(issue);
+        }
+        for component in &issue.fields.components {
+            groups
+                .entry(component.name.as_str())
+                .or_default()
+                .push(issue);
+        }
+    }
+    groups
+}
+
+/// Retrieves the issues used as time codes, as located by the configured `time_code_jql`
+async fn find_time_codes(
+    jira_client: &Jira,
+    time_code_jql: &str,
+) -> Result<Vec<IssueSummary>, WorklogError> {
+    if time_code_jql.trim().is_empty() {
+        return Err(WorklogError::BadInput(
+            "time_code_jql in the configuration file may not be empty".to_string(),
+        ));
+    }
+    jira_client
+        .fetch_with_jql(
+            time_code_jql,
+            vec!["id", "key", "summary", "components", "parent"],
+        )
+        .await
+        .map_err(|e| match e {
+            JiraError::Fault { code, errors, .. } if code.as_u16() == 400 => {
+                WorklogError::BadInput(format!(
+                "Jira rejected the time_code_jql '{time_code_jql}' in the configuration file: {}",
+                errors.error_messages.join(", ")
+            ))
+            }
+            other => other.into(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jira::builder::DEFAULT_API_VERSION;
+    use jira::Credentials;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_find_time_codes_uses_configured_jql() -> Result<(), WorklogError> {
+        let jql = "project = CODES AND labels = billable";
+        let mut server = Server::new_async().await;
+        let search = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/search/jql").as_str(),
+            )
+            .match_query(Matcher::UrlEncoded("jql".to_string(), jql.to_string()))
+            .with_status(200)
+            .with_body(
+                r#"{"issues": [{"id": "1", "key": "CODES-1", "fields": {"summary": "Meetings", "components": []}}]}"#,
+            )
+            .create_async()
+            .await;
+        let jira_client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let codes = find_time_codes(&jira_client, jql).await?;
+
+        search.assert_async().await;
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].fields.summary, "Meetings");
+        Ok(())
+    }
+
+    fn issue(key: &str, summary: &str, components: &[&str]) -> IssueSummary {
+        IssueSummary {
+            id: "1".to_string(),
+            key: key.into(),
+            fields: jira::models::core::Fields {
+                summary: summary.to_string(),
+                components: components
+                    .iter()
+                    .map(|name| jira::models::project::Component {
+                        id: "10".to_string(),
+                        name: (*name).to_string(),
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_sort_and_dedup() {
+        let codes = sort_and_dedup(vec![
+            issue("TIME-94", "Meetings", &[]),
+            issue("TIME-40", "Training", &[]),
+            issue("ABC-1", "Support", &[]),
+            issue("TIME-94", "Meetings", &[]),
+        ]);
+        let keys: Vec<_> = codes.iter().map(|c| c.key.to_string()).collect();
+        assert_eq!(keys, ["ABC-1", "TIME-40", "TIME-94"]);
+    }
 
-    Ok(issues)
+    #[test]
+    fn test_group_by_component() {
+        let codes = [
+            issue("TIME-1", "Meetings", &["Internal"]),
+            issue("TIME-2", "Support", &["Internal", "Customer"]),
+            issue("TIME-3", "Holiday", &[]),
+        ];
+        let groups = group_by_component(&codes);
+        let keys = |name: &str| -> Vec<String> {
+            groups[name].iter().map(|c| c.key.to_string()).collect()
+        };
+        assert_eq!(
+            groups.keys().copied().collect::<Vec<_>>(),
+            [NO_COMPONENT, "Customer", "Internal"]
+        );
+        assert_eq!(keys("Internal"), ["TIME-1", "TIME-2"]);
+        assert_eq!(keys("Customer"), ["TIME-2"]);
+        assert_eq!(keys(NO_COMPONENT), ["TIME-3"]);
+    }
 }
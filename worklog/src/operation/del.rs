@@ -1,4 +1,6 @@
 use crate::{error::WorklogError, ApplicationRuntime};
+use jira::models::core::IssueKey;
+use log::debug;
 
 pub struct Del {
     pub issue_id: String,
@@ -13,10 +15,11 @@ pub(crate) async fn execute(
 
     let current_user = client.get_current_user().await?;
     let worklog_entry = client
-        .get_work_log_by_issue_and_id(&instructions.issue_id, &instructions.worklog_id)
+        .get_worklog(&instructions.issue_id, &instructions.worklog_id)
         .await?;
 
-    if worklog_entry.author.accountId != current_user.account_id {
+    let identity = client.user_identity().await?;
+    if !identity.matches(&worklog_entry.author, &current_user) {
         return Err(WorklogError::BadInput(format!(
             "ERROR: You are not the owner of worklog with id {}",
             &instructions.worklog_id
@@ -25,12 +28,22 @@ pub(crate) async fn execute(
 
     client
         .delete_worklog(
-            instructions.issue_id.clone(),
-            instructions.worklog_id.clone(),
+            &IssueKey::from(instructions.issue_id.as_str()),
+            &instructions.worklog_id,
+            true,
         )
         .await?;
-    runtime
-        .worklog_service()
-        .remove_entry_by_worklog_id(instructions.worklog_id.as_str())?;
+    if runtime.prune_orphan_issues {
+        if let Some(issue_key) = runtime
+            .worklog_service()
+            .remove_entry_and_orphaned_issue(instructions.worklog_id.as_str())?
+        {
+            debug!("Removed {issue_key} from the local database, as it has no work logs left");
+        }
+    } else {
+        runtime
+            .worklog_service()
+            .remove_entry_by_worklog_id(instructions.worklog_id.as_str())?;
+    }
     Ok(instructions.worklog_id.clone())
 }
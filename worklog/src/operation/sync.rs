@@ -1,5 +1,5 @@
-use chrono::{DateTime, Days, Local};
-use log::debug;
+use chrono::{DateTime, Days, Local, NaiveDate};
+use log::{debug, warn};
 use std::process::exit;
 
 use crate::error::WorklogError;
@@ -7,29 +7,62 @@ use crate::types::LocalWorklog;
 use crate::{date, ApplicationRuntime};
 use jira::models::core::IssueKey;
 use jira::models::issue::IssueSummary;
+use jira::models::jql::Jql;
+use jira::models::user::User;
+use jira::ISSUE_SUMMARY_FIELDS;
+use std::collections::{HashMap, HashSet};
 
 pub struct Sync {
     pub started: Option<String>,
     pub all_users: bool,
+    /// Limits the synchronisation to the issues of these projects
     pub projects: Vec<String>,
-    pub issues: Vec<String>,
+    /// Limits the synchronisation to these issues
+    pub issues: Vec<IssueKey>,
+    /// Upper limit on the number of issues to resolve from Jira
+    pub max_issues: Option<usize>,
+    /// Upload the completed timers, and remove the local work log entries, which have been
+    /// deleted in Jira
+    pub prune: bool,
+}
+
+/// Summary of a synchronisation with Jira
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub struct SyncReport {
+    /// Completed timers uploaded to Jira as work log entries
+    pub pushed: usize,
+    /// Work log entries pulled from Jira, which were not in the local database
+    pub created: usize,
+    /// Local work log entries updated with changes made in Jira
+    pub updated: usize,
+    /// Local work log entries removed, as they have been deleted in Jira
+    pub deleted: usize,
 }
 
 /// Executes the main synchronization logic for work logs with Jira.
 ///
 /// This function performs the following tasks:
+/// - If `prune` is set, uploads the completed timers, which have not been synchronised with
+///   Jira yet. The default synchronisation only pulls from Jira.
 /// - Parses the start date from the provided `sync_cmd` structure, or falls back to a default date.
 /// - Prepares issue keys for synchronization by resolving them from the command-line input or the local database.
 /// - Logs and outputs the list of issues being synchronized.
 /// - Fetches work log entries from Jira for the specified issues and filters them based on the synchronization options.
 /// - Updates the local database with issue summary information and inserts the fetched work logs.
+/// - If `prune` is set, removes the local work logs of the synchronised issues and period,
+///   which are no longer found in Jira. The local work logs within the scope of `sync_cmd`
+///   are synchronised too, as their issues no longer match the search once all of their
+///   work logs have been deleted in Jira.
+///
+/// Running it repeatedly is safe, a second run reports no changes unless Jira was modified meanwhile.
 ///
 /// # Arguments
 /// * `runtime` - The application runtime that provides access to services, including Jira and the worklog database.
 /// * `sync_cmd` - The synchronization command containing options like start date, projects, issues, and user settings.
 ///
 /// # Returns
-/// * `Result<(), WorklogError>` - Returns `Ok(())` on successful execution, or a `WorklogError` if any error occurs.
+/// * `Result<SyncReport, WorklogError>` - The number of entries pushed, created, updated and deleted,
+///   or a `WorklogError` if any error occurs.
 ///
 /// # Errors
 /// This function will return an error if:
@@ -46,30 +79,48 @@ pub struct Sync {
 /// These panics are due to calls to `expect` when creating `DateTime` or during date manipulation.
 /// Ensure the input data is valid and that system date/time functionality behaves as expected.
 /// # Behavior
+/// If neither a start date, projects nor issues are given and the local database holds no issues,
+/// `WorklogError::BadInput` is returned.
 /// If no issues are found, the function will print an error message and exit with a status code of 4.
 /// The function uses debugging logs to trace execution details.
-pub async fn execute(runtime: &ApplicationRuntime, sync_cmd: &Sync) -> Result<(), WorklogError> {
+pub async fn execute(
+    runtime: &ApplicationRuntime,
+    sync_cmd: &Sync,
+) -> Result<SyncReport, WorklogError> {
     let current_user = runtime.jira_client().get_current_user().await?;
     runtime
         .user_service()
         .insert_or_update_current_user(&current_user)?;
 
+    let pushed = if sync_cmd.prune {
+        runtime.timer_service().sync_timers_to_jira().await?.len()
+    } else {
+        0
+    };
+    if pushed > 0 {
+        println!("Uploaded {pushed} completed timers to Jira");
+    }
+    let users: &[User] = if sync_cmd.all_users {
+        &[]
+    } else {
+        std::slice::from_ref(&current_user)
+    };
+
     // Parse the start date or fall back to the default
-    let date_time = sync_cmd
-        .started
-        .as_deref()
-        .and_then(|s| date::str_to_date_time(s).ok())
-        .unwrap_or_else(get_default_start_date);
+    let date_time = parse_started(sync_cmd)?.unwrap_or_else(get_default_start_date);
 
     let start_after_naive_date_time = DateTime::from_timestamp_millis(date_time.timestamp_millis())
         .expect("Invalid timestamp")
         .naive_local();
 
     let issue_summaries = prepare_issue_keys_for_sync(sync_cmd, runtime).await?;
-    if issue_summaries.is_empty() {
-        eprintln!(
-            "No issue keys to synchronise supplied on commandline or found in the local dbms"
-        );
+    let local_worklogs_in_scope = if sync_cmd.prune {
+        find_local_worklogs_in_scope(runtime, sync_cmd, date_time, users)?
+    } else {
+        vec![]
+    };
+    if issue_summaries.is_empty() && local_worklogs_in_scope.is_empty() {
+        eprintln!("No issues with work logs matched the synchronisation scope");
         exit(4);
     }
 
@@ -84,13 +135,30 @@ pub async fn execute(runtime: &ApplicationRuntime, sync_cmd: &Sync) -> Result<()
 
     println!("Fetching work logs, this might take some time...");
     // Fetch all worklogs for all the specified issue keys
-    let mut all_issue_work_logs = runtime
-        .jira_client()
-        .chunked_work_logs(
-            &issue_summaries.iter().map(|s| s.key.clone()).collect(),
-            start_after_naive_date_time,
-        )
-        .await?;
+    let mut issue_keys_by_id: HashMap<String, IssueKey> = issue_summaries
+        .iter()
+        .map(|issue| (issue.id.clone(), issue.key.clone()))
+        .collect();
+    for worklog in &local_worklogs_in_scope {
+        issue_keys_by_id
+            .entry(worklog.issueId.to_string())
+            .or_insert_with(|| worklog.issue_key.clone());
+    }
+    let mut issue_keys: Vec<IssueKey> = issue_keys_by_id.values().cloned().collect();
+    issue_keys.sort();
+    issue_keys.dedup();
+    let mut all_issue_work_logs = if sync_cmd.prune {
+        // Work logs missing due to a failed request would otherwise be pruned
+        runtime
+            .jira_client()
+            .try_chunked_work_logs(&issue_keys, start_after_naive_date_time, None)
+            .await?
+    } else {
+        runtime
+            .jira_client()
+            .chunked_work_logs(&issue_keys, start_after_naive_date_time, None)
+            .await?
+    };
 
     // Filter for current user or all users
     if sync_cmd.all_users {
@@ -100,7 +168,8 @@ pub async fn execute(runtime: &ApplicationRuntime, sync_cmd: &Sync) -> Result<()
             "Filtering work logs for current user: {:?} ",
             current_user.display_name
         );
-        all_issue_work_logs.retain(|wl| current_user.account_id == wl.author.accountId);
+        let identity = runtime.jira_client().user_identity().await?;
+        all_issue_work_logs.retain(|wl| identity.matches(&wl.author, &current_user));
     }
 
     eprintln!("Found {} work logs", all_issue_work_logs.len());
@@ -109,35 +178,98 @@ pub async fn execute(runtime: &ApplicationRuntime, sync_cmd: &Sync) -> Result<()
     sync_jira_issue_information(runtime, &issue_summaries)?;
 
     eprintln!("Updated database with issue summary information");
-    // Create map of IssueKey -> IssueSummary
-    let issue_map: std::collections::HashMap<String, &IssueSummary> = issue_summaries
+
+    // Inserts or updates the work log entries in the database
+    let local_worklogs: Vec<LocalWorklog> = all_issue_work_logs
         .iter()
-        .map(|issue| (issue.id.clone(), issue))
+        .filter_map(|worklog| {
+            let Some(issue_key) = issue_keys_by_id.get(&worklog.issueId) else {
+                warn!(
+                    "Skipping work log {} of the unknown issue {}",
+                    worklog.id, worklog.issueId
+                );
+                return None;
+            };
+            Some(LocalWorklog::from_worklog(worklog, issue_key))
+        })
         .collect();
+    debug!("Upserting {} work logs", local_worklogs.len());
+    let counts = match runtime
+        .worklog_service()
+        .add_synced_entries(&local_worklogs)
+        .await
+    {
+        Ok(counts) => counts,
+        Err(err) => {
+            eprintln!("Insert into database failed, cause: {err:?}");
+            exit(4);
+        }
+    };
 
-    // Inserts the work log entries into the database
-    for worklog in &all_issue_work_logs {
-        debug!("Removing and adding {:?}", &worklog);
+    let deleted = if sync_cmd.prune {
+        prune_deleted_worklogs(runtime, date_time, &issue_keys, users, &local_worklogs)?
+    } else {
+        0
+    };
 
-        // Delete the existing one if it exists
-        if let Err(e) = runtime.worklog_service().remove_worklog_entry(worklog) {
-            debug!("Unable to remove {:?}: {}", &worklog, e);
-        }
+    Ok(SyncReport {
+        pushed,
+        created: counts.created,
+        updated: counts.updated,
+        deleted,
+    })
+}
 
-        debug!("Adding {} {:?}", &worklog.issueId, &worklog);
+/// The local work logs of the users started after `started`, limited to the projects and
+/// issues of `sync_cmd`, if any
+fn find_local_worklogs_in_scope(
+    runtime: &ApplicationRuntime,
+    sync_cmd: &Sync,
+    started: DateTime<Local>,
+    users: &[User],
+) -> Result<Vec<LocalWorklog>, WorklogError> {
+    let unscoped = sync_cmd.issues.is_empty() && sync_cmd.projects.is_empty();
+    Ok(runtime
+        .worklog_service()
+        .find_worklogs_after(started, &[], users)?
+        .into_iter()
+        .filter(|wl| {
+            unscoped
+                || sync_cmd.issues.contains(&wl.issue_key)
+                || sync_cmd
+                    .projects
+                    .iter()
+                    .any(|project| project.eq_ignore_ascii_case(wl.issue_key.project_key()))
+        })
+        .collect())
+}
 
-        let issue_summary = issue_map.get(&worklog.issueId).unwrap();
-        let local_worklog = LocalWorklog::from_worklog(worklog, &issue_summary.key);
-        if let Err(err) = runtime.worklog_service().add_entry(&local_worklog).await {
-            eprintln!(
-                "Insert into database failed for {:?}, cause: {:?}",
-                &local_worklog, err
-            );
-            exit(4);
-        }
+/// Removes the local work logs of the issues and users started after `started`, which are
+/// not amongst the work logs just retrieved from Jira, i.e. they have been deleted in Jira.
+fn prune_deleted_worklogs(
+    runtime: &ApplicationRuntime,
+    started: DateTime<Local>,
+    issue_keys: &[IssueKey],
+    users: &[User],
+    remote_worklogs: &[LocalWorklog],
+) -> Result<usize, WorklogError> {
+    let remote_ids: HashSet<&str> = remote_worklogs.iter().map(|wl| wl.id.as_str()).collect();
+    let deleted: Vec<LocalWorklog> = runtime
+        .worklog_service()
+        .find_worklogs_after(started, issue_keys, users)?
+        .into_iter()
+        .filter(|wl| !remote_ids.contains(wl.id.as_str()))
+        .collect();
+    for worklog in &deleted {
+        debug!(
+            "Pruning work log {} of {}, deleted in Jira",
+            worklog.id, worklog.issue_key
+        );
+        runtime
+            .worklog_service()
+            .remove_entry_by_worklog_id(&worklog.id)?;
     }
-
-    Ok(())
+    Ok(deleted.len())
 }
 
 fn get_default_start_date() -> DateTime<Local> {
@@ -146,39 +278,105 @@ fn get_default_start_date() -> DateTime<Local> {
         .expect("Failed to create default fallback date")
 }
 
-/// Helper function to transform a list of strings into a list of `IssueKey`s
-fn collect_issue_keys(issue_strings: &[String]) -> Vec<IssueKey> {
-    issue_strings
-        .iter()
-        .map(|s| IssueKey::from(s.as_str()))
-        .collect()
+/// Composes the JQL selecting the issues to synchronise.
+///
+/// The scope is the union of the projects and issues of `sync_cmd`, falling back to `local_keys`
+/// if neither is given. The scope is intersected with the current user or all users clause,
+/// and with the `started` watermark if supplied.
+///
+/// # Errors
+/// Returns `WorklogError::BadInput` if there is neither a scope nor a watermark to limit the search.
+fn compose_sync_jql(
+    sync_cmd: &Sync,
+    local_keys: &[IssueKey],
+    started: Option<NaiveDate>,
+) -> Result<String, WorklogError> {
+    let issue_keys = if sync_cmd.issues.is_empty() && sync_cmd.projects.is_empty() {
+        local_keys
+    } else {
+        sync_cmd.issues.as_slice()
+    };
+
+    let mut scope = None;
+    if !sync_cmd.projects.is_empty() {
+        scope = Some(Jql::project_in(&sync_cmd.projects));
+    }
+    if !issue_keys.is_empty() {
+        let issues = Jql::issue_key_in(issue_keys);
+        scope = Some(match scope {
+            Some(projects) => projects.or(issues),
+            None => issues,
+        });
+    }
+    if scope.is_none() && started.is_none() {
+        return Err(WorklogError::BadInput(
+            "No issues or projects to synchronise, specify a start date, projects or issues"
+                .to_string(),
+        ));
+    }
+
+    let authors = if sync_cmd.all_users {
+        Jql::worklog_author_is_not_empty()
+    } else {
+        Jql::worklog_author_is_current_user()
+    };
+    let mut jql = match scope {
+        Some(scope) => scope.and(authors),
+        None => authors,
+    };
+    if let Some(started) = started {
+        jql = jql.and(Jql::worklog_date_from(started));
+    }
+    Ok(jql.to_string())
+}
+
+/// Parses the `--started` option, which is rejected rather than ignored if invalid
+fn parse_started(sync_cmd: &Sync) -> Result<Option<DateTime<Local>>, WorklogError> {
+    sync_cmd
+        .started
+        .as_deref()
+        .map(|s| {
+            date::str_to_date_time(s)
+                .map_err(|e| WorklogError::BadInput(format!("Unable to parse '{s}': {e}")))
+        })
+        .transpose()
 }
 
 async fn prepare_issue_keys_for_sync(
     sync_cmd: &Sync,
     runtime: &ApplicationRuntime,
 ) -> Result<Vec<IssueSummary>, WorklogError> {
-    // Transform from list of strings to list of IssueKey
-    let mut issue_keys_to_sync = collect_issue_keys(&sync_cmd.issues);
-
     // If no projects and no issues were specified on the command line
     // have a look in the database and create a unique list from
     // entries in the past
-    if issue_keys_to_sync.is_empty() && sync_cmd.projects.is_empty() {
-        issue_keys_to_sync = runtime.issue_service().find_unique_keys()?;
-    }
+    let local_keys = if sync_cmd.issues.is_empty() && sync_cmd.projects.is_empty() {
+        runtime.issue_service().find_unique_keys()?
+    } else {
+        vec![]
+    };
+    let started = parse_started(sync_cmd)?.map(|dt| dt.date_naive());
 
-    let projects_as_str: Vec<&str> = sync_cmd.projects.iter().map(String::as_str).collect();
-    println!(
-        "Searching for issues in these projects: {:?}",
-        &projects_as_str
-    );
+    let jql = compose_sync_jql(sync_cmd, &local_keys, started)?;
+    println!("Searching for issues matching: {jql}");
 
     // Gets the Issue Summaries for all the filter options specified on the command line
-    let mut issue_keys_to_sync = runtime
+    let search_result = runtime
         .jira_client()
-        .get_issue_summaries(&projects_as_str, &issue_keys_to_sync, sync_cmd.all_users)
+        .fetch_with_jql_limited(&jql, ISSUE_SUMMARY_FIELDS.to_vec(), sync_cmd.max_issues)
         .await?;
+    if search_result.truncated {
+        eprintln!(
+            "WARNING: more issues matched than the limit of {}, results are truncated",
+            sync_cmd.max_issues.unwrap_or_default()
+        );
+    }
+    if !search_result.unknown_fields.is_empty() {
+        eprintln!(
+            "WARNING: Jira knows no field named {}",
+            search_result.unknown_fields.join(", ")
+        );
+    }
+    let mut issue_keys_to_sync = search_result.issues;
 
     println!("Resolved {} issues", issue_keys_to_sync.len());
 
@@ -205,3 +403,259 @@ fn sync_jira_issue_information(
     debug!("sync_jira_issue_information: done");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApplicationRuntimeBuilder;
+    use chrono::Duration;
+    use jira::builder::DEFAULT_API_VERSION;
+    use jira::{Credentials, JiraBuilder};
+    use mockito::{Matcher, Server};
+
+    fn jira_time(time: DateTime<Local>) -> String {
+        time.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string()
+    }
+
+    fn worklog_json(id: &str, started: DateTime<Local>, updated: DateTime<Local>) -> String {
+        format!(
+            r#"{{"id": "{id}", "issueId": "1", "timeSpent": "1h", "timeSpentSeconds": 3600,
+                "created": "{}", "updated": "{}", "started": "{}",
+                "author": {{"accountId": "abc", "displayName": "Ola Dunk"}}}}"#,
+            jira_time(started),
+            jira_time(updated),
+            jira_time(started)
+        )
+    }
+
+    fn local_worklog(id: &str, started: DateTime<Local>) -> LocalWorklog {
+        LocalWorklog::builder(IssueKey::from("TIME-1"), id, 3600)
+            .author("Ola Dunk")
+            .created(started)
+            .updated(started)
+            .started(started)
+            .issue_id(1)
+            .build()
+    }
+
+    fn sync_cmd(projects: &[&str], issues: &[&str], all_users: bool) -> Sync {
+        Sync {
+            started: None,
+            all_users,
+            projects: projects.iter().map(ToString::to_string).collect(),
+            issues: issues.iter().map(|key| IssueKey::from(*key)).collect(),
+            max_issues: None,
+            prune: false,
+        }
+    }
+
+    #[test]
+    fn test_compose_sync_jql_for_project_scope() -> Result<(), WorklogError> {
+        let started = NaiveDate::from_ymd_opt(2025, 3, 1);
+        assert_eq!(
+            compose_sync_jql(&sync_cmd(&["TIME", "KT"], &[], false), &[], started)?,
+            r#"project in ("TIME", "KT") AND worklogAuthor = currentUser() AND worklogDate >= "2025-03-01""#
+        );
+        assert_eq!(
+            compose_sync_jql(&sync_cmd(&["TIME"], &[], true), &[], None)?,
+            r#"project in ("TIME") AND worklogAuthor is not EMPTY"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_sync_jql_for_issue_scope() -> Result<(), WorklogError> {
+        let local_keys = [IssueKey::from("OLD-1")];
+        assert_eq!(
+            compose_sync_jql(
+                &sync_cmd(&[], &["time-1", "TIME-2"], false),
+                &local_keys,
+                None
+            )?,
+            r#"issueKey in ("TIME-1", "TIME-2") AND worklogAuthor = currentUser()"#
+        );
+        // Projects and issues form a union, which is intersected with the user clause
+        assert_eq!(
+            compose_sync_jql(&sync_cmd(&["KT"], &["TIME-1"], true), &[], None)?,
+            r#"(project in ("KT") OR issueKey in ("TIME-1")) AND worklogAuthor is not EMPTY"#
+        );
+        // Without an explicit scope, the issues of the local database are used
+        assert_eq!(
+            compose_sync_jql(&sync_cmd(&[], &[], false), &local_keys, None)?,
+            r#"issueKey in ("OLD-1") AND worklogAuthor = currentUser()"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_sync_jql_requires_scope_or_watermark() {
+        let cmd = sync_cmd(&[], &[], false);
+        assert!(matches!(
+            compose_sync_jql(&cmd, &[], None),
+            Err(WorklogError::BadInput(_))
+        ));
+        assert_eq!(
+            compose_sync_jql(&cmd, &[], NaiveDate::from_ymd_opt(2025, 3, 1)).unwrap(),
+            r#"worklogAuthor = currentUser() AND worklogDate >= "2025-03-01""#
+        );
+    }
+
+    #[test]
+    fn test_invalid_started_is_rejected() {
+        let mut cmd = sync_cmd(&["TIME"], &[], false);
+        cmd.started = Some("last tuesday".to_string());
+        assert!(matches!(
+            parse_started(&cmd),
+            Err(WorklogError::BadInput(msg)) if msg.contains("last tuesday")
+        ));
+        cmd.started = Some("2025-03-01".to_string());
+        assert!(parse_started(&cmd).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sync_with_prune_pushes_pulls_and_prunes() -> Result<(), WorklogError> {
+        let now = Local::now();
+        let mut server = Server::new_async().await;
+        let api = format!("/rest/api/{DEFAULT_API_VERSION}");
+        let _myself = server
+            .mock("GET", format!("{api}/myself").as_str())
+            .with_body(
+                r#"{"self": "", "accountId": "abc", "emailAddress": "ola@dunk.com",
+                    "displayName": "Ola Dunk", "timeZone": "Europe/Oslo"}"#,
+            )
+            .create_async()
+            .await;
+        let _server_info = server
+            .mock("GET", format!("{api}/serverInfo").as_str())
+            .with_body(r#"{"baseUrl": "", "version": "1001.0.0", "deploymentType": "Cloud"}"#)
+            .create_async()
+            .await;
+        let issue =
+            r#"{"id": "1", "key": "TIME-1", "fields": {"summary": "Time", "components": []}}"#;
+        let _issue = server
+            .mock("GET", format!("{api}/issue/TIME-1").as_str())
+            .match_query(Matcher::Any)
+            .with_body(issue)
+            .create_async()
+            .await;
+        // All the work logs of TIME-2 have been deleted, hence the search no longer finds it
+        let _deleted_issue = server
+            .mock("GET", format!("{api}/issue/TIME-2").as_str())
+            .match_query(Matcher::Any)
+            .with_body(
+                r#"{"id": "2", "key": "TIME-2", "fields": {"summary": "Gone", "components": []}}"#,
+            )
+            .create_async()
+            .await;
+        let _deleted_pull = server
+            .mock("GET", format!("{api}/issue/TIME-2/worklog").as_str())
+            .match_query(Matcher::Any)
+            .with_body(r#"{"startAt": 0, "maxResults": 5000, "total": 0, "worklogs": []}"#)
+            .create_async()
+            .await;
+        let _search = server
+            .mock("GET", format!("{api}/search/jql").as_str())
+            .match_query(Matcher::Any)
+            .with_body(format!(r#"{{"issues": [{issue}], "total": 1}}"#))
+            .create_async()
+            .await;
+        // The timer is uploaded as work log 12
+        let timer_started = now - Duration::hours(2);
+        let pushed_worklog = worklog_json("12", timer_started, now);
+        let push = server
+            .mock("POST", format!("{api}/issue/TIME-1/worklog").as_str())
+            .match_query(Matcher::Any)
+            .with_status(201)
+            .with_body(&pushed_worklog)
+            .expect(1)
+            .create_async()
+            .await;
+        // Work log 10 was added and 11 was updated in Jira, while 13 has been deleted
+        let _pull = server
+            .mock("GET", format!("{api}/issue/TIME-1/worklog").as_str())
+            .match_query(Matcher::Any)
+            .with_body(format!(
+                r#"{{"startAt": 0, "maxResults": 5000, "total": 3, "worklogs": [{}, {}, {pushed_worklog}]}}"#,
+                worklog_json("10", now - Duration::days(1), now),
+                worklog_json("11", now - Duration::days(2), now),
+            ))
+            .create_async()
+            .await;
+
+        let jira = JiraBuilder::new()
+            .host(server.url())
+            .credentials(Credentials::Anonymous)
+            .time_tracking_precheck(false)
+            .build()
+            .map_err(WorklogError::JiraBuildError)?;
+        let runtime = ApplicationRuntimeBuilder::new()
+            .use_in_memory_db()
+            .use_jira_client(jira)
+            .build()?;
+        let worklog_service = runtime.worklog_service();
+        worklog_service
+            .add_entry(&local_worklog("11", now - Duration::days(2)))
+            .await?;
+        worklog_service
+            .add_entry(&local_worklog("13", now - Duration::days(3)))
+            .await?;
+        worklog_service
+            .add_entry(
+                &LocalWorklog::builder(IssueKey::from("TIME-2"), "14", 3600)
+                    .author("Ola Dunk")
+                    .started(now - Duration::days(4))
+                    .issue_id(2)
+                    .build(),
+            )
+            .await?;
+        runtime
+            .timer_service()
+            .start_timer("TIME-1", timer_started, None)
+            .await?;
+        runtime
+            .timer_service()
+            .stop_active_timer(now - Duration::hours(1), None)?;
+
+        let mut sync = Sync {
+            started: None,
+            all_users: false,
+            projects: vec!["time".to_string()],
+            issues: vec![],
+            max_issues: None,
+            prune: true,
+        };
+        let report = execute(&runtime, &sync).await?;
+        assert_eq!(
+            report,
+            SyncReport {
+                pushed: 1,
+                created: 1,
+                updated: 1,
+                deleted: 2
+            }
+        );
+        let mut ids: Vec<String> = worklog_service
+            .find_worklogs_after(now - Duration::days(30), &[], &[])?
+            .into_iter()
+            .map(|wl| wl.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["10", "11", "12"]);
+
+        // Nothing has changed in Jira since, hence a second run is a no-op
+        assert_eq!(execute(&runtime, &sync).await?, SyncReport::default());
+
+        // Without --prune, completed timers are not uploaded
+        runtime
+            .timer_service()
+            .start_timer("TIME-1", now - Duration::minutes(50), None)
+            .await?;
+        runtime
+            .timer_service()
+            .stop_active_timer(now - Duration::minutes(20), None)?;
+        sync.prune = false;
+        assert_eq!(execute(&runtime, &sync).await?, SyncReport::default());
+        push.assert_async().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+//! Re-exports of the most commonly used types, allowing downstream crates to simply
+//! `use worklog::prelude::*;` rather than importing each type from its module.
+//!
+//! ```no_run
+//! use worklog::prelude::*;
+//!
+//! # async fn run() -> Result<(), WorklogError> {
+//! let runtime = ApplicationRuntimeBuilder::new().build()?;
+//! if let OperationResult::IssueSummaries(issues) = runtime.execute(Operation::Codes).await? {
+//!     println!("Found {} time codes", issues.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+pub use crate::error::WorklogError;
+pub use crate::operation::{
+    add::{Add, AddBatch, AddEntry, BatchReport},
+    del::Del,
+    sync::Sync,
+};
+pub use crate::service::{
+    component::ComponentService, issue::IssueService, timer::TimerService, user::UserService,
+    worklog::WorkLogService,
+};
+pub use crate::types::{JiraIssueInfo, LocalWorklog, Timer};
+pub use crate::{ApplicationRuntime, ApplicationRuntimeBuilder, Operation, OperationResult};
+pub use jira::models::core::IssueKey;
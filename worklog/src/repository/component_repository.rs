@@ -16,13 +16,31 @@ pub trait ComponentRepository: Send + Sync {
     /// * `components` - A list of `Component` objects to add to the database.
     ///
     /// # Errors
-    /// Returns a `WorklogError` if any SQL operation fails during the insertion or association.
-    ///
-    /// # Panics
-    /// This method panics if it encounters any error during the execution of the SQL statements.
+    /// Returns a `WorklogError` if any SQL operation fails during the insertion or association,
+    /// in which case none of the components are added.
     fn create_component(
         &self,
         issue_key: &IssueKey,
         components: &[Component],
     ) -> Result<(), WorklogError>;
+
+    /// Removes all components and their associations with issues.
+    ///
+    /// # Returns
+    /// The number of components removed
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if any of the SQL statements fail
+    fn clear_components(&self) -> Result<usize, WorklogError>;
+
+    /// Finds the cached component of the project with the supplied name, ignoring case.
+    /// Components are only known for projects of which issues have been synchronized.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the SQL query fails
+    fn find_component_by_name(
+        &self,
+        project_key: &str,
+        name: &str,
+    ) -> Result<Option<Component>, WorklogError>;
 }
@@ -29,6 +29,7 @@
 //! ```
 
 use crate::error::WorklogError;
+use crate::repository::issue_repository::IssueRepository;
 use crate::repository::sqlite;
 use crate::repository::sqlite::sqlite_component_repo::SqliteComponentRepository;
 use crate::repository::sqlite::sqlite_issue_repo::SqliteIssueRepository;
@@ -36,10 +37,16 @@ use crate::repository::sqlite::sqlite_timer_repo::SqliteTimerRepository;
 use crate::repository::sqlite::sqlite_user_repo::SqliteUserRepository;
 use crate::repository::sqlite::sqlite_worklog_repo::SqliteWorklogRepository;
 use crate::repository::sqlite::SharedSqliteConnection;
+use crate::repository::timer_repository::TimerRepository;
 use crate::repository::user_repository::UserRepository;
-use rusqlite::{Connection, Result};
+use crate::repository::worklog_repository::WorkLogRepository;
+use crate::types::{LocalWorklog, SchemaIssue, Timer};
+use chrono::{DateTime, Local, Utc};
+use jira::models::core::IssueKey;
+use jira::models::user::User;
+use rusqlite::{Connection, OpenFlags, Result};
 use std::borrow::Cow;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 /// A configuration enum that defines the parameters required for initializing
@@ -139,6 +146,93 @@ pub enum DbConnection {
 
 pub struct DatabaseManager {
     connection: DbConnection,
+    /// The database file, from which read-only connections are opened. `None` if held in memory
+    path: Option<PathBuf>,
+}
+
+/// A read-only connection to the database, separate from the connection used for writing.
+///
+/// As the database is in WAL mode, queries on it proceed while a write is in progress,
+/// seeing the data as of the last commit. See [`DatabaseManager::snapshot_read`]
+pub struct ReadConn {
+    connection: SharedSqliteConnection,
+    issue_repo: SqliteIssueRepository,
+    worklog_repo: SqliteWorklogRepository,
+    timer_repo: SqliteTimerRepository,
+}
+
+impl ReadConn {
+    fn new(connection: &SharedSqliteConnection) -> Self {
+        Self {
+            connection: connection.clone(),
+            issue_repo: SqliteIssueRepository::new(connection.clone()),
+            worklog_repo: SqliteWorklogRepository::new(connection.clone()),
+            timer_repo: SqliteTimerRepository::new(connection.clone()),
+        }
+    }
+
+    /// See [`WorkLogRepository::get_count`]
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the query fails
+    pub fn count_worklogs(&self) -> Result<i64, WorklogError> {
+        self.worklog_repo.get_count()
+    }
+
+    /// The size of the database in bytes, excluding the write-ahead log
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the query fails
+    pub fn database_size(&self) -> Result<u64, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let size = conn.query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(size)
+    }
+
+    /// See [`IssueRepository::find_unique_keys`]
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the query fails
+    pub fn find_unique_keys(&self) -> Result<Vec<IssueKey>, WorklogError> {
+        self.issue_repo.find_unique_keys()
+    }
+
+    /// See [`WorkLogRepository::find_worklogs_after`]
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the query fails
+    pub fn find_worklogs_after(
+        &self,
+        start_datetime: DateTime<Local>,
+        keys_filter: &[IssueKey],
+        users_filter: &[User],
+    ) -> Result<Vec<LocalWorklog>, WorklogError> {
+        self.worklog_repo
+            .find_worklogs_after(start_datetime, keys_filter, users_filter)
+    }
+
+    /// See [`TimerRepository::find_after_date`]
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the query fails
+    pub fn find_timers_after(&self, since: DateTime<Local>) -> Result<Vec<Timer>, WorklogError> {
+        self.timer_repo.find_after_date(since.with_timezone(&Utc))
+    }
+
+    /// See [`TimerRepository::find_active_timer`]
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the query fails
+    pub fn find_active_timer(&self) -> Result<Option<Timer>, WorklogError> {
+        self.timer_repo.find_active_timer()
+    }
 }
 
 impl DatabaseManager {
@@ -146,7 +240,12 @@ impl DatabaseManager {
     pub fn new(config: &DatabaseConfig) -> Result<Self, WorklogError> {
         let connection = match config {
             DatabaseConfig::SqliteOnDisk { path } => Self::create_sqlite_connection(
-                || Connection::open(path),
+                || {
+                    let connection = Connection::open(path)?;
+                    // Lets the readers of `snapshot_read` proceed while writing
+                    connection.pragma_update(None, "journal_mode", "WAL")?;
+                    Ok(connection)
+                },
                 || Cow::from(path.to_string_lossy().into_owned()),
             ),
             DatabaseConfig::SqliteInMemory => {
@@ -160,7 +259,96 @@ impl DatabaseManager {
             WorklogError::DatabaseError(format!("Failed to initialize schema: {e}"))
         })?;
 
-        Ok(Self { connection })
+        let path = match config {
+            DatabaseConfig::SqliteOnDisk { path } => Some(path.clone()),
+            _ => None,
+        };
+        Ok(Self { connection, path })
+    }
+
+    /// Opens an additional read-only connection to the database, allowing queries to run
+    /// concurrently with the connection used for writing.
+    ///
+    /// An in-memory database can only be reached through its single connection, which is
+    /// shared with the writers in that case.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the database file could not be opened
+    pub fn snapshot_read(&self) -> Result<ReadConn, WorklogError> {
+        let DbConnection::Sqlite(write_connection) = &self.connection;
+        let Some(path) = &self.path else {
+            return Ok(ReadConn::new(write_connection));
+        };
+        let connection = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| {
+            WorklogError::DatabaseError(format!(
+                "Failed to open read-only connection to {}: {e}",
+                path.display()
+            ))
+        })?;
+        Ok(ReadConn::new(&Arc::new(Mutex::new(connection))))
+    }
+
+    /// Compares the tables and columns of the database with the schema expected by this
+    /// version, reporting the missing and unexpected ones.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the schema could not be read
+    pub fn verify_schema(&self) -> Result<Vec<SchemaIssue>, WorklogError> {
+        let DbConnection::Sqlite(conn) = &self.connection;
+        let conn = conn.lock().map_err(|_| WorklogError::LockPoisoned)?;
+        sqlite::verify_schema(&conn)
+    }
+
+    /// Creates the missing tables and adds the missing columns, returning the issues repaired.
+    /// Columns which cannot be added, like keys, are still reported by [`Self::verify_schema`].
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the schema could not be read or altered
+    pub fn repair_schema(&self) -> Result<Vec<SchemaIssue>, WorklogError> {
+        let missing_tables: Vec<SchemaIssue> = self
+            .verify_schema()?
+            .into_iter()
+            .filter(|issue| matches!(issue, SchemaIssue::MissingTable(_)))
+            .collect();
+        Self::initialize_schema(&self.connection)?;
+
+        let DbConnection::Sqlite(conn) = &self.connection;
+        let conn = conn.lock().map_err(|_| WorklogError::LockPoisoned)?;
+        let mut repaired = missing_tables;
+        repaired.extend(sqlite::repair_schema(&conn)?);
+        Ok(repaired)
+    }
+
+    /// Moves the work logs started before `cutoff` into a separate archive database at
+    /// `archive_path`, keeping the active database small. The archive is created with the
+    /// current schema if missing, and receives the issues and tags of the work logs too.
+    ///
+    /// The work logs are copied and deleted within a single transaction, hence a failure
+    /// leaves both databases unchanged. The archive is an ordinary database, which reports
+    /// may read alongside the active one.
+    ///
+    /// Returns the number of work logs moved.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the archive could not be created or written, or the
+    /// work logs could not be deleted
+    pub fn archive_before(
+        &self,
+        cutoff: DateTime<Local>,
+        archive_path: &Path,
+    ) -> Result<usize, WorklogError> {
+        // Creates the archive and its schema, closing it before it is attached
+        DatabaseManager::new(&DatabaseConfig::SqliteOnDisk {
+            path: archive_path.to_path_buf(),
+        })?;
+
+        let DbConnection::Sqlite(conn) = &self.connection;
+        let mut conn = conn.lock().map_err(|_| WorklogError::LockPoisoned)?;
+        sqlite::archive_before(&mut conn, cutoff, archive_path)
     }
 
     /// Helper function to create a ``SQLite`` connection with error handling.
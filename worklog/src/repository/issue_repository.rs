@@ -1,5 +1,5 @@
 use crate::error::WorklogError;
-use crate::types::JiraIssueInfo;
+use crate::types::{Favorite, JiraIssueInfo};
 use jira::models::core::IssueKey;
 use jira::models::issue::IssueSummary;
 
@@ -16,11 +16,8 @@ pub trait IssueRepository: Sync + Send {
     ///
     /// # Errors
     ///
-    /// Returns a `WorklogError` if any SQL operation fails during the insertion or update.
-    ///
-    /// # Panics
-    ///
-    /// This method panics if any SQL statement execution fails due to unexpected conditions.
+    /// Returns a `WorklogError` if an issue id is not numeric, or any SQL operation fails
+    /// during the insertion or update, in which case none of the issues are added.
     ///
     /// # Examples
     ///
@@ -76,4 +73,55 @@ pub trait IssueRepository: Sync + Send {
     /// # Errors
     /// Returns an error something goes wrong
     fn find_unique_keys(&self) -> Result<Vec<IssueKey>, WorklogError>;
+
+    /// Retrieves the keys of all the issues in the local database, in ascending order,
+    /// regardless of whether any work has been logged on them.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the SQL query fails
+    fn all_issue_keys(&self) -> Result<Vec<IssueKey>, WorklogError>;
+
+    /// Removes the cached Jira issues, which are not referenced by any local work log
+    /// or timer. Referenced issues are retained, as removing them would cascade into
+    /// the work log entries.
+    ///
+    /// # Returns
+    /// The number of issues removed
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if any of the SQL statements fail
+    fn clear_issues(&self) -> Result<usize, WorklogError>;
+
+    /// Pins the issue, which prevents it from being removed from the local database
+    /// when its last work log entry is deleted. See `prune_orphan_issues` of the configuration.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the issue is not in the local database or the SQL statement fails
+    fn set_pinned(&self, issue_key: &IssueKey, pinned: bool) -> Result<(), WorklogError>;
+
+    /// Adds the issue to the favorites, or replaces the alias of an existing favorite.
+    ///
+    /// # Errors
+    /// Returns `WorklogError::InvalidInput` if the alias is used by another favorite, or
+    /// the issue is not in the local database
+    fn add_favorite(&self, issue_key: &IssueKey, alias: Option<&str>) -> Result<(), WorklogError>;
+
+    /// Removes the issue from the favorites, returning the number of favorites removed
+    ///
+    /// # Errors
+    /// Returns an error if the database operation fails
+    fn remove_favorite(&self, issue_key: &IssueKey) -> Result<usize, WorklogError>;
+
+    /// Retrieves the favorites in the order they were added
+    ///
+    /// # Errors
+    /// Returns an error if the database operation fails
+    fn find_favorites(&self) -> Result<Vec<Favorite>, WorklogError>;
+
+    /// Removes the issue along with its work logs, timers, components and favorite in a single
+    /// transaction, returning the number of work logs removed
+    ///
+    /// # Errors
+    /// Returns an error if the database operation fails
+    fn remove_issue(&self, issue_key: &IssueKey) -> Result<usize, WorklogError>;
 }
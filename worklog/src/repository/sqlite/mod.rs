@@ -1,5 +1,9 @@
 use crate::error::WorklogError;
-use rusqlite::Connection;
+use crate::types::SchemaIssue;
+use chrono::{DateTime, Local};
+use rusqlite::{Connection, Transaction};
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 pub(crate) mod sqlite_component_repo;
@@ -11,6 +15,23 @@ pub(crate) mod sqlite_worklog_repo;
 /// A thread-safe, shared connection to an ``SQLite`` database,
 pub(crate) type SharedSqliteConnection = Arc<Mutex<Connection>>;
 
+/// Runs `f` within a transaction, which is committed if `f` succeeds and rolled back if it
+/// fails, hence writes spanning several statements or tables are never left half done.
+pub(crate) fn with_transaction<T, F>(
+    connection: &SharedSqliteConnection,
+    f: F,
+) -> Result<T, WorklogError>
+where
+    F: FnOnce(&Transaction) -> Result<T, WorklogError>,
+{
+    let mut conn = connection.lock().map_err(|_| WorklogError::LockPoisoned)?;
+    let tx = conn.transaction()?;
+    // The transaction is rolled back when dropped without being committed
+    let result = f(&tx)?;
+    tx.commit()?;
+    Ok(result)
+}
+
 /// Creates the entire database schema by running schema creation functions for all entities.
 #[allow(clippy::module_name_repetitions)]
 pub(crate) fn create_schema(connection: &SharedSqliteConnection) -> Result<(), WorklogError> {
@@ -24,5 +45,217 @@ pub(crate) fn create_schema(connection: &SharedSqliteConnection) -> Result<(), W
     Ok(())
 }
 
+/// A column name along with the definition used to add it with `ALTER TABLE`, if it is missing.
+/// Key and unique columns cannot be added that way, and have no definition.
+type ColumnDefinition = (&'static str, Option<&'static str>);
+
+/// The tables created by `create_schema`, with their columns
+pub(crate) const SCHEMA_TABLES: [(&str, &[ColumnDefinition]); 8] = [
+    (
+        "issue",
+        &[
+            ("id", None),
+            ("key", None),
+            ("summary", Some("varchar(1024) not null default ''")),
+            ("parent_key", Some("varchar(32)")),
+            ("pinned", Some("boolean not null default 0")),
+            ("status", Some("varchar(64)")),
+            ("resolution", Some("varchar(64)")),
+            ("assignee", Some("varchar(1024)")),
+            ("assignee_account_id", Some("varchar(128)")),
+            ("assignee_name", Some("varchar(255)")),
+            ("assignee_key", Some("varchar(255)")),
+        ],
+    ),
+    (
+        "favorite",
+        &[("id", None), ("issue_key", None), ("alias", None)],
+    ),
+    (
+        "worklog",
+        &[
+            ("id", None),
+            ("issue_key", Some("varchar(32)")),
+            ("issue_id", Some("integer")),
+            ("author", Some("varchar(1024)")),
+            ("created", Some("datetime")),
+            ("updated", Some("datetime")),
+            ("started", Some("datetime")),
+            ("time_spent", Some("varchar(32)")),
+            ("time_spent_seconds", Some("integer")),
+            ("comment", Some("varchar(1024)")),
+            ("source", Some("varchar(8) not null default 'jira'")),
+            ("last_synced_at", Some("datetime")),
+        ],
+    ),
+    ("worklog_tag", &[("worklog_id", None), ("tag", None)]),
+    (
+        "timer",
+        &[
+            ("id", None),
+            ("issue_key", Some("varchar(32)")),
+            ("created", Some("datetime")),
+            ("started", Some("datetime")),
+            ("end", Some("datetime")),
+            ("synced", Some("boolean")),
+            ("comment", Some("varchar(1024)")),
+            ("summary_snapshot", Some("varchar(1024)")),
+        ],
+    ),
+    (
+        "component",
+        &[
+            ("id", None),
+            ("name", Some("varchar(1024) not null default ''")),
+        ],
+    ),
+    (
+        "issue_component",
+        &[("id", None), ("key", None), ("component_id", None)],
+    ),
+    (
+        "user",
+        &[
+            ("account_id", None),
+            ("email", None),
+            ("display_name", Some("varchar(512) not null default ''")),
+            ("timezone", Some("varchar(64) not null default ''")),
+        ],
+    ),
+];
+
+/// Compares the tables and columns of the database with `SCHEMA_TABLES`
+pub(crate) fn verify_schema(connection: &Connection) -> Result<Vec<SchemaIssue>, WorklogError> {
+    let mut stmt = connection.prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?;
+    let existing_tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    let mut issues = Vec::new();
+    for (table, expected_columns) in SCHEMA_TABLES {
+        if !existing_tables.contains(table) {
+            issues.push(SchemaIssue::MissingTable(table.to_string()));
+            continue;
+        }
+        let mut stmt = connection.prepare("SELECT name FROM pragma_table_info(?1)")?;
+        let columns = stmt
+            .query_map([table], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        issues.extend(
+            expected_columns
+                .iter()
+                .filter(|(column, _)| !columns.iter().any(|c| c == column))
+                .map(|(column, _)| SchemaIssue::MissingColumn {
+                    table: table.to_string(),
+                    column: (*column).to_string(),
+                }),
+        );
+        issues.extend(
+            columns
+                .into_iter()
+                .filter(|c| !expected_columns.iter().any(|(column, _)| c == column))
+                .map(|column| SchemaIssue::ExtraColumn {
+                    table: table.to_string(),
+                    column,
+                }),
+        );
+    }
+    Ok(issues)
+}
+
+/// Adds the missing columns, which may be added with `ALTER TABLE`, returning the repaired
+/// issues. Missing tables are created by `create_schema`, while extra columns are left as is.
+pub(crate) fn repair_schema(connection: &Connection) -> Result<Vec<SchemaIssue>, WorklogError> {
+    let mut repaired = Vec::new();
+    for issue in verify_schema(connection)? {
+        let SchemaIssue::MissingColumn { table, column } = &issue else {
+            continue;
+        };
+        let definition = SCHEMA_TABLES
+            .iter()
+            .filter(|(name, _)| name == table)
+            .flat_map(|(_, columns)| columns.iter())
+            .find_map(|(name, definition)| if name == column { *definition } else { None });
+        if let Some(definition) = definition {
+            connection.execute(
+                &format!("ALTER TABLE \"{table}\" ADD COLUMN \"{column}\" {definition}"),
+                [],
+            )?;
+            repaired.push(issue);
+        }
+    }
+    Ok(repaired)
+}
+
+/// The comma separated columns of `table` in `SCHEMA_TABLES`
+fn column_list(table: &str) -> String {
+    SCHEMA_TABLES
+        .iter()
+        .filter(|(name, _)| *name == table)
+        .flat_map(|(_, columns)| columns.iter())
+        .map(|(column, _)| format!("\"{column}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Moves the work logs started before `cutoff` into the database attached as `archive`,
+/// along with their issues and tags, returning the number of work logs moved.
+fn move_worklogs_to_archive(
+    connection: &mut Connection,
+    cutoff: DateTime<Local>,
+) -> Result<usize, WorklogError> {
+    let (issue_columns, worklog_columns, tag_columns) = (
+        column_list("issue"),
+        column_list("worklog"),
+        column_list("worklog_tag"),
+    );
+    let tx = connection.transaction()?;
+    // Existing issues are kept, as replacing them would cascade to their archived work logs
+    tx.execute(
+        &format!(
+            "INSERT OR IGNORE INTO archive.issue ({issue_columns})
+             SELECT {issue_columns} FROM main.issue
+             WHERE id IN (SELECT issue_id FROM main.worklog WHERE started < ?1)
+                OR key IN (SELECT issue_key FROM main.worklog WHERE started < ?1)"
+        ),
+        [cutoff],
+    )?;
+    tx.execute(
+        &format!(
+            "INSERT OR REPLACE INTO archive.worklog ({worklog_columns})
+             SELECT {worklog_columns} FROM main.worklog WHERE started < ?1"
+        ),
+        [cutoff],
+    )?;
+    tx.execute(
+        &format!(
+            "INSERT OR REPLACE INTO archive.worklog_tag ({tag_columns})
+             SELECT {tag_columns} FROM main.worklog_tag
+             WHERE worklog_id IN (SELECT id FROM main.worklog WHERE started < ?1)"
+        ),
+        [cutoff],
+    )?;
+    // The tags are removed along with the work logs
+    let moved = tx.execute("DELETE FROM main.worklog WHERE started < ?1", [cutoff])?;
+    tx.commit()?;
+    Ok(moved)
+}
+
+/// Moves the work logs started before `cutoff` into the database at `archive_path`, which
+/// must hold the schema already. See [`crate::repository::database_manager::DatabaseManager::archive_before`]
+pub(crate) fn archive_before(
+    connection: &mut Connection,
+    cutoff: DateTime<Local>,
+    archive_path: &Path,
+) -> Result<usize, WorklogError> {
+    connection.execute(
+        "ATTACH DATABASE ?1 AS archive",
+        [archive_path.to_string_lossy()],
+    )?;
+    let moved = move_worklogs_to_archive(connection, cutoff);
+    connection.execute("DETACH DATABASE archive", [])?;
+    moved
+}
+
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
@@ -1,10 +1,10 @@
 use crate::error::WorklogError;
 use crate::repository::component_repository::ComponentRepository;
-use crate::repository::sqlite::SharedSqliteConnection;
+use crate::repository::sqlite::{with_transaction, SharedSqliteConnection};
 use jira::models::core::IssueKey;
 use jira::models::project::Component;
 use log::debug;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 
 pub struct SqliteComponentRepository {
     connection: SharedSqliteConnection,
@@ -63,10 +63,8 @@ impl ComponentRepository for SqliteComponentRepository {
     /// * `components` - A list of `Component` objects to add to the database.
     ///
     /// # Errors
-    /// Returns a `WorklogError` if any SQL operation fails during the insertion or association.
-    ///
-    /// # Panics
-    /// This method panics if it encounters any error during the execution of the SQL statements.
+    /// Returns a `WorklogError` if any SQL operation fails during the insertion or association,
+    /// in which case none of the components are added.
     fn create_component(
         &self,
         issue_key: &IssueKey,
@@ -74,54 +72,76 @@ impl ComponentRepository for SqliteComponentRepository {
     ) -> Result<(), WorklogError> {
         debug!("Inserting components ...");
 
-        let conn = self
-            .connection
-            .lock()
-            .expect("component connection mutex poisoned");
-        let mut insert_component_stmt = conn.prepare(
-            "INSERT INTO component (id, name)
-            VALUES (?1, ?2)
-            ON CONFLICT(id) DO UPDATE SET name = excluded.name",
-        )?;
-
-        debug!(
-            "Adding {} components for issue {issue_key}",
-            components.len()
-        );
-        for component in components {
-            debug!("Adding component id {} for issue {issue_key}", component.id);
-            // Consider using the return value to count number of rows that were actually
-            // inserted
-            if let Err(e) =
-                insert_component_stmt.execute(params![component.id, component.name.clone()])
-            {
-                panic!(
-                    "Unable to insert component({},{}): {}",
-                    component.id, component.name, e
-                );
-            }
-        }
-        debug!("Adding issue_components, waiting for mutex");
+        with_transaction(&self.connection, |tx| {
+            let mut insert_component_stmt = tx.prepare(
+                "INSERT INTO component (id, name)
+                VALUES (?1, ?2)
+                ON CONFLICT(id) DO UPDATE SET name = excluded.name",
+            )?;
 
-        // Links the components with the issues to maintain the many-to-many relationship
-        let mut insert_issue_component_stmt = conn
-            .prepare("INSERT OR IGNORE INTO issue_component (key, component_id) VALUES (?1, ?2)")?;
-        for component in components {
             debug!(
-                "Adding issue_component ({}, {})",
-                issue_key.value, component.id
+                "Adding {} components for issue {issue_key}",
+                components.len()
             );
-            if let Err(e) =
-                insert_issue_component_stmt.execute(params![issue_key.value(), component.id])
-            {
-                panic!(
-                    "Unable to insert issue_component({},{}): {}",
-                    issue_key.value(),
-                    component.id,
-                    e
+            for component in components {
+                debug!("Adding component id {} for issue {issue_key}", component.id);
+                insert_component_stmt.execute(params![component.id, component.name])?;
+            }
+
+            // Links the components with the issues to maintain the many-to-many relationship
+            let mut insert_issue_component_stmt = tx.prepare(
+                "INSERT OR IGNORE INTO issue_component (key, component_id) VALUES (?1, ?2)",
+            )?;
+            for component in components {
+                debug!(
+                    "Adding issue_component ({}, {})",
+                    issue_key.value, component.id
                 );
+                insert_issue_component_stmt.execute(params![issue_key.value(), component.id])?;
             }
-        }
-        Ok(())
+            Ok(())
+        })
+    }
+
+    fn clear_components(&self) -> Result<usize, WorklogError> {
+        let mut conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        // The association table references the components, so it must be cleared first
+        tx.execute("DELETE FROM issue_component", [])?;
+        let removed = tx.execute("DELETE FROM component", [])?;
+        tx.commit()?;
+        debug!("clear_components() :- removed {removed} components");
+        Ok(removed)
+    }
+
+    fn find_component_by_name(
+        &self,
+        project_key: &str,
+        name: &str,
+    ) -> Result<Option<Component>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        // Components belong to a project, which is given by the keys of the issues using them
+        let component = conn
+            .query_row(
+                "SELECT DISTINCT c.id, c.name FROM component c
+                JOIN issue_component ic ON ic.component_id = c.id
+                WHERE ic.key LIKE ?1 || '-%' AND lower(c.name) = lower(?2)
+                ORDER BY c.id",
+                params![project_key.to_uppercase(), name],
+                |row| {
+                    Ok(Component {
+                        id: row.get::<_, i64>(0)?.to_string(),
+                        name: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(component)
     }
 }
@@ -1,11 +1,11 @@
 use crate::error::WorklogError;
 use crate::repository::issue_repository::IssueRepository;
-use crate::repository::sqlite::SharedSqliteConnection;
-use crate::types::JiraIssueInfo;
-use jira::models::core::IssueKey;
+use crate::repository::sqlite::{with_transaction, SharedSqliteConnection};
+use crate::types::{Favorite, JiraIssueInfo};
+use jira::models::core::{Author, IssueKey};
 use jira::models::issue::IssueSummary;
 use log::debug;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 
 pub struct SqliteIssueRepository {
     connection: SharedSqliteConnection,
@@ -21,16 +21,119 @@ const CREATE_ISSUE_TABLE_SQL: &str = r"
     CREATE TABLE IF NOT EXISTS issue (
         id integer primary key,
         key varchar(32) not null unique,
-        summary varchar(1024) not null
+        summary varchar(1024) not null,
+        parent_key varchar(32),
+        pinned boolean not null default 0,
+        status varchar(64),
+        resolution varchar(64),
+        assignee varchar(1024),
+        assignee_account_id varchar(128),
+        assignee_name varchar(255),
+        assignee_key varchar(255)
+    );
+";
+
+/// SQL statement to create the `favorite` table, holding the issues frequently logged against
+const CREATE_FAVORITE_TABLE_SQL: &str = r"
+    CREATE TABLE IF NOT EXISTS favorite (
+        id integer primary key autoincrement,
+        issue_key varchar(32) not null unique,
+        alias varchar(64) unique,
+        FOREIGN KEY (issue_key) REFERENCES issue(key) ON DELETE CASCADE
     );
 ";
 
 pub(crate) fn create_issue_table(conn: &SharedSqliteConnection) -> Result<(), rusqlite::Error> {
     let conn = conn.lock().unwrap();
     conn.execute(CREATE_ISSUE_TABLE_SQL, [])?;
+    add_parent_key_column(&conn)?;
+    add_pinned_column(&conn)?;
+    add_status_columns(&conn)?;
+    add_assignee_columns(&conn)?;
+    conn.execute(CREATE_FAVORITE_TABLE_SQL, [])?;
+    Ok(())
+}
+
+/// Adds the `parent_key` column to `issue` tables created by older versions
+fn add_parent_key_column(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('issue') WHERE name = 'parent_key'")?
+        .exists([])?;
+    if !exists {
+        conn.execute("ALTER TABLE issue ADD COLUMN parent_key varchar(32)", [])?;
+    }
     Ok(())
 }
 
+/// Adds the `pinned` column to `issue` tables created by older versions
+fn add_pinned_column(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('issue') WHERE name = 'pinned'")?
+        .exists([])?;
+    if !exists {
+        conn.execute(
+            "ALTER TABLE issue ADD COLUMN pinned boolean not null default 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// Adds the `status` and `resolution` columns to `issue` tables created by older versions
+fn add_status_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
+    for column in ["status", "resolution"] {
+        let exists = conn
+            .prepare("SELECT 1 FROM pragma_table_info('issue') WHERE name = ?1")?
+            .exists([column])?;
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE issue ADD COLUMN {column} varchar(64)"),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds the `assignee` columns to `issue` tables created by older versions
+fn add_assignee_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
+    for (column, definition) in [
+        ("assignee", "varchar(1024)"),
+        ("assignee_account_id", "varchar(128)"),
+        ("assignee_name", "varchar(255)"),
+        ("assignee_key", "varchar(255)"),
+    ] {
+        let exists = conn
+            .prepare("SELECT 1 FROM pragma_table_info('issue') WHERE name = ?1")?
+            .exists([column])?;
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE issue ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Maps the `assignee`, `assignee_account_id`, `assignee_name` and `assignee_key` columns,
+/// selected in that order starting at `first`, to the assignee of the issue
+pub(crate) fn assignee_from_row(
+    row: &rusqlite::Row,
+    first: usize,
+) -> Result<Option<Author>, rusqlite::Error> {
+    let Some(display_name) = row.get::<_, Option<String>>(first)? else {
+        return Ok(None);
+    };
+    Ok(Some(Author {
+        accountId: row.get::<_, Option<String>>(first + 1)?.unwrap_or_default(),
+        emailAddress: None,
+        displayName: display_name,
+        name: row.get(first + 2)?,
+        key: row.get(first + 3)?,
+    }))
+}
+
 impl IssueRepository for SqliteIssueRepository {
     ///
     /// Adds multiple Jira issues to the local database.
@@ -44,11 +147,8 @@ impl IssueRepository for SqliteIssueRepository {
     ///
     /// # Errors
     ///
-    /// Returns a `WorklogError` if any SQL operation fails during the insertion or update.
-    ///
-    /// # Panics
-    ///
-    /// This method panics if any SQL statement execution fails due to unexpected conditions.
+    /// Returns a `WorklogError` if an issue id is not numeric, or any SQL operation fails
+    /// during the insertion or update, in which case none of the issues are added.
     ///
     /// # Examples
     ///
@@ -66,50 +166,62 @@ impl IssueRepository for SqliteIssueRepository {
                 "add_jira_issues() :- No issues to add".to_string(),
             ));
         }
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|_| WorklogError::LockPoisoned)?;
         debug!(
             "add_jira_issues() :- preparing statement for inserting {:?}",
             &jira_issues
         );
 
-        let insert_sql = "INSERT INTO issue (id, key, summary)
-            VALUES (?1, ?2, ?3)
-            ON CONFLICT(id) DO UPDATE SET summary = excluded.summary, key = excluded.key";
-        let mut stmt = match conn.prepare(insert_sql) {
-            Ok(stmt) => stmt,
-            Err(e) => {
-                // Log the error and return a WorklogError with context
+        let insert_sql =
+            "INSERT INTO issue (id, key, summary, parent_key, status, resolution, assignee,
+                assignee_account_id, assignee_name, assignee_key)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            ON CONFLICT(id) DO UPDATE SET summary = excluded.summary, key = excluded.key,
+                parent_key = excluded.parent_key, status = excluded.status,
+                resolution = excluded.resolution, assignee = excluded.assignee,
+                assignee_account_id = excluded.assignee_account_id,
+                assignee_name = excluded.assignee_name, assignee_key = excluded.assignee_key";
+        with_transaction(&self.connection, |tx| {
+            let mut stmt = tx.prepare(insert_sql).inspect_err(|e| {
                 log::error!(
                     "add_jira_issues(): Failed to prepare SQL statement: {insert_sql}, cause:'{e}'"
                 );
-                return Err(e.into());
-            }
-        };
-        debug!("add_jira_issues() :- statement prepared");
-
-        for issue in jira_issues {
-            debug!("add_jira_issues() :- inserting issue: {issue:?}");
-            if !issue.id.to_string().chars().all(char::is_numeric) {
-                return Err(WorklogError::InvalidInput(format!(
-                    "Issue ID must contain only digits, got: {}",
-                    issue.id
-                )));
-            }
-            if let Err(e) = stmt.execute(params![
-                issue.id,
-                issue.key.to_string(),
-                issue.fields.summary
-            ]) {
-                panic!(
-                    "Unable to insert issue({},{}): {}",
-                    issue.key, issue.fields.summary, e
-                );
+            })?;
+            debug!("add_jira_issues() :- statement prepared");
+
+            for issue in jira_issues {
+                debug!("add_jira_issues() :- inserting issue: {issue:?}");
+                if !issue.id.to_string().chars().all(char::is_numeric) {
+                    return Err(WorklogError::InvalidInput(format!(
+                        "Issue ID must contain only digits, got: {}",
+                        issue.id
+                    )));
+                }
+                let assignee = issue.fields.assignee.as_ref();
+                stmt.execute(params![
+                    issue.id,
+                    issue.key.to_string(),
+                    issue.fields.summary,
+                    issue
+                        .fields
+                        .parent
+                        .as_ref()
+                        .map(|parent| parent.key.to_string()),
+                    issue.fields.status.as_ref().map(|status| &status.name),
+                    issue
+                        .fields
+                        .resolution
+                        .as_ref()
+                        .map(|resolution| &resolution.name),
+                    assignee.map(|assignee| &assignee.displayName),
+                    assignee
+                        .map(|assignee| &assignee.accountId)
+                        .filter(|account_id| !account_id.is_empty()),
+                    assignee.and_then(|assignee| assignee.name.as_ref()),
+                    assignee.and_then(|assignee| assignee.key.as_ref())
+                ])?;
             }
-        }
-        Ok(())
+            Ok(())
+        })
     }
 
     ///
@@ -159,7 +271,8 @@ impl IssueRepository for SqliteIssueRepository {
         // Build the `IN` clause dynamically
         let placeholders = keys.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
         let sql = format!(
-            "SELECT key, summary
+            "SELECT key, summary, parent_key, status, resolution, assignee,
+                assignee_account_id, assignee_name, assignee_key
             FROM issue
             WHERE key IN ({placeholders})"
         );
@@ -174,6 +287,10 @@ impl IssueRepository for SqliteIssueRepository {
                 Ok(JiraIssueInfo {
                     issue_key: IssueKey::new(&row.get::<_, String>(0)?),
                     summary: row.get(1)?,
+                    parent_key: row.get::<_, Option<String>>(2)?.map(IssueKey::from),
+                    status: row.get(3)?,
+                    resolution: row.get(4)?,
+                    assignee: assignee_from_row(row, 5)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -197,4 +314,248 @@ impl IssueRepository for SqliteIssueRepository {
             .collect();
         Ok(issue_keys)
     }
+
+    fn all_issue_keys(&self) -> Result<Vec<IssueKey>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let mut stmt = conn.prepare("SELECT key FROM issue ORDER BY key")?;
+        let issue_keys = stmt
+            .query_map([], |row| Ok(IssueKey::from(row.get::<_, String>(0)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(issue_keys)
+    }
+
+    fn clear_issues(&self) -> Result<usize, WorklogError> {
+        let mut conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        let unreferenced = "SELECT key FROM issue
+            WHERE id NOT IN (SELECT issue_id FROM worklog WHERE issue_id IS NOT NULL)
+            AND key NOT IN (SELECT issue_key FROM timer)
+            AND key NOT IN (SELECT issue_key FROM favorite)";
+        tx.execute(
+            &format!("DELETE FROM issue_component WHERE key IN ({unreferenced})"),
+            [],
+        )?;
+        let removed = tx.execute(
+            &format!("DELETE FROM issue WHERE key IN ({unreferenced})"),
+            [],
+        )?;
+        tx.commit()?;
+        debug!("clear_issues() :- removed {removed} issues");
+        Ok(removed)
+    }
+
+    fn set_pinned(&self, issue_key: &IssueKey, pinned: bool) -> Result<(), WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let updated = conn.execute(
+            "UPDATE issue SET pinned = ?1 WHERE key = ?2",
+            params![pinned, issue_key.value()],
+        )?;
+        if updated == 0 {
+            return Err(WorklogError::IssueNotFoundInLocalDBMS(
+                issue_key.value().to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn add_favorite(&self, issue_key: &IssueKey, alias: Option<&str>) -> Result<(), WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        conn.execute(
+            "INSERT INTO favorite (issue_key, alias) VALUES (?1, ?2)
+            ON CONFLICT(issue_key) DO UPDATE SET alias = excluded.alias",
+            params![issue_key.value(), alias],
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(error, _)
+                if error.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                WorklogError::InvalidInput(format!(
+                    "Unable to add {issue_key} to the favorites, the issue is unknown or the alias is in use"
+                ))
+            }
+            e => e.into(),
+        })?;
+        Ok(())
+    }
+
+    fn remove_favorite(&self, issue_key: &IssueKey) -> Result<usize, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        Ok(conn.execute(
+            "DELETE FROM favorite WHERE issue_key = ?1",
+            params![issue_key.value()],
+        )?)
+    }
+
+    fn remove_issue(&self, issue_key: &IssueKey) -> Result<usize, WorklogError> {
+        let mut conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        let key = issue_key.value();
+        tx.execute(
+            "DELETE FROM worklog_tag WHERE worklog_id IN (SELECT id FROM worklog WHERE issue_key = ?1)",
+            params![key],
+        )?;
+        let worklogs = tx.execute("DELETE FROM worklog WHERE issue_key = ?1", params![key])?;
+        tx.execute("DELETE FROM timer WHERE issue_key = ?1", params![key])?;
+        tx.execute("DELETE FROM favorite WHERE issue_key = ?1", params![key])?;
+        tx.execute("DELETE FROM issue_component WHERE key = ?1", params![key])?;
+        tx.execute("DELETE FROM issue WHERE key = ?1", params![key])?;
+        tx.commit()?;
+        debug!("Removed {key} along with {worklogs} work logs");
+        Ok(worklogs)
+    }
+
+    fn find_favorites(&self) -> Result<Vec<Favorite>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let mut stmt = conn.prepare("SELECT issue_key, alias FROM favorite ORDER BY id")?;
+        let favorites = stmt
+            .query_map([], |row| {
+                Ok(Favorite {
+                    issue_key: IssueKey::from(row.get::<_, String>(0)?),
+                    alias: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(favorites)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::sqlite::tests::test_database_manager;
+    use jira::models::core::{Fields, IssueRef};
+
+    #[test]
+    fn all_issue_keys() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        let issue_repo = db_manager.create_issue_repository();
+        assert!(issue_repo.all_issue_keys()?.is_empty());
+
+        issue_repo.add_jira_issues(&[
+            IssueSummary {
+                id: "2".to_string(),
+                key: IssueKey::from("ABC-2"),
+                fields: Fields::default(),
+            },
+            IssueSummary {
+                id: "1".to_string(),
+                key: IssueKey::from("ABC-1"),
+                fields: Fields::default(),
+            },
+        ])?;
+        assert_eq!(
+            issue_repo.all_issue_keys()?,
+            vec![IssueKey::from("ABC-1"), IssueKey::from("ABC-2")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn add_jira_issues_with_parent() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        let issue_repo = db_manager.create_issue_repository();
+
+        issue_repo.add_jira_issues(&[
+            IssueSummary {
+                id: "1".to_string(),
+                key: IssueKey::from("ABC-1"),
+                fields: Fields {
+                    summary: "Parent".to_string(),
+                    ..Default::default()
+                },
+            },
+            IssueSummary {
+                id: "2".to_string(),
+                key: IssueKey::from("ABC-2"),
+                fields: Fields {
+                    summary: "Subtask".to_string(),
+                    parent: Some(IssueRef {
+                        id: "1".to_string(),
+                        key: IssueKey::from("ABC-1"),
+                    }),
+                    ..Default::default()
+                },
+            },
+        ])?;
+
+        let mut issues = issue_repo
+            .get_issues_filtered_by_keys(&[IssueKey::from("ABC-1"), IssueKey::from("ABC-2")])?;
+        issues.sort_by(|a, b| a.issue_key.cmp(&b.issue_key));
+        assert_eq!(issues[0].parent_key, None);
+        assert_eq!(issues[1].parent_key, Some(IssueKey::from("ABC-1")));
+        Ok(())
+    }
+
+    #[test]
+    fn add_and_remove_favorites() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        let issue_repo = db_manager.create_issue_repository();
+        issue_repo.add_jira_issues(&[
+            IssueSummary {
+                id: "1".to_string(),
+                key: IssueKey::from("ABC-1"),
+                fields: Fields::default(),
+            },
+            IssueSummary {
+                id: "2".to_string(),
+                key: IssueKey::from("ABC-2"),
+                fields: Fields::default(),
+            },
+        ])?;
+
+        issue_repo.add_favorite(&IssueKey::from("ABC-2"), Some("meetings"))?;
+        issue_repo.add_favorite(&IssueKey::from("ABC-1"), None)?;
+        // Replaces the alias rather than adding the issue twice
+        issue_repo.add_favorite(&IssueKey::from("ABC-2"), Some("standup"))?;
+        assert_eq!(
+            issue_repo.find_favorites()?,
+            vec![
+                Favorite {
+                    issue_key: IssueKey::from("ABC-2"),
+                    alias: Some("standup".to_string())
+                },
+                Favorite {
+                    issue_key: IssueKey::from("ABC-1"),
+                    alias: None
+                },
+            ]
+        );
+
+        // The alias is unique and the issue must be known
+        assert!(issue_repo
+            .add_favorite(&IssueKey::from("ABC-1"), Some("standup"))
+            .is_err());
+        assert!(issue_repo
+            .add_favorite(&IssueKey::from("ABC-3"), None)
+            .is_err());
+
+        // Favorites are kept when the cache is cleared
+        assert_eq!(issue_repo.clear_issues()?, 0);
+
+        assert_eq!(issue_repo.remove_favorite(&IssueKey::from("ABC-2"))?, 1);
+        assert_eq!(issue_repo.remove_favorite(&IssueKey::from("ABC-2"))?, 0);
+        assert_eq!(issue_repo.find_favorites()?.len(), 1);
+        Ok(())
+    }
 }
@@ -22,6 +22,7 @@ const CREATE_TIMER_TABLE_SQL: &str = r"
         end datetime,
         synced boolean,
         comment varchar(1024),
+        summary_snapshot varchar(1024),
         FOREIGN KEY (issue_key) REFERENCES issue(key) ON DELETE CASCADE
     );
     
@@ -33,6 +34,21 @@ const CREATE_TIMER_TABLE_SQL: &str = r"
 pub fn create_timer_table(connection: &SharedSqliteConnection) -> Result<(), WorklogError> {
     let conn = connection.lock().unwrap();
     conn.execute_batch(CREATE_TIMER_TABLE_SQL)?;
+    add_summary_snapshot_column(&conn)?;
+    Ok(())
+}
+
+/// Adds the `summary_snapshot` column to `timer` tables created by older versions
+fn add_summary_snapshot_column(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('timer') WHERE name = 'summary_snapshot'")?
+        .exists([])?;
+    if !exists {
+        conn.execute(
+            "ALTER TABLE timer ADD COLUMN summary_snapshot varchar(1024)",
+            [],
+        )?;
+    }
     Ok(())
 }
 
@@ -50,8 +66,8 @@ impl TimerRepository for SqliteTimerRepository {
             .lock()
             .map_err(|_| WorklogError::LockPoisoned)?;
         let result: SqliteResult<i64> = conn.query_row(
-            r"INSERT INTO timer (issue_key, created, started, end, synced, comment)
-              VALUES (?, ?, ?, ?, ?, ?)
+            r"INSERT INTO timer (issue_key, created, started, end, synced, comment, summary_snapshot)
+              VALUES (?, ?, ?, ?, ?, ?, ?)
               RETURNING id",
             params![
                 timer.issue_key,
@@ -60,6 +76,7 @@ impl TimerRepository for SqliteTimerRepository {
                 timer.stopped_at,
                 timer.synced,
                 timer.comment,
+                timer.summary_snapshot,
             ],
             |row| row.get(0),
         );
@@ -85,7 +102,7 @@ impl TimerRepository for SqliteTimerRepository {
             .map_err(|_| WorklogError::DatabaseLockError)?;
 
         let result = conn.query_row(
-            r"SELECT id, issue_key, created, started, end, synced, comment 
+            r"SELECT id, issue_key, created, started, end, synced, comment, summary_snapshot 
               FROM timer 
               WHERE end IS NULL",
             [],
@@ -98,6 +115,7 @@ impl TimerRepository for SqliteTimerRepository {
                     stopped_at: row.get(4)?,
                     synced: row.get(5)?,
                     comment: row.get(6)?,
+                    summary_snapshot: row.get(7)?,
                 })
             },
         );
@@ -146,7 +164,7 @@ impl TimerRepository for SqliteTimerRepository {
             .map_err(|_| WorklogError::DatabaseLockError)?;
 
         let mut stmt = conn.prepare(
-            r"SELECT id, issue_key, created, started, end, synced, comment 
+            r"SELECT id, issue_key, created, started, end, synced, comment, summary_snapshot 
               FROM timer 
               WHERE issue_ke = ? 
               ORDER BY started DESC",
@@ -161,6 +179,7 @@ impl TimerRepository for SqliteTimerRepository {
                 stopped_at: row.get(4)?,
                 synced: row.get(5)?,
                 comment: row.get(6)?,
+                summary_snapshot: row.get(7)?,
             })
         })?;
 
@@ -180,7 +199,7 @@ impl TimerRepository for SqliteTimerRepository {
             .map_err(|_| WorklogError::DatabaseLockError)?;
 
         let mut stmt = conn.prepare(
-            r"SELECT id, issue_key, created, started, end, synced, comment 
+            r"SELECT id, issue_key, created, started, end, synced, comment, summary_snapshot 
               FROM timer 
               WHERE started >= ? 
               ORDER BY started DESC",
@@ -195,6 +214,7 @@ impl TimerRepository for SqliteTimerRepository {
                 stopped_at: row.get(4)?,
                 synced: row.get(5)?,
                 comment: row.get(6)?,
+                summary_snapshot: row.get(7)?,
             })
         })?;
 
@@ -237,7 +257,8 @@ impl TimerRepository for SqliteTimerRepository {
 
         let rows_affected = conn.execute(
             r"UPDATE timer 
-              SET issue_key = ?, created = ?, started = ?, end = ?, synced = ?, comment = ? 
+              SET issue_key = ?, created = ?, started = ?, end = ?, synced = ?, comment = ?,
+                summary_snapshot = ?
               WHERE id = ?",
             params![
                 timer.issue_key,
@@ -246,6 +267,7 @@ impl TimerRepository for SqliteTimerRepository {
                 timer.stopped_at,
                 timer.synced,
                 timer.comment,
+                timer.summary_snapshot,
                 timer.id,
             ],
         )?;
@@ -66,6 +66,17 @@ impl UserRepository for SqliteUserRepository {
             .ok_or_else(|| WorklogError::Sql("No user found".to_string()))?;
         Ok(user)
     }
+
+    fn clear_users(&self) -> Result<usize, WorklogError> {
+        let mut conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        let removed = tx.execute("DELETE FROM user", [])?;
+        tx.commit()?;
+        Ok(removed)
+    }
 }
 
 #[cfg(test)]
@@ -80,7 +91,8 @@ mod tests {
             email_address: "steinar@gastroplanner.no".to_string(),
             display_name: "Steinar Overbeck Cook".to_string(),
             time_zone: "Europe/Oslo".to_string(),
-            self_url: "https://xxxxxxxx.atlassian.net/rest/api/2/user?accountId=713020:719b6d98-78c7-4c63-a564-299916c67765".to_string()
+            self_url: "https://xxxxxxxx.atlassian.net/rest/api/2/user?accountId=713020:719b6d98-78c7-4c63-a564-299916c67765".to_string(),
+            ..Default::default()
         };
         let user_repo = db_manager.create_user_repository();
         user_repo.insert_or_update_current_user(&user)?;
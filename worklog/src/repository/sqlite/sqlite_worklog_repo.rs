@@ -1,13 +1,14 @@
 use crate::error::WorklogError;
+use crate::repository::sqlite::sqlite_issue_repo::assignee_from_row;
 use crate::repository::sqlite::SharedSqliteConnection;
 use crate::repository::worklog_repository::WorkLogRepository;
-use crate::types::LocalWorklog;
+use crate::types::{JiraIssueInfo, LocalWorklog, UpsertCounts, WorklogSource};
 use chrono::{DateTime, Local};
 use jira::models::core::IssueKey;
 use jira::models::user::User;
 use jira::models::worklog::Worklog;
 use log::debug;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -28,14 +29,44 @@ const CREATE_WORKLOG_TABLE_SQL: &str = r"
         time_spent varchar(32),
         time_spent_seconds integer,
         comment varchar(1024),
+        source varchar(8) not null default 'jira',
+        last_synced_at datetime,
         FOREIGN KEY (issue_id) REFERENCES issue(id) ON DELETE CASCADE
     );
 ";
 
+/// SQL statement to create the `worklog_tag` table, holding tags which are never sent to Jira.
+const CREATE_WORKLOG_TAG_TABLE_SQL: &str = r"
+    CREATE TABLE IF NOT EXISTS worklog_tag (
+        worklog_id integer not null,
+        tag varchar(64) not null,
+        PRIMARY KEY (worklog_id, tag),
+        FOREIGN KEY (worklog_id) REFERENCES worklog(id) ON DELETE CASCADE
+    );
+";
+
 /// Creates the `worklog` table in the database.
 pub fn create_worklog_table(connection: &SharedSqliteConnection) -> Result<(), WorklogError> {
     let conn = connection.lock().unwrap();
     conn.execute(CREATE_WORKLOG_TABLE_SQL, [])?;
+    add_sync_columns(&conn)?;
+    conn.execute(CREATE_WORKLOG_TAG_TABLE_SQL, [])?;
+    Ok(())
+}
+
+/// Adds the `source` and `last_synced_at` columns to `worklog` tables created by older versions.
+/// Existing rows were all pulled from Jira, or have since been, hence the default of `jira`.
+fn add_sync_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('worklog') WHERE name = 'source'")?
+        .exists([])?;
+    if !exists {
+        conn.execute(
+            "ALTER TABLE worklog ADD COLUMN source varchar(8) not null default 'jira'",
+            [],
+        )?;
+        conn.execute("ALTER TABLE worklog ADD COLUMN last_synced_at datetime", [])?;
+    }
     Ok(())
 }
 
@@ -60,6 +91,49 @@ impl WorkLogRepository for SqliteWorklogRepository {
         Ok(())
     }
 
+    fn remove_entry_and_orphaned_issue(
+        &self,
+        wl_id: &str,
+    ) -> Result<Option<IssueKey>, WorklogError> {
+        let mut conn = self
+            .connection
+            .lock()
+            .map_err(|_| WorklogError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        let issue_key: Option<String> = tx
+            .query_row(
+                "SELECT issue_key FROM worklog WHERE id = ?1",
+                params![wl_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        tx.execute("DELETE FROM worklog WHERE id = ?1", params![wl_id])?;
+
+        let Some(issue_key) = issue_key else {
+            tx.commit()?;
+            return Ok(None);
+        };
+        let orphaned = "SELECT key FROM issue WHERE key = ?1 AND NOT pinned
+            AND key NOT IN (SELECT issue_key FROM worklog WHERE issue_key IS NOT NULL)
+            AND key NOT IN (SELECT issue_key FROM timer WHERE issue_key IS NOT NULL)
+            AND key NOT IN (SELECT issue_key FROM favorite)";
+        tx.execute(
+            &format!("DELETE FROM issue_component WHERE key IN ({orphaned})"),
+            params![issue_key],
+        )?;
+        let removed = tx.execute(
+            &format!("DELETE FROM issue WHERE key IN ({orphaned})"),
+            params![issue_key],
+        )?;
+        tx.commit()?;
+        if removed == 0 {
+            return Ok(None);
+        }
+        debug!("Removed the orphaned issue {issue_key} along with worklog {wl_id}");
+        Ok(Some(IssueKey::from(issue_key)))
+    }
+
     fn add_entry(&self, local_worklog: &LocalWorklog) -> Result<(), WorklogError> {
         debug!("Adding {:?} to DBMS", &local_worklog);
         let worklog = local_worklog.clone();
@@ -74,8 +148,8 @@ impl WorkLogRepository for SqliteWorklogRepository {
         // Prepare the SQL insert statement
         let mut stmt = conn.prepare(r"
             INSERT INTO worklog
-                (id, issue_key, issue_id, author, created, updated, started, time_spent, time_spent_seconds, comment)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                (id, issue_key, issue_id, author, created, updated, started, time_spent, time_spent_seconds, comment, source)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         ")?;
 
         // Execute the insert statement for each LocalWorklog instance
@@ -91,6 +165,7 @@ impl WorkLogRepository for SqliteWorklogRepository {
                 worklog.timeSpent,
                 worklog.timeSpentSeconds,
                 worklog.comment,
+                WorklogSource::Local.as_str(),
             ]);
             match result {
                 Ok(_) => {}
@@ -115,6 +190,107 @@ impl WorkLogRepository for SqliteWorklogRepository {
         Ok(())
     }
 
+    fn upsert_synced_entries(
+        &self,
+        worklogs: &[LocalWorklog],
+        synced_at: DateTime<Local>,
+    ) -> Result<UpsertCounts, WorklogError> {
+        let mut conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        let mut counts = UpsertCounts::default();
+        let mut find_updated = tx.prepare("SELECT updated FROM worklog WHERE id = ?1")?;
+        // The source of existing rows is retained, entries added locally remain `local`
+        let mut stmt = tx.prepare(r"
+            INSERT INTO worklog
+                (id, issue_key, issue_id, author, created, updated, started, time_spent, time_spent_seconds, comment, source, last_synced_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                issue_key = excluded.issue_key,
+                issue_id = excluded.issue_id,
+                author = excluded.author,
+                created = excluded.created,
+                updated = excluded.updated,
+                started = excluded.started,
+                time_spent = excluded.time_spent,
+                time_spent_seconds = excluded.time_spent_seconds,
+                comment = excluded.comment,
+                last_synced_at = excluded.last_synced_at
+        ")?;
+        for worklog in worklogs {
+            let updated: Option<DateTime<Local>> = find_updated
+                .query_row(params![worklog.id], |row| row.get(0))
+                .optional()?;
+            match updated {
+                None => counts.created += 1,
+                Some(updated) if updated != worklog.updated => counts.updated += 1,
+                Some(_) => {}
+            }
+            stmt.execute(params![
+                worklog.id,
+                worklog.issue_key.to_string(),
+                worklog.issueId,
+                worklog.author,
+                worklog.created,
+                worklog.updated,
+                worklog.started,
+                worklog.timeSpent,
+                worklog.timeSpentSeconds,
+                worklog.comment,
+                WorklogSource::Jira.as_str(),
+                synced_at,
+            ])
+            .map_err(|e| match e {
+                rusqlite::Error::SqliteFailure(error, _)
+                    if error.code == rusqlite::ErrorCode::ConstraintViolation =>
+                {
+                    WorklogError::MissingWorklogParentIssue(worklog.issue_key.clone())
+                }
+                e => WorklogError::Sql(format!("Unable to upsert worklog: {e:?}")),
+            })?;
+        }
+        drop(find_updated);
+        drop(stmt);
+        tx.commit()?;
+        Ok(counts)
+    }
+
+    fn find_sync_state(
+        &self,
+        worklog_id: &str,
+    ) -> Result<(WorklogSource, Option<DateTime<Local>>), WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let (source, last_synced_at): (String, Option<DateTime<Local>>) = conn.query_row(
+            "SELECT source, last_synced_at FROM worklog WHERE id = ?1",
+            params![worklog_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let source = source.parse().map_err(WorklogError::Sql)?;
+        Ok((source, last_synced_at))
+    }
+
+    fn find_worklogs_without_source(&self) -> Result<Vec<String>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let mut stmt = conn.prepare(
+            "SELECT id FROM worklog WHERE source IS NULL OR source NOT IN (?1, ?2) ORDER BY id",
+        )?;
+        let ids = stmt
+            .query_map(
+                params![WorklogSource::Local.as_str(), WorklogSource::Jira.as_str()],
+                |row| Ok(row.get::<_, i64>(0)?.to_string()),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
     fn get_count(&self) -> Result<i64, WorklogError> {
         let conn = self
             .connection
@@ -208,6 +384,9 @@ impl WorkLogRepository for SqliteWorklogRepository {
             );
         }
 
+        // Compares the instants, as the textual times may have different UTC offsets
+        sql.push_str(" ORDER BY julianday(started), id");
+
         // Convert `params` to a slice of `&dyn ToSql`
         let params_slice: Vec<&dyn rusqlite::ToSql> = params.iter().map(AsRef::as_ref).collect();
 
@@ -240,6 +419,160 @@ impl WorkLogRepository for SqliteWorklogRepository {
 
         Ok(worklogs)
     }
+
+    fn most_recent_issues(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<(JiraIssueInfo, DateTime<Local>)>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        // Sqlite returns the `started` of the row holding the max() value. julianday()
+        // ensures timestamps with different UTC offsets are compared correctly.
+        let mut stmt = conn.prepare(
+            r"
+            SELECT issue.key, issue.summary, issue.parent_key, issue.status, issue.resolution,
+                issue.assignee, issue.assignee_account_id, issue.assignee_name,
+                issue.assignee_key, worklog.started,
+                max(julianday(worklog.started)) AS latest
+            FROM worklog
+                JOIN issue ON issue.id = worklog.issue_id
+            GROUP BY issue.key
+            ORDER BY latest DESC
+            LIMIT ?1",
+        )?;
+        let issues = stmt
+            .query_map(params![limit], |row| {
+                Ok((
+                    JiraIssueInfo {
+                        issue_key: IssueKey::new(&row.get::<_, String>(0)?),
+                        summary: row.get(1)?,
+                        parent_key: row.get::<_, Option<String>>(2)?.map(IssueKey::from),
+                        status: row.get(3)?,
+                        resolution: row.get(4)?,
+                        assignee: assignee_from_row(row, 5)?,
+                    },
+                    row.get(9)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(issues)
+    }
+
+    fn add_tags(&self, worklog_id: &str, tags: &[String]) -> Result<(), WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let mut stmt =
+            conn.prepare("INSERT OR IGNORE INTO worklog_tag (worklog_id, tag) VALUES (?1, ?2)")?;
+        for tag in tags {
+            stmt.execute(params![worklog_id, tag])
+                .map_err(|e| match e {
+                    rusqlite::Error::SqliteFailure(error, _)
+                        if error.code == rusqlite::ErrorCode::ConstraintViolation =>
+                    {
+                        WorklogError::InvalidInput(format!("No work log with id {worklog_id}"))
+                    }
+                    e => e.into(),
+                })?;
+        }
+        Ok(())
+    }
+
+    fn remove_tags(&self, worklog_id: &str, tags: &[String]) -> Result<usize, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let mut stmt =
+            conn.prepare("DELETE FROM worklog_tag WHERE worklog_id = ?1 AND tag = ?2")?;
+        let mut removed = 0;
+        for tag in tags {
+            removed += stmt.execute(params![worklog_id, tag])?;
+        }
+        Ok(removed)
+    }
+
+    fn find_by_tag(&self, tag: &str) -> Result<Vec<String>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let mut stmt =
+            conn.prepare("SELECT worklog_id FROM worklog_tag WHERE tag = ?1 ORDER BY worklog_id")?;
+        let ids = stmt
+            .query_map(params![tag], |row| Ok(row.get::<_, i64>(0)?.to_string()))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    fn find_tags(&self, worklog_id: &str) -> Result<Vec<String>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        let mut stmt =
+            conn.prepare("SELECT tag FROM worklog_tag WHERE worklog_id = ?1 ORDER BY tag")?;
+        let tags = stmt
+            .query_map(params![worklog_id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tags)
+    }
+
+    fn find_duplicates(&self) -> Result<Vec<Vec<LocalWorklog>>, WorklogError> {
+        let conn = self
+            .connection
+            .lock()
+            .map_err(|_e| WorklogError::LockPoisoned)?;
+        // `IS` rather than `=` makes two entries without a comment compare equal
+        let mut stmt = conn.prepare(
+            "SELECT w.issue_key, w.id, w.author, w.created, w.updated, w.started, w.time_spent,
+                w.time_spent_seconds, w.issue_id, w.comment
+            FROM worklog w
+            WHERE EXISTS (
+                SELECT 1 FROM worklog d
+                WHERE d.id <> w.id AND d.issue_key = w.issue_key AND d.author = w.author
+                    AND d.started = w.started AND d.time_spent_seconds = w.time_spent_seconds
+                    AND d.comment IS w.comment)
+            ORDER BY w.issue_key, w.author, w.started, w.time_spent_seconds, w.comment, w.id",
+        )?;
+        let worklogs = stmt
+            .query_map([], |row| {
+                Ok(LocalWorklog {
+                    issue_key: IssueKey::new(&row.get::<_, String>(0)?),
+                    id: row.get::<_, i32>(1)?.to_string(),
+                    author: row.get(2)?,
+                    created: row.get(3)?,
+                    updated: row.get(4)?,
+                    started: row.get(5)?,
+                    timeSpent: row.get(6)?,
+                    timeSpentSeconds: row.get(7)?,
+                    issueId: row.get(8)?,
+                    comment: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut groups: Vec<Vec<LocalWorklog>> = Vec::new();
+        for worklog in worklogs {
+            match groups.last_mut() {
+                Some(group) if is_duplicate(&group[0], &worklog) => group.push(worklog),
+                _ => groups.push(vec![worklog]),
+            }
+        }
+        Ok(groups)
+    }
+}
+
+/// Do the two entries record the same work, according to [`WorkLogRepository::find_duplicates`]?
+fn is_duplicate(a: &LocalWorklog, b: &LocalWorklog) -> bool {
+    a.issue_key == b.issue_key
+        && a.author == b.author
+        && a.started == b.started
+        && a.timeSpentSeconds == b.timeSpentSeconds
+        && a.comment == b.comment
 }
 
 #[cfg(test)]
@@ -255,18 +588,11 @@ mod tests {
     const ISSUE_ID: &str = "123";
     #[test]
     fn add_worklog_entry() -> Result<(), WorklogError> {
-        let worklog = LocalWorklog {
-            id: "123".to_string(),
-            issue_key: IssueKey::from("ABC-123"),
-            author: "Ola Dunk".to_string(),
-            created: Local::now(),
-            updated: Local::now(),
-            started: Local::now(),
-            timeSpent: "1h".to_string(),
-            timeSpentSeconds: 3600,
-            issueId: ISSUE_ID.parse().unwrap(),
-            comment: Some("Worked on the issue".to_string()),
-        };
+        let worklog = LocalWorklog::builder(IssueKey::from("ABC-123"), "123", 3600)
+            .author("Ola Dunk")
+            .issue_id(ISSUE_ID.parse().unwrap())
+            .comment("Worked on the issue")
+            .build();
 
         let db_manager = test_database_manager()?;
         let issue_repo_for_test = db_manager.create_issue_repository();
@@ -293,18 +619,11 @@ mod tests {
 
     #[test]
     fn add_worklog_entries() -> Result<(), WorklogError> {
-        let worklog = LocalWorklog {
-            issue_key: IssueKey::from("ABC-789"),
-            id: "1".to_string(),
-            author: "John Doe".to_string(),
-            created: Local::now(),
-            updated: Local::now(),
-            started: Local::now(),
-            timeSpent: "1h".to_string(),
-            timeSpentSeconds: 3600,
-            issueId: ISSUE_ID.parse().unwrap(),
-            comment: Some("Worked on the issue".to_string()),
-        };
+        let worklog = LocalWorklog::builder(IssueKey::from("ABC-789"), "1", 3600)
+            .author("John Doe")
+            .issue_id(ISSUE_ID.parse().unwrap())
+            .comment("Worked on the issue")
+            .build();
         let db_manager = test_database_manager()?;
         let issue_repo = db_manager.create_issue_repository();
         issue_repo.add_jira_issues(&[IssueSummary {
@@ -330,18 +649,11 @@ mod tests {
     fn find_worklogs_after() -> Result<(), WorklogError> {
         let db_manager = test_database_manager()?;
 
-        let worklog = LocalWorklog {
-            issue_key: IssueKey::from("ABC-456"),
-            id: "1".to_string(),
-            author: "John Doe".to_string(),
-            created: Local::now(),
-            updated: Local::now(),
-            started: Local::now(),
-            timeSpent: "1h".to_string(),
-            timeSpentSeconds: 3600,
-            issueId: ISSUE_ID.parse().unwrap(),
-            comment: Some("Worked on the issue".to_string()),
-        };
+        let worklog = LocalWorklog::builder(IssueKey::from("ABC-456"), "1", 3600)
+            .author("John Doe")
+            .issue_id(ISSUE_ID.parse().unwrap())
+            .comment("Worked on the issue")
+            .build();
         let test_issue_repo = db_manager.create_issue_repository();
         test_issue_repo.add_jira_issues(&[IssueSummary {
             id: 123.to_string(),
@@ -377,4 +689,237 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn find_worklogs_after_orders_by_started() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        db_manager
+            .create_issue_repository()
+            .add_jira_issues(&[IssueSummary {
+                id: ISSUE_ID.to_string(),
+                key: IssueKey::from("ABC-456"),
+                fields: Fields::default(),
+            }])?;
+        let worklog_repo = db_manager.create_worklog_repository();
+
+        let now = Local::now();
+        let worklog = |id: &str, hours_ago: i64| {
+            let started = now - chrono::Duration::hours(hours_ago);
+            LocalWorklog::builder(IssueKey::from("ABC-456"), id, 3600)
+                .author("John Doe")
+                .created(started)
+                .updated(started)
+                .started(started)
+                .issue_id(ISSUE_ID.parse().unwrap())
+                .build()
+        };
+        worklog_repo.add_worklog_entries(&[
+            worklog("1", 2),
+            worklog("2", 30),
+            worklog("3", 5),
+            worklog("4", 72),
+        ])?;
+
+        let ids: Vec<String> = worklog_repo
+            .find_worklogs_after(now - chrono::Duration::days(7), &[], &[])?
+            .into_iter()
+            .map(|wl| wl.id)
+            .collect();
+        assert_eq!(ids, vec!["4", "2", "3", "1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn find_duplicates() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        db_manager
+            .create_issue_repository()
+            .add_jira_issues(&[IssueSummary {
+                id: ISSUE_ID.to_string(),
+                key: IssueKey::from("ABC-456"),
+                fields: Fields::default(),
+            }])?;
+        let worklog_repo = db_manager.create_worklog_repository();
+
+        let started = Local::now();
+        let worklog = |id: &str, comment: &str| {
+            LocalWorklog::builder(IssueKey::from("ABC-456"), id, 3600)
+                .author("John Doe")
+                .created(started)
+                .updated(started)
+                .started(started)
+                .issue_id(ISSUE_ID.parse().unwrap())
+                .comment(comment)
+                .build()
+        };
+        // 11 duplicates 2, whereas 3 has the same duration but a different comment
+        worklog_repo.add_worklog_entries(&[
+            worklog("11", "Stand-up"),
+            worklog("2", "Stand-up"),
+            worklog("3", "Code review"),
+        ])?;
+
+        let groups = worklog_repo.find_duplicates()?;
+        let ids: Vec<Vec<&str>> = groups
+            .iter()
+            .map(|group| group.iter().map(|wl| wl.id.as_str()).collect())
+            .collect();
+        assert_eq!(ids, vec![vec!["2", "11"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn most_recent_issues() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        let issue_repo = db_manager.create_issue_repository();
+        let worklog_repo = db_manager.create_worklog_repository();
+
+        let now = Local::now();
+        // Issue id, key and the number of days ago work was started for each of the worklogs
+        let issues = [
+            ("1", "ABC-1", vec![10, 2]),
+            ("2", "ABC-2", vec![1, 20]),
+            ("3", "ABC-3", vec![5]),
+        ];
+        let mut worklog_id = 0;
+        for (id, key, days_ago) in &issues {
+            issue_repo.add_jira_issues(&[IssueSummary {
+                id: (*id).to_string(),
+                key: IssueKey::from(*key),
+                fields: Fields {
+                    summary: format!("Summary of {key}"),
+                    ..Default::default()
+                },
+            }])?;
+            for days in days_ago {
+                worklog_id += 1;
+                let started = now.checked_sub_days(Days::new(*days)).unwrap();
+                worklog_repo.add_entry(
+                    &LocalWorklog::builder(IssueKey::from(*key), worklog_id.to_string(), 3600)
+                        .author("John Doe")
+                        .created(started)
+                        .updated(started)
+                        .started(started)
+                        .issue_id(id.parse().unwrap())
+                        .build(),
+                )?;
+            }
+        }
+
+        let result = worklog_repo.most_recent_issues(2)?;
+        let keys: Vec<&str> = result
+            .iter()
+            .map(|(issue, _)| issue.issue_key.value())
+            .collect();
+        assert_eq!(keys, vec!["ABC-2", "ABC-1"]);
+        assert_eq!(result[0].0.summary, "Summary of ABC-2");
+        assert_eq!(result[0].1, now.checked_sub_days(Days::new(1)).unwrap());
+        assert_eq!(result[1].1, now.checked_sub_days(Days::new(2)).unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn local_and_synced_entries_are_tagged_with_their_source() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        db_manager
+            .create_issue_repository()
+            .add_jira_issues(&[IssueSummary {
+                id: ISSUE_ID.to_string(),
+                key: IssueKey::from("ABC-1"),
+                fields: Fields {
+                    summary: "Test".to_string(),
+                    ..Default::default()
+                },
+            }])?;
+        let worklog_repo = db_manager.create_worklog_repository();
+        let worklog = |id: &str| {
+            LocalWorklog::builder(IssueKey::from("ABC-1"), id, 3600)
+                .author("John Doe")
+                .issue_id(ISSUE_ID.parse().unwrap())
+                .build()
+        };
+
+        worklog_repo.add_entry(&worklog("1"))?;
+        assert_eq!(
+            worklog_repo.find_sync_state("1")?,
+            (WorklogSource::Local, None)
+        );
+
+        let synced_at = Local::now();
+        worklog_repo.upsert_synced_entries(&[worklog("1"), worklog("2")], synced_at)?;
+        // Pulling an entry added locally retains its source
+        assert_eq!(
+            worklog_repo.find_sync_state("1")?,
+            (WorklogSource::Local, Some(synced_at))
+        );
+        assert_eq!(
+            worklog_repo.find_sync_state("2")?,
+            (WorklogSource::Jira, Some(synced_at))
+        );
+        assert_eq!(worklog_repo.get_count()?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn tags_are_found_and_survive_upsert() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        db_manager
+            .create_issue_repository()
+            .add_jira_issues(&[IssueSummary {
+                id: ISSUE_ID.to_string(),
+                key: IssueKey::from("ABC-1"),
+                fields: Fields::default(),
+            }])?;
+        let worklog_repo = db_manager.create_worklog_repository();
+        let worklog = |id: &str| {
+            LocalWorklog::builder(IssueKey::from("ABC-1"), id, 3600)
+                .author("John Doe")
+                .issue_id(ISSUE_ID.parse().unwrap())
+                .build()
+        };
+        worklog_repo.add_worklog_entries(&[worklog("1"), worklog("2")])?;
+
+        let billable = vec!["billable".to_string()];
+        worklog_repo.add_tags("1", &["internal".to_string(), "billable".to_string()])?;
+        worklog_repo.add_tags("2", &billable)?;
+        // Tagging again is harmless
+        worklog_repo.add_tags("2", &billable)?;
+        assert_eq!(worklog_repo.find_by_tag("billable")?, vec!["1", "2"]);
+        assert_eq!(worklog_repo.find_by_tag("internal")?, vec!["1"]);
+        assert!(worklog_repo.find_by_tag("unknown")?.is_empty());
+        assert!(worklog_repo.add_tags("3", &billable).is_err());
+
+        // Pulling the entries from Jira again must retain the tags
+        worklog_repo.upsert_synced_entries(&[worklog("1"), worklog("2")], Local::now())?;
+        assert_eq!(worklog_repo.find_tags("1")?, vec!["billable", "internal"]);
+
+        assert_eq!(worklog_repo.remove_tags("2", &billable)?, 1);
+        assert_eq!(worklog_repo.find_by_tag("billable")?, vec!["1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn existing_worklogs_are_migrated_to_jira_source() -> Result<(), WorklogError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute(
+            "CREATE TABLE worklog (id integer primary key not null, issue_key varchar(32))",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO worklog (id, issue_key) VALUES (1, 'ABC-1')",
+            [],
+        )?;
+        let connection = Arc::new(Mutex::new(conn));
+
+        create_worklog_table(&connection)?;
+        // Running the migration again must be harmless
+        create_worklog_table(&connection)?;
+
+        let worklog_repo = SqliteWorklogRepository::new(connection);
+        assert_eq!(
+            worklog_repo.find_sync_state("1")?,
+            (WorklogSource::Jira, None)
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,68 @@
+use crate::error::WorklogError;
+use crate::repository::database_manager::{DatabaseConfig, DatabaseManager};
+use crate::repository::issue_repository::IssueRepository;
+use crate::repository::sqlite::tests::test_database_manager;
+use crate::repository::worklog_repository::WorkLogRepository;
+use crate::types::LocalWorklog;
+use chrono::{DateTime, Days, Local};
+use jira::models::core::{Fields, IssueKey};
+use jira::models::issue::IssueSummary;
+
+fn worklog(id: &str, started: DateTime<Local>) -> LocalWorklog {
+    LocalWorklog::builder(IssueKey::from("ABC-1"), id, 3600)
+        .author("John Doe")
+        .created(started)
+        .updated(started)
+        .started(started)
+        .issue_id(1)
+        .build()
+}
+
+fn worklog_ids(db_manager: &DatabaseManager, since: DateTime<Local>) -> Vec<String> {
+    let mut ids: Vec<String> = db_manager
+        .create_worklog_repository()
+        .find_worklogs_after(since, &[], &[])
+        .unwrap()
+        .into_iter()
+        .map(|wl| wl.id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn test_archive_before_moves_old_worklogs() -> Result<(), WorklogError> {
+    let path = std::env::temp_dir().join(format!("archive_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let db_manager = test_database_manager()?;
+    db_manager
+        .create_issue_repository()
+        .add_jira_issues(&[IssueSummary {
+            id: "1".to_string(),
+            key: IssueKey::from("ABC-1"),
+            fields: Fields::default(),
+        }])?;
+    let now = Local::now();
+    let worklog_repo = db_manager.create_worklog_repository();
+    for (id, days_ago) in [("1", 400), ("2", 380), ("3", 10)] {
+        worklog_repo.add_entry(&worklog(id, now - Days::new(days_ago)))?;
+    }
+
+    let cutoff = now - Days::new(365);
+    assert_eq!(db_manager.archive_before(cutoff, &path)?, 2);
+
+    let beginning = now - Days::new(1000);
+    assert_eq!(worklog_ids(&db_manager, beginning), vec!["3"]);
+    let archive = DatabaseManager::new(&DatabaseConfig::SqliteOnDisk { path: path.clone() })?;
+    assert_eq!(worklog_ids(&archive, beginning), vec!["1", "2"]);
+    assert_eq!(
+        archive.create_issue_repository().find_unique_keys()?,
+        vec![IssueKey::from("ABC-1")]
+    );
+
+    // Nothing is left to archive
+    assert_eq!(db_manager.archive_before(cutoff, &path)?, 0);
+    drop(archive);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
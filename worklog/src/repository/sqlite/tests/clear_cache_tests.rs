@@ -0,0 +1,140 @@
+use crate::error::WorklogError;
+use crate::repository::component_repository::ComponentRepository;
+use crate::repository::database_manager::DbConnection;
+use crate::repository::issue_repository::IssueRepository;
+use crate::repository::sqlite::tests::test_database_manager;
+use crate::repository::worklog_repository::WorkLogRepository;
+use crate::types::LocalWorklog;
+use jira::models::core::{Fields, IssueKey};
+use jira::models::issue::IssueSummary;
+use jira::models::project::Component;
+use jira::models::user::User;
+
+fn count(conn: &DbConnection, table: &str) -> Result<i64, WorklogError> {
+    let DbConnection::Sqlite(conn) = conn;
+    let conn = conn.lock().map_err(|_| WorklogError::LockPoisoned)?;
+    Ok(
+        conn.query_row(&format!("select count(*) from {table}"), [], |row| {
+            row.get(0)
+        })?,
+    )
+}
+
+fn local_worklog(id: &str, issue_id: i32, issue_key: &str) -> LocalWorklog {
+    LocalWorklog::builder(IssueKey::from(issue_key), id, 3600)
+        .author("Ola Dunk")
+        .issue_id(issue_id)
+        .build()
+}
+
+fn issue_summary(id: &str, key: &str) -> IssueSummary {
+    IssueSummary {
+        id: id.to_string(),
+        key: IssueKey::from(key),
+        fields: Fields {
+            summary: format!("Summary of {key}"),
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn test_clear_caches_retains_worklogs() -> Result<(), WorklogError> {
+    let db_manager = test_database_manager()?;
+    let issue_repo = db_manager.create_issue_repository();
+    let component_repo = db_manager.create_component_repository();
+    let user_repo = db_manager.create_user_repository();
+    let worklog_repo = db_manager.create_worklog_repository();
+
+    issue_repo.add_jira_issues(&[issue_summary("1", "ABC-1"), issue_summary("2", "ABC-2")])?;
+    let components = [Component {
+        id: "10".to_string(),
+        name: "Backend".to_string(),
+    }];
+    component_repo.create_component(&IssueKey::from("ABC-1"), &components)?;
+    component_repo.create_component(&IssueKey::from("ABC-2"), &components)?;
+    user_repo.insert_or_update_current_user(&User {
+        account_id: "abc123".to_string(),
+        email_address: "ola@dunk.com".to_string(),
+        display_name: "Ola Dunk".to_string(),
+        time_zone: "Europe/Oslo".to_string(),
+        ..Default::default()
+    })?;
+    worklog_repo.add_entry(&local_worklog("100", 1, "ABC-1"))?;
+
+    let conn = db_manager.get_connection();
+    assert_eq!(count(conn, "issue")?, 2);
+    assert_eq!(count(conn, "component")?, 1);
+    assert_eq!(count(conn, "issue_component")?, 2);
+    assert_eq!(count(conn, "user")?, 1);
+
+    assert_eq!(component_repo.clear_components()?, 1);
+    assert_eq!(count(conn, "component")?, 0);
+    assert_eq!(count(conn, "issue_component")?, 0);
+
+    // ABC-1 is referenced by a work log and must be retained
+    assert_eq!(issue_repo.clear_issues()?, 1);
+    assert_eq!(count(conn, "issue")?, 1);
+
+    assert_eq!(user_repo.clear_users()?, 1);
+    assert_eq!(count(conn, "user")?, 0);
+
+    assert_eq!(worklog_repo.get_count()?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_deleting_last_worklog_prunes_the_issue() -> Result<(), WorklogError> {
+    let db_manager = test_database_manager()?;
+    let issue_repo = db_manager.create_issue_repository();
+    let component_repo = db_manager.create_component_repository();
+    let worklog_repo = db_manager.create_worklog_repository();
+
+    issue_repo.add_jira_issues(&[issue_summary("1", "ABC-1")])?;
+    component_repo.create_component(
+        &IssueKey::from("ABC-1"),
+        &[Component {
+            id: "10".to_string(),
+            name: "Backend".to_string(),
+        }],
+    )?;
+    worklog_repo.add_worklog_entries(&[
+        local_worklog("100", 1, "ABC-1"),
+        local_worklog("101", 1, "ABC-1"),
+    ])?;
+    let conn = db_manager.get_connection();
+
+    // Another work log entry still refers to the issue
+    assert_eq!(worklog_repo.remove_entry_and_orphaned_issue("100")?, None);
+    assert_eq!(count(conn, "issue")?, 1);
+
+    assert_eq!(
+        worklog_repo.remove_entry_and_orphaned_issue("101")?,
+        Some(IssueKey::from("ABC-1"))
+    );
+    assert_eq!(worklog_repo.get_count()?, 0);
+    assert_eq!(count(conn, "issue")?, 0);
+    assert_eq!(count(conn, "issue_component")?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_deleting_last_worklog_retains_pinned_issue() -> Result<(), WorklogError> {
+    let db_manager = test_database_manager()?;
+    let issue_repo = db_manager.create_issue_repository();
+    let worklog_repo = db_manager.create_worklog_repository();
+
+    issue_repo.add_jira_issues(&[issue_summary("1", "ABC-1")])?;
+    issue_repo.set_pinned(&IssueKey::from("ABC-1"), true)?;
+    worklog_repo.add_entry(&local_worklog("100", 1, "ABC-1"))?;
+
+    assert_eq!(worklog_repo.remove_entry_and_orphaned_issue("100")?, None);
+    assert_eq!(worklog_repo.get_count()?, 0);
+    assert_eq!(issue_repo.all_issue_keys()?, vec![IssueKey::from("ABC-1")]);
+
+    assert!(matches!(
+        issue_repo.set_pinned(&IssueKey::from("XYZ-1"), true),
+        Err(WorklogError::IssueNotFoundInLocalDBMS(_))
+    ));
+    Ok(())
+}
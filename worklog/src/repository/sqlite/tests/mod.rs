@@ -1,4 +1,8 @@
+mod archive_tests;
+mod clear_cache_tests;
 mod schema_tests;
+mod snapshot_read_tests;
+mod transaction_tests;
 
 use super::*;
 use crate::repository::database_manager::{DatabaseConfig, DatabaseManager};
@@ -2,6 +2,7 @@ use crate::error::WorklogError;
 use crate::repository::database_manager::DbConnection;
 use crate::repository::sqlite::tests::test_database_manager;
 use crate::repository::sqlite::SharedSqliteConnection;
+use crate::types::SchemaIssue;
 
 #[test]
 fn test_foreign_keys_enabled() {
@@ -38,3 +39,33 @@ fn is_foreign_keys_enabled(conn: &SharedSqliteConnection) -> Result<bool, Worklo
         Ok(false)
     }
 }
+
+#[test]
+fn test_verify_and_repair_schema() -> Result<(), WorklogError> {
+    let db_manager = test_database_manager()?;
+    assert_eq!(db_manager.verify_schema()?, vec![]);
+
+    {
+        let DbConnection::Sqlite(conn) = db_manager.get_connection();
+        let conn = conn.lock().map_err(|_| WorklogError::LockPoisoned)?;
+        conn.execute("ALTER TABLE worklog DROP COLUMN comment", [])?;
+        conn.execute("ALTER TABLE timer ADD COLUMN rubbish varchar(8)", [])?;
+    }
+    let missing_comment = SchemaIssue::MissingColumn {
+        table: "worklog".to_string(),
+        column: "comment".to_string(),
+    };
+    let extra_column = SchemaIssue::ExtraColumn {
+        table: "timer".to_string(),
+        column: "rubbish".to_string(),
+    };
+    assert_eq!(
+        db_manager.verify_schema()?,
+        vec![missing_comment.clone(), extra_column.clone()]
+    );
+
+    assert_eq!(db_manager.repair_schema()?, vec![missing_comment]);
+    // Unexpected columns are left as is
+    assert_eq!(db_manager.verify_schema()?, vec![extra_column]);
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::error::WorklogError;
+use crate::repository::database_manager::{DatabaseConfig, DatabaseManager, DbConnection};
+use crate::repository::issue_repository::IssueRepository;
+use crate::repository::worklog_repository::WorkLogRepository;
+use crate::types::LocalWorklog;
+use chrono::{Days, Local};
+use jira::models::core::{Fields, IssueKey};
+use jira::models::issue::IssueSummary;
+use std::thread;
+
+fn worklog(id: &str) -> LocalWorklog {
+    let now = Local::now();
+    LocalWorklog::builder(IssueKey::from("ABC-1"), id, 3600)
+        .author("John Doe")
+        .created(now)
+        .updated(now)
+        .started(now)
+        .issue_id(1)
+        .build()
+}
+
+#[test]
+fn test_concurrent_reads_during_write() -> Result<(), WorklogError> {
+    let path = std::env::temp_dir().join(format!("snapshot_read_{}.db", std::process::id()));
+    let db_manager = DatabaseManager::new(&DatabaseConfig::SqliteOnDisk { path: path.clone() })?;
+    db_manager
+        .create_issue_repository()
+        .add_jira_issues(&[IssueSummary {
+            id: "1".to_string(),
+            key: IssueKey::from("ABC-1"),
+            fields: Fields::default(),
+        }])?;
+    let worklog_repo = db_manager.create_worklog_repository();
+    worklog_repo.add_entry(&worklog("1"))?;
+
+    // Leaves a write transaction open on the connection used for writing
+    let DbConnection::Sqlite(write_connection) = db_manager.get_connection();
+    {
+        let conn = write_connection.lock().unwrap();
+        let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?;
+        assert_eq!(journal_mode, "wal");
+        conn.execute_batch("BEGIN IMMEDIATE")?;
+    }
+    worklog_repo.add_entry(&worklog("2"))?;
+
+    let since = Local::now().checked_sub_days(Days::new(1)).unwrap();
+    let readers = (0..4)
+        .map(|_| {
+            let read_conn = db_manager.snapshot_read()?;
+            Ok(thread::spawn(move || {
+                read_conn
+                    .find_worklogs_after(since, &[], &[])
+                    .map(|w| w.len())
+            }))
+        })
+        .collect::<Result<Vec<_>, WorklogError>>()?;
+    for reader in readers {
+        // The uncommitted work log is not visible
+        assert_eq!(reader.join().unwrap()?, 1);
+    }
+
+    write_connection.lock().unwrap().execute_batch("COMMIT")?;
+    let read_conn = db_manager.snapshot_read()?;
+    assert_eq!(read_conn.find_worklogs_after(since, &[], &[])?.len(), 2);
+    assert_eq!(read_conn.find_unique_keys()?, vec![IssueKey::from("ABC-1")]);
+
+    drop(read_conn);
+    drop(worklog_repo);
+    drop(db_manager);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::error::WorklogError;
+use crate::repository::database_manager::DbConnection;
+use crate::repository::issue_repository::IssueRepository;
+use crate::repository::sqlite::tests::test_database_manager;
+use crate::repository::sqlite::with_transaction;
+use jira::models::core::{Fields, IssueKey};
+use jira::models::issue::IssueSummary;
+use rusqlite::params;
+
+fn issue_summary(id: &str, key: &str) -> IssueSummary {
+    IssueSummary {
+        id: id.to_string(),
+        key: IssueKey::from(key),
+        fields: Fields {
+            summary: format!("Summary of {key}"),
+            ..Default::default()
+        },
+    }
+}
+
+fn count_issues(conn: &DbConnection) -> Result<i64, WorklogError> {
+    let DbConnection::Sqlite(conn) = conn;
+    let conn = conn.lock().map_err(|_| WorklogError::LockPoisoned)?;
+    Ok(conn.query_row("select count(*) from issue", [], |row| row.get(0))?)
+}
+
+#[test]
+fn test_failed_transaction_is_rolled_back() -> Result<(), WorklogError> {
+    let db_manager = test_database_manager()?;
+    let DbConnection::Sqlite(conn) = db_manager.get_connection();
+
+    let insert = "INSERT INTO issue (id, key, summary) VALUES (?1, ?2, ?3)";
+    let result = with_transaction(conn, |tx| {
+        tx.execute(insert, params![1, "ABC-1", "First"])?;
+        // Violates the primary key, failing the second insert
+        tx.execute(insert, params![1, "ABC-2", "Second"])?;
+        Ok(())
+    });
+    assert!(matches!(result, Err(WorklogError::Sql(_))), "{result:?}");
+    assert_eq!(count_issues(db_manager.get_connection())?, 0);
+
+    let inserted = with_transaction(conn, |tx| {
+        Ok(tx.execute(insert, params![1, "ABC-1", "First"])?)
+    })?;
+    assert_eq!(inserted, 1);
+    assert_eq!(count_issues(db_manager.get_connection())?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_add_jira_issues_adds_none_on_failure() -> Result<(), WorklogError> {
+    let db_manager = test_database_manager()?;
+    let issue_repo = db_manager.create_issue_repository();
+
+    let result = issue_repo.add_jira_issues(&[
+        issue_summary("1", "ABC-1"),
+        issue_summary("not-a-number", "ABC-2"),
+    ]);
+    assert!(matches!(result, Err(WorklogError::InvalidInput(_))));
+    assert_eq!(count_issues(db_manager.get_connection())?, 0);
+    Ok(())
+}
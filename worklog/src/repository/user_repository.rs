@@ -35,4 +35,12 @@ pub trait UserRepository: Send + Sync {
     /// * `Ok(User)` - If the user was found successfully.
     /// * `Err(WorklogError)` - If there was an issue, such as the user not being found.
     fn find_user(&self) -> Result<User, WorklogError>;
+
+    /// Removes all users from the repository.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of users removed.
+    /// * `Err(WorklogError)` - If there was an issue during the operation.
+    fn clear_users(&self) -> Result<usize, WorklogError>;
 }
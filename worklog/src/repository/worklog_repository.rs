@@ -5,7 +5,7 @@
 /// documentation about its purpose, input parameters, potential return values,
 /// and the errors it might produce.
 use crate::error::WorklogError;
-use crate::types::LocalWorklog;
+use crate::types::{JiraIssueInfo, LocalWorklog, UpsertCounts, WorklogSource};
 use chrono::{DateTime, Local};
 use jira::models::core::IssueKey;
 use jira::models::user::User;
@@ -41,6 +41,23 @@ pub trait WorkLogRepository: Send + Sync {
     ///
     fn remove_entry_by_worklog_id(&self, wl_id: &str) -> Result<(), WorklogError>;
 
+    ///
+    /// Removes a worklog entry by its unique identifier and, within the same transaction,
+    /// the issue of the entry if it is left without any work log entries or timers and
+    /// has not been pinned. The components of a removed issue are removed as well.
+    ///
+    /// # Returns
+    /// * `Ok(Some(IssueKey))` - The key of the issue removed along with the entry.
+    /// * `Ok(None)` - If the issue was retained.
+    ///
+    /// # Errors
+    /// * This function returns a `WorklogError` if the operation fails.
+    ///
+    fn remove_entry_and_orphaned_issue(
+        &self,
+        wl_id: &str,
+    ) -> Result<Option<IssueKey>, WorklogError>;
+
     ///
     /// Adds a worklog entry to the repository.
     ///
@@ -69,6 +86,56 @@ pub trait WorkLogRepository: Send + Sync {
     /// * This function returns a `WorklogError` if the operation fails for any entry.
     fn add_worklog_entries(&self, worklogs: &[LocalWorklog]) -> Result<(), WorklogError>;
 
+    ///
+    /// Inserts or updates worklog entries pulled from Jira.
+    ///
+    /// New entries are tagged with `WorklogSource::Jira`, while existing entries keep their source.
+    /// The entries are upserted in a single transaction.
+    ///
+    /// # Arguments
+    /// * `worklogs` - A slice of `LocalWorklog` objects holding the worklog details retrieved from Jira.
+    /// * `synced_at` - The time of the synchronisation, recorded as `last_synced_at`.
+    ///
+    /// # Returns
+    /// The number of entries created and updated, entries unchanged since the previous
+    /// synchronisation are not counted.
+    ///
+    /// # Errors
+    /// * This function returns a `WorklogError` if the operation fails for any entry.
+    fn upsert_synced_entries(
+        &self,
+        worklogs: &[LocalWorklog],
+        synced_at: DateTime<Local>,
+    ) -> Result<UpsertCounts, WorklogError>;
+
+    ///
+    /// Retrieves where a worklog entry originates from and when it was last synchronised with Jira.
+    ///
+    /// # Arguments
+    /// * `worklog_id` - The unique identifier of the worklog entry.
+    ///
+    /// # Returns
+    /// * `Ok((WorklogSource, Option<DateTime<Local>>))` - The source and the time of the last synchronisation, if any.
+    /// * `Err(WorklogError)` - If the entry is not found or the retrieval fails.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the operation fails.
+    fn find_sync_state(
+        &self,
+        worklog_id: &str,
+    ) -> Result<(WorklogSource, Option<DateTime<Local>>), WorklogError>;
+
+    ///
+    /// Finds the worklog entries whose source is not recorded, or is not a known `WorklogSource`.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<String>)` - The identifiers of the worklog entries.
+    /// * `Err(WorklogError)` - If there is an error during the retrieval process.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the database query fails for any reason.
+    fn find_worklogs_without_source(&self) -> Result<Vec<String>, WorklogError>;
+
     ///
     /// Retrieves the total count of worklog entries in the repository.
     ///
@@ -116,8 +183,9 @@ pub trait WorkLogRepository: Send + Sync {
     ///   If empty, no filtering on authors is done.
     ///
     /// # Returns
-    /// A `Result` containing a `Vec` of `LocalWorklog` objects that match the criteria, or a `WorklogError`
-    /// if something goes wrong during query execution.
+    /// A `Result` containing a `Vec` of `LocalWorklog` objects that match the criteria, ordered by
+    /// ascending `started`, or a `WorklogError` if something goes wrong during query execution.
+    /// Entries started at the same instant are ordered by their id.
     ///
     /// # Errors
     /// * Returns a `WorklogError` if the database query fails for any reason.
@@ -145,4 +213,65 @@ pub trait WorkLogRepository: Send + Sync {
         keys_filter: &[IssueKey],
         users_filter: &[User],
     ) -> Result<Vec<LocalWorklog>, WorklogError>;
+
+    ///
+    /// Finds the issues most recently worked on, ordered by the latest `started` of their worklogs.
+    ///
+    /// # Arguments
+    /// * `limit` - The maximum number of issues to return.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<(JiraIssueInfo, DateTime<Local>)>)` - Each issue along with the latest time work was started on it.
+    /// * `Err(WorklogError)` - If there is an error during the retrieval process.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the database query fails for any reason.
+    fn most_recent_issues(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<(JiraIssueInfo, DateTime<Local>)>, WorklogError>;
+
+    ///
+    /// Tags a worklog entry. Tags are kept in the local database only and are never sent to Jira.
+    /// Tags already on the entry are ignored.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the worklog entry does not exist or the database operation fails.
+    fn add_tags(&self, worklog_id: &str, tags: &[String]) -> Result<(), WorklogError>;
+
+    ///
+    /// Removes tags from a worklog entry.
+    ///
+    /// # Returns
+    /// * The number of tags removed.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the database operation fails.
+    fn remove_tags(&self, worklog_id: &str, tags: &[String]) -> Result<usize, WorklogError>;
+
+    ///
+    /// Finds the ids of the worklog entries having the tag.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the database query fails.
+    fn find_by_tag(&self, tag: &str) -> Result<Vec<String>, WorklogError>;
+
+    ///
+    /// Retrieves the tags of a worklog entry, in alphabetical order.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the database query fails.
+    fn find_tags(&self, worklog_id: &str) -> Result<Vec<String>, WorklogError>;
+
+    ///
+    /// Finds the groups of worklog entries which are duplicates of each other, i.e. entries by the
+    /// same author on the same issue with identical `started`, `timeSpentSeconds` and comment.
+    /// Entries differing only in their comment are considered distinct.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Vec<LocalWorklog>>)` - The groups holding more than one entry, each ordered by id.
+    ///
+    /// # Errors
+    /// * Returns a `WorklogError` if the database query fails.
+    fn find_duplicates(&self) -> Result<Vec<Vec<LocalWorklog>>, WorklogError>;
 }
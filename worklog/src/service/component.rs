@@ -41,7 +41,9 @@
 use crate::error::WorklogError;
 use crate::repository::component_repository::ComponentRepository;
 use jira::models::core::IssueKey;
+use jira::models::issue::ComponentId;
 use jira::models::project::Component;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[allow(clippy::module_name_repetitions)]
@@ -82,4 +84,123 @@ impl ComponentService {
     ) -> Result<(), WorklogError> {
         self.repository.create_component(issue_key, components)
     }
+
+    /// Removes all cached components, including their association with issues.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` with the number of components removed.
+    ///
+    /// # Errors
+    ///
+    /// * `WorklogError` - if the repository fails to remove the components.
+    pub fn clear_components(&self) -> Result<usize, WorklogError> {
+        self.repository.clear_components()
+    }
+
+    /// Resolves the names of the components of a new issue in the project into their ids.
+    /// The `requested` names are used if any, otherwise the names configured for the project
+    /// in `default_components`, which may give no components at all.
+    ///
+    /// # Errors
+    ///
+    /// * `WorklogError::InvalidInput` - if a component is not in the local cache, which only
+    ///   holds the components of projects of which issues have been synchronized.
+    /// * `WorklogError` - if the repository fails to look up the components.
+    pub fn components_for_new_issue(
+        &self,
+        project_key: &str,
+        requested: &[String],
+        default_components: &BTreeMap<String, Vec<String>>,
+    ) -> Result<Vec<ComponentId>, WorklogError> {
+        let names = if requested.is_empty() {
+            default_components
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(project_key))
+                .map_or(&[][..], |(_, names)| names.as_slice())
+        } else {
+            requested
+        };
+        names
+            .iter()
+            .map(|name| {
+                self.repository
+                    .find_component_by_name(project_key, name)?
+                    .map(|component| ComponentId { id: component.id })
+                    .ok_or_else(|| {
+                        WorklogError::InvalidInput(format!(
+                            "Unknown component '{name}' of project {project_key}, run 'timesheet sync' to refresh the components"
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::issue_repository::IssueRepository;
+    use crate::repository::sqlite::tests::test_database_manager;
+    use jira::models::core::Fields;
+    use jira::models::issue::IssueSummary;
+
+    fn component(id: &str, name: &str) -> Component {
+        Component {
+            id: id.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn component_service() -> Result<ComponentService, WorklogError> {
+        let db_manager = test_database_manager()?;
+        db_manager
+            .create_issue_repository()
+            .add_jira_issues(&[IssueSummary {
+                id: "1".to_string(),
+                key: IssueKey::from("TIME-1"),
+                fields: Fields {
+                    summary: "Meetings".to_string(),
+                    ..Default::default()
+                },
+            }])?;
+        let repository = db_manager.create_component_repository();
+        repository.create_component(
+            &IssueKey::from("TIME-1"),
+            &[component("10", "Backend"), component("11", "Frontend")],
+        )?;
+        Ok(ComponentService::new(repository))
+    }
+
+    fn ids(components: &[ComponentId]) -> Vec<&str> {
+        components.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_components_applied_to_configured_project() -> Result<(), WorklogError> {
+        let service = component_service()?;
+        let defaults = BTreeMap::from([("TIME".to_string(), vec!["backend".to_string()])]);
+
+        let components = service.components_for_new_issue("TIME", &[], &defaults)?;
+        assert_eq!(ids(&components), ["10"]);
+        assert!(service
+            .components_for_new_issue("OTHER", &[], &defaults)?
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_components_skipped_when_components_given() -> Result<(), WorklogError> {
+        let service = component_service()?;
+        let defaults = BTreeMap::from([("TIME".to_string(), vec!["Backend".to_string()])]);
+
+        let components =
+            service.components_for_new_issue("TIME", &["Frontend".to_string()], &defaults)?;
+        assert_eq!(ids(&components), ["11"]);
+        assert!(matches!(
+            service.components_for_new_issue("TIME", &["Legal".to_string()], &defaults),
+            Err(WorklogError::InvalidInput(_))
+        ));
+        Ok(())
+    }
 }
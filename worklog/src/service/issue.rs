@@ -18,25 +18,30 @@
 //! - `jira`: For handling Jira-related structures such as `IssueKey` and `IssueSummary`.
 //! - `std::sync::Arc`: For handling thread-safe references to the `IssueRepository`.
 //!
+//! Edits of issues are sent to Jira before the local copy is refreshed.
+//!
 //! # Examples
 //!
 //! Please see individual method documentation for usage examples.
 use crate::error::WorklogError;
 use crate::repository::issue_repository::IssueRepository;
-use crate::types::JiraIssueInfo;
+use crate::types::{Favorite, JiraIssueInfo};
 use jira::models::core::IssueKey;
 use jira::models::issue::IssueSummary;
+use jira::{Jira, JiraError};
+use serde_json::{Map, Value};
 use std::sync::Arc;
 
 #[allow(clippy::module_name_repetitions)]
 pub struct IssueService {
     repo: Arc<dyn IssueRepository>,
+    jira_client: Jira,
 }
 
 #[allow(clippy::module_name_repetitions)]
 impl IssueService {
-    pub fn new(repo: Arc<dyn IssueRepository>) -> Self {
-        Self { repo }
+    pub fn new(repo: Arc<dyn IssueRepository>, jira_client: Jira) -> Self {
+        Self { repo, jira_client }
     }
     ///
     /// Adds multiple Jira issues to the local database.
@@ -50,11 +55,8 @@ impl IssueService {
     ///
     /// # Errors
     ///
-    /// Returns a `WorklogError` if any SQL operation fails during the insertion or update.
-    ///
-    /// # Panics
-    ///
-    /// This method panics if any SQL statement execution fails due to unexpected conditions.
+    /// Returns a `WorklogError` if an issue id is not numeric, or any SQL operation fails
+    /// during the insertion or update, in which case none of the issues are added.
     ///
     /// # Examples
     ///
@@ -139,4 +141,498 @@ impl IssueService {
     pub fn find_unique_keys(&self) -> Result<Vec<IssueKey>, WorklogError> {
         self.repo.find_unique_keys()
     }
+
+    /// Retrieves the keys of all the issues in the local database, in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WorklogError` if the database operation fails.
+    pub fn all_issue_keys(&self) -> Result<Vec<IssueKey>, WorklogError> {
+        self.repo.all_issue_keys()
+    }
+
+    /// Removes the cached Jira issues from the local database, without touching
+    /// the work log entries. Issues still referenced by a work log or a timer are retained.
+    ///
+    /// # Returns
+    ///
+    /// The number of issues removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WorklogError` if the database operation fails.
+    pub fn clear_issues(&self) -> Result<usize, WorklogError> {
+        self.repo.clear_issues()
+    }
+
+    /// Pins or unpins an issue. Pinned issues are retained in the local database, even
+    /// when orphaned issues are pruned as their last work log entry is deleted.
+    /// An issue to pin is retrieved from Jira if it is not in the local database.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WorklogError` if the issue could not be found, locally when unpinning and
+    /// in Jira when pinning, or the database operation fails.
+    pub async fn set_pinned(&self, issue_key: &IssueKey, pinned: bool) -> Result<(), WorklogError> {
+        if pinned {
+            self.ensure_issue(issue_key).await?;
+        }
+        self.repo.set_pinned(issue_key, pinned)
+    }
+
+    /// Updates the fields of an issue in Jira and refreshes the local copy of the issue
+    /// with what Jira holds afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `issue_key` - The key of the issue to update.
+    /// * `fields` - The fields to set, like `summary` or `description`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WorklogError` if `fields` is empty, if Jira rejects the update or if
+    /// the local database could not be updated.
+    pub async fn update_issue(
+        &self,
+        issue_key: &IssueKey,
+        fields: Map<String, Value>,
+    ) -> Result<(), WorklogError> {
+        if fields.is_empty() {
+            return Err(WorklogError::InvalidInput(format!(
+                "No fields to update for issue {issue_key}"
+            )));
+        }
+        self.jira_client.update_issue(issue_key, fields).await?;
+
+        let issue_summary = self.jira_client.get_issue_summary(issue_key).await?;
+        self.repo.add_jira_issues(&[issue_summary])
+    }
+
+    /// Returns the issue from the local database, retrieving it from Jira and adding it
+    /// to the local database if missing. Use it before writing a work log entry or timer
+    /// referencing the issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorklogError::IssueNotFound` if the issue does not exist in Jira, or another
+    /// `WorklogError` if Jira or the local database could not be queried.
+    pub async fn ensure_issue(&self, issue_key: &IssueKey) -> Result<JiraIssueInfo, WorklogError> {
+        if let Some(issue) = self
+            .repo
+            .get_issues_filtered_by_keys(std::slice::from_ref(issue_key))?
+            .into_iter()
+            .next()
+        {
+            return Ok(issue);
+        }
+
+        let issue_summary = match self.jira_client.get_issue_summary(issue_key).await {
+            Ok(issue_summary) => issue_summary,
+            Err(JiraError::NotFound(key)) => return Err(WorklogError::IssueNotFound(key)),
+            Err(e) => return Err(e.into()),
+        };
+        self.repo
+            .add_jira_issues(std::slice::from_ref(&issue_summary))?;
+        Ok(JiraIssueInfo {
+            issue_key: issue_summary.key,
+            summary: issue_summary.fields.summary,
+            parent_key: issue_summary.fields.parent.map(|parent| parent.key),
+            status: issue_summary.fields.status.map(|status| status.name),
+            resolution: issue_summary
+                .fields
+                .resolution
+                .map(|resolution| resolution.name),
+            assignee: issue_summary.fields.assignee,
+        })
+    }
+
+    /// Adds the issue to the favorites, retrieving it from Jira if not in the local database.
+    /// The optional alias may be used in place of the issue key, and is stored in lower case.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorklogError::InvalidInput` if the alias is a number, contains whitespace or is
+    /// used by another favorite, and `WorklogError::IssueNotFound` if the issue does not exist.
+    pub async fn add_favorite(
+        &self,
+        issue_key: &IssueKey,
+        alias: Option<&str>,
+    ) -> Result<(), WorklogError> {
+        let alias = alias.map(str::to_lowercase);
+        if let Some(alias) = &alias {
+            if alias.is_empty()
+                || alias.chars().any(char::is_whitespace)
+                || alias.parse::<usize>().is_ok()
+            {
+                return Err(WorklogError::InvalidInput(format!(
+                    "Invalid alias '{alias}', it must be a single word, which is not a number"
+                )));
+            }
+        }
+        self.ensure_issue(issue_key).await?;
+        self.repo.add_favorite(issue_key, alias.as_deref())
+    }
+
+    /// Removes the favorite referred to by its position, alias or issue key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorklogError::InvalidInput` if there is no such favorite.
+    pub fn remove_favorite(&self, reference: &str) -> Result<IssueKey, WorklogError> {
+        let issue_key = self.resolve_favorite(reference)?;
+        self.repo.remove_favorite(&issue_key)?;
+        Ok(issue_key)
+    }
+
+    /// Retrieves the favorites, in the order they were added.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WorklogError` if the local database could not be queried.
+    pub fn favorites(&self) -> Result<Vec<Favorite>, WorklogError> {
+        self.repo.find_favorites()
+    }
+
+    /// Resolves a reference to a favorite, being either its position in the list of favorites,
+    /// starting at 1, its alias or its issue key, into the key of the issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorklogError::InvalidInput` if there is no such favorite.
+    pub fn resolve_favorite(&self, reference: &str) -> Result<IssueKey, WorklogError> {
+        let favorites = self.favorites()?;
+        let favorite = match reference.parse::<usize>() {
+            Ok(position) => position
+                .checked_sub(1)
+                .and_then(|index| favorites.get(index)),
+            Err(_) => favorites.iter().find(|favorite| {
+                favorite
+                    .alias
+                    .as_deref()
+                    .is_some_and(|alias| alias.eq_ignore_ascii_case(reference))
+                    || favorite.issue_key.value().eq_ignore_ascii_case(reference)
+            }),
+        };
+        favorite
+            .map(|favorite| favorite.issue_key.clone())
+            .ok_or_else(|| {
+                WorklogError::InvalidInput(format!(
+                    "No favorite '{reference}', use 'timesheet fav list' to list them"
+                ))
+            })
+    }
+
+    /// Changes the summary of an issue, both in Jira and locally.
+    ///
+    /// # Errors
+    ///
+    /// See [`IssueService::update_issue`]
+    pub async fn set_summary(
+        &self,
+        issue_key: &IssueKey,
+        summary: &str,
+    ) -> Result<(), WorklogError> {
+        let mut fields = Map::new();
+        fields.insert("summary".to_string(), summary.into());
+        self.update_issue(issue_key, fields).await
+    }
+
+    /// Deletes the issue in Jira, and then its work logs, timers, components and favorite
+    /// from the local database, returning the number of local work logs removed.
+    ///
+    /// The local data is left intact if Jira refuses to delete the issue. An issue which
+    /// no longer exists in Jira is removed from the local database with a warning.
+    ///
+    /// # Errors
+    /// Returns `WorklogError::JiraError` if Jira could not delete the issue, or a database
+    /// error if the local data could not be removed, in which case `timesheet sync` will
+    /// not bring the issue back
+    pub async fn delete_issue(&self, issue_key: &IssueKey) -> Result<usize, WorklogError> {
+        match self.jira_client.delete_issue(issue_key).await {
+            Ok(()) => {}
+            Err(JiraError::NotFound(_)) => {
+                eprintln!("WARNING: {issue_key} does not exist in Jira, removing the local copy");
+            }
+            Err(err) => return Err(err.into()),
+        }
+        self.repo.remove_issue(issue_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::sqlite::tests::test_database_manager;
+    use jira::builder::DEFAULT_API_VERSION;
+    use jira::models::core::Fields;
+    use jira::Credentials;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_set_summary_refreshes_local_issue() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let update = server
+            .mock(
+                "PUT",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1").as_str(),
+            )
+            .match_body(Matcher::Json(serde_json::json!({
+                "fields": {"summary": "Renamed"}
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
+        let _refresh = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1").as_str(),
+            )
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(
+                r#"{"id": "10", "key": "TIME-1", "fields": {"summary": "Renamed", "components": []}}"#,
+            )
+            .create_async()
+            .await;
+
+        let db_manager = test_database_manager()?;
+        let issue_service = IssueService::new(
+            db_manager.create_issue_repository(),
+            Jira::new(server.url(), Credentials::Anonymous)?,
+        );
+        issue_service.add_jira_issues(&[IssueSummary {
+            id: "10".to_string(),
+            key: IssueKey::from("TIME-1"),
+            fields: Fields {
+                summary: "Quick issue".to_string(),
+                ..Default::default()
+            },
+        }])?;
+
+        issue_service
+            .set_summary(&IssueKey::from("TIME-1"), "Renamed")
+            .await?;
+
+        update.assert_async().await;
+        let issues = issue_service.get_issues_filtered_by_keys(&[IssueKey::from("TIME-1")])?;
+        assert_eq!(issues[0].summary, "Renamed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ensure_issue_fetches_once() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let fetch = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-2").as_str(),
+            )
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(
+                r#"{"id": "20", "key": "TIME-2", "fields": {"summary": "Meetings", "components": [],
+                    "assignee": {"displayName": "Ola Dunk", "name": "ola", "key": "JIRAUSER10100"}}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let db_manager = test_database_manager()?;
+        let issue_service = IssueService::new(
+            db_manager.create_issue_repository(),
+            Jira::new(server.url(), Credentials::Anonymous)?,
+        );
+
+        let issue_key = IssueKey::from("TIME-2");
+        let fetched = issue_service.ensure_issue(&issue_key).await?;
+        let cached = issue_service.ensure_issue(&issue_key).await?;
+
+        fetch.assert_async().await;
+        assert_eq!(fetched.summary, "Meetings");
+        assert_eq!(
+            fetched.assignee.as_ref().and_then(|a| a.key.as_deref()),
+            Some("JIRAUSER10100")
+        );
+        assert_eq!(fetched, cached);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_favorite() -> Result<(), WorklogError> {
+        let db_manager = test_database_manager()?;
+        let server = Server::new_async().await;
+        let issue_service = IssueService::new(
+            db_manager.create_issue_repository(),
+            Jira::new(server.url(), Credentials::Anonymous)?,
+        );
+        for (id, key) in [("1", "TIME-1"), ("2", "TIME-2")] {
+            issue_service.add_jira_issues(&[IssueSummary {
+                id: id.to_string(),
+                key: IssueKey::from(key),
+                fields: Fields::default(),
+            }])?;
+        }
+        issue_service
+            .add_favorite(&IssueKey::from("TIME-1"), None)
+            .await?;
+        issue_service
+            .add_favorite(&IssueKey::from("TIME-2"), Some("Meetings"))
+            .await?;
+
+        assert_eq!(
+            issue_service.resolve_favorite("2")?,
+            IssueKey::from("TIME-2")
+        );
+        assert_eq!(
+            issue_service.resolve_favorite("meetings")?,
+            IssueKey::from("TIME-2")
+        );
+        assert_eq!(
+            issue_service.resolve_favorite("time-1")?,
+            IssueKey::from("TIME-1")
+        );
+        assert!(issue_service.resolve_favorite("0").is_err());
+        assert!(issue_service.resolve_favorite("3").is_err());
+        assert!(issue_service
+            .add_favorite(&IssueKey::from("TIME-1"), Some("42"))
+            .await
+            .is_err());
+
+        assert_eq!(
+            issue_service.remove_favorite("1")?,
+            IssueKey::from("TIME-1")
+        );
+        // The remaining favorite moves up
+        assert_eq!(
+            issue_service.resolve_favorite("1")?,
+            IssueKey::from("TIME-2")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ensure_issue_not_in_jira() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let _fetch = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-3").as_str(),
+            )
+            .match_query(Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let db_manager = test_database_manager()?;
+        let issue_service = IssueService::new(
+            db_manager.create_issue_repository(),
+            Jira::new(server.url(), Credentials::Anonymous)?,
+        );
+
+        let result = issue_service.ensure_issue(&IssueKey::from("TIME-3")).await;
+        assert!(matches!(result, Err(WorklogError::IssueNotFound(key)) if key == "TIME-3"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_issue_removes_local_data() -> Result<(), WorklogError> {
+        use crate::repository::worklog_repository::WorkLogRepository;
+        use crate::types::LocalWorklog;
+
+        let mut server = Server::new_async().await;
+        let refused = server
+            .mock(
+                "DELETE",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1").as_str(),
+            )
+            .with_status(403)
+            .with_body(r#"{"errorMessages": ["You may not delete issues"]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let db_manager = test_database_manager()?;
+        let issue_service = IssueService::new(
+            db_manager.create_issue_repository(),
+            Jira::new(server.url(), Credentials::Anonymous)?,
+        );
+        let worklog_repo = db_manager.create_worklog_repository();
+        let issue_key = IssueKey::from("TIME-1");
+        issue_service.add_jira_issues(&[IssueSummary {
+            id: "10".to_string(),
+            key: issue_key.clone(),
+            fields: Fields::default(),
+        }])?;
+        worklog_repo.add_entry(
+            &LocalWorklog::builder(issue_key.clone(), "100", 3600)
+                .author("Ola Dunk")
+                .issue_id(10)
+                .build(),
+        )?;
+        issue_service.add_favorite(&issue_key, None).await?;
+
+        // Jira refuses, leaving the local data intact
+        assert!(matches!(
+            issue_service.delete_issue(&issue_key).await,
+            Err(WorklogError::JiraError(_))
+        ));
+        refused.assert_async().await;
+        assert_eq!(issue_service.all_issue_keys()?, vec![issue_key.clone()]);
+        assert!(worklog_repo.find_worklog_by_id("100").is_ok());
+
+        let deleted = server
+            .mock(
+                "DELETE",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1").as_str(),
+            )
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+        assert_eq!(issue_service.delete_issue(&issue_key).await?, 1);
+        deleted.assert_async().await;
+        assert!(issue_service.all_issue_keys()?.is_empty());
+        assert!(issue_service.favorites()?.is_empty());
+        assert!(worklog_repo.find_worklog_by_id("100").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pinned_issue_survives_deleting_its_last_worklog() -> Result<(), WorklogError> {
+        use crate::repository::worklog_repository::WorkLogRepository;
+        use crate::types::LocalWorklog;
+
+        let db_manager = test_database_manager()?;
+        let issue_service = IssueService::new(
+            db_manager.create_issue_repository(),
+            Jira::new("http://localhost", Credentials::Anonymous)?,
+        );
+        let worklog_repo = db_manager.create_worklog_repository();
+        let issue_key = IssueKey::from("TIME-1");
+        issue_service.add_jira_issues(&[IssueSummary {
+            id: "10".to_string(),
+            key: issue_key.clone(),
+            fields: Fields::default(),
+        }])?;
+        let worklog = |id: &str| {
+            LocalWorklog::builder(issue_key.clone(), id, 3600)
+                .author("Ola Dunk")
+                .issue_id(10)
+                .build()
+        };
+
+        issue_service.set_pinned(&issue_key, true).await?;
+        worklog_repo.add_entry(&worklog("100"))?;
+        assert_eq!(worklog_repo.remove_entry_and_orphaned_issue("100")?, None);
+        assert_eq!(issue_service.all_issue_keys()?, vec![issue_key.clone()]);
+
+        issue_service.set_pinned(&issue_key, false).await?;
+        worklog_repo.add_entry(&worklog("101"))?;
+        assert_eq!(
+            worklog_repo.remove_entry_and_orphaned_issue("101")?,
+            Some(issue_key.clone())
+        );
+        assert!(issue_service.all_issue_keys()?.is_empty());
+        Ok(())
+    }
 }
@@ -46,19 +46,35 @@
 //! # }
 //! ```
 
+use crate::config::CommentConfiguration;
 use crate::error::WorklogError;
 use crate::repository::timer_repository::TimerRepository;
 use crate::service::issue::IssueService;
 use crate::service::worklog::WorkLogService;
-use crate::types::{LocalWorklog, Timer};
+use crate::types::{JiraIssueInfo, LocalWorklog, Timer};
 use chrono::{DateTime, Duration, Local, Utc};
 use jira::models::core::IssueKey;
+use jira::Jira;
 use jira::JiraError::WorklogDurationTooShort;
-use jira::{Jira, JiraError};
 use log::debug;
 use num_traits::ToPrimitive;
 use std::sync::Arc;
 
+/// The maximum difference between the start of a timer and the start of the work log created from it
+const TIMER_WORKLOG_TOLERANCE_SECONDS: i64 = 60;
+
+/// Outcome of cross-checking the synced timers against the local work logs,
+/// see [`TimerService::recompute_from_timers`]
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    /// Timers marked as synced, which have no matching work log in the local database
+    pub timers_without_worklog: Vec<Timer>,
+    /// Work logs retrieved from Jira and added to the local database for those timers
+    pub repaired: Vec<LocalWorklog>,
+    /// Identifiers of the local work logs whose source is not recorded
+    pub worklogs_without_source: Vec<String>,
+}
+
 /// Service for managing timer operations and synchronization with Jira worklogs
 ///
 /// The `TimerService` provides functionality for:
@@ -97,6 +113,7 @@ pub struct TimerService {
     issue_service: Arc<IssueService>,
     worklog_service: Arc<WorkLogService>,
     jira_client: Jira,
+    comment_configuration: CommentConfiguration,
 }
 
 impl TimerService {
@@ -106,27 +123,45 @@ impl TimerService {
         issue_service: Arc<IssueService>,
         worklog_service: Arc<WorkLogService>,
         jira_client: Jira,
+        comment_configuration: CommentConfiguration,
     ) -> Self {
         Self {
             timer_repository,
             issue_service,
             worklog_service,
             jira_client,
+            comment_configuration,
+        }
+    }
+
+    /// Applies the comment policy, telling the user if the comment was truncated
+    fn enforce_comment_policy(
+        &self,
+        comment: Option<String>,
+    ) -> Result<Option<String>, WorklogError> {
+        let (comment, notice) = self.comment_configuration.enforce(comment)?;
+        if let Some(notice) = notice {
+            eprintln!("WARNING: {notice}");
         }
+        Ok(comment)
     }
 
     /// Starts a new timer for the specified issue. Creates an entry in
-    /// the local database. No requests are sent to Jira
+    /// the local database.
     ///
-    /// Validates that the issue exists before starting the timer
+    /// Validates that the issue exists before starting the timer. Jira is only consulted if
+    /// the issue is not in the local database, hence timers may be started offline for known issues.
+    /// The summary of the issue is stored with the timer, see [`Timer::summary_snapshot`].
     ///
     /// # Errors
     /// Return a `WorklogError` if:
-    /// - The issue does not exist in the database
+    /// - The issue is neither in the local database nor in Jira, or Jira could not be reached
+    ///   to look up an issue missing from the local database
     /// - There is already an active timer running
     /// - There's an error accessing the timer repository
     /// - Database operations fail
     /// - an Issue key format is invalid
+    /// - The comment is too long according to the comment policy
     pub async fn start_timer(
         &self,
         issue_key: &str,
@@ -134,27 +169,12 @@ impl TimerService {
         comment: Option<String>,
     ) -> Result<Timer, WorklogError> {
         let issue_key = IssueKey::new(issue_key);
+        let comment = self.enforce_comment_policy(comment)?;
 
         debug!("Starting timer for issue: {issue_key}");
 
-        // Check if the issue exists in Jira, if not, return an error
-        match self.jira_client.get_issue_summary(&issue_key).await {
-            Ok(issue_summary) => {
-                // Issue exists in Jira, check if it exists in the local database
-                debug!("Issue for key {issue_key} found, now checking local database");
-                if self
-                    .issue_service
-                    .get_issues_filtered_by_keys(&[issue_key.clone()])?
-                    .is_empty()
-                {
-                    self.issue_service.add_jira_issues(&[issue_summary])?;
-                }
-            }
-            Err(JiraError::NotFound(k)) => return Err(WorklogError::IssueNotFound(k)),
-            Err(e) => {
-                return Err(WorklogError::JiraError(e.to_string()));
-            }
-        }
+        // The issue must exist, either in the local database or in Jira
+        let issue = self.issue_service.ensure_issue(&issue_key).await?;
 
         // Check if there's already an active timer
         if self.timer_repository.find_active_timer()?.is_some() {
@@ -170,6 +190,7 @@ impl TimerService {
             stopped_at: None,
             synced: false,
             comment,
+            summary_snapshot: Some(issue.summary),
         };
 
         // Start the timer and get its ID
@@ -185,6 +206,39 @@ impl TimerService {
         })
     }
 
+    /// Starts a new timer for the issue most recently worked on, as
+    /// determined by the work logs in the local database.
+    ///
+    /// # Returns
+    /// The started timer along with the issue it was started for
+    ///
+    /// # Errors
+    /// Return a `WorklogError` if:
+    /// - There is already an active timer running
+    /// - No work has been logged, i.e. there is nothing to resume
+    /// - The timer could not be started, see [`TimerService::start_timer`]
+    pub async fn resume_timer(
+        &self,
+        started_at: DateTime<Local>,
+        comment: Option<String>,
+    ) -> Result<(Timer, JiraIssueInfo), WorklogError> {
+        if self.timer_repository.find_active_timer()?.is_some() {
+            return Err(WorklogError::ActiveTimerExists);
+        }
+        let Some((issue, _)) = self
+            .worklog_service
+            .most_recent_issues(1)?
+            .into_iter()
+            .next()
+        else {
+            return Err(WorklogError::NothingToResume);
+        };
+        let timer = self
+            .start_timer(issue.issue_key.value(), started_at, comment)
+            .await?;
+        Ok((timer, issue))
+    }
+
     /// Stops the currently active timer if one exists. The corresponding
     /// entry in the worklog database is also updated. No requests are sent
     /// to Jira. See also [`TimerService::sync_timers_to_jira`]
@@ -200,6 +254,7 @@ impl TimerService {
     /// - No active timer exists
     /// - There's an error accessing the timer repository
     /// - Database operations fail
+    /// - The comment is too long according to the comment policy
     ///
     /// # Panics
     /// This method will panic if the timer duration in seconds cannot be converted to i32
@@ -208,6 +263,7 @@ impl TimerService {
         stop_time: DateTime<Local>,
         comment: Option<String>,
     ) -> Result<Timer, WorklogError> {
+        let comment = self.enforce_comment_policy(comment)?;
         // Retrieves the current timer
         let timer = self
             .get_active_timer()?
@@ -290,10 +346,12 @@ impl TimerService {
                 let work_log = match self
                     .jira_client
                     .insert_worklog(
-                        &timer.issue_key,
+                        &IssueKey::from(timer.issue_key.as_str()),
                         timer.started_at.with_timezone(&Local),
                         duration_seconds.to_i32().unwrap(),
                         comment,
+                        // Syncing a batch of timers would otherwise flood the watchers with notifications
+                        false,
                     )
                     .await
                 {
@@ -342,6 +400,74 @@ impl TimerService {
         Ok(synced_timers)
     }
 
+    /// Cross-checks the timers marked as synced against the local work logs, which may
+    /// have drifted apart after a partial failure. A timer is matched by a work log on the
+    /// same issue, started within a minute of the timer.
+    ///
+    /// # Arguments
+    /// * `repair` - Retrieve the work log of each unmatched timer from Jira and add it to
+    ///   the local database. Timers without a matching work log in Jira are only reported.
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if:
+    /// - Database operations fail
+    /// - The work logs could not be retrieved from Jira when repairing
+    pub async fn recompute_from_timers(
+        &self,
+        repair: bool,
+    ) -> Result<ReconcileReport, WorklogError> {
+        let tolerance = Duration::seconds(TIMER_WORKLOG_TOLERANCE_SECONDS);
+        let mut report = ReconcileReport::default();
+
+        let synced_timers = self
+            .timer_repository
+            .find_after_date(DateTime::<Utc>::UNIX_EPOCH)?
+            .into_iter()
+            .filter(|timer| timer.synced);
+        for timer in synced_timers {
+            let issue_key = IssueKey::from(timer.issue_key.as_str());
+            // Looks back a full day, the matching is done below
+            let has_worklog = self
+                .worklog_service
+                .find_worklogs_after(
+                    timer.started_at - Duration::days(1),
+                    std::slice::from_ref(&issue_key),
+                    &[],
+                )?
+                .iter()
+                .any(|worklog| (worklog.started - timer.started_at).abs() <= tolerance);
+            if has_worklog {
+                continue;
+            }
+            debug!("Synced timer {timer:?} has no matching work log");
+
+            if repair {
+                let jira_worklog = self
+                    .jira_client
+                    .get_work_logs_for_issue(
+                        issue_key.as_str(),
+                        (timer.started_at - tolerance).naive_utc(),
+                        None,
+                    )
+                    .await?
+                    .into_iter()
+                    .find(|worklog| {
+                        (worklog.started.with_timezone(&Local) - timer.started_at).abs()
+                            <= tolerance
+                    });
+                if let Some(worklog) = jira_worklog {
+                    let local_worklog = LocalWorklog::from_worklog(&worklog, &issue_key);
+                    self.worklog_service.add_entry(&local_worklog).await?;
+                    report.repaired.push(local_worklog);
+                }
+            }
+            report.timers_without_worklog.push(timer);
+        }
+
+        report.worklogs_without_source = self.worklog_service.find_worklogs_without_source()?;
+        Ok(report)
+    }
+
     /// Finds all timers that have been completed but not synced with Jira
     fn find_unsynced_completed_timers(&self) -> Result<Vec<Timer>, WorklogError> {
         // Implementation would depend on your repository capabilities
@@ -450,6 +576,15 @@ impl TimerService {
             .ok_or(WorklogError::TimerNotFound(timer_id))
     }
 
+    /// Lists the timers started at or after `since`, the most recent first
+    ///
+    /// # Errors
+    /// Returns a `WorklogError` if the timers could not be retrieved from the database
+    pub fn list_timers(&self, since: DateTime<Local>) -> Result<Vec<Timer>, WorklogError> {
+        self.timer_repository
+            .find_after_date(since.with_timezone(&Utc))
+    }
+
     /// Gets all recent timers for a specific issue
     ///
     /// # Errors
@@ -487,6 +622,7 @@ mod tests {
             stopped_at: None,
             synced: false,
             comment: Some("Test comment".to_string()),
+            summary_snapshot: None,
         };
 
         assert_eq!(timer.id, Some(1));
@@ -509,6 +645,7 @@ mod tests {
             stopped_at: Some(stop_time),
             synced: false,
             comment: None,
+            summary_snapshot: None,
         };
 
         if let Some(duration) = timer.duration() {
@@ -530,6 +667,7 @@ mod tests {
             stopped_at: None,
             synced: false,
             comment: None,
+            summary_snapshot: None,
         };
 
         // Active timer should have no duration until stopped
@@ -559,6 +697,7 @@ mod tests {
                 stopped_at: None,
                 synced: false,
                 comment: None,
+                summary_snapshot: None,
             };
             assert_eq!(timer.issue_key, issue_key);
         }
@@ -574,6 +713,7 @@ mod tests {
             stopped_at: None,
             synced: false,
             comment: None,
+            summary_snapshot: None,
         };
 
         // Initially not synced
@@ -594,6 +734,7 @@ mod tests {
             stopped_at: None,
             synced: false,
             comment: Some("Working on feature".to_string()),
+            summary_snapshot: None,
         };
 
         let timer_without_comment = Timer {
@@ -604,6 +745,7 @@ mod tests {
             stopped_at: None,
             synced: false,
             comment: None,
+            summary_snapshot: None,
         };
 
         assert_eq!(
@@ -627,6 +769,7 @@ mod tests {
             stopped_at: Some(stop_time),
             synced: false,
             comment: None,
+            summary_snapshot: None,
         };
 
         if let Some(duration) = timer.duration() {
@@ -636,4 +779,279 @@ mod tests {
             panic!("Timer should have a duration");
         }
     }
+
+    #[tokio::test]
+    async fn test_start_timer_offline_for_cached_issue() -> Result<(), WorklogError> {
+        use crate::repository::sqlite::tests::test_database_manager;
+        use jira::models::core::Fields;
+        use jira::models::issue::IssueSummary;
+        use jira::Credentials;
+        use mockito::{Matcher, Server};
+
+        // Jira is down, failing every request
+        let mut server = Server::new_async().await;
+        let unavailable = server
+            .mock("GET", Matcher::Any)
+            .match_query(Matcher::Any)
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let jira_client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let db_manager = test_database_manager()?;
+        let issue_service = Arc::new(IssueService::new(
+            db_manager.create_issue_repository(),
+            jira_client.clone(),
+        ));
+        issue_service.add_jira_issues(&[IssueSummary {
+            id: "1".to_string(),
+            key: IssueKey::from("TIME-1"),
+            fields: Fields::default(),
+        }])?;
+        let worklog_service = Arc::new(WorkLogService::new(
+            db_manager.create_worklog_repository(),
+            issue_service.clone(),
+            jira_client.clone(),
+        ));
+        let timer_service = TimerService::new(
+            db_manager.create_timer_repository(),
+            issue_service,
+            worklog_service,
+            jira_client,
+            CommentConfiguration::default(),
+        );
+
+        // An issue unknown to the local database requires Jira
+        assert!(matches!(
+            timer_service
+                .start_timer("TIME-2", Local::now(), None)
+                .await,
+            Err(WorklogError::JiraError(_))
+        ));
+        let timer = timer_service
+            .start_timer("TIME-1", Local::now(), None)
+            .await?;
+        assert_eq!(timer.issue_key, "TIME-1");
+        assert!(timer.is_active());
+        unavailable.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_start_timer_captures_summary_snapshot() -> Result<(), WorklogError> {
+        use crate::repository::sqlite::tests::test_database_manager;
+        use jira::models::core::Fields;
+        use jira::models::issue::IssueSummary;
+        use jira::Credentials;
+
+        let jira_client = Jira::new("http://localhost:1", Credentials::Anonymous)?;
+        let db_manager = test_database_manager()?;
+        let issue_service = Arc::new(IssueService::new(
+            db_manager.create_issue_repository(),
+            jira_client.clone(),
+        ));
+        let issue = |summary: &str| IssueSummary {
+            id: "1".to_string(),
+            key: IssueKey::from("TIME-1"),
+            fields: Fields {
+                summary: summary.to_string(),
+                ..Default::default()
+            },
+        };
+        issue_service.add_jira_issues(&[issue("Before the rename")])?;
+        let worklog_service = Arc::new(WorkLogService::new(
+            db_manager.create_worklog_repository(),
+            issue_service.clone(),
+            jira_client.clone(),
+        ));
+        let timer_service = TimerService::new(
+            db_manager.create_timer_repository(),
+            issue_service.clone(),
+            worklog_service,
+            jira_client,
+            CommentConfiguration::default(),
+        );
+
+        let timer = timer_service
+            .start_timer("TIME-1", Local::now(), None)
+            .await?;
+        assert_eq!(timer.summary_snapshot.as_deref(), Some("Before the rename"));
+
+        // The issue is renamed while the timer is running
+        issue_service.add_jira_issues(&[issue("After the rename")])?;
+        let active = timer_service.get_active_timer()?.expect("an active timer");
+        assert_eq!(
+            active.summary_snapshot.as_deref(),
+            Some("Before the rename")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resume_timer_starts_on_most_recent_issue() -> Result<(), WorklogError> {
+        use crate::repository::issue_repository::IssueRepository;
+        use crate::repository::sqlite::tests::test_database_manager;
+        use crate::repository::worklog_repository::WorkLogRepository;
+        use jira::builder::DEFAULT_API_VERSION;
+        use jira::models::core::Fields;
+        use jira::models::issue::IssueSummary;
+        use jira::Credentials;
+        use mockito::{Matcher, Server};
+
+        let mut server = Server::new_async().await;
+        let _issue = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-2").as_str(),
+            )
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"id": "2", "key": "TIME-2", "fields": {"summary": "Recent", "components": []}}"#)
+            .create_async()
+            .await;
+        let jira_client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let db_manager = test_database_manager()?;
+        let issue_repo = db_manager.create_issue_repository();
+        let worklog_repo = db_manager.create_worklog_repository();
+        let issue_service = Arc::new(IssueService::new(issue_repo.clone(), jira_client.clone()));
+        let worklog_service = Arc::new(WorkLogService::new(
+            worklog_repo.clone(),
+            issue_service.clone(),
+            jira_client.clone(),
+        ));
+        let timer_service = TimerService::new(
+            db_manager.create_timer_repository(),
+            issue_service,
+            worklog_service,
+            jira_client,
+            CommentConfiguration::default(),
+        );
+
+        assert!(matches!(
+            timer_service.resume_timer(Local::now(), None).await,
+            Err(WorklogError::NothingToResume)
+        ));
+
+        let now = Local::now();
+        for (id, key, hours_ago) in [(1, "TIME-1", 5), (2, "TIME-2", 1)] {
+            issue_repo.add_jira_issues(&[IssueSummary {
+                id: id.to_string(),
+                key: IssueKey::from(key),
+                fields: Fields {
+                    summary: "Recent".to_string(),
+                    ..Default::default()
+                },
+            }])?;
+            let started = now - Duration::hours(hours_ago);
+            worklog_repo.add_entry(
+                &LocalWorklog::builder(IssueKey::from(key), id.to_string(), 3600)
+                    .author("John Doe")
+                    .created(started)
+                    .updated(started)
+                    .started(started)
+                    .issue_id(id)
+                    .build(),
+            )?;
+        }
+
+        let (timer, issue) = timer_service.resume_timer(now, None).await?;
+        assert_eq!(issue.issue_key, IssueKey::from("TIME-2"));
+        assert_eq!(timer.issue_key, "TIME-2");
+        assert!(timer.is_active());
+
+        assert!(matches!(
+            timer_service.resume_timer(now, None).await,
+            Err(WorklogError::ActiveTimerExists)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recompute_from_timers_detects_and_repairs_missing_worklog(
+    ) -> Result<(), WorklogError> {
+        use crate::repository::issue_repository::IssueRepository;
+        use crate::repository::sqlite::tests::test_database_manager;
+        use crate::repository::timer_repository::TimerRepository;
+        use chrono::TimeZone;
+        use jira::builder::DEFAULT_API_VERSION;
+        use jira::models::core::Fields;
+        use jira::models::issue::IssueSummary;
+        use jira::Credentials;
+        use mockito::{Matcher, Server};
+
+        let started_at = Local.with_ymd_and_hms(2024, 2, 5, 8, 0, 0).unwrap();
+        let mut server = Server::new_async().await;
+        let _worklogs = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog").as_str(),
+            )
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "startAt": 0, "maxResults": 5000, "total": 1,
+                    "worklogs": [{
+                        "id": "42", "issueId": "1", "timeSpent": "1h", "timeSpentSeconds": 3600,
+                        "author": {"accountId": "abc", "displayName": "Ola"},
+                        "started": started_at.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string()
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let jira_client = Jira::new(server.url(), Credentials::Anonymous)?;
+
+        let db_manager = test_database_manager()?;
+        let issue_repo = db_manager.create_issue_repository();
+        issue_repo.add_jira_issues(&[IssueSummary {
+            id: "1".to_string(),
+            key: IssueKey::from("TIME-1"),
+            fields: Fields {
+                summary: "Synced".to_string(),
+                ..Default::default()
+            },
+        }])?;
+        let issue_service = Arc::new(IssueService::new(issue_repo, jira_client.clone()));
+        let worklog_service = Arc::new(WorkLogService::new(
+            db_manager.create_worklog_repository(),
+            issue_service.clone(),
+            jira_client.clone(),
+        ));
+        let timer_repository = db_manager.create_timer_repository();
+        timer_repository.start_timer(&Timer {
+            id: None,
+            issue_key: "TIME-1".to_string(),
+            created_at: started_at,
+            started_at,
+            stopped_at: Some(started_at + Duration::hours(1)),
+            synced: true,
+            comment: None,
+            summary_snapshot: None,
+        })?;
+        let timer_service = TimerService::new(
+            timer_repository,
+            issue_service,
+            worklog_service,
+            jira_client,
+            CommentConfiguration::default(),
+        );
+
+        let report = timer_service.recompute_from_timers(false).await?;
+        assert_eq!(report.timers_without_worklog.len(), 1);
+        assert!(report.repaired.is_empty());
+        assert!(report.worklogs_without_source.is_empty());
+
+        let report = timer_service.recompute_from_timers(true).await?;
+        assert_eq!(report.timers_without_worklog.len(), 1);
+        assert_eq!(report.repaired.len(), 1);
+        assert_eq!(report.repaired[0].id, "42");
+
+        let report = timer_service.recompute_from_timers(false).await?;
+        assert!(report.timers_without_worklog.is_empty());
+        Ok(())
+    }
 }
@@ -57,4 +57,20 @@ impl UserService {
     pub fn find_current_user(&self) -> Result<User, WorklogError> {
         self.repo.find_user()
     }
+
+    /// Removes all cached users from the repository.
+    ///
+    /// The current user will be re-populated upon the next synchronisation with Jira.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of users removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WorklogError` if the operation fails due to an issue with
+    /// the repository or data source.
+    pub fn clear_users(&self) -> Result<usize, WorklogError> {
+        self.repo.clear_users()
+    }
 }
@@ -3,16 +3,19 @@
 //! updating, and retrieving worklogs. The service interacts with a repository that implements
 //! the `WorkLogRepository` trait to perform these operations.
 
+use crate::date;
 use crate::error::WorklogError;
 use crate::repository::worklog_repository::WorkLogRepository;
 use crate::service::issue::IssueService;
-use crate::types::LocalWorklog;
-use chrono::{DateTime, Local};
+use crate::types::{
+    GroupBy, JiraIssueInfo, LocalWorklog, PeriodSummary, UpsertCounts, WorklogSource,
+};
+use chrono::{DateTime, Days, Duration, Local, NaiveDate};
 use jira::models::core::IssueKey;
 use jira::models::user::User;
-use jira::models::worklog::Worklog;
+use jira::models::worklog::{DeleteReport, Worklog};
 use jira::Jira;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
 pub struct WorkLogService {
@@ -82,6 +85,50 @@ impl WorkLogService {
         self.repo.remove_entry_by_worklog_id(wl_id)
     }
 
+    /// Removes a work log entry from the local repository, together with its issue if no
+    /// other work log entries or timers refer to it and it has not been pinned.
+    ///
+    /// # Returns
+    ///
+    /// The key of the issue, if it was removed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the repository operation fails.
+    pub fn remove_entry_and_orphaned_issue(
+        &self,
+        wl_id: &str,
+    ) -> Result<Option<IssueKey>, WorklogError> {
+        self.repo.remove_entry_and_orphaned_issue(wl_id)
+    }
+
+    /// Deletes a batch of work logs from Jira and removes the successfully deleted ones,
+    /// including those already gone from Jira, from the local repository.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The issue key and the work log id of each work log to delete.
+    ///
+    /// # Returns
+    ///
+    /// A `DeleteReport` holding the outcome for each of the work logs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if:
+    /// - The Jira request could not be issued.
+    /// - The repository operation fails due to a database issue or unexpected error.
+    pub async fn bulk_delete_worklogs(
+        &self,
+        items: &[(IssueKey, String)],
+    ) -> Result<DeleteReport, WorklogError> {
+        let report = self.jira_client.bulk_delete_worklogs(items).await?;
+        for (_, worklog_id) in report.succeeded() {
+            self.repo.remove_entry_by_worklog_id(worklog_id)?;
+        }
+        Ok(report)
+    }
+
     /// Adds a new worklog entry to the repository.
     ///
     /// # Arguments
@@ -97,7 +144,8 @@ impl WorkLogService {
     /// This function will return a `WorklogError` if:
     /// - The repository operation fails due to a database issue or unexpected error.
     pub async fn add_entry(&self, local_worklog: &LocalWorklog) -> Result<(), WorklogError> {
-        self.add_worklog_entries(&[local_worklog.clone()]).await
+        self.add_worklog_entries(std::slice::from_ref(local_worklog))
+            .await
     }
 
     /// Adds multiple worklog entries to the repository.
@@ -113,38 +161,81 @@ impl WorkLogService {
         &self,
         worklogs: &[LocalWorklog],
     ) -> Result<(), WorklogError> {
-        // Check the DBMS to ensure all worklogs are referencing a valid issue
-        let existing_issues = self.issue_service.get_issues_filtered_by_keys(
-            worklogs
-                .iter()
-                .map(|wl| wl.issue_key.clone())
-                .collect::<Vec<IssueKey>>()
-                .as_slice(),
-        )?;
-        // Create a set of existing issue keys for efficient lookup
-        let existing_keys: HashSet<IssueKey> = existing_issues
-            .iter()
-            .map(|issue| issue.issue_key.clone())
-            .collect();
-        // Find the issue keys, which are not in the local database
-        let new_keys: Vec<IssueKey> = worklogs
-            .iter()
-            .map(|worklog| worklog.issue_key.clone()) // Extract an issue key from each worklog
-            .filter(|key| !existing_keys.contains(key)) // Keep only keys not in existing_keys
-            .collect();
-        if !new_keys.is_empty() {
-            // Fetch data from jira for the missing issues
-            let issue_summaries_to_sync = self
-                .jira_client
-                .get_issue_summaries(&[], new_keys.as_slice(), true)
-                .await?;
-
-            self.issue_service
-                .add_jira_issues(issue_summaries_to_sync.as_slice())?;
-        }
+        self.ensure_issues_exist(worklogs).await?;
+        self.repo.add_worklog_entries(worklogs)
+    }
+
+    /// Inserts or updates work log entries pulled from Jira, recording the time of the
+    /// synchronisation. Entries not already in the repository are tagged as originating from Jira.
+    ///
+    /// # Arguments
+    ///
+    /// * `worklogs` - The work log entries retrieved from Jira.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries created and updated.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if:
+    /// - The missing issues could not be retrieved from Jira.
+    /// - The repository operation fails due to a database issue or unexpected error.
+    pub async fn add_synced_entries(
+        &self,
+        worklogs: &[LocalWorklog],
+    ) -> Result<UpsertCounts, WorklogError> {
+        self.ensure_issues_exist(worklogs).await?;
+        self.repo.upsert_synced_entries(worklogs, Local::now())
+    }
+
+    /// Retrieves where a work log entry originates from and when it was last synchronised with Jira.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the entry does not exist or the
+    /// repository operation fails.
+    pub fn find_sync_state(
+        &self,
+        worklog_id: &str,
+    ) -> Result<(WorklogSource, Option<DateTime<Local>>), WorklogError> {
+        self.repo.find_sync_state(worklog_id)
+    }
+
+    /// Finds the work log entries whose source is not recorded.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the repository operation fails.
+    pub fn find_worklogs_without_source(&self) -> Result<Vec<String>, WorklogError> {
+        self.repo.find_worklogs_without_source()
+    }
+
+    /// Writes work log entries whose issues are already in the local repository, without
+    /// consulting Jira. Used where Jira must not be called between the entries, like when
+    /// persisting each entry of a batch as soon as it has been created.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the repository operation fails, for
+    /// instance if an issue is missing from the local repository.
+    pub(crate) fn add_entries_of_cached_issues(
+        &self,
+        worklogs: &[LocalWorklog],
+    ) -> Result<(), WorklogError> {
         self.repo.add_worklog_entries(worklogs)
     }
 
+    /// Ensures the issues referenced by the work logs exist in the local repository,
+    /// retrieving the missing ones from Jira.
+    async fn ensure_issues_exist(&self, worklogs: &[LocalWorklog]) -> Result<(), WorklogError> {
+        let issue_keys: BTreeSet<&IssueKey> = worklogs.iter().map(|wl| &wl.issue_key).collect();
+        for issue_key in issue_keys {
+            self.issue_service.ensure_issue(issue_key).await?;
+        }
+        Ok(())
+    }
+
     /// Returns the total count of worklog entries in the repository.
     ///
     /// # Returns
@@ -192,7 +283,8 @@ impl WorkLogService {
     /// # Returns
     ///
     /// A `Result`:
-    /// - `Ok(Vec<LocalWorklog>)` - A vector of matching `LocalWorklog` entries if found successfully.
+    /// - `Ok(Vec<LocalWorklog>)` - A vector of matching `LocalWorklog` entries if found successfully,
+    ///   ordered by ascending `started`.
     /// - `Err(WorklogError)` - An error if the operation fails or no matching worklogs are found.
     ///
     /// # Errors
@@ -209,14 +301,187 @@ impl WorkLogService {
         self.repo
             .find_worklogs_after(start_datetime, keys_filter, users_filter)
     }
+
+    /// Finds the working days between `start` and `end`, both inclusive, on which less than
+    /// `min_seconds_per_day` has been logged.
+    ///
+    /// Working days are determined by the number of working days per week configured in Jira,
+    /// excluding the `holidays`.
+    /// Only the work logs of the `current_user` are counted, as the local repository holds
+    /// the work logs of other users too after synchronising with `--all-users`.
+    ///
+    /// # Returns
+    ///
+    /// The dates of the working days lacking work logs, in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if:
+    /// - The time tracking configuration could not be retrieved from Jira.
+    /// - The repository operation fails due to a database issue or unexpected error.
+    pub async fn find_gaps(
+        &self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        min_seconds_per_day: i64,
+        holidays: &[NaiveDate],
+        current_user: &User,
+    ) -> Result<Vec<NaiveDate>, WorklogError> {
+        let time_tracking_options = self.jira_client.get_time_tracking_options().await?;
+
+        // The repository only finds work logs started strictly after the supplied point in time
+        let day_before_start = start - Days::new(1);
+        let mut seconds_per_day: HashMap<NaiveDate, i64> = HashMap::new();
+        for worklog in self.repo.find_worklogs_after(
+            day_before_start,
+            &[],
+            std::slice::from_ref(current_user),
+        )? {
+            if worklog.started <= end {
+                *seconds_per_day
+                    .entry(worklog.started.date_naive())
+                    .or_default() += i64::from(worklog.timeSpentSeconds);
+            }
+        }
+
+        Ok(start
+            .date_naive()
+            .iter_days()
+            .take_while(|day| *day <= end.date_naive())
+            .filter(|day| date::is_working_day(*day, time_tracking_options.workingDaysPerWeek))
+            .filter(|day| !holidays.contains(day))
+            .filter(|day| seconds_per_day.get(day).copied().unwrap_or(0) < min_seconds_per_day)
+            .collect())
+    }
+
+    /// Summarises the time logged from `start` up to, but not including, `end`, grouped as
+    /// given by `group_by`. All work logs in the local repository are counted, regardless of
+    /// the author.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the repository operation fails.
+    pub fn summary_for_period(
+        &self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        group_by: GroupBy,
+    ) -> Result<PeriodSummary, WorklogError> {
+        // The repository only finds work logs started strictly after the supplied point in time
+        let worklogs: Vec<LocalWorklog> = self
+            .repo
+            .find_worklogs_after(start - Duration::seconds(1), &[], &[])?
+            .into_iter()
+            .filter(|worklog| worklog.started >= start && worklog.started < end)
+            .collect();
+        Ok(PeriodSummary::from_worklogs(&worklogs, group_by))
+    }
+
+    /// Finds the issues most recently worked on, ordered by the latest time work was started.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of issues to return.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if:
+    /// - The repository operation fails due to a database issue or unexpected error.
+    pub fn most_recent_issues(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<(JiraIssueInfo, DateTime<Local>)>, WorklogError> {
+        self.repo.most_recent_issues(limit)
+    }
+
+    /// Tags a work log entry with local tags like `billable` or `#internal`, which are never
+    /// sent to Jira. A leading `#` is dropped and tags are stored in lower case.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if:
+    /// - A tag is empty or holds white space.
+    /// - The entry does not exist or the repository operation fails.
+    pub fn add_tags(&self, worklog_id: &str, tags: &[String]) -> Result<(), WorklogError> {
+        let tags = normalise_tags(tags)?;
+        self.repo.add_tags(worklog_id, &tags)
+    }
+
+    /// Removes local tags from a work log entry, returning the number of tags removed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if a tag is invalid or the repository operation fails.
+    pub fn remove_tags(&self, worklog_id: &str, tags: &[String]) -> Result<usize, WorklogError> {
+        let tags = normalise_tags(tags)?;
+        self.repo.remove_tags(worklog_id, &tags)
+    }
+
+    /// Finds the ids of the work log entries having the local tag.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the tag is invalid or the repository operation fails.
+    pub fn find_by_tag(&self, tag: &str) -> Result<Vec<String>, WorklogError> {
+        self.repo.find_by_tag(&normalise_tag(tag)?)
+    }
+
+    /// Retrieves the local tags of a work log entry.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the repository operation fails.
+    pub fn find_tags(&self, worklog_id: &str) -> Result<Vec<String>, WorklogError> {
+        self.repo.find_tags(worklog_id)
+    }
+
+    /// Finds the groups of work logs which are accidental duplicates of each other, i.e. logged by
+    /// the same author on the same issue with identical start, duration and comment.
+    /// Entries with the same start and duration, but different comments, are not duplicates.
+    ///
+    /// # Returns
+    ///
+    /// The groups with more than one entry, each ordered by ascending id, hence the first entry of
+    /// a group is the one to keep.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `WorklogError` if the repository operation fails.
+    pub fn find_duplicates(&self) -> Result<Vec<Vec<LocalWorklog>>, WorklogError> {
+        self.repo.find_duplicates()
+    }
+}
+
+/// Drops the leading `#` of a tag and converts it to lower case, hence `#Billable` equals `billable`
+fn normalise_tag(tag: &str) -> Result<String, WorklogError> {
+    let normalised = tag.trim().trim_start_matches('#').to_lowercase();
+    if normalised.is_empty() || normalised.contains(char::is_whitespace) {
+        return Err(WorklogError::InvalidInput(format!(
+            "Invalid tag '{tag}', tags must be a single word"
+        )));
+    }
+    Ok(normalised)
+}
+
+fn normalise_tags(tags: &[String]) -> Result<Vec<String>, WorklogError> {
+    tags.iter().map(|tag| normalise_tag(tag)).collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::types::LocalWorklog;
-    use chrono::Local;
-    use jira::models::core::IssueKey;
+    use super::WorkLogService;
+    use crate::error::WorklogError;
+    use crate::repository::sqlite::tests::test_database_manager;
+    use crate::service::issue::IssueService;
+    use crate::types::{GroupBy, GroupTotal, LocalWorklog, PeriodSummary};
+    use chrono::{Days, Local, NaiveDate, TimeZone};
+    use jira::builder::DEFAULT_API_VERSION;
+    use jira::models::core::{Fields, IssueKey};
+    use jira::models::issue::IssueSummary;
     use jira::models::user::User;
+    use jira::{Credentials, Jira};
+    use mockito::Server;
+    use std::sync::Arc;
 
     #[test]
     fn test_worklog_service_struct_creation() {
@@ -227,18 +492,14 @@ mod tests {
     #[test]
     fn test_local_worklog_creation() {
         let now = Local::now();
-        let worklog = LocalWorklog {
-            issue_key: IssueKey::from("TEST-123"),
-            id: "test-id".to_string(),
-            author: "Test User".to_string(),
-            created: now,
-            updated: now,
-            started: now,
-            timeSpent: "1h".to_string(),
-            timeSpentSeconds: 3600, // 1 hour
-            issueId: 12345,
-            comment: Some("Test work".to_string()),
-        };
+        let worklog = LocalWorklog::builder(IssueKey::from("TEST-123"), "test-id", 3600) // 1 hour
+            .author("Test User")
+            .created(now)
+            .updated(now)
+            .started(now)
+            .issue_id(12345)
+            .comment("Test work")
+            .build();
 
         assert_eq!(worklog.id, "test-id");
         assert_eq!(worklog.issue_key.value(), "TEST-123");
@@ -252,18 +513,14 @@ mod tests {
     #[test]
     fn test_local_worklog_time_calculations() {
         let now = Local::now();
-        let worklog = LocalWorklog {
-            issue_key: IssueKey::from("TEST-123"),
-            id: "test-id".to_string(),
-            author: "Test User".to_string(),
-            created: now,
-            updated: now,
-            started: now,
-            timeSpent: "2h".to_string(),
-            timeSpentSeconds: 7200, // 2 hours
-            issueId: 12345,
-            comment: Some("Test work".to_string()),
-        };
+        let worklog = LocalWorklog::builder(IssueKey::from("TEST-123"), "test-id", 7200) // 2 hours
+            .author("Test User")
+            .created(now)
+            .updated(now)
+            .started(now)
+            .issue_id(12345)
+            .comment("Test work")
+            .build();
 
         // 2 hours = 7200 seconds
         assert_eq!(worklog.timeSpentSeconds, 7200);
@@ -285,18 +542,19 @@ mod tests {
 
         for (seconds, time_spent, description) in test_cases {
             let now = Local::now();
-            let worklog = LocalWorklog {
-                issue_key: IssueKey::from("TEST-123"),
-                id: format!("test-{seconds}"),
-                author: "Test User".to_string(),
-                created: now,
-                updated: now,
-                started: now,
-                timeSpent: time_spent.to_string(),
-                timeSpentSeconds: seconds,
-                issueId: 12345,
-                comment: Some(description.to_string()),
-            };
+            let worklog = LocalWorklog::builder(
+                IssueKey::from("TEST-123"),
+                format!("test-{seconds}"),
+                seconds,
+            )
+            .author("Test User")
+            .created(now)
+            .updated(now)
+            .started(now)
+            .time_spent(time_spent.to_string())
+            .issue_id(12345)
+            .comment(description.to_string())
+            .build();
 
             assert_eq!(worklog.timeSpentSeconds, seconds);
             assert_eq!(worklog.timeSpent, time_spent);
@@ -312,6 +570,8 @@ mod tests {
             email_address: "john.doe@example.com".to_string(),
             display_name: "John Doe".to_string(),
             time_zone: "UTC".to_string(),
+            name: None,
+            key: None,
         };
 
         assert_eq!(user.account_id, "acc123");
@@ -327,18 +587,14 @@ mod tests {
 
         for key in issue_keys {
             let now = Local::now();
-            let worklog = LocalWorklog {
-                issue_key: IssueKey::from(key),
-                id: format!("test-{key}"),
-                author: "Test User".to_string(),
-                created: now,
-                updated: now,
-                started: now,
-                timeSpent: "1h".to_string(),
-                timeSpentSeconds: 3600,
-                issueId: 12345,
-                comment: Some(format!("Work on {key}")),
-            };
+            let worklog = LocalWorklog::builder(IssueKey::from(key), format!("test-{key}"), 3600)
+                .author("Test User")
+                .created(now)
+                .updated(now)
+                .started(now)
+                .issue_id(12345)
+                .comment(format!("Work on {key}"))
+                .build();
 
             assert_eq!(worklog.issue_key.value(), key);
             assert_eq!(worklog.comment, Some(format!("Work on {key}")));
@@ -357,18 +613,19 @@ mod tests {
 
         for (seconds, time_spent, description) in test_cases {
             let now = Local::now();
-            let worklog = LocalWorklog {
-                issue_key: IssueKey::from("BOUNDARY-1"),
-                id: format!("boundary-{seconds}"),
-                author: "Test User".to_string(),
-                created: now,
-                updated: now,
-                started: now,
-                timeSpent: time_spent.to_string(),
-                timeSpentSeconds: seconds,
-                issueId: 12345,
-                comment: Some(description.to_string()),
-            };
+            let worklog = LocalWorklog::builder(
+                IssueKey::from("BOUNDARY-1"),
+                format!("boundary-{seconds}"),
+                seconds,
+            )
+            .author("Test User")
+            .created(now)
+            .updated(now)
+            .started(now)
+            .time_spent(time_spent.to_string())
+            .issue_id(12345)
+            .comment(description.to_string())
+            .build();
 
             assert_eq!(worklog.timeSpentSeconds, seconds);
             assert!(worklog.timeSpentSeconds > 0);
@@ -387,18 +644,17 @@ mod tests {
 
         for comment in comments {
             let now = Local::now();
-            let worklog = LocalWorklog {
-                issue_key: IssueKey::from("COMMENT-1"),
-                id: "test-comment".to_string(),
-                author: "Test User".to_string(),
-                created: now,
-                updated: now,
-                started: now,
-                timeSpent: "1h".to_string(),
-                timeSpentSeconds: 3600,
-                issueId: 12345,
-                comment: comment.clone(),
-            };
+            let mut builder =
+                LocalWorklog::builder(IssueKey::from("COMMENT-1"), "test-comment", 3600)
+                    .author("Test User")
+                    .created(now)
+                    .updated(now)
+                    .started(now)
+                    .issue_id(12345);
+            if let Some(comment) = &comment {
+                builder = builder.comment(comment);
+            }
+            let worklog = builder.build();
 
             assert_eq!(worklog.comment, comment);
         }
@@ -407,21 +663,289 @@ mod tests {
     #[test]
     fn test_local_worklog_without_comment() {
         let now = Local::now();
-        let worklog = LocalWorklog {
-            issue_key: IssueKey::from("NO-COMMENT-1"),
-            id: "test-no-comment".to_string(),
-            author: "Test User".to_string(),
-            created: now,
-            updated: now,
-            started: now,
-            timeSpent: "30m".to_string(),
-            timeSpentSeconds: 1800,
-            issueId: 12345,
-            comment: None,
-        };
+        let worklog =
+            LocalWorklog::builder(IssueKey::from("NO-COMMENT-1"), "test-no-comment", 1800)
+                .author("Test User")
+                .created(now)
+                .updated(now)
+                .started(now)
+                .issue_id(12345)
+                .build();
 
         assert!(worklog.comment.is_none());
         assert_eq!(worklog.timeSpentSeconds, 1800);
         assert_eq!(worklog.timeSpent, "30m");
     }
+
+    #[test]
+    fn test_normalise_tag() {
+        assert_eq!(super::normalise_tag("#Billable").unwrap(), "billable");
+        assert_eq!(super::normalise_tag(" internal ").unwrap(), "internal");
+        assert!(super::normalise_tag("#").is_err());
+        assert!(super::normalise_tag("two words").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_worklogs_removes_local_entries() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let jira_client = Jira::new(server.url(), Credentials::Anonymous)?;
+        let db_manager = test_database_manager()?;
+        let issue_service = Arc::new(IssueService::new(
+            db_manager.create_issue_repository(),
+            jira_client.clone(),
+        ));
+        issue_service.add_jira_issues(&[IssueSummary {
+            id: "10".to_string(),
+            key: IssueKey::from("TIME-1"),
+            fields: Fields::default(),
+        }])?;
+
+        let mut mocks = Vec::new();
+        for (worklog_id, status) in [("1", 204), ("2", 404), ("3", 204)] {
+            mocks.push(
+                server
+                    .mock(
+                        "DELETE",
+                        format!(
+                            "/rest/api/{DEFAULT_API_VERSION}/issue/TIME-1/worklog/{worklog_id}"
+                        )
+                        .as_str(),
+                    )
+                    .with_status(status)
+                    .create_async()
+                    .await,
+            );
+        }
+        let worklog_service = WorkLogService::new(
+            db_manager.create_worklog_repository(),
+            issue_service,
+            jira_client,
+        );
+
+        let now = Local::now();
+        let worklog_ids = ["1", "2", "3"];
+        for id in worklog_ids {
+            worklog_service.repo.add_entry(
+                &LocalWorklog::builder(IssueKey::from("TIME-1"), id, 3600)
+                    .author("Test User")
+                    .created(now)
+                    .updated(now)
+                    .started(now)
+                    .issue_id(10)
+                    .build(),
+            )?;
+        }
+
+        let items: Vec<(IssueKey, String)> = worklog_ids
+            .iter()
+            .map(|id| (IssueKey::from("TIME-1"), (*id).to_string()))
+            .collect();
+        let report = worklog_service.bulk_delete_worklogs(&items).await?;
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        assert_eq!(report.deleted.len(), 2);
+        assert_eq!(report.not_found.len(), 1);
+        assert!(!report.has_failures());
+        for id in worklog_ids {
+            assert!(worklog_service.find_worklog_by_id(id).is_err());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_gaps_reports_weekday_without_work_logs() -> Result<(), WorklogError> {
+        let mut server = Server::new_async().await;
+        let _configuration = server
+            .mock(
+                "GET",
+                format!("/rest/api/{DEFAULT_API_VERSION}/configuration").as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"votingEnabled": true, "watchingEnabled": true, "unassignedIssuesAllowed": true,
+                    "subTasksEnabled": true, "issueLinkingEnabled": true, "timeTrackingEnabled": true,
+                    "attachmentsEnabled": true,
+                    "timeTrackingConfiguration": {"workingHoursPerDay": 7.5, "workingDaysPerWeek": 5.0,
+                        "timeFormat": "pretty", "defaultUnit": "hour"}}"#,
+            )
+            .create_async()
+            .await;
+        let jira_client = Jira::new(server.url(), Credentials::Anonymous)?;
+        let db_manager = test_database_manager()?;
+        let issue_service = Arc::new(IssueService::new(
+            db_manager.create_issue_repository(),
+            jira_client.clone(),
+        ));
+        issue_service.add_jira_issues(&[IssueSummary {
+            id: "10".to_string(),
+            key: IssueKey::from("TIME-1"),
+            fields: Fields::default(),
+        }])?;
+        let worklog_service = WorkLogService::new(
+            db_manager.create_worklog_repository(),
+            issue_service,
+            jira_client,
+        );
+
+        // Monday 5th to Sunday 11th of February 2024, with nothing logged on Wednesday
+        let monday = Local.with_ymd_and_hms(2024, 2, 5, 8, 0, 0).unwrap();
+        for day in [0, 1, 3, 4] {
+            let started = monday + Days::new(day);
+            worklog_service.repo.add_entry(
+                &LocalWorklog::builder(IssueKey::from("TIME-1"), format!("{day}"), 27_000)
+                    .author("Test User")
+                    .created(started)
+                    .updated(started)
+                    .started(started)
+                    .time_spent("7h 30m")
+                    .issue_id(10)
+                    .build(),
+            )?;
+        }
+
+        // A colleague logging the whole Wednesday does not fill the gap
+        let wednesday = monday + Days::new(2);
+        worklog_service.repo.add_entry(
+            &LocalWorklog::builder(IssueKey::from("TIME-1"), "100", 27_000)
+                .author("Kari Nordmann")
+                .created(wednesday)
+                .updated(wednesday)
+                .started(wednesday)
+                .time_spent("7h 30m")
+                .issue_id(10)
+                .build(),
+        )?;
+
+        let current_user = User {
+            display_name: "Test User".to_string(),
+            ..Default::default()
+        };
+        let sunday = Local.with_ymd_and_hms(2024, 2, 11, 23, 59, 0).unwrap();
+        let gaps = worklog_service
+            .find_gaps(monday, sunday, 27_000, &[], &current_user)
+            .await?;
+        assert_eq!(gaps, vec![NaiveDate::from_ymd_opt(2024, 2, 7).unwrap()]);
+        // Nothing is missing if Wednesday is a holiday
+        let holidays = [NaiveDate::from_ymd_opt(2024, 2, 7).unwrap()];
+        let gaps = worklog_service
+            .find_gaps(monday, sunday, 27_000, &holidays, &current_user)
+            .await?;
+        assert!(gaps.is_empty());
+        Ok(())
+    }
+
+    /// Work logs of two days in February 2024 on three issues of two projects, with
+    /// work logged on the day before and the day after as well
+    fn seeded_worklog_service() -> Result<WorkLogService, WorklogError> {
+        let jira_client = Jira::new("http://localhost", Credentials::Anonymous)?;
+        let db_manager = test_database_manager()?;
+        let issue_service = Arc::new(IssueService::new(
+            db_manager.create_issue_repository(),
+            jira_client.clone(),
+        ));
+        let issues: Vec<IssueSummary> = [("10", "TIME-1"), ("11", "TIME-2"), ("20", "ABC-1")]
+            .iter()
+            .map(|(id, key)| IssueSummary {
+                id: (*id).to_string(),
+                key: IssueKey::from(*key),
+                fields: Fields::default(),
+            })
+            .collect();
+        issue_service.add_jira_issues(&issues)?;
+        let worklog_service = WorkLogService::new(
+            db_manager.create_worklog_repository(),
+            issue_service,
+            jira_client,
+        );
+
+        let entries = [
+            (4, "TIME-1", 10, 3600),
+            (5, "TIME-1", 10, 3600),
+            (5, "TIME-2", 11, 1800),
+            (5, "ABC-1", 20, 5400),
+            (6, "TIME-2", 11, 7200),
+            (7, "ABC-1", 20, 900),
+        ];
+        for (i, (day, issue_key, issue_id, seconds)) in entries.into_iter().enumerate() {
+            let started = Local.with_ymd_and_hms(2024, 2, day, 9, 0, 0).unwrap();
+            worklog_service.repo.add_entry(
+                &LocalWorklog::builder(IssueKey::from(issue_key), i.to_string(), seconds)
+                    .issue_id(issue_id)
+                    .started(started)
+                    .build(),
+            )?;
+        }
+        Ok(worklog_service)
+    }
+
+    fn summary(group_by: GroupBy) -> Result<PeriodSummary, WorklogError> {
+        let start = Local.with_ymd_and_hms(2024, 2, 5, 0, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2024, 2, 7, 0, 0, 0).unwrap();
+        seeded_worklog_service()?.summary_for_period(start, end, group_by)
+    }
+
+    fn total(group: &str, total_seconds: i64) -> GroupTotal {
+        GroupTotal {
+            group: group.to_string(),
+            total_seconds,
+        }
+    }
+
+    /// The totals of the groups and the days add up to the total of the period
+    fn assert_reconciles(summary: &PeriodSummary) {
+        let groups: i64 = summary.per_group.iter().map(|g| g.total_seconds).sum();
+        let days: i64 = summary.per_day.iter().map(|(_, seconds)| seconds).sum();
+        assert_eq!(groups, summary.total_seconds);
+        assert_eq!(days, summary.total_seconds);
+    }
+
+    #[test]
+    fn test_summary_for_period_by_project() -> Result<(), WorklogError> {
+        let summary = summary(GroupBy::Project)?;
+
+        assert_eq!(summary.total_seconds, 18_000);
+        assert_eq!(
+            summary.per_group,
+            vec![total("ABC", 5400), total("TIME", 12_600)]
+        );
+        assert_eq!(
+            summary.per_day,
+            vec![
+                (NaiveDate::from_ymd_opt(2024, 2, 5).unwrap(), 10_800),
+                (NaiveDate::from_ymd_opt(2024, 2, 6).unwrap(), 7200),
+            ]
+        );
+        assert_reconciles(&summary);
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary_for_period_by_issue() -> Result<(), WorklogError> {
+        let summary = summary(GroupBy::Issue)?;
+
+        assert_eq!(
+            summary.per_group,
+            vec![
+                total("ABC-1", 5400),
+                total("TIME-1", 3600),
+                total("TIME-2", 9000)
+            ]
+        );
+        assert_reconciles(&summary);
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary_for_period_by_day() -> Result<(), WorklogError> {
+        let summary = summary(GroupBy::Day)?;
+
+        assert_eq!(
+            summary.per_group,
+            vec![total("2024-02-05", 10_800), total("2024-02-06", 7200)]
+        );
+        assert_reconciles(&summary);
+        Ok(())
+    }
 }
@@ -1,8 +1,13 @@
+use crate::config::DisplayTimeZone;
+use crate::date::{format_seconds, DurationStyle};
 use chrono::Utc;
-use chrono::{DateTime, Local};
-use jira::models::core::IssueKey;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
+use jira::models::core::{Author, IssueKey};
 use jira::models::worklog::Worklog;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Clone)]
 #[allow(non_snake_case)]
@@ -37,25 +42,299 @@ impl LocalWorklog {
     /// This function will panic if `worklog.issueId` cannot be parsed into an `i32`.
     #[must_use]
     pub fn from_worklog(worklog: &Worklog, issue_key: &IssueKey) -> Self {
-        LocalWorklog {
-            issue_key: issue_key.clone(),
-            id: worklog.id.clone(),
-            author: worklog.author.displayName.clone(),
-            created: worklog.created.with_timezone(&Local),
-            updated: worklog.updated.with_timezone(&Local),
-            started: worklog.started.with_timezone(&Local),
-            timeSpent: worklog.timeSpent.clone(),
-            timeSpentSeconds: worklog.timeSpentSeconds,
-            issueId: worklog.issueId.parse().unwrap(),
-            comment: worklog.comment.clone(),
+        let builder = LocalWorklog::builder(
+            issue_key.clone(),
+            worklog.id.clone(),
+            worklog.timeSpentSeconds,
+        )
+        .author(worklog.author.displayName.clone())
+        .created(worklog.created.with_timezone(&Local))
+        .updated(worklog.updated.with_timezone(&Local))
+        .started(worklog.started.with_timezone(&Local))
+        .time_spent(worklog.timeSpent.clone())
+        .issue_id(worklog.issueId.parse().unwrap());
+        match &worklog.comment {
+            Some(comment) => builder.comment(comment),
+            None => builder,
+        }
+        .build()
+    }
+
+    /// The time the work was started, presented in the supplied time zone.
+    /// The `started` field holds the same point in time, expressed in the local time zone.
+    #[must_use]
+    pub fn started_in(&self, display_tz: DisplayTimeZone) -> DateTime<FixedOffset> {
+        display_tz.convert(&self.started)
+    }
+
+    /// Starts building a work log entry, see [`LocalWorklogBuilder`]
+    #[must_use]
+    pub fn builder(
+        issue_key: IssueKey,
+        id: impl Into<String>,
+        time_spent_seconds: i32,
+    ) -> LocalWorklogBuilder {
+        LocalWorklogBuilder::new(issue_key, id, time_spent_seconds)
+    }
+}
+
+/// Builds a `LocalWorklog` from the issue key, id and time spent, which are required.
+/// The work was started, created and updated now, by no author in particular, with the time
+/// spent formatted like Jira does, i.e. `1h 30m`, and neither an issue id nor a comment.
+#[derive(Debug, Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct LocalWorklogBuilder {
+    worklog: LocalWorklog,
+}
+
+impl LocalWorklogBuilder {
+    #[must_use]
+    pub fn new(issue_key: IssueKey, id: impl Into<String>, time_spent_seconds: i32) -> Self {
+        let now = Local::now();
+        Self {
+            worklog: LocalWorklog {
+                issue_key,
+                id: id.into(),
+                author: String::new(),
+                created: now,
+                updated: now,
+                started: now,
+                timeSpent: format_seconds(
+                    i64::from(time_spent_seconds),
+                    DurationStyle::HoursAndMinutes,
+                ),
+                timeSpentSeconds: time_spent_seconds,
+                issueId: 0,
+                comment: None,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.worklog.author = author.into();
+        self
+    }
+
+    #[must_use]
+    pub fn created(mut self, created: DateTime<Local>) -> Self {
+        self.worklog.created = created;
+        self
+    }
+
+    #[must_use]
+    pub fn updated(mut self, updated: DateTime<Local>) -> Self {
+        self.worklog.updated = updated;
+        self
+    }
+
+    #[must_use]
+    pub fn started(mut self, started: DateTime<Local>) -> Self {
+        self.worklog.started = started;
+        self
+    }
+
+    /// Overrides the time spent formatted from the number of seconds
+    #[must_use]
+    pub fn time_spent(mut self, time_spent: impl Into<String>) -> Self {
+        self.worklog.timeSpent = time_spent.into();
+        self
+    }
+
+    #[must_use]
+    pub fn issue_id(mut self, issue_id: i32) -> Self {
+        self.worklog.issueId = issue_id;
+        self
+    }
+
+    #[must_use]
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.worklog.comment = Some(comment.into());
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> LocalWorklog {
+        self.worklog
+    }
+}
+
+/// Where a work log entry in the local database originates from
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum WorklogSource {
+    /// Added with this application and written through to Jira
+    Local,
+    /// Pulled from Jira by synchronisation
+    Jira,
+}
+
+impl WorklogSource {
+    /// The value stored in the `source` column of the `worklog` table
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorklogSource::Local => "local",
+            WorklogSource::Jira => "jira",
+        }
+    }
+}
+
+impl FromStr for WorklogSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local" => Ok(WorklogSource::Local),
+            "jira" => Ok(WorklogSource::Jira),
+            other => Err(format!("Unknown work log source '{other}'")),
         }
     }
 }
 
+/// The number of work log entries inserted and changed, when upserting entries pulled from Jira
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub struct UpsertCounts {
+    /// Entries not previously in the local database
+    pub created: usize,
+    /// Existing entries, which have been updated in Jira since they were stored
+    pub updated: usize,
+}
+
+/// How the work logs of a [`PeriodSummary`] are grouped
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// By the key of the project, like `TIME`
+    Project,
+    /// By the issue key, like `TIME-94`
+    Issue,
+    /// By the local date work was started, like `2024-05-31`
+    Day,
+}
+
+/// The time logged for one group of a [`PeriodSummary`]
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GroupTotal {
+    pub group: String,
+    pub total_seconds: i64,
+}
+
+/// The time logged within a period, which the front-ends render as they see fit
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
+pub struct PeriodSummary {
+    pub total_seconds: i64,
+    /// Ordered by ascending group
+    pub per_group: Vec<GroupTotal>,
+    /// The days on which work was logged, in ascending order
+    pub per_day: Vec<(NaiveDate, i64)>,
+}
+
+impl PeriodSummary {
+    /// Sums up the work logs, which should already be limited to the period
+    #[must_use]
+    pub fn from_worklogs(worklogs: &[LocalWorklog], group_by: GroupBy) -> Self {
+        let mut per_group: BTreeMap<String, i64> = BTreeMap::new();
+        let mut per_day: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+        let mut total_seconds = 0;
+        for worklog in worklogs {
+            let seconds = i64::from(worklog.timeSpentSeconds);
+            let day = worklog.started.date_naive();
+            let group = match group_by {
+                GroupBy::Project => worklog.issue_key.project_key().to_string(),
+                GroupBy::Issue => worklog.issue_key.to_string(),
+                GroupBy::Day => day.format("%Y-%m-%d").to_string(),
+            };
+            *per_group.entry(group).or_default() += seconds;
+            *per_day.entry(day).or_default() += seconds;
+            total_seconds += seconds;
+        }
+        PeriodSummary {
+            total_seconds,
+            per_group: per_group
+                .into_iter()
+                .map(|(group, total_seconds)| GroupTotal {
+                    group,
+                    total_seconds,
+                })
+                .collect(),
+            per_day: per_day.into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
 pub struct JiraIssueInfo {
     pub issue_key: IssueKey,
     pub summary: String,
+    /// The parent issue if this is a subtask
+    #[serde(default)]
+    pub parent_key: Option<IssueKey>,
+    /// The workflow status, like `In Progress`, if known
+    #[serde(default)]
+    pub status: Option<String>,
+    /// How the issue was resolved, like `Done`, or `None` if unresolved
+    #[serde(default)]
+    pub resolution: Option<String>,
+    /// The user the issue is assigned to, `None` if unassigned or unknown
+    #[serde(default)]
+    pub assignee: Option<Author>,
+}
+
+impl JiraIssueInfo {
+    /// Time is rarely logged against a resolved issue on purpose
+    #[must_use]
+    pub fn is_resolved(&self) -> bool {
+        self.resolution.is_some()
+    }
+
+    /// Warns about logging time against the issue if it is resolved, `None` otherwise
+    #[must_use]
+    pub fn resolution_warning(&self) -> Option<String> {
+        self.resolution.as_ref().map(|resolution| {
+            format!(
+                "{} is resolved as '{resolution}'. Use --force to silence this warning",
+                self.issue_key
+            )
+        })
+    }
+}
+
+/// An issue frequently logged against, which may be referred to by its alias or its
+/// position in the list of favorites, starting at 1
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Favorite {
+    pub issue_key: IssueKey,
+    pub alias: Option<String>,
+}
+
+/// A difference between the tables of the local database and the schema expected by this version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaIssue {
+    MissingTable(String),
+    MissingColumn {
+        table: String,
+        column: String,
+    },
+    /// A column unknown to this version, which is left as is
+    ExtraColumn {
+        table: String,
+        column: String,
+    },
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaIssue::MissingTable(table) => write!(f, "missing table {table}"),
+            SchemaIssue::MissingColumn { table, column } => {
+                write!(f, "missing column {table}.{column}")
+            }
+            SchemaIssue::ExtraColumn { table, column } => {
+                write!(f, "unexpected column {table}.{column}")
+            }
+        }
+    }
 }
 
 /// Represents a timer record in the database
@@ -85,6 +364,11 @@ pub struct Timer {
 
     /// Optional comment about the work being tracked
     pub comment: Option<String>,
+
+    /// The summary of the issue when the timer was started, shown in timer listings even if
+    /// the issue has been renamed since. Advisory only, it is never sent to Jira.
+    #[serde(default)]
+    pub summary_snapshot: Option<String>,
 }
 
 impl Timer {
@@ -100,6 +384,7 @@ impl Timer {
             stopped_at: None,
             synced: false,
             comment: None,
+            summary_snapshot: None,
         }
     }
 
@@ -126,7 +411,7 @@ impl Timer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Local;
+    use chrono::{Local, TimeZone};
     use jira::models::core::IssueKey;
 
     #[test]
@@ -141,6 +426,21 @@ mod tests {
         assert!(timer.duration().is_none()); // Active timer has no duration
     }
 
+    #[test]
+    fn test_timer_serializes_times_as_rfc3339() {
+        let timer = Timer::start_new("TEST-123".to_string());
+        let json = serde_json::to_value(&timer).unwrap();
+
+        let started_at = json["started_at"].as_str().unwrap();
+        assert_eq!(
+            DateTime::parse_from_rfc3339(started_at).unwrap(),
+            timer.started_at
+        );
+        assert!(json["stopped_at"].is_null());
+        let parsed: Timer = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, timer);
+    }
+
     #[test]
     fn test_timer_is_active() {
         let mut timer = Timer::start_new("TEST-123".to_string());
@@ -202,6 +502,7 @@ mod tests {
             stopped_at: Some(stop_time),
             synced: false,
             comment: None,
+            summary_snapshot: None,
         };
 
         let duration = timer.duration().unwrap();
@@ -226,6 +527,8 @@ mod tests {
             accountId: "acc123".to_string(),
             emailAddress: Some("test@example.com".to_string()),
             displayName: "Test User".to_string(),
+            name: None,
+            key: None,
         };
 
         let worklog = Worklog {
@@ -252,11 +555,41 @@ mod tests {
         assert_eq!(local_worklog.comment, Some("Test comment".to_string()));
     }
 
+    #[test]
+    fn test_local_worklog_started_in_display_tz() {
+        use crate::config::DisplayTimeZone;
+        use jira::models::worklog::Worklog;
+
+        let worklog: Worklog = serde_json::from_str(
+            r#"{"id": "1", "issueId": "10", "timeSpent": "1h", "timeSpentSeconds": 3600,
+                "created": "2024-01-15T23:30:00.000+0100", "updated": "2024-01-15T23:30:00.000+0100",
+                "started": "2024-01-15T23:30:00.000+0100",
+                "author": {"accountId": "abc", "displayName": "Ola Dunk"}}"#,
+        )
+        .unwrap();
+        let local_worklog = LocalWorklog::from_worklog(&worklog, &IssueKey::from("TEST-1"));
+
+        let utc = local_worklog.started_in(DisplayTimeZone::Utc);
+        assert_eq!(utc.to_rfc3339(), "2024-01-15T22:30:00+00:00");
+
+        let oslo = local_worklog.started_in("Europe/Oslo".parse().unwrap());
+        assert_eq!(oslo.to_rfc3339(), "2024-01-15T23:30:00+01:00");
+
+        // Monday evening in Oslo is Tuesday morning in Tokyo
+        let tokyo = local_worklog.started_in("Asia/Tokyo".parse().unwrap());
+        assert_eq!(tokyo.to_rfc3339(), "2024-01-16T07:30:00+09:00");
+
+        let local = local_worklog.started_in(DisplayTimeZone::Local);
+        assert_eq!(local, local_worklog.started.fixed_offset());
+        assert_eq!(local, utc);
+    }
+
     #[test]
     fn test_jira_issue_info_creation() {
         let issue_info = JiraIssueInfo {
             issue_key: IssueKey::from("PROJ-456"),
             summary: "Test issue summary".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(issue_info.issue_key.value(), "PROJ-456");
@@ -290,4 +623,52 @@ mod tests {
 
         assert_eq!(timer.id, Some(42));
     }
+
+    #[test]
+    fn test_local_worklog_builder_defaults() {
+        let before = Local::now();
+        let worklog = LocalWorklog::builder(IssueKey::from("TIME-94"), "10", 5400).build();
+
+        assert_eq!(worklog.issue_key, IssueKey::from("TIME-94"));
+        assert_eq!(worklog.id, "10");
+        assert_eq!(worklog.timeSpentSeconds, 5400);
+        assert_eq!(worklog.timeSpent, "1h 30m");
+        assert!(worklog.started >= before && worklog.started <= Local::now());
+        assert_eq!(worklog.created, worklog.started);
+        assert_eq!(worklog.updated, worklog.started);
+        assert_eq!(worklog.issueId, 0);
+        assert!(worklog.author.is_empty());
+        assert!(worklog.comment.is_none());
+    }
+
+    #[test]
+    fn test_local_worklog_builder_with_all_fields() {
+        let created = Local.with_ymd_and_hms(2024, 5, 31, 17, 0, 0).unwrap();
+        let started = Local.with_ymd_and_hms(2024, 5, 31, 9, 0, 0).unwrap();
+        let worklog = LocalWorklog::builder(IssueKey::from("TIME-94"), "10", 27000)
+            .author("Ola Dunk")
+            .created(created)
+            .updated(created)
+            .started(started)
+            .time_spent("1d")
+            .issue_id(94)
+            .comment("Meetings")
+            .build();
+
+        assert_eq!(
+            worklog,
+            LocalWorklog {
+                issue_key: IssueKey::from("TIME-94"),
+                id: "10".to_string(),
+                author: "Ola Dunk".to_string(),
+                created,
+                updated: created,
+                started,
+                timeSpent: "1d".to_string(),
+                timeSpentSeconds: 27000,
+                issueId: 94,
+                comment: Some("Meetings".to_string()),
+            }
+        );
+    }
 }
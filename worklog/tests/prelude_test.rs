@@ -0,0 +1,36 @@
+//! Verifies that a runtime can be built and an operation executed using nothing but the prelude
+use jira::builder::JiraEnvVars;
+use mockito::Server;
+use worklog::prelude::*;
+
+#[tokio::test]
+async fn test_execute_operation_using_prelude() -> Result<(), WorklogError> {
+    let mut server = Server::new_async().await;
+    let _search = server
+        .mock("GET", "/rest/api/latest/search/jql")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_body(
+            r#"{"issues": [{"id": "1", "key": "TIME-1", "fields": {"summary": "Meetings", "components": []}}]}"#,
+        )
+        .create_async()
+        .await;
+
+    // Directs the Jira test instance to the mock server
+    std::env::set_var(JiraEnvVars::HOST, server.url());
+    std::env::set_var(JiraEnvVars::USER, "ola@dunk.com");
+    std::env::set_var(JiraEnvVars::TOKEN, "token");
+    std::env::remove_var(JiraEnvVars::API_VERSION);
+
+    let runtime: ApplicationRuntime = ApplicationRuntimeBuilder::new()
+        .use_in_memory_db()
+        .use_jira_test_instance()
+        .build()?;
+
+    let OperationResult::IssueSummaries(issues) = runtime.execute(Operation::Codes).await? else {
+        panic!("Expected issue summaries");
+    };
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].key, IssueKey::from("TIME-1"));
+    Ok(())
+}
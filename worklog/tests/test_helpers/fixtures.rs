@@ -26,24 +26,16 @@ pub fn create_test_timer(issue_key: &str, active: bool) -> Timer {
         },
         synced: false,
         comment: Some("Test timer comment".to_string()),
+        summary_snapshot: Some(TEST_ISSUE_SUMMARY.to_string()),
     }
 }
 
 #[cfg(test)]
 #[allow(dead_code)]
 pub fn create_worklog_entry(issue_key: IssueKey) -> LocalWorklog {
-    LocalWorklog {
-        id: "123456789".to_string(),
-        issue_key,
-        created: Local::now(),
-        updated: Local::now(),
-        started: Local::now() - Duration::hours(1),
-        timeSpent: "3600".to_string(),
-        timeSpentSeconds: 3600,
-        issueId: 0,
-        author: String::new(),
-        comment: None,
-    }
+    LocalWorklog::builder(issue_key, "123456789", 3600)
+        .started(Local::now() - Duration::hours(1))
+        .build()
 }
 
 /// Creates a sample `IssueSummary` for testing
@@ -54,6 +46,7 @@ pub fn create_test_issue_info() -> IssueSummary {
         fields: Fields {
             summary: TEST_ISSUE_SUMMARY.to_string(),
             components: vec![],
+            ..Default::default()
         },
     }
 }
@@ -70,6 +63,7 @@ pub fn create_test_issues() -> Vec<IssueSummary> {
             fields: Fields {
                 summary: "Another test issue".to_string(),
                 components: vec![],
+                ..Default::default()
             },
         },
         IssueSummary {
@@ -78,6 +72,7 @@ pub fn create_test_issues() -> Vec<IssueSummary> {
             fields: Fields {
                 summary: "Yet another test issue".to_string(),
                 components: vec![],
+                ..Default::default()
             },
         },
     ]
@@ -86,7 +86,7 @@ impl TimerServiceTestContext {
             .jira_client
             .create_issue(
                 &JiraProjectKey {
-                    key: TEST_PROJECT_KEY,
+                    key: TEST_PROJECT_KEY.to_string(),
                 },
                 "Test summary",
                 None,
@@ -116,6 +116,7 @@ impl TimerServiceTestContext {
             fields: Fields {
                 summary: "Generated by unit testing".to_string(),
                 components: vec![],
+                ..Default::default()
             },
         };
         let result = self.runtime.issue_service.add_jira_issues(&[issue_summary]);
@@ -255,7 +256,7 @@ async fn test_sync_timers_to_jira() {
         .jira_client()
         .create_issue(
             &JiraProjectKey {
-                key: TEST_PROJECT_KEY,
+                key: TEST_PROJECT_KEY.to_string(),
             },
             "TEST summary",
             None,
@@ -274,6 +275,7 @@ async fn test_sync_timers_to_jira() {
         fields: Fields {
             summary: "TEST Summary".to_string(),
             components: vec![],
+            ..Default::default()
         },
     };
 
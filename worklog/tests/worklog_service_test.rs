@@ -43,7 +43,7 @@ impl WorkLogServiceTestContext {
             .jira_client
             .create_issue(
                 &JiraProjectKey {
-                    key: TEST_PROJECT_KEY,
+                    key: TEST_PROJECT_KEY.to_string(),
                 },
                 "Testing summary",
                 Some("Test description".into()),
@@ -81,6 +81,9 @@ async fn test_add_worklog_to_issue_not_synchronized() {
         issue_key: key.to_string(),
         started: None,
         comment: Some("Rubbish".to_string()),
+        timezone: None,
+        tags: vec![],
+        force: false,
     };
 
     let add_result = worklog::operation::add::execute(&ctx.runtime, &mut add_params).await;
@@ -102,6 +105,9 @@ async fn test_add_to_empty_issue_not_synchronized() {
         issue_key: "TWIZ-1".to_string(),
         started: None,
         comment: Some("Rubbish".to_string()),
+        timezone: None,
+        tags: vec![],
+        force: false,
     };
 
     let add_result = worklog::operation::add::execute(&ctx.runtime, &mut add_params).await;